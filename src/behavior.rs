@@ -0,0 +1,112 @@
+// behavior trees for creature AI, one per archetype, read from
+// behaviors.ron so creatures can be retuned without a rebuild. A tree is run
+// from the root every frame: a sequence succeeds if all its children do, in
+// order, a selector as soon as one of them does, a condition asks something
+// of the creature and an action tries to do something, failing if it can't.
+// What the conditions and actions are is up to each kind of creature.
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt, fs, path::Path, path::PathBuf};
+
+use crate::wildlife::AnimalBehaviors;
+
+pub const DEFAULT_BEHAVIOR_FILE: &str = "assets/behaviors.ron";
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum BehaviorNode<C, A> {
+    Sequence(Vec<BehaviorNode<C, A>>),
+    Selector(Vec<BehaviorNode<C, A>>),
+    Condition(C),
+    Action(A),
+}
+
+// Implemented by whatever a tree is being run for.
+pub trait Behavior<C, A> {
+    fn check(&mut self, condition: &C) -> bool;
+    fn act(&mut self, action: &A) -> bool;
+}
+
+impl<C, A> BehaviorNode<C, A> {
+    pub fn run(&self, behavior: &mut impl Behavior<C, A>) -> bool {
+        match self {
+            BehaviorNode::Sequence(children) => children.iter().all(|child| child.run(behavior)),
+            BehaviorNode::Selector(children) => children.iter().any(|child| child.run(behavior)),
+            BehaviorNode::Condition(condition) => behavior.check(condition),
+            BehaviorNode::Action(action) => behavior.act(action),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BehaviorError {
+    Io(std::io::Error),
+    Parse(String),
+    UnknownArchetype(String),
+}
+
+impl fmt::Display for BehaviorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BehaviorError::Io(err) => write!(f, "could not read behavior trees: {err}"),
+            BehaviorError::Parse(err) => write!(f, "could not parse behavior trees: {err}"),
+            BehaviorError::UnknownArchetype(name) => {
+                write!(f, "behavior tree for unknown creature {name}")
+            }
+        }
+    }
+}
+
+// Trees keyed by archetype name; which names mean anything is for the
+// caller to check.
+pub fn load_behavior_trees<C: DeserializeOwned, A: DeserializeOwned>(
+    path: &Path,
+) -> Result<HashMap<String, BehaviorNode<C, A>>, BehaviorError> {
+    let text = fs::read_to_string(path).map_err(BehaviorError::Io)?;
+    ron::from_str(&text).map_err(|err| BehaviorError::Parse(err.to_string()))
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct BehaviorConfig {
+    pub behavior_path: PathBuf,
+}
+
+// Creatures without a tree in the file keep their built-in one.
+fn load_behaviors(mut commands: Commands, config: Res<BehaviorConfig>) {
+    match AnimalBehaviors::load(&config.behavior_path) {
+        Ok(behaviors) => commands.insert_resource(behaviors),
+        Err(err) => warn!(
+            "no behavior trees from {}: {err}",
+            config.behavior_path.display()
+        ),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BehaviorPlugin {
+    pub behavior_path: PathBuf,
+}
+
+impl Default for BehaviorPlugin {
+    fn default() -> Self {
+        Self {
+            behavior_path: PathBuf::from(DEFAULT_BEHAVIOR_FILE),
+        }
+    }
+}
+
+impl BehaviorPlugin {
+    pub fn with_behavior_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.behavior_path = path.into();
+        self
+    }
+}
+
+impl Plugin for BehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BehaviorConfig {
+            behavior_path: self.behavior_path.clone(),
+        })
+        .add_systems(Startup, load_behaviors);
+    }
+}
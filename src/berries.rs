@@ -0,0 +1,213 @@
+// berry bushes scattered at generation: E picks whatever has grown, and the
+// bush fills up again over time, faster in the light and by water
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::event_log::EventLog;
+use crate::food::Location2D;
+use crate::food_catalog::{FoodCatalog, FoodItem};
+use crate::interact::Interactable;
+use crate::items::{spawn_world_item, Inventory, ItemKind};
+use crate::light::{is_lit, Lightable};
+use crate::menu::AppState;
+use crate::player::{solid_overlaps, DeathRespawnState, Player};
+use crate::settings::{Action, GameSettings};
+use crate::world::{world_rng, Biome, WorldGrid, WorldRegenerated, WorldSeed, WORLD_TILE_SIZE};
+
+const BUSH_COUNT: usize = 60;
+const BUSH_SIZE: f32 = 8.0;
+const EDGE_MARGIN: i32 = 16;
+const SPAWN_CLEARING_RADIUS: i32 = 48;
+const BUSH_SEED_SALT: u64 = 0xbe77;
+const BERRY_NAME: &str = "Berries";
+const MAX_BERRIES: u32 = 3;
+const BERRY_SECONDS: f32 = 45.0;
+const BUSH_HARVEST_RADIUS: f32 = 16.0;
+const LIT_GROWTH_FACTOR: f32 = 2.0;
+// Bushes on the shore grow faster, bushes on rubble slower.
+const SHORE_GROWTH_FACTOR: f32 = 1.5;
+const RUBBLE_GROWTH_FACTOR: f32 = 0.5;
+const BARE_TINT: Color = Color::srgb(0.2, 0.4, 0.18);
+
+#[derive(Component)]
+pub struct BerryBush {
+    pub food: FoodItem,
+    pub berries: u32,
+    growth: f32,
+    // From the biome it stands in, fixed at placement.
+    terrain_factor: f32,
+}
+
+fn terrain_factor(grid: &WorldGrid, tile: IVec2) -> f32 {
+    match grid.biome(tile) {
+        Biome::Shore => SHORE_GROWTH_FACTOR,
+        Biome::Rubble => RUBBLE_GROWTH_FACTOR,
+        Biome::Plain => 1.0,
+    }
+}
+
+fn bush_tint(catalog: &FoodCatalog, bush: &BerryBush) -> Color {
+    let ripe = catalog.get(bush.food).map_or(Color::WHITE, |def| def.tint);
+    BARE_TINT.mix(&ripe, bush.berries as f32 / MAX_BERRIES as f32)
+}
+
+fn spawn_bushes(
+    mut commands: Commands,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    catalog: Res<FoodCatalog>,
+) {
+    place_bushes(&mut commands, &grid, *seed, &catalog);
+}
+
+fn respawn_bushes(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    catalog: Res<FoodCatalog>,
+    bush_query: Query<Entity, With<BerryBush>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &bush_query {
+        commands.entity(entity).despawn();
+    }
+    place_bushes(&mut commands, &grid, *seed, &catalog);
+}
+
+// Bushes start full so a new run has something to find.
+fn place_bushes(
+    commands: &mut Commands,
+    grid: &WorldGrid,
+    seed: WorldSeed,
+    catalog: &FoodCatalog,
+) {
+    if grid.width as i32 <= EDGE_MARGIN * 2 || grid.height as i32 <= EDGE_MARGIN * 2 {
+        return;
+    }
+    let Some(def) = catalog.find(BERRY_NAME) else {
+        warn!("no {BERRY_NAME} in the food table, skipping berry bushes");
+        return;
+    };
+
+    let mut rng = world_rng(seed, BUSH_SEED_SALT);
+    let center = IVec2::new(grid.width as i32 / 2, grid.height as i32 / 2);
+    for _ in 0..BUSH_COUNT {
+        let tile = IVec2::new(
+            rng.random_range(EDGE_MARGIN..grid.width as i32 - EDGE_MARGIN),
+            rng.random_range(EDGE_MARGIN..grid.height as i32 - EDGE_MARGIN),
+        );
+        if tile.distance_squared(center) < SPAWN_CLEARING_RADIUS * SPAWN_CLEARING_RADIUS {
+            continue;
+        }
+        let position = tile.as_vec2() * WORLD_TILE_SIZE;
+        if solid_overlaps(grid, position, BUSH_SIZE / 2.0) {
+            continue;
+        }
+        let bush = BerryBush {
+            food: def.item,
+            berries: MAX_BERRIES,
+            growth: 0.0,
+            terrain_factor: terrain_factor(grid, tile),
+        };
+        commands.spawn((
+            Lightable {
+                tint: bush_tint(catalog, &bush),
+            },
+            bush,
+            Interactable::new("interact.pick_berries", BUSH_HARVEST_RADIUS * WORLD_TILE_SIZE),
+            Location2D {
+                x: tile.x,
+                y: tile.y,
+            },
+            Sprite::from_color(Color::WHITE, Vec2::splat(BUSH_SIZE)),
+            Visibility::Hidden,
+            Transform::from_translation(position.extend(0.6)),
+        ));
+    }
+}
+
+fn grow_berries(
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    catalog: Res<FoodCatalog>,
+    mut bush_query: Query<(&mut BerryBush, &Location2D, &mut Lightable, &mut Interactable)>,
+) {
+    let dt = time.delta_secs();
+    for (mut bush, location, mut lightable, mut interactable) in &mut bush_query {
+        if bush.berries >= MAX_BERRIES {
+            continue;
+        }
+        let light = if is_lit(&grid, location.x, location.y) {
+            LIT_GROWTH_FACTOR
+        } else {
+            1.0
+        };
+        bush.growth += dt * light * bush.terrain_factor;
+        if bush.growth < BERRY_SECONDS {
+            continue;
+        }
+        bush.growth = 0.0;
+        bush.berries += 1;
+        lightable.tint = bush_tint(&catalog, &bush);
+        interactable.enabled = true;
+    }
+}
+
+fn harvest_berries(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    catalog: Res<FoodCatalog>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut bush_query: Query<
+        (&mut BerryBush, &Location2D, &Transform, &mut Lightable, &mut Interactable),
+        Without<Player>,
+    >,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::Interact)) {
+        return;
+    }
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (mut bush, location, transform, mut lightable, mut interactable) in &mut bush_query {
+        if bush.berries == 0 {
+            continue;
+        }
+        let distance = transform.translation.truncate().distance(player_pos);
+        if distance > BUSH_HARVEST_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        // Whatever doesn't fit drops under the bush.
+        let kind = ItemKind::Food(bush.food);
+        let picked = bush.berries;
+        for _ in 0..inventory.add_within_limit(kind, picked) {
+            spawn_world_item(&mut commands, kind, *location);
+        }
+        bush.berries = 0;
+        bush.growth = 0.0;
+        lightable.tint = bush_tint(&catalog, &bush);
+        interactable.enabled = false;
+        event_log.push(&time, format!("picked {} x{picked}", bush.food.name.to_lowercase()));
+    }
+}
+
+pub struct BerryPlugin;
+
+impl Plugin for BerryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_bushes).add_systems(
+            Update,
+            (respawn_bushes, grow_berries, harvest_berries)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,393 @@
+// the boss that guards each lair: it stalks the player once they come close,
+// starts charging below two thirds of its health and calls in minions below
+// one third. The boss bar follows the fight, and the boss drops its loot
+// table, including the Gloam Heart, when it dies.
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::boss_bar::BossEncounterEvent;
+use crate::combat::{Health, Loot};
+use crate::enemy::spawn_enemy;
+use crate::event_log::EventLog;
+use crate::faction::Faction;
+use crate::food::Location2D;
+use crate::health_bar::NoHealthBar;
+use crate::landmark::Lair;
+use crate::light::Lightable;
+use crate::obstacle::Obstacle;
+use crate::pathfinding::{PathFollower, Pathfinder};
+use crate::player::{solid_overlaps, DeathCause, DeathRespawnState, Player, RunSummary, Stats};
+use crate::progress::XpGained;
+use crate::world::{WorldGrid, WORLD_TILE_SIZE};
+
+const BOSS_NAME: &str = "Gloam Stalker";
+const BOSS_SIZE: f32 = 24.0;
+const BOSS_HEALTH: f32 = 400.0;
+const BOSS_PHASES: usize = 3;
+const BOSS_LOOT_TABLE: &str = "boss";
+const BOSS_TINT: Color = Color::srgb(0.4, 0.1, 0.35);
+const CHARGE_TINT: Color = Color::srgb(0.9, 0.3, 0.3);
+const BOSS_XP: u32 = 100;
+// Wakes when the player comes this close and gives up, healing, once they
+// get this far from the lair.
+const AGGRO_RADIUS: f32 = 40.0;
+const LEASH_RADIUS: f32 = 120.0;
+const STALK_SPEED: f32 = 18.0;
+const REPLAN_SECONDS: f32 = 0.5;
+const CONTACT_RADIUS: f32 = 14.0;
+const CONTACT_DAMAGE: f32 = 20.0;
+const CONTACT_COOLDOWN_SECONDS: f32 = 1.0;
+const CHARGE_COOLDOWN_SECONDS: f32 = 5.0;
+const WIND_UP_SECONDS: f32 = 0.7;
+const CHARGE_SECONDS: f32 = 0.6;
+const CHARGE_SPEED: f32 = 110.0;
+const SUMMON_COOLDOWN_SECONDS: f32 = 10.0;
+const MINIONS_PER_SUMMON: usize = 2;
+const MINION_SPREAD: f32 = 16.0;
+const MINION_STRENGTH: f32 = 0.6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BossAction {
+    Stalk,
+    WindUp { timer: f32, direction: Vec2 },
+    Charge { timer: f32, direction: Vec2 },
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub phase: usize,
+    pub engaged: bool,
+    lair: Entity,
+    home: Vec2,
+    action: BossAction,
+    charge_cooldown: f32,
+    summon_cooldown: f32,
+    contact_cooldown: f32,
+}
+
+impl Boss {
+    fn phase_for(health: &Health) -> usize {
+        let lost = 1.0 - (health.current / health.max).clamp(0.0, 1.0);
+        ((lost * BOSS_PHASES as f32) as usize).min(BOSS_PHASES - 1)
+    }
+}
+
+// The boss currently on the boss bar; cleared without a reward when its
+// lair goes away with the world.
+#[derive(Resource, Default)]
+struct BossEncounter {
+    active: Option<Entity>,
+}
+
+fn spawn_bosses(mut commands: Commands, lair_query: Query<(Entity, &Transform), Added<Lair>>) {
+    for (lair, transform) in &lair_query {
+        let home = transform.translation.truncate();
+        let tile = (home / WORLD_TILE_SIZE).floor().as_ivec2();
+        commands.spawn((
+            Boss {
+                phase: 0,
+                engaged: false,
+                lair,
+                home,
+                action: BossAction::Stalk,
+                charge_cooldown: CHARGE_COOLDOWN_SECONDS,
+                summon_cooldown: 0.0,
+                contact_cooldown: 0.0,
+            },
+            PathFollower::new(REPLAN_SECONDS),
+            Health::new(BOSS_HEALTH),
+            Loot {
+                table: BOSS_LOOT_TABLE,
+            },
+            NoHealthBar,
+            Faction::Monster,
+            Lightable { tint: BOSS_TINT },
+            Location2D {
+                x: tile.x,
+                y: tile.y,
+            },
+            Sprite::from_color(Color::WHITE, Vec2::splat(BOSS_SIZE)),
+            Visibility::Hidden,
+            Transform::from_translation(home.extend(0.75)),
+        ));
+    }
+}
+
+// Lairs are respawned with the world, and their bosses go with them.
+fn despawn_orphaned_bosses(
+    mut commands: Commands,
+    mut encounter: ResMut<BossEncounter>,
+    mut boss_events: MessageWriter<BossEncounterEvent>,
+    boss_query: Query<(Entity, &Boss)>,
+    lair_query: Query<(), With<Lair>>,
+) {
+    for (entity, boss) in &boss_query {
+        if lair_query.contains(boss.lair) {
+            continue;
+        }
+        if encounter.active == Some(entity) {
+            encounter.active = None;
+            boss_events.write(BossEncounterEvent::Ended);
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+// Starts and ends the fight. A boss that loses the player goes home healed.
+fn engage_bosses(
+    death_state: Res<DeathRespawnState>,
+    mut encounter: ResMut<BossEncounter>,
+    mut boss_events: MessageWriter<BossEncounterEvent>,
+    player_query: Query<&Transform, With<Player>>,
+    mut boss_query: Query<
+        (Entity, &mut Boss, &mut Health, &mut Location2D, &mut Transform),
+        Without<Player>,
+    >,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (entity, mut boss, mut health, mut location, mut transform) in &mut boss_query {
+        let from_home = player_pos.distance(boss.home);
+        if boss.engaged && (death_state.is_dead || from_home > LEASH_RADIUS * WORLD_TILE_SIZE) {
+            boss.engaged = false;
+            boss.phase = 0;
+            boss.action = BossAction::Stalk;
+            health.current = health.max;
+            transform.translation.x = boss.home.x;
+            transform.translation.y = boss.home.y;
+            let tile = (boss.home / WORLD_TILE_SIZE).floor().as_ivec2();
+            location.set_if_neq(Location2D {
+                x: tile.x,
+                y: tile.y,
+            });
+            if encounter.active == Some(entity) {
+                encounter.active = None;
+                boss_events.write(BossEncounterEvent::Ended);
+            }
+            continue;
+        }
+        let distance = player_pos.distance(transform.translation.truncate());
+        if boss.engaged || death_state.is_dead || distance > AGGRO_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        boss.engaged = true;
+        boss.charge_cooldown = CHARGE_COOLDOWN_SECONDS;
+        boss.summon_cooldown = 0.0;
+        encounter.active = Some(entity);
+        boss_events.write(BossEncounterEvent::Started {
+            name: BOSS_NAME.to_string(),
+            max_health: health.max,
+            phases: BOSS_PHASES,
+        });
+    }
+}
+
+fn report_boss_damage(
+    encounter: Res<BossEncounter>,
+    mut boss_events: MessageWriter<BossEncounterEvent>,
+    mut boss_query: Query<(Entity, &mut Boss, &Health), Changed<Health>>,
+) {
+    for (entity, mut boss, health) in &mut boss_query {
+        boss.phase = Boss::phase_for(health);
+        if encounter.active != Some(entity) {
+            continue;
+        }
+        boss_events.write(BossEncounterEvent::Damaged {
+            health: health.current,
+            phase: boss.phase,
+        });
+    }
+}
+
+fn boss_behaviour(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    mut pathfinder: ResMut<Pathfinder>,
+    player_query: Query<&Transform, With<Player>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Boss>>,
+    mut boss_query: Query<
+        (&mut Boss, &mut PathFollower, &mut Location2D, &mut Transform, &mut Lightable),
+        Without<Player>,
+    >,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let dt = time.delta_secs();
+    let radius = BOSS_SIZE / 2.0;
+    let blocked = |candidate: Vec2| {
+        solid_overlaps(&grid, candidate, radius)
+            || obstacle_query.iter().any(|(obstacle_transform, obstacle)| {
+                let reach = obstacle.radius + radius;
+                obstacle_transform.translation.truncate().distance_squared(candidate) < reach * reach
+            })
+    };
+    let mut rng = rand::rng();
+
+    for (mut boss, mut follower, mut location, mut transform, mut lightable) in &mut boss_query {
+        if !boss.engaged {
+            follower.clear();
+            lightable.tint = BOSS_TINT;
+            continue;
+        }
+        let position = transform.translation.truncate();
+        boss.charge_cooldown = (boss.charge_cooldown - dt).max(0.0);
+        boss.summon_cooldown = (boss.summon_cooldown - dt).max(0.0);
+
+        if boss.phase >= 2 && boss.summon_cooldown <= 0.0 {
+            boss.summon_cooldown = SUMMON_COOLDOWN_SECONDS;
+            for _ in 0..MINIONS_PER_SUMMON {
+                let angle = rng.random_range(0.0..std::f32::consts::TAU);
+                let spot = position + Vec2::from_angle(angle) * MINION_SPREAD * WORLD_TILE_SIZE;
+                if !solid_overlaps(&grid, spot, radius / 2.0) {
+                    spawn_enemy(&mut commands, spot, MINION_STRENGTH);
+                }
+            }
+        }
+
+        let (heading, speed) = match boss.action {
+            BossAction::Stalk => {
+                if boss.phase >= 1 && boss.charge_cooldown <= 0.0 {
+                    boss.action = BossAction::WindUp {
+                        timer: WIND_UP_SECONDS,
+                        direction: (player_pos - position).normalize_or(Vec2::X),
+                    };
+                    (Vec2::ZERO, 0.0)
+                } else {
+                    let waypoint = follower
+                        .next_waypoint(&mut pathfinder, position, player_pos, dt)
+                        .unwrap_or(player_pos);
+                    ((waypoint - position).normalize_or_zero(), STALK_SPEED)
+                }
+            }
+            // Stands still, flushing red, so the player can see it coming.
+            BossAction::WindUp { timer, direction } => {
+                let timer = timer - dt;
+                boss.action = if timer <= 0.0 {
+                    BossAction::Charge {
+                        timer: CHARGE_SECONDS,
+                        direction,
+                    }
+                } else {
+                    BossAction::WindUp { timer, direction }
+                };
+                (Vec2::ZERO, 0.0)
+            }
+            BossAction::Charge { timer, direction } => {
+                let timer = timer - dt;
+                boss.action = if timer <= 0.0 {
+                    boss.charge_cooldown = CHARGE_COOLDOWN_SECONDS;
+                    BossAction::Stalk
+                } else {
+                    BossAction::Charge { timer, direction }
+                };
+                (direction, CHARGE_SPEED)
+            }
+        };
+        lightable.tint = match boss.action {
+            BossAction::WindUp { timer, .. } => {
+                BOSS_TINT.mix(&CHARGE_TINT, 1.0 - timer / WIND_UP_SECONDS)
+            }
+            BossAction::Charge { .. } => CHARGE_TINT,
+            BossAction::Stalk => BOSS_TINT,
+        };
+        if heading == Vec2::ZERO {
+            continue;
+        }
+
+        let step = heading * speed * WORLD_TILE_SIZE * dt;
+        let next = [step, Vec2::new(step.x, 0.0), Vec2::new(0.0, step.y)]
+            .into_iter()
+            .map(|step| position + step)
+            .find(|candidate| !blocked(*candidate));
+        let Some(next) = next else {
+            // A charge into a wall ends it early.
+            if matches!(boss.action, BossAction::Charge { .. }) {
+                boss.action = BossAction::Stalk;
+                boss.charge_cooldown = CHARGE_COOLDOWN_SECONDS;
+            }
+            continue;
+        };
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+        let tile = (next / WORLD_TILE_SIZE).floor().as_ivec2();
+        location.set_if_neq(Location2D {
+            x: tile.x,
+            y: tile.y,
+        });
+    }
+}
+
+fn boss_contact_damage(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut summary: ResMut<RunSummary>,
+    mut player_query: Query<(&Transform, &mut Stats), With<Player>>,
+    mut boss_query: Query<(&mut Boss, &Transform), Without<Player>>,
+) {
+    let dt = time.delta_secs();
+    let Ok((player_transform, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (mut boss, transform) in &mut boss_query {
+        boss.contact_cooldown = (boss.contact_cooldown - dt).max(0.0);
+        if death_state.is_dead || !boss.engaged || boss.contact_cooldown > 0.0 {
+            continue;
+        }
+        let distance = transform.translation.truncate().distance(player_pos);
+        if distance > CONTACT_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        boss.contact_cooldown = CONTACT_COOLDOWN_SECONDS;
+        stats.health = (stats.health - CONTACT_DAMAGE).max(0.0);
+        summary.last_damage = Some(DeathCause::Enemy);
+    }
+}
+
+// Combat despawns the boss and drops its loot; this wraps up the fight.
+fn finish_boss_fights(
+    time: Res<Time>,
+    mut removed: RemovedComponents<Boss>,
+    mut encounter: ResMut<BossEncounter>,
+    mut boss_events: MessageWriter<BossEncounterEvent>,
+    mut xp: MessageWriter<XpGained>,
+    mut event_log: ResMut<EventLog>,
+) {
+    for entity in removed.read() {
+        if encounter.active != Some(entity) {
+            continue;
+        }
+        encounter.active = None;
+        boss_events.write(BossEncounterEvent::Ended);
+        xp.write(XpGained {
+            amount: BOSS_XP,
+            reason: "defeated a boss",
+        });
+        event_log.push(&time, format!("the {} is defeated", BOSS_NAME.to_lowercase()));
+    }
+}
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossEncounter>().add_systems(
+            Update,
+            (
+                despawn_orphaned_bosses,
+                spawn_bosses,
+                engage_bosses,
+                report_boss_damage,
+                boss_behaviour,
+                boss_contact_damage,
+                finish_boss_fights,
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,223 @@
+// top-of-screen health bar for bosses and other large enemies
+use bevy::prelude::*;
+
+use crate::hud::HudElement;
+
+const BAR_MIN_WIDTH: f32 = 240.0;
+const BAR_MAX_WIDTH: f32 = 520.0;
+const BAR_HEIGHT: f32 = 14.0;
+const BAR_BASE_HEALTH: f32 = 200.0;
+const MAX_PHASE_PIPS: usize = 5;
+const PHASE_PIP_SIZE: f32 = 10.0;
+const DAMAGE_FLASH_SECONDS: f32 = 0.25;
+
+// Sent by the boss module as a fight starts, goes on and ends.
+#[derive(Message, Clone, Debug)]
+pub enum BossEncounterEvent {
+    Started {
+        name: String,
+        max_health: f32,
+        phases: usize,
+    },
+    Damaged {
+        health: f32,
+        phase: usize,
+    },
+    Ended,
+}
+
+#[derive(Resource, Default)]
+struct BossBarState {
+    active: bool,
+    max_health: f32,
+    health: f32,
+    phases: usize,
+    phase: usize,
+    flash: f32,
+}
+
+#[derive(Component)]
+struct BossBarRoot;
+
+#[derive(Component)]
+struct BossBarName;
+
+#[derive(Component)]
+struct BossBarTrack;
+
+#[derive(Component)]
+struct BossBarFill;
+
+#[derive(Component)]
+struct BossPhasePip {
+    index: usize,
+}
+
+fn setup_boss_bar(mut commands: Commands) {
+    commands.insert_resource(BossBarState::default());
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(12.0),
+                width: percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: px(4.0),
+                ..default()
+            },
+            GlobalZIndex(50),
+            Visibility::Hidden,
+            HudElement::default(),
+            BossBarRoot,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Text::new(""),
+                TextFont::from_font_size(20.0),
+                TextColor(Color::srgb(0.95, 0.9, 0.85)),
+                BossBarName,
+            ));
+            root.spawn((
+                Node {
+                    width: px(BAR_MIN_WIDTH),
+                    height: px(BAR_HEIGHT),
+                    border: UiRect::all(px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.05, 0.05)),
+                BorderColor::all(Color::srgb(0.05, 0.05, 0.05)),
+                BossBarTrack,
+            ))
+            .with_children(|track| {
+                track.spawn((
+                    Node {
+                        width: percent(100.0),
+                        height: percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.75, 0.1, 0.1)),
+                    BossBarFill,
+                ));
+            });
+            root.spawn(Node {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                column_gap: px(6.0),
+                ..default()
+            })
+            .with_children(|row| {
+                for index in 0..MAX_PHASE_PIPS {
+                    row.spawn((
+                        Node {
+                            width: px(PHASE_PIP_SIZE),
+                            height: px(PHASE_PIP_SIZE),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                        Visibility::Hidden,
+                        BossPhasePip { index },
+                    ));
+                }
+            });
+        });
+}
+
+fn handle_boss_events(
+    mut events: MessageReader<BossEncounterEvent>,
+    mut state: ResMut<BossBarState>,
+    mut name_query: Query<&mut Text, With<BossBarName>>,
+) {
+    for event in events.read() {
+        match event {
+            BossEncounterEvent::Started {
+                name,
+                max_health,
+                phases,
+            } => {
+                state.active = true;
+                state.max_health = max_health.max(1.0);
+                state.health = state.max_health;
+                state.phases = (*phases).clamp(1, MAX_PHASE_PIPS);
+                state.phase = 0;
+                state.flash = 0.0;
+                if let Ok(mut text) = name_query.single_mut() {
+                    text.0 = name.clone();
+                }
+            }
+            BossEncounterEvent::Damaged { health, phase } => {
+                if health < &state.health {
+                    state.flash = DAMAGE_FLASH_SECONDS;
+                }
+                state.health = health.clamp(0.0, state.max_health);
+                state.phase = (*phase).min(state.phases.saturating_sub(1));
+            }
+            BossEncounterEvent::Ended => {
+                state.active = false;
+            }
+        }
+    }
+}
+
+fn update_boss_bar(
+    time: Res<Time>,
+    mut state: ResMut<BossBarState>,
+    mut root_query: Query<&mut Visibility, (With<BossBarRoot>, Without<BossPhasePip>)>,
+    mut track_query: Query<&mut Node, (With<BossBarTrack>, Without<BossBarFill>)>,
+    mut fill_query: Query<(&mut Node, &mut BackgroundColor), With<BossBarFill>>,
+    mut pip_query: Query<
+        (&BossPhasePip, &mut Visibility, &mut BackgroundColor),
+        Without<BossBarFill>,
+    >,
+) {
+    let Ok(mut root_visibility) = root_query.single_mut() else {
+        return;
+    };
+    if !state.active {
+        *root_visibility = Visibility::Hidden;
+        return;
+    }
+    *root_visibility = Visibility::Visible;
+
+    state.flash = (state.flash - time.delta_secs()).max(0.0);
+
+    // Tougher bosses get a longer bar so difficulty reads at a glance.
+    if let Ok(mut track) = track_query.single_mut() {
+        let scale = (state.max_health / BAR_BASE_HEALTH).sqrt();
+        track.width = px((BAR_MIN_WIDTH * scale).clamp(BAR_MIN_WIDTH, BAR_MAX_WIDTH));
+    }
+
+    if let Ok((mut fill, mut color)) = fill_query.single_mut() {
+        let ratio = (state.health / state.max_health).clamp(0.0, 1.0);
+        fill.width = percent(ratio * 100.0);
+        let flash = (state.flash / DAMAGE_FLASH_SECONDS).clamp(0.0, 1.0);
+        let base = Color::srgb(0.75, 0.1, 0.1).mix(&Color::WHITE, flash);
+        color.0 = base;
+    }
+
+    for (pip, mut visibility, mut color) in &mut pip_query {
+        if pip.index >= state.phases {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        color.0 = if pip.index <= state.phase {
+            Color::srgb(0.9, 0.75, 0.2)
+        } else {
+            Color::srgb(0.3, 0.3, 0.3)
+        };
+    }
+}
+
+pub struct BossBarPlugin;
+
+impl Plugin for BossBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<BossEncounterEvent>()
+            .add_systems(Startup, setup_boss_bar)
+            .add_systems(Update, (handle_boss_events, update_boss_bar).chain());
+    }
+}
@@ -0,0 +1,386 @@
+// in-game bug report form: description, screenshot, lighting dump, seeds,
+// event log and system info bundled into a zip under reports/
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::InputSystems;
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{save_to_disk, Screenshot, ScreenshotCaptured};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::{env, fs, thread};
+
+use crate::event_log::EventLog;
+use crate::food::RandomSelectionConfig;
+use crate::light_dump::write_light_dump;
+use crate::locale::{Locale, LocalizedText};
+use crate::world::{WorldGrid, WorldSeed};
+
+const REPORTS_DIR: &str = "reports";
+const MAX_DESCRIPTION_CHARS: usize = 500;
+
+#[derive(Resource, Default)]
+pub struct BugReportForm {
+    pub open: bool,
+    description: String,
+    staging_dir: PathBuf,
+    // The overlay stays hidden until the screenshot is on disk, so it
+    // isn't in the picture.
+    screenshot_saved: bool,
+    // Opened from a menu, game time was already stopped and stays so.
+    was_paused: bool,
+}
+
+// Where the form takes its typing, ahead of the menus reading the keys, so
+// Escape cancels the form without also backing out of a menu.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BugReportInput;
+
+#[derive(Component)]
+struct BugReportOverlay;
+
+#[derive(Component)]
+struct BugReportText;
+
+fn setup_bug_report_form(mut commands: Commands) {
+    commands.insert_resource(BugReportForm::default());
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(0.0),
+                width: percent(100.0),
+                height: percent(100.0),
+                display: Display::Flex,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            GlobalZIndex(200),
+            Visibility::Hidden,
+            BugReportOverlay,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: px(560.0),
+                        padding: UiRect::all(px(16.0)),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        row_gap: px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.86, 0.86, 0.86)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(24.0),
+                        TextColor(Color::srgb(0.1, 0.1, 0.1)),
+                        LocalizedText("bug_report.title"),
+                    ));
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(18.0),
+                        TextColor(Color::srgb(0.15, 0.15, 0.15)),
+                        BugReportText,
+                    ));
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(14.0),
+                        TextColor(Color::srgb(0.35, 0.35, 0.35)),
+                        LocalizedText("bug_report.hint"),
+                    ));
+                });
+        });
+}
+
+fn report_stamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn open_bug_report(
+    commands: &mut Commands,
+    form: &mut BugReportForm,
+    time: &mut Time<Virtual>,
+    grid: &WorldGrid,
+) {
+    if form.open {
+        return;
+    }
+    form.open = true;
+    form.screenshot_saved = false;
+    form.was_paused = time.is_paused();
+    form.description.clear();
+    form.staging_dir = Path::new(REPORTS_DIR).join(format!("report-{}", report_stamp()));
+    if let Err(err) = fs::create_dir_all(&form.staging_dir) {
+        warn!("could not create {}: {err}", form.staging_dir.display());
+    }
+    let mut save = save_to_disk(form.staging_dir.join("screenshot.png"));
+    commands.spawn(Screenshot::primary_window()).observe(
+        move |captured: On<ScreenshotCaptured>, mut form: ResMut<BugReportForm>| {
+            save(captured);
+            form.screenshot_saved = true;
+        },
+    );
+    if let Err(err) = write_light_dump(grid, &form.staging_dir, true) {
+        warn!("could not dump lighting for the bug report: {err}");
+    }
+    time.pause();
+}
+
+fn toggle_bug_report(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut form: ResMut<BugReportForm>,
+    mut time: ResMut<Time<Virtual>>,
+    grid: Res<WorldGrid>,
+) {
+    if input.just_pressed(KeyCode::F8) {
+        open_bug_report(&mut commands, &mut form, &mut time, &grid);
+    }
+}
+
+// Runs right after input collection so typed text never reaches gameplay.
+fn bug_report_text_entry(
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut input: ResMut<ButtonInput<KeyCode>>,
+    mut form: ResMut<BugReportForm>,
+    mut time: ResMut<Time<Virtual>>,
+    seeds: (Res<WorldSeed>, Option<Res<RandomSelectionConfig>>),
+    event_log: Res<EventLog>,
+) {
+    if !form.open {
+        keyboard.clear();
+        return;
+    }
+
+    let mut submit = false;
+    let mut cancel = false;
+    for event in keyboard.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => submit = true,
+            Key::Escape => cancel = true,
+            Key::Backspace => {
+                form.description.pop();
+            }
+            _ => {
+                if let Some(text) = &event.text
+                    && form.description.chars().count() < MAX_DESCRIPTION_CHARS
+                {
+                    form.description.extend(text.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+    }
+    input.reset_all();
+
+    if submit {
+        let (world_seed, food_rng) = seeds;
+        let food_seed = food_rng.map(|config| config.seed);
+        write_report(&form, *world_seed, food_seed, &event_log);
+    }
+    if submit || cancel {
+        if cancel {
+            let _ = fs::remove_dir_all(&form.staging_dir);
+        }
+        form.open = false;
+        if !form.was_paused {
+            time.unpause();
+        }
+    }
+}
+
+fn update_bug_report_form(
+    form: Res<BugReportForm>,
+    locale: Res<Locale>,
+    mut overlay_query: Query<&mut Visibility, With<BugReportOverlay>>,
+    mut text_query: Query<&mut Text, With<BugReportText>>,
+) {
+    if !(form.is_changed() || locale.is_changed()) {
+        return;
+    }
+    if let Ok(mut visibility) = overlay_query.single_mut() {
+        *visibility = if form.open && form.screenshot_saved {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+    if let Ok(mut text) = text_query.single_mut() {
+        text.0 = locale.format("bug_report.description", &[("text", &form.description)]);
+    }
+}
+
+fn system_info() -> String {
+    let threads = thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1);
+    format!(
+        "game version: {}\nos: {}\narch: {}\ncpu threads: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        env::consts::OS,
+        env::consts::ARCH,
+        threads,
+    )
+}
+
+fn write_report(form: &BugReportForm, world_seed: WorldSeed, food_seed: Option<u64>, event_log: &EventLog) {
+    let mut report = String::new();
+    report.push_str("description:\n");
+    report.push_str(&form.description);
+    report.push_str("\n\nworld seed: ");
+    report.push_str(&world_seed.0.to_string());
+    report.push_str("\nfood seed: ");
+    report.push_str(&food_seed.map(|seed| seed.to_string()).unwrap_or_else(|| "unknown".into()));
+    // Sent before the screenshot came back, so the zip goes without it.
+    if !form.screenshot_saved {
+        report.push_str("\nscreenshot: not saved yet");
+    }
+    report.push_str("\n\n");
+    report.push_str(&system_info());
+    report.push_str("\nrecent events:\n");
+    for line in event_log.lines() {
+        report.push_str(line);
+        report.push('\n');
+    }
+
+    let mut entries = vec![("report.txt".to_string(), report.into_bytes())];
+    let attachments = ["screenshot.png", "brightness.png", "visibility.png", "light.csv"];
+    for name in attachments {
+        match fs::read(form.staging_dir.join(name)) {
+            Ok(bytes) => entries.push((name.to_string(), bytes)),
+            Err(err) => warn!("bug report has no {name}: {err}"),
+        }
+    }
+
+    let zip = build_stored_zip(&entries);
+    let zip_path = form.staging_dir.with_extension("zip");
+    match fs::write(&zip_path, &zip) {
+        Ok(()) => info!("bug report saved to {}", zip_path.display()),
+        Err(err) => {
+            warn!("could not write {}: {err}", zip_path.display());
+            return;
+        }
+    }
+    let _ = fs::remove_dir_all(&form.staging_dir);
+
+    if let Ok(endpoint) = env::var("BUG_REPORT_ENDPOINT") {
+        thread::spawn(move || {
+            if let Err(err) = post_report(&endpoint, &zip) {
+                warn!("could not send bug report to {endpoint}: {err}");
+            }
+        });
+    }
+}
+
+// Minimal zip writer: every entry is stored uncompressed.
+fn build_stored_zip(entries: &[(String, Vec<u8>)]) -> Vec<u8> {
+    const DOS_DATE_1980: u16 = 0x21;
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in entries {
+        let offset = out.len() as u32;
+        let crc = crc32fast::hash(data);
+        let size = data.len() as u32;
+
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&20u16.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&DOS_DATE_1980.to_le_bytes());
+        out.extend_from_slice(&crc.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&DOS_DATE_1980.to_le_bytes());
+        central.extend_from_slice(&crc.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central.extend_from_slice(&[0; 12]);
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    let central_size = central.len() as u32;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    out.extend_from_slice(&central_size.to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out
+}
+
+// Plain-HTTP POST; endpoints look like http://host:port/path.
+fn post_report(endpoint: &str, body: &[u8]) -> std::io::Result<()> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg.to_string());
+    let rest = endpoint
+        .strip_prefix("http://")
+        .ok_or_else(|| invalid("only http:// endpoints are supported"))?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+
+    let mut stream = TcpStream::connect(address)?;
+    let header = format!(
+        "POST {path} HTTP/1.1\r\nHost: {authority}\r\nContent-Type: application/zip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(header.as_bytes())?;
+    stream.write_all(body)?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let ok = response
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|status| status.starts_with('2'));
+    if ok {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(response.lines().next().unwrap_or("no response").to_string()))
+    }
+}
+
+pub struct BugReportPlugin;
+
+impl Plugin for BugReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_bug_report_form)
+            .add_systems(
+                PreUpdate,
+                bug_report_text_entry.in_set(BugReportInput).after(InputSystems),
+            )
+            .add_systems(Update, (toggle_bug_report, update_bug_report_form).chain());
+    }
+}
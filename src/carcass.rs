@@ -0,0 +1,177 @@
+// carcasses: what a dead animal leaves behind. Standing over one holding E
+// butchers it a piece of raw meat at a time, but the smell draws enemies
+// from around, so the longer it's worked the riskier it gets. Left alone it
+// rots away.
+use bevy::prelude::*;
+
+use crate::enemy::Enemy;
+use crate::event_log::EventLog;
+use crate::food::Location2D;
+use crate::food_catalog::FoodCatalog;
+use crate::interact::Interactable;
+use crate::items::{spawn_world_item, Inventory, ItemKind};
+use crate::light::Lightable;
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player};
+use crate::settings::{Action, GameSettings};
+use crate::world::{WorldRegenerated, WORLD_TILE_SIZE};
+
+const MEAT_KEY: &str = "Food:Raw Meat";
+const CARCASS_TINT: Color = Color::srgb(0.45, 0.12, 0.1);
+const BASE_SIZE: f32 = 5.0;
+const SIZE_PER_MEAT: f32 = 2.0;
+const HARVEST_RADIUS: f32 = 10.0;
+const HARVEST_SECONDS: f32 = 2.0;
+const ROT_SECONDS: f32 = 120.0;
+// How far the smell reaches, and how often it draws enemies in again.
+const SCENT_RADIUS: f32 = 80.0;
+const SCENT_SECONDS: f32 = 5.0;
+
+// On an animal: when killed, it leaves a carcass with this much meat.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LeavesCarcass {
+    pub meat: u32,
+}
+
+#[derive(Component)]
+pub struct Carcass {
+    pub meat: u32,
+    harvest_progress: f32,
+    rot_remaining: f32,
+    scent_timer: f32,
+}
+
+pub fn spawn_carcass(commands: &mut Commands, position: Vec2, meat: u32) {
+    if meat == 0 {
+        return;
+    }
+    let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+    commands.spawn((
+        Carcass {
+            meat,
+            harvest_progress: 0.0,
+            rot_remaining: ROT_SECONDS,
+            scent_timer: 0.0,
+        },
+        Interactable::new("interact.butcher", HARVEST_RADIUS * WORLD_TILE_SIZE),
+        Lightable {
+            tint: CARCASS_TINT,
+        },
+        Location2D {
+            x: tile.x,
+            y: tile.y,
+        },
+        Sprite::from_color(Color::WHITE, Vec2::splat(BASE_SIZE + SIZE_PER_MEAT * meat as f32)),
+        Visibility::Hidden,
+        Transform::from_translation(position.extend(0.5)),
+    ));
+}
+
+fn despawn_carcasses(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    carcass_query: Query<Entity, With<Carcass>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &carcass_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+// Meat goes into the bag, or onto the ground when there's no room.
+fn harvest_carcasses(
+    mut commands: Commands,
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    death_state: Res<DeathRespawnState>,
+    catalog: Res<FoodCatalog>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut carcass_query: Query<(Entity, &mut Carcass, &Location2D, &Transform), Without<Player>>,
+) {
+    if death_state.is_dead || !input.pressed(settings.key(Action::Interact)) {
+        return;
+    }
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let Some(meat) = ItemKind::from_save_key(MEAT_KEY, &catalog) else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let Some((entity, mut carcass, location, _)) = carcass_query
+        .iter_mut()
+        .filter(|(_, _, _, transform)| {
+            transform.translation.truncate().distance(player_pos) <= HARVEST_RADIUS * WORLD_TILE_SIZE
+        })
+        .min_by(|a, b| {
+            let distance = |transform: &Transform| transform.translation.truncate().distance(player_pos);
+            distance(a.3).total_cmp(&distance(b.3))
+        })
+    else {
+        return;
+    };
+    carcass.harvest_progress += time.delta_secs();
+    if carcass.harvest_progress < HARVEST_SECONDS {
+        return;
+    }
+    carcass.harvest_progress = 0.0;
+    carcass.meat -= 1;
+    if inventory.add_within_limit(meat, 1) > 0 {
+        spawn_world_item(&mut commands, meat, *location);
+    }
+    if carcass.meat == 0 {
+        commands.entity(entity).despawn();
+        event_log.push(&time, "butchered the carcass clean");
+    } else {
+        event_log.push(&time, "cut some meat from the carcass");
+    }
+}
+
+// Idle enemies within smelling distance come over to see; ones already
+// after something keep at it.
+fn rot_and_smell(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut carcass_query: Query<(Entity, &mut Carcass, &Transform)>,
+    mut enemy_query: Query<(&mut Enemy, &Transform), Without<Carcass>>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut carcass, transform) in &mut carcass_query {
+        carcass.rot_remaining -= dt;
+        if carcass.rot_remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        carcass.scent_timer -= dt;
+        if carcass.scent_timer > 0.0 {
+            continue;
+        }
+        carcass.scent_timer = SCENT_SECONDS;
+        let position = transform.translation.truncate();
+        for (mut enemy, enemy_transform) in &mut enemy_query {
+            if enemy.chasing || enemy.hunting.is_some() || enemy.investigating.is_some() {
+                continue;
+            }
+            if enemy_transform.translation.truncate().distance(position) <= SCENT_RADIUS * WORLD_TILE_SIZE {
+                enemy.investigate(position);
+            }
+        }
+    }
+}
+
+pub struct CarcassPlugin;
+
+impl Plugin for CarcassPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (despawn_carcasses, harvest_carcasses, rot_and_smell)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// character picker shown when a game starts, before the player is spawned
+use bevy::prelude::*;
+use std::env;
+
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::player::{FOOD_BAR_MAX, STATS_MAX};
+use crate::rules::GameRules;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CharacterKind {
+    Wanderer,
+    Scout,
+    Brute,
+}
+
+impl CharacterKind {
+    pub const ALL: [CharacterKind; 3] = [
+        CharacterKind::Wanderer,
+        CharacterKind::Scout,
+        CharacterKind::Brute,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CharacterKind::Wanderer => "Wanderer",
+            CharacterKind::Scout => "Scout",
+            CharacterKind::Brute => "Brute",
+        }
+    }
+
+    fn label_key(self) -> &'static str {
+        match self {
+            CharacterKind::Wanderer => "character.wanderer",
+            CharacterKind::Scout => "character.scout",
+            CharacterKind::Brute => "character.brute",
+        }
+    }
+
+    fn blurb_key(self) -> &'static str {
+        match self {
+            CharacterKind::Wanderer => "character.wanderer.blurb",
+            CharacterKind::Scout => "character.scout.blurb",
+            CharacterKind::Brute => "character.brute.blurb",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn profile(self) -> PlayerProfile {
+        match self {
+            CharacterKind::Wanderer => PlayerProfile {
+                kind: self,
+                sprite_sheet: "player.png",
+                health: STATS_MAX,
+                stamina: STATS_MAX,
+                food_bar: FOOD_BAR_MAX,
+                speed_factor: 1.0,
+            },
+            CharacterKind::Scout => PlayerProfile {
+                kind: self,
+                sprite_sheet: "player_scout.png",
+                health: 75.0,
+                stamina: STATS_MAX,
+                food_bar: 75.0,
+                speed_factor: 1.2,
+            },
+            CharacterKind::Brute => PlayerProfile {
+                kind: self,
+                sprite_sheet: "player_brute.png",
+                health: STATS_MAX,
+                stamina: 60.0,
+                food_bar: FOOD_BAR_MAX,
+                speed_factor: 0.8,
+            },
+        }
+    }
+}
+
+// The chosen character. The player is spawned once this is inserted, and
+// respawns restore these starting stats.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PlayerProfile {
+    pub kind: CharacterKind,
+    pub sprite_sheet: &'static str,
+    pub health: f32,
+    pub stamina: f32,
+    pub food_bar: f32,
+    pub speed_factor: f32,
+}
+
+#[derive(Resource)]
+struct CharacterPicker {
+    selected: usize,
+}
+
+#[derive(Component)]
+struct CharacterPickerRoot;
+
+#[derive(Component)]
+struct CharacterPickerText;
+
+fn setup_character_picker(mut commands: Commands, preset: Option<Res<PlayerProfile>>) {
+    // PlayerPlugin::with_profile already chose one, or this is a run being
+    // continued.
+    if preset.is_some() {
+        return;
+    }
+    // PLAYER_CHARACTER skips the picker, which is handy when iterating.
+    let character_key = "PLAYER_CHARACTER";
+    if let Some(kind) = env::var(character_key)
+        .ok()
+        .and_then(|value| CharacterKind::from_name(&value))
+    {
+        commands.insert_resource(kind.profile());
+        return;
+    }
+
+    commands.insert_resource(CharacterPicker { selected: 0 });
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(0.0),
+                width: percent(100.0),
+                height: percent(100.0),
+                display: Display::Flex,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.9)),
+            GlobalZIndex(150),
+            CharacterPickerRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont::from_font_size(26.0),
+                TextColor(Color::srgb(0.9, 0.85, 0.7)),
+                TextLayout::new_with_justify(Justify::Center),
+                CharacterPickerText,
+            ));
+        });
+}
+
+fn character_picker_input(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut picker: ResMut<CharacterPicker>,
+    mut rules: ResMut<GameRules>,
+    root_query: Query<Entity, With<CharacterPickerRoot>>,
+) {
+    let count = CharacterKind::ALL.len();
+    if input.just_pressed(KeyCode::ArrowLeft) || input.just_pressed(KeyCode::ArrowUp) {
+        picker.selected = (picker.selected + count - 1) % count;
+    }
+    if input.just_pressed(KeyCode::ArrowRight) || input.just_pressed(KeyCode::ArrowDown) {
+        picker.selected = (picker.selected + 1) % count;
+    }
+    let digits = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+    for (index, key) in digits.into_iter().enumerate().take(count) {
+        if input.just_pressed(key) {
+            picker.selected = index;
+        }
+    }
+
+    if input.just_pressed(KeyCode::Tab) {
+        rules.death_mode = rules.death_mode.toggled();
+    }
+    if input.just_pressed(KeyCode::KeyP) {
+        rules.keep_progress = !rules.keep_progress;
+    }
+
+    if !input.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    commands.insert_resource(CharacterKind::ALL[picker.selected].profile());
+    commands.remove_resource::<CharacterPicker>();
+    for entity in &root_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn update_character_picker(
+    picker: Res<CharacterPicker>,
+    rules: Res<GameRules>,
+    locale: Res<Locale>,
+    mut text_query: Query<&mut Text, With<CharacterPickerText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let mut lines = vec![locale.get("character.title").to_string(), String::new()];
+    for (index, kind) in CharacterKind::ALL.into_iter().enumerate() {
+        let profile = kind.profile();
+        let marker = if index == picker.selected { ">" } else { " " };
+        lines.push(locale.format(
+            "character.line",
+            &[
+                ("marker", marker),
+                ("number", &(index + 1).to_string()),
+                ("name", locale.get(kind.label_key())),
+                ("health", &format!("{:.0}", profile.health)),
+                ("stamina", &format!("{:.0}", profile.stamina)),
+                ("food", &format!("{:.0}", profile.food_bar)),
+                ("speed", &format!("{:.1}", profile.speed_factor)),
+            ],
+        ));
+    }
+    lines.push(String::new());
+    lines.push(locale.get(CharacterKind::ALL[picker.selected].blurb_key()).to_string());
+    lines.push(String::new());
+    let mode_key = format!("death_mode.{}", rules.death_mode.name().to_lowercase());
+    lines.push(locale.format("character.death_mode", &[("mode", locale.get(&mode_key))]));
+    let keep_key = if rules.keep_progress {
+        "character.progress_kept"
+    } else {
+        "character.progress_lost"
+    };
+    lines.push(locale.get(keep_key).to_string());
+    lines.push(locale.get("character.hint").to_string());
+    text.0 = lines.join("\n");
+}
+
+pub struct CharacterPlugin;
+
+impl Plugin for CharacterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Playing), setup_character_picker)
+            .add_systems(
+                Update,
+                (character_picker_input, update_character_picker)
+                    .chain()
+                    .distributive_run_if(resource_exists::<CharacterPicker>),
+            );
+    }
+}
@@ -0,0 +1,133 @@
+// day counter and sun/moon dial in the HUD. The marker goes round the dial
+// once a day, from the top at nightfall, and is a moon by night and a sun by
+// day. Dawn and dusk are announced on the narration line.
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::day_night::{DayNightCycle, DayPhaseChanged};
+use crate::hud::{HudCorner, HudLayout, HudWidget};
+use crate::locale::Locale;
+use crate::script::ScriptRunner;
+
+const DIAL_SIZE: f32 = 36.0;
+const DIAL_BORDER: f32 = 2.0;
+const MARKER_SIZE: f32 = 10.0;
+const NIGHT_SKY: Color = Color::srgba(0.08, 0.1, 0.25, 0.85);
+const DAY_SKY: Color = Color::srgba(0.45, 0.65, 0.9, 0.85);
+const MOON_COLOR: Color = Color::srgb(0.85, 0.88, 0.95);
+const SUN_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+
+#[derive(Component)]
+struct ClockDial;
+
+#[derive(Component)]
+struct ClockMarker;
+
+#[derive(Component)]
+struct ClockDayText;
+
+fn attach_clock(
+    mut commands: Commands,
+    layout: Res<HudLayout>,
+    corner_query: Query<(Entity, &HudCorner), Added<HudCorner>>,
+) {
+    let anchor = layout.anchor(HudWidget::Clock);
+    for (corner, _) in corner_query.iter().filter(|(_, corner)| corner.0 == anchor) {
+        commands.entity(corner).with_children(|corner| {
+            corner
+                .spawn(Node {
+                    align_items: AlignItems::Center,
+                    column_gap: px(6.0),
+                    ..default()
+                })
+                .with_children(|clock| {
+                    clock.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(16.0),
+                        TextColor(Color::srgb(0.9, 0.88, 0.8)),
+                        ClockDayText,
+                    ));
+                    clock
+                        .spawn((
+                            Node {
+                                width: px(DIAL_SIZE),
+                                height: px(DIAL_SIZE),
+                                border: UiRect::all(px(DIAL_BORDER)),
+                                border_radius: BorderRadius::MAX,
+                                ..default()
+                            },
+                            BackgroundColor(NIGHT_SKY),
+                            BorderColor::all(Color::srgb(0.6, 0.55, 0.4)),
+                            ClockDial,
+                        ))
+                        .with_child((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                width: px(MARKER_SIZE),
+                                height: px(MARKER_SIZE),
+                                border_radius: BorderRadius::MAX,
+                                ..default()
+                            },
+                            BackgroundColor(MOON_COLOR),
+                            ClockMarker,
+                        ));
+                });
+        });
+    }
+}
+
+fn update_clock(
+    cycle: Res<DayNightCycle>,
+    locale: Res<Locale>,
+    mut dial_query: Query<&mut BackgroundColor, (With<ClockDial>, Without<ClockMarker>)>,
+    mut marker_query: Query<(&mut Node, &mut BackgroundColor), With<ClockMarker>>,
+    mut text_query: Query<&mut Text, With<ClockDayText>>,
+) {
+    let night = cycle.is_night();
+    if let Ok(mut sky) = dial_query.single_mut() {
+        sky.set_if_neq(BackgroundColor(if night { NIGHT_SKY } else { DAY_SKY }));
+    }
+    if let Ok((mut node, mut color)) = marker_query.single_mut() {
+        // Inside the border, turning clockwise from the top.
+        let inner = DIAL_SIZE - DIAL_BORDER * 2.0;
+        let radius = (inner - MARKER_SIZE) * 0.5;
+        let angle = cycle.time_of_day() * TAU;
+        let position = Vec2::new(angle.sin(), -angle.cos()) * radius
+            + Vec2::splat((inner - MARKER_SIZE) * 0.5);
+        node.left = px(position.x);
+        node.top = px(position.y);
+        color.set_if_neq(BackgroundColor(if night { MOON_COLOR } else { SUN_COLOR }));
+    }
+    let label = locale.format("clock.day", &[("day", &cycle.day().to_string())]);
+    if let Ok(mut text) = text_query.single_mut()
+        && text.0 != label
+    {
+        text.0 = label;
+    }
+}
+
+fn announce_day_phase(
+    cycle: Res<DayNightCycle>,
+    locale: Res<Locale>,
+    mut phases: MessageReader<DayPhaseChanged>,
+    mut runner: ResMut<ScriptRunner>,
+) {
+    for phase in phases.read() {
+        let key = match phase {
+            DayPhaseChanged::Dawn => "clock.dawn",
+            DayPhaseChanged::Dusk => "clock.dusk",
+        };
+        runner.narrate(locale.format(key, &[("day", &cycle.day().to_string())]));
+    }
+}
+
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            ((attach_clock, update_clock).chain(), announce_day_phase),
+        );
+    }
+}
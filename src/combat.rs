@@ -0,0 +1,192 @@
+// melee attacks and damageable entities. Creatures attack with the same
+// events as the player; nobody can hurt their own side.
+use bevy::prelude::*;
+
+use crate::carcass::{spawn_carcass, LeavesCarcass};
+use crate::faction::{Faction, FactionRelations, Provoked, Relation};
+use crate::food::{FoodTracker, Location2D};
+use crate::food_catalog::FoodCatalog;
+use crate::light::facing_dir;
+use crate::loot::{drop_loot, LootTables};
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player, PlayerState, StaminaMessages, StaminaUse, Stats};
+use crate::settings::{Action, GameSettings};
+use crate::world::{WorldGrid, WORLD_TILE_SIZE};
+
+const ATTACK_RANGE: f32 = 26.0;
+const ATTACK_ARC_DEGREES: f32 = 100.0;
+const ATTACK_DAMAGE: f32 = 25.0;
+const ATTACK_COOLDOWN_SECONDS: f32 = 0.45;
+const ATTACK_FRAME_SECONDS: f32 = 0.15;
+const ATTACK_STAMINA_COST: f32 = 4.0;
+
+// Anything with Health can be hit by attacks and is despawned at zero.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+// Rolled from the named loot table and left on the ground where the entity
+// dies.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Loot {
+    pub table: &'static str,
+}
+
+// Attacks pass straight through anything with this on it, like a lurker
+// that's still hidden in the dark.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Shrouded;
+
+#[derive(Message, Debug, Clone, Copy)]
+pub struct AttackEvent {
+    pub attacker: Entity,
+    pub origin: Vec2,
+    pub direction: Vec2,
+    pub range: f32,
+    pub damage: f32,
+}
+
+#[derive(Component, Default)]
+pub struct AttackState {
+    cooldown: f32,
+    frame_timer: f32,
+}
+
+impl AttackState {
+    pub fn is_swinging(&self) -> bool {
+        self.frame_timer > 0.0
+    }
+}
+
+fn player_attack(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut attacks: MessageWriter<AttackEvent>,
+    mut stamina_messages: StaminaMessages,
+    mut query: Query<
+        (Entity, &Transform, &PlayerState, &mut AttackState, &mut Stats),
+        With<Player>,
+    >,
+) {
+    let Ok((entity, transform, state, mut attack, mut stats)) = query.single_mut() else {
+        return;
+    };
+    let dt = time.delta_secs();
+    attack.cooldown = (attack.cooldown - dt).max(0.0);
+    attack.frame_timer = (attack.frame_timer - dt).max(0.0);
+
+    let pressed = input.just_pressed(settings.key(Action::Attack));
+    if death_state.is_dead || attack.cooldown > 0.0 || !pressed {
+        return;
+    }
+    if !stats.try_spend_stamina(entity, ATTACK_STAMINA_COST, StaminaUse::Attack, &mut stamina_messages) {
+        return;
+    }
+    attack.cooldown = ATTACK_COOLDOWN_SECONDS;
+    attack.frame_timer = ATTACK_FRAME_SECONDS;
+    attacks.write(AttackEvent {
+        attacker: entity,
+        origin: transform.translation.truncate(),
+        direction: facing_dir(state.facing).as_vec2().normalize(),
+        range: ATTACK_RANGE * WORLD_TILE_SIZE,
+        damage: ATTACK_DAMAGE,
+    });
+}
+
+fn resolve_attacks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    grid: Res<WorldGrid>,
+    catalog: Res<FoodCatalog>,
+    mut food_tracker: ResMut<FoodTracker>,
+    mut attacks: MessageReader<AttackEvent>,
+    loot_tables: Option<Res<LootTables>>,
+    relations: Res<FactionRelations>,
+    faction_query: Query<&Faction>,
+    mut target_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Health,
+            Option<&Loot>,
+            Option<&Faction>,
+            Option<&LeavesCarcass>,
+        ),
+        Without<Shrouded>,
+    >,
+) {
+    let min_cos = (ATTACK_ARC_DEGREES.to_radians() * 0.5).cos();
+    for attack in attacks.read() {
+        let attacker_faction = faction_query.get(attack.attacker).ok().copied();
+        for (entity, transform, mut health, loot, faction, carcass) in &mut target_query {
+            if entity == attack.attacker || health.current <= 0.0 {
+                continue;
+            }
+            let relation = attacker_faction
+                .zip(faction.copied())
+                .map(|(attacker, target)| relations.relation(attacker, target));
+            if relation == Some(Relation::Friendly) {
+                continue;
+            }
+            let offset = transform.translation.truncate() - attack.origin;
+            let distance = offset.length();
+            if distance > attack.range {
+                continue;
+            }
+            let in_arc = distance <= f32::EPSILON || offset.dot(attack.direction) / distance >= min_cos;
+            if !in_arc {
+                continue;
+            }
+            health.current = (health.current - attack.damage).max(0.0);
+            if health.current > 0.0 {
+                if attacker_faction == Some(Faction::Player) && relation == Some(Relation::Neutral) {
+                    commands.entity(entity).insert(Provoked {
+                        remaining: relations.provoked_seconds,
+                    });
+                }
+                continue;
+            }
+            if let (Some(loot), Some(tables)) = (loot, loot_tables.as_deref()) {
+                let tile = (transform.translation.truncate() / WORLD_TILE_SIZE).floor().as_ivec2();
+                let drops = tables.roll(loot.table, &mut rand::rng());
+                drop_loot(
+                    &mut commands,
+                    &asset_server,
+                    &grid,
+                    &catalog,
+                    &mut food_tracker,
+                    &drops,
+                    Location2D { x: tile.x, y: tile.y },
+                );
+            }
+            if let Some(carcass) = carcass {
+                spawn_carcass(&mut commands, transform.translation.truncate(), carcass.meat);
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<AttackEvent>()
+            .add_systems(
+                Update,
+                (player_attack, resolve_attacks)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
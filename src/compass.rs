@@ -0,0 +1,151 @@
+// a small compass in the HUD whose needle points at the closest food the
+// player has seen, lit up now or remembered from earlier, until it's eaten
+// or rots away. It only shows with a compass in the inventory (crafted) or
+// the Wayfinder perk.
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use crate::food::{Food, FoodTracker, Location2D};
+use crate::hud::{HudCorner, HudLayout, HudWidget};
+use crate::items::{Inventory, ItemKind};
+use crate::player::Player;
+use crate::progress::{Perk, PersistentProgress};
+use crate::world::WORLD_TILE_SIZE;
+
+const DIAL_SIZE: f32 = 40.0;
+const NEEDLE_LENGTH: f32 = 28.0;
+const NEEDLE_WIDTH: f32 = 4.0;
+const DIAL_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.75);
+const NEEDLE_TIP_COLOR: Color = Color::srgb(0.9, 0.3, 0.25);
+const NEEDLE_TAIL_COLOR: Color = Color::srgb(0.85, 0.85, 0.8);
+
+// Food the player has seen and that may still be there.
+#[derive(Resource, Default)]
+struct FoodMemory {
+    seen: HashSet<Location2D>,
+}
+
+#[derive(Component)]
+struct CompassDial;
+
+#[derive(Component)]
+struct CompassNeedle;
+
+fn attach_compass(
+    mut commands: Commands,
+    layout: Res<HudLayout>,
+    corner_query: Query<(Entity, &HudCorner), Added<HudCorner>>,
+) {
+    let anchor = layout.anchor(HudWidget::Compass);
+    for (corner, _) in corner_query.iter().filter(|(_, corner)| corner.0 == anchor) {
+        commands.entity(corner).with_children(|corner| {
+            corner
+                .spawn((
+                    Node {
+                        width: px(DIAL_SIZE),
+                        height: px(DIAL_SIZE),
+                        display: Display::None,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(px(2.0)),
+                        border_radius: BorderRadius::MAX,
+                        ..default()
+                    },
+                    BackgroundColor(DIAL_COLOR),
+                    BorderColor::all(Color::srgb(0.6, 0.55, 0.4)),
+                    CompassDial,
+                ))
+                .with_children(|dial| {
+                    // Upright points up the screen; it turns about its centre.
+                    dial.spawn((
+                        Node {
+                            width: px(NEEDLE_WIDTH),
+                            height: px(NEEDLE_LENGTH),
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        UiTransform::default(),
+                        Visibility::Hidden,
+                        CompassNeedle,
+                    ))
+                    .with_children(|needle| {
+                        for color in [NEEDLE_TIP_COLOR, NEEDLE_TAIL_COLOR] {
+                            needle.spawn((
+                                Node {
+                                    width: percent(100.0),
+                                    height: percent(50.0),
+                                    ..default()
+                                },
+                                BackgroundColor(color),
+                            ));
+                        }
+                    });
+                });
+        });
+    }
+}
+
+// Forgets food that's gone, whether eaten, rotted or lost with the world.
+fn remember_food(
+    food_tracker: Res<FoodTracker>,
+    mut memory: ResMut<FoodMemory>,
+    food_query: Query<(&Location2D, &Visibility), With<Food>>,
+) {
+    memory.seen.retain(|location| food_tracker.contains(location));
+    for (location, visibility) in &food_query {
+        if matches!(*visibility, Visibility::Visible) {
+            memory.seen.insert(*location);
+        }
+    }
+}
+
+fn update_compass(
+    memory: Res<FoodMemory>,
+    progress: Res<PersistentProgress>,
+    player_query: Query<(&Transform, &Inventory), With<Player>>,
+    mut dial_query: Query<&mut Node, With<CompassDial>>,
+    mut needle_query: Query<(&mut UiTransform, &mut Visibility), With<CompassNeedle>>,
+) {
+    let Ok(mut dial) = dial_query.single_mut() else {
+        return;
+    };
+    let player = player_query.single().ok();
+    let unlocked = progress.has_perk(Perk::Wayfinder)
+        || player.is_some_and(|(_, inventory)| inventory.count(ItemKind::Compass) > 0);
+    let display = if unlocked { Display::Flex } else { Display::None };
+    if dial.display != display {
+        dial.display = display;
+    }
+    let Ok((mut transform, mut visibility)) = needle_query.single_mut() else {
+        return;
+    };
+    let heading = player.filter(|_| unlocked).and_then(|(player, _)| {
+        let player_pos = player.translation.truncate();
+        memory
+            .seen
+            .iter()
+            .map(|location| Vec2::new(location.x as f32, location.y as f32) * WORLD_TILE_SIZE)
+            .map(|food| food - player_pos)
+            .filter(|offset| *offset != Vec2::ZERO)
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+    });
+    // With nothing to point at the needle goes, rather than point anywhere.
+    let Some(heading) = heading else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+    // Turning clockwise from up the screen, as the world's y runs up too.
+    transform.rotation = Rot2::radians(heading.x.atan2(heading.y));
+}
+
+pub struct CompassPlugin;
+
+impl Plugin for CompassPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FoodMemory>().add_systems(
+            Update,
+            (attach_compass, remember_food, update_compass).chain(),
+        );
+    }
+}
@@ -0,0 +1,407 @@
+// crafting: recipes from recipes.ron turn gathered wood, stone and fiber
+// into tools, torches and campfires from a panel opened with K
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::{fmt, fs, path::Path, path::PathBuf};
+
+use crate::event_log::EventLog;
+use crate::food::Location2D;
+use crate::food_catalog::FoodCatalog;
+use crate::items::{spawn_world_item, Inventory, ItemKind};
+use crate::locale::{Locale, LocalizedText};
+use crate::menu::AppState;
+use crate::objectives::{ObjectiveGoal, ObjectiveProgress};
+use crate::player::{DeathRespawnState, Player};
+use crate::progress::PersistentProgress;
+use crate::settings::{Action, GameSettings};
+use crate::tooltip::Tooltip;
+use crate::world::WORLD_TILE_SIZE;
+
+pub const DEFAULT_RECIPES_FILE: &str = "assets/recipes.ron";
+const CRAFTABLE_COLOR: Color = Color::srgb(0.3, 0.34, 0.3);
+const UNMET_COLOR: Color = Color::srgb(0.18, 0.18, 0.19);
+const CRAFTABLE_TEXT: Color = Color::srgb(0.92, 0.92, 0.9);
+const UNMET_TEXT: Color = Color::srgb(0.45, 0.45, 0.45);
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct RecipeRecord {
+    output: String,
+    #[serde(default = "one")]
+    amount: u32,
+    inputs: Vec<(String, u32)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Recipe {
+    pub output: ItemKind,
+    pub amount: u32,
+    pub inputs: Vec<(ItemKind, u32)>,
+}
+
+impl Recipe {
+    pub fn can_craft(&self, inventory: &Inventory) -> bool {
+        self.inputs
+            .iter()
+            .all(|(kind, amount)| inventory.count(*kind) >= *amount)
+    }
+
+    fn label(&self, progress: &PersistentProgress, locale: &Locale) -> String {
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|(kind, amount)| format!("{} {amount}", kind.display_name(progress, locale)))
+            .collect();
+        let output = self.output.display_name(progress, locale);
+        format!("{output} x{}  ({})", self.amount, inputs.join(", "))
+    }
+
+    // What comes out, then each input against what's carried.
+    fn tooltip(
+        &self,
+        inventory: &Inventory,
+        catalog: &FoodCatalog,
+        progress: &PersistentProgress,
+        locale: &Locale,
+    ) -> String {
+        let mut lines = vec![self.output.describe(catalog, progress, locale)];
+        for (kind, amount) in &self.inputs {
+            let have = inventory.count(*kind);
+            lines.push(format!("{}: {have}/{amount}", kind.display_name(progress, locale)));
+        }
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug)]
+pub enum RecipeError {
+    Io(std::io::Error),
+    Parse(String),
+    UnknownItem(String),
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::Io(err) => write!(f, "could not read recipes: {err}"),
+            RecipeError::Parse(err) => write!(f, "could not parse recipes: {err}"),
+            RecipeError::UnknownItem(item) => write!(f, "recipes mention unknown item {item}"),
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default)]
+pub struct RecipeBook {
+    recipes: Vec<Recipe>,
+}
+
+impl RecipeBook {
+    // Items are named by their save keys, so recipes can use any food in
+    // the catalog.
+    pub fn load(path: &Path, catalog: &FoodCatalog) -> Result<Self, RecipeError> {
+        let text = fs::read_to_string(path).map_err(RecipeError::Io)?;
+        let records: Vec<RecipeRecord> =
+            ron::from_str(&text).map_err(|err| RecipeError::Parse(err.to_string()))?;
+        let item = |key: &str| {
+            ItemKind::from_save_key(key, catalog)
+                .ok_or_else(|| RecipeError::UnknownItem(key.to_string()))
+        };
+        let mut recipes = Vec::with_capacity(records.len());
+        for record in records {
+            let inputs = record
+                .inputs
+                .iter()
+                .map(|(key, amount)| Ok((item(key)?, *amount)))
+                .collect::<Result<Vec<_>, RecipeError>>()?;
+            recipes.push(Recipe {
+                output: item(&record.output)?,
+                amount: record.amount.max(1),
+                inputs,
+            });
+        }
+        Ok(Self { recipes })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Recipe> {
+        self.recipes.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct CraftingConfig {
+    pub recipes_path: PathBuf,
+}
+
+#[derive(Resource, Default)]
+pub struct CraftingScreen {
+    pub open: bool,
+}
+
+#[derive(Component)]
+struct CraftingOverlay;
+
+#[derive(Component)]
+struct RecipeButton(usize);
+
+#[derive(Component)]
+struct RecipeLabel(usize);
+
+// Needs the food catalog, so this waits for Startup rather than plugin
+// build. A missing or broken file leaves crafting empty rather than
+// refusing to start.
+fn load_recipe_book(
+    mut commands: Commands,
+    config: Res<CraftingConfig>,
+    catalog: Res<FoodCatalog>,
+) {
+    let book = match RecipeBook::load(&config.recipes_path, &catalog) {
+        Ok(book) => book,
+        Err(err) => {
+            warn!("no recipes from {}: {err}", config.recipes_path.display());
+            RecipeBook::default()
+        }
+    };
+    commands.insert_resource(book);
+}
+
+fn setup_crafting_screen(mut commands: Commands, book: Res<RecipeBook>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(0.0),
+                width: percent(100.0),
+                height: percent(100.0),
+                display: Display::Flex,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            GlobalZIndex(150),
+            Visibility::Hidden,
+            CraftingOverlay,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(px(16.0)),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        row_gap: px(6.0),
+                        min_width: px(280.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.12, 0.12, 0.13)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(24.0),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        LocalizedText("crafting.title"),
+                    ));
+                    if book.recipes.is_empty() {
+                        panel.spawn((
+                            Text::new(""),
+                            TextFont::from_font_size(16.0),
+                            TextColor(UNMET_TEXT),
+                            LocalizedText("crafting.empty"),
+                        ));
+                    }
+                    for index in 0..book.recipes.len() {
+                        panel
+                            .spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(px(10.0), px(6.0)),
+                                    ..default()
+                                },
+                                BackgroundColor(UNMET_COLOR),
+                                Tooltip::default(),
+                                RecipeButton(index),
+                            ))
+                            .with_child((
+                                Text::new(""),
+                                TextFont::from_font_size(16.0),
+                                TextColor(UNMET_TEXT),
+                                RecipeLabel(index),
+                            ));
+                    }
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(14.0),
+                        TextColor(Color::srgb(0.55, 0.55, 0.55)),
+                        LocalizedText("crafting.hint"),
+                    ));
+                });
+        });
+}
+
+fn toggle_crafting_screen(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    death_state: Res<DeathRespawnState>,
+    mut screen: ResMut<CraftingScreen>,
+) {
+    if death_state.is_dead {
+        screen.open = false;
+        return;
+    }
+    if input.just_pressed(settings.key(Action::Crafting)) {
+        screen.open = !screen.open;
+    } else if screen.open && input.just_pressed(KeyCode::Escape) {
+        screen.open = false;
+    }
+}
+
+// Whatever doesn't fit in the inventory is dropped at the player's feet.
+fn craft(
+    mut commands: Commands,
+    time: Res<Time>,
+    screen: Res<CraftingScreen>,
+    book: Res<RecipeBook>,
+    progress: Res<PersistentProgress>,
+    locale: Res<Locale>,
+    mut event_log: ResMut<EventLog>,
+    mut objectives: MessageWriter<ObjectiveProgress>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    button_query: Query<(&Interaction, &RecipeButton), Changed<Interaction>>,
+) {
+    if !screen.open {
+        return;
+    }
+    let Ok((transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    for (interaction, button) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(recipe) = book.get(button.0) else {
+            continue;
+        };
+        let name = recipe.output.display_name(&progress, &locale).to_lowercase();
+        if !recipe.can_craft(&inventory) {
+            event_log.push(&time, format!("not enough materials for {name}"));
+            continue;
+        }
+        for (kind, amount) in &recipe.inputs {
+            inventory.remove(*kind, *amount);
+        }
+        let left_over = inventory.add_within_limit(recipe.output, recipe.amount);
+        let tile = (transform.translation.truncate() / WORLD_TILE_SIZE).floor().as_ivec2();
+        for _ in 0..left_over {
+            spawn_world_item(&mut commands, recipe.output, Location2D { x: tile.x, y: tile.y });
+        }
+        event_log.push(&time, format!("crafted {name} x{}", recipe.amount));
+        objectives.write(ObjectiveProgress {
+            goal: ObjectiveGoal::Craft(recipe.output),
+            amount: recipe.amount,
+        });
+    }
+}
+
+// Recipes the player can't afford are greyed out.
+fn update_crafting_screen(
+    screen: Res<CraftingScreen>,
+    book: Res<RecipeBook>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    locale: Res<Locale>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut overlay_query: Query<&mut Visibility, With<CraftingOverlay>>,
+    mut button_query: Query<(&RecipeButton, &mut BackgroundColor, &mut Tooltip)>,
+    mut label_query: Query<(&RecipeLabel, &mut Text, &mut TextColor)>,
+) {
+    if let Ok(mut visibility) = overlay_query.single_mut() {
+        visibility.set_if_neq(if screen.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !screen.open {
+        return;
+    }
+    let Ok(inventory) = player_query.single() else {
+        return;
+    };
+    let craftable =
+        |index: usize| book.get(index).is_some_and(|recipe| recipe.can_craft(inventory));
+    for (button, mut color, mut tooltip) in &mut button_query {
+        let fill = if craftable(button.0) {
+            CRAFTABLE_COLOR
+        } else {
+            UNMET_COLOR
+        };
+        color.set_if_neq(BackgroundColor(fill));
+        let text = book
+            .get(button.0)
+            .map(|recipe| recipe.tooltip(inventory, &catalog, &progress, &locale))
+            .unwrap_or_default();
+        if tooltip.0 != text {
+            tooltip.0 = text;
+        }
+    }
+    for (label, mut text, mut color) in &mut label_query {
+        let line = book
+            .get(label.0)
+            .map(|recipe| recipe.label(&progress, &locale))
+            .unwrap_or_default();
+        if text.0 != line {
+            text.0 = line;
+        }
+        let fill = if craftable(label.0) {
+            CRAFTABLE_TEXT
+        } else {
+            UNMET_TEXT
+        };
+        color.set_if_neq(TextColor(fill));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CraftingPlugin {
+    pub recipes_path: PathBuf,
+}
+
+impl Default for CraftingPlugin {
+    fn default() -> Self {
+        Self {
+            recipes_path: PathBuf::from(DEFAULT_RECIPES_FILE),
+        }
+    }
+}
+
+impl CraftingPlugin {
+    pub fn with_recipes_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.recipes_path = path.into();
+        self
+    }
+}
+
+impl Plugin for CraftingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CraftingConfig {
+            recipes_path: self.recipes_path.clone(),
+        })
+        .insert_resource(CraftingScreen::default())
+        .add_systems(Startup, (load_recipe_book, setup_crafting_screen).chain())
+        .add_systems(
+            Update,
+            (toggle_crafting_screen, craft, update_crafting_screen)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
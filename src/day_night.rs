@@ -0,0 +1,116 @@
+// the day/night clock. A run starts at nightfall; each day is mostly night
+// with a shorter stretch of day. Only the clock lives here so far (the map
+// stays lantern-lit either way), but anything tied to the time of day can
+// read it or listen for dusk and dawn.
+use bevy::prelude::*;
+
+use crate::event_log::EventLog;
+use crate::player::DeathRespawnState;
+use crate::world::WorldRegenerated;
+
+const DEFAULT_DAY_SECONDS: f32 = 600.0;
+const DEFAULT_NIGHT_SHARE: f32 = 0.6;
+
+#[derive(Resource, Debug, Clone)]
+pub struct DayNightCycle {
+    // Length of a whole day, night included.
+    pub day_seconds: f32,
+    // How much of each day is night, from 0 to 1.
+    pub night_share: f32,
+    elapsed: f32,
+}
+
+impl DayNightCycle {
+    // 0.0 at nightfall, going round to 1.0 at the next one.
+    pub fn time_of_day(&self) -> f32 {
+        (self.elapsed / self.day_seconds.max(1.0)).fract()
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.time_of_day() < self.night_share
+    }
+
+    // Counting from 1; a new day starts at each nightfall.
+    pub fn day(&self) -> u32 {
+        (self.elapsed / self.day_seconds.max(1.0)) as u32 + 1
+    }
+
+    // Zero during the day.
+    pub fn seconds_until_dawn(&self) -> f32 {
+        ((self.night_share - self.time_of_day()) * self.day_seconds).max(0.0)
+    }
+}
+
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayPhaseChanged {
+    Dusk,
+    Dawn,
+}
+
+// The clock stops while the player is dead and starts over at nightfall
+// with each new world.
+fn advance_clock(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    mut cycle: ResMut<DayNightCycle>,
+    mut event_log: ResMut<EventLog>,
+    mut phases: MessageWriter<DayPhaseChanged>,
+) {
+    let was_night = cycle.is_night();
+    if regenerated.read().count() > 0 {
+        cycle.elapsed = 0.0;
+    } else if !death_state.is_dead {
+        cycle.elapsed += time.delta_secs();
+    }
+    match (was_night, cycle.is_night()) {
+        (true, false) => {
+            event_log.push(&time, "dawn breaks");
+            phases.write(DayPhaseChanged::Dawn);
+        }
+        (false, true) => {
+            event_log.push(&time, "night falls");
+            phases.write(DayPhaseChanged::Dusk);
+        }
+        _ => {}
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DayNightPlugin {
+    pub day_seconds: f32,
+    pub night_share: f32,
+}
+
+impl Default for DayNightPlugin {
+    fn default() -> Self {
+        Self {
+            day_seconds: DEFAULT_DAY_SECONDS,
+            night_share: DEFAULT_NIGHT_SHARE,
+        }
+    }
+}
+
+impl DayNightPlugin {
+    pub fn with_day_seconds(mut self, seconds: f32) -> Self {
+        self.day_seconds = seconds.max(1.0);
+        self
+    }
+
+    pub fn with_night_share(mut self, share: f32) -> Self {
+        self.night_share = share.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DayNightCycle {
+            day_seconds: self.day_seconds,
+            night_share: self.night_share,
+            elapsed: 0.0,
+        })
+        .add_message::<DayPhaseChanged>()
+        .add_systems(Update, advance_clock);
+    }
+}
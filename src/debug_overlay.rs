@@ -0,0 +1,166 @@
+// debug overlay: F3 shows frame rate, frame time, entity count, where the
+// player stands (tile and chunk), the light on that tile and how much food
+// is out, for tuning lighting and spawning while playing
+use bevy::diagnostic::{
+    DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+};
+use bevy::prelude::*;
+
+use crate::food::FoodTracker;
+use crate::player::Player;
+use crate::world::{WorldGrid, CHUNK_SIZE, WORLD_TILE_SIZE};
+
+const DEFAULT_TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+#[derive(Resource, Clone, Debug)]
+pub struct DebugOverlay {
+    pub shown: bool,
+    pub toggle_key: KeyCode,
+}
+
+#[derive(Component)]
+struct DebugOverlayRoot;
+
+#[derive(Component)]
+struct DebugOverlayText;
+
+// Top centre, clear of the HUD in the corners; under the menus.
+fn setup_debug_overlay(mut commands: Commands, overlay: Res<DebugOverlay>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(16.0),
+                width: percent(100.0),
+                justify_content: JustifyContent::Center,
+                display: if overlay.shown { Display::Flex } else { Display::None },
+                ..default()
+            },
+            GlobalZIndex(190),
+            DebugOverlayRoot,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(px(8.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+                ))
+                .with_child((
+                    Text::new(""),
+                    TextFont::from_font_size(14.0),
+                    TextColor(Color::srgb(0.7, 0.95, 0.7)),
+                    DebugOverlayText,
+                ));
+        });
+}
+
+fn toggle_debug_overlay(
+    input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+    mut root_query: Query<&mut Node, With<DebugOverlayRoot>>,
+) {
+    if !input.just_pressed(overlay.toggle_key) {
+        return;
+    }
+    overlay.shown = !overlay.shown;
+    if let Ok(mut node) = root_query.single_mut() {
+        node.display = if overlay.shown { Display::Flex } else { Display::None };
+    }
+}
+
+fn update_debug_overlay(
+    overlay: Res<DebugOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    grid: Option<Res<WorldGrid>>,
+    food_tracker: Option<Res<FoodTracker>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut text_query: Query<&mut Text, With<DebugOverlayText>>,
+) {
+    if !overlay.shown {
+        return;
+    }
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let smoothed = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(0.0)
+    };
+    let mut lines = vec![
+        format!(
+            "FPS {:.0}  ({:.1} ms)",
+            smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+            smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+        ),
+        format!("Entities {:.0}", smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT)),
+    ];
+    // Before a game starts there's no player or world to report on.
+    if let Ok(transform) = player_query.single() {
+        let tile = (transform.translation.truncate() / WORLD_TILE_SIZE).floor().as_ivec2();
+        let chunk = tile.div_euclid(IVec2::splat(CHUNK_SIZE as i32));
+        lines.push(format!("Tile {}, {}  chunk {}, {}", tile.x, tile.y, chunk.x, chunk.y));
+        if let Some(grid) = &grid
+            && grid.in_bounds(tile.x, tile.y)
+        {
+            let brightness = grid.brightness[tile.y as usize][tile.x as usize];
+            lines.push(format!("Brightness {brightness:.3}"));
+        }
+    }
+    if let Some(food_tracker) = &food_tracker {
+        lines.push(format!("Food {}", food_tracker.food_amount));
+    }
+    text.0 = lines.join("\n");
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugOverlayPlugin {
+    pub toggle_key: KeyCode,
+    // Starts with the overlay up.
+    pub shown: bool,
+}
+
+impl Default for DebugOverlayPlugin {
+    fn default() -> Self {
+        Self {
+            toggle_key: DEFAULT_TOGGLE_KEY,
+            shown: false,
+        }
+    }
+}
+
+impl DebugOverlayPlugin {
+    pub fn with_toggle_key(mut self, key: KeyCode) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    pub fn shown(mut self) -> Self {
+        self.shown = true;
+        self
+    }
+}
+
+// The diagnostics plugins may already be in the app, e.g. added alongside
+// DefaultPlugins for logging.
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+            app.add_plugins(EntityCountDiagnosticsPlugin::default());
+        }
+        app.insert_resource(DebugOverlay {
+            shown: self.shown,
+            toggle_key: self.toggle_key,
+        })
+        .add_systems(Startup, setup_debug_overlay)
+        .add_systems(Update, (toggle_debug_overlay, update_debug_overlay).chain());
+    }
+}
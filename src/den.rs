@@ -0,0 +1,207 @@
+// dens and nests: landmarks that breed their creature every so often, up to
+// a few at a time, while the player is around. They can be smashed like
+// anything with health; a destroyed den breeds nothing more, though whatever
+// already came out of it stays.
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::combat::{Health, Loot};
+use crate::enemy::{spawn_enemy, Enemy};
+use crate::event_log::EventLog;
+use crate::food::Location2D;
+use crate::landmark::Den;
+use crate::light::is_lit;
+use crate::player::{DeathRespawnState, Player};
+use crate::progress::XpGained;
+use crate::wildlife::{spawn_animal, Animal, AnimalKind};
+use crate::world::{WorldGrid, WorldRegenerated, WORLD_TILE_SIZE};
+
+const DEFAULT_MAX_INHABITANTS: usize = 3;
+const DEFAULT_SPAWN_SECONDS: f32 = 40.0;
+// Dens further than this from the player lie dormant.
+const ACTIVE_RADIUS: f32 = 120.0;
+const SHADE_STRENGTH: f32 = 1.0;
+
+impl Den {
+    fn name(self) -> &'static str {
+        match self {
+            Den::Shade => "shade den",
+            Den::Rabbit => "rabbit nest",
+        }
+    }
+
+    fn health(self) -> f32 {
+        match self {
+            Den::Shade => 150.0,
+            Den::Rabbit => 40.0,
+        }
+    }
+
+    fn loot_table(self) -> &'static str {
+        match self {
+            Den::Shade => "den",
+            Den::Rabbit => "nest",
+        }
+    }
+
+    fn xp(self) -> u32 {
+        match self {
+            Den::Shade => 40,
+            Den::Rabbit => 0,
+        }
+    }
+}
+
+// What a den has bred and is still alive, and when it breeds next.
+#[derive(Component)]
+pub struct DenBrood {
+    pub inhabitants: Vec<Entity>,
+    timer: Timer,
+}
+
+#[derive(Resource)]
+pub struct DenConfig {
+    pub max_inhabitants: usize,
+    pub spawn_seconds: f32,
+}
+
+// Dens the player has hurt, so a den despawned by its last hit can be told
+// apart from one cleared away with the old world.
+#[derive(Resource, Default)]
+struct DamagedDens(HashMap<Entity, Den>);
+
+fn set_up_dens(
+    mut commands: Commands,
+    config: Res<DenConfig>,
+    den_query: Query<(Entity, &Den), Added<Den>>,
+) {
+    for (entity, den) in &den_query {
+        commands.entity(entity).insert((
+            DenBrood {
+                inhabitants: Vec::new(),
+                timer: Timer::new(
+                    Duration::from_secs_f32(config.spawn_seconds.max(0.1)),
+                    TimerMode::Repeating,
+                ),
+            },
+            Health::new(den.health()),
+            Loot {
+                table: den.loot_table(),
+            },
+        ));
+    }
+}
+
+// Shades only crawl out while the den is dark.
+fn breed(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    death_state: Res<DeathRespawnState>,
+    config: Res<DenConfig>,
+    player_query: Query<&Transform, With<Player>>,
+    alive_query: Query<(), Or<(With<Enemy>, With<Animal>)>>,
+    mut den_query: Query<(&Den, &mut DenBrood, &Location2D, &Transform)>,
+) {
+    if death_state.is_dead {
+        return;
+    }
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (den, mut brood, location, transform) in &mut den_query {
+        brood.inhabitants.retain(|entity| alive_query.contains(*entity));
+        let position = transform.translation.truncate();
+        if position.distance(player_pos) > ACTIVE_RADIUS * WORLD_TILE_SIZE
+            || !brood.timer.tick(time.delta()).just_finished()
+            || brood.inhabitants.len() >= config.max_inhabitants
+        {
+            continue;
+        }
+        let inhabitant = match den {
+            Den::Shade if is_lit(&grid, location.x, location.y) => continue,
+            Den::Shade => spawn_enemy(&mut commands, position, SHADE_STRENGTH),
+            Den::Rabbit => spawn_animal(&mut commands, AnimalKind::Rabbit, position),
+        };
+        brood.inhabitants.push(inhabitant);
+    }
+}
+
+fn track_damaged_dens(
+    mut regenerated: MessageReader<WorldRegenerated>,
+    mut damaged: ResMut<DamagedDens>,
+    den_query: Query<(Entity, &Den, &Health), Changed<Health>>,
+) {
+    if regenerated.read().count() > 0 {
+        damaged.0.clear();
+    }
+    for (entity, den, health) in &den_query {
+        if health.current < health.max {
+            damaged.0.insert(entity, *den);
+        }
+    }
+}
+
+fn report_destroyed_dens(
+    time: Res<Time>,
+    mut removed: RemovedComponents<Den>,
+    mut damaged: ResMut<DamagedDens>,
+    mut xp: MessageWriter<XpGained>,
+    mut event_log: ResMut<EventLog>,
+) {
+    for entity in removed.read() {
+        let Some(den) = damaged.0.remove(&entity) else {
+            continue;
+        };
+        event_log.push(&time, format!("destroyed a {}", den.name()));
+        if den.xp() > 0 {
+            xp.write(XpGained {
+                amount: den.xp(),
+                reason: "destroyed a den",
+            });
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DenPlugin {
+    pub max_inhabitants: usize,
+    pub spawn_seconds: f32,
+}
+
+impl Default for DenPlugin {
+    fn default() -> Self {
+        Self {
+            max_inhabitants: DEFAULT_MAX_INHABITANTS,
+            spawn_seconds: DEFAULT_SPAWN_SECONDS,
+        }
+    }
+}
+
+impl DenPlugin {
+    pub fn with_max_inhabitants(mut self, max_inhabitants: usize) -> Self {
+        self.max_inhabitants = max_inhabitants;
+        self
+    }
+
+    pub fn with_spawn_seconds(mut self, seconds: f32) -> Self {
+        self.spawn_seconds = seconds;
+        self
+    }
+}
+
+impl Plugin for DenPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DenConfig {
+            max_inhabitants: self.max_inhabitants,
+            spawn_seconds: self.spawn_seconds,
+        })
+        .init_resource::<DamagedDens>()
+        .add_systems(
+            Update,
+            (set_up_dens, breed, track_damaged_dens, report_destroyed_dens).chain(),
+        );
+    }
+}
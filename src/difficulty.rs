@@ -0,0 +1,186 @@
+// run-wide threat level that only ever goes up, and the difficulty curve
+// the spawn director follows. Curves come from difficulty.ron by preset
+// name, so harder presets or a New Game+ run only need a different curve.
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt, fs, path::Path, path::PathBuf};
+
+use crate::world::WorldRegenerated;
+
+pub const DEFAULT_DIFFICULTY_FILE: &str = "assets/difficulty.ron";
+pub const DEFAULT_DIFFICULTY_PRESET: &str = "normal";
+const THREAT_DRAIN_STEP: f32 = 0.15;
+
+#[derive(Resource, Default)]
+pub struct Difficulty {
+    pub threat_level: u32,
+}
+
+impl Difficulty {
+    pub fn raise_threat(&mut self) {
+        self.threat_level += 1;
+    }
+
+    pub fn drain_multiplier(&self) -> f32 {
+        1.0 + THREAT_DRAIN_STEP * self.threat_level as f32
+    }
+}
+
+// Points of (minutes into the run, value), joined by straight lines and held
+// flat past either end.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct CurvePoints(pub Vec<(f32, f32)>);
+
+impl CurvePoints {
+    pub fn sample(&self, minutes: f32) -> f32 {
+        let points = &self.0;
+        let Some(&(first_at, first)) = points.first() else {
+            return 1.0;
+        };
+        if minutes <= first_at {
+            return first;
+        }
+        for pair in points.windows(2) {
+            let ((from_at, from), (to_at, to)) = (pair[0], pair[1]);
+            if minutes <= to_at {
+                let span = (to_at - from_at).max(f32::EPSILON);
+                return from.lerp(to, (minutes - from_at) / span);
+            }
+        }
+        points.last().map_or(first, |&(_, value)| value)
+    }
+}
+
+// Spawn budget scales how often enemies spawn and how many can be about;
+// strength scales the health and damage of new spawns, and the multipliers
+// sit on top of that. Threat and the player's condition still nudge the
+// budget, which is capped at max_pressure.
+#[derive(Resource, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DifficultyCurve {
+    pub spawn_budget: CurvePoints,
+    pub strength: CurvePoints,
+    pub pressure_per_threat: f32,
+    pub max_pressure: f32,
+    // A player at zero health and food sees this share of the budget.
+    pub struggling_factor: f32,
+    pub health_multiplier: f32,
+    pub damage_multiplier: f32,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self {
+            spawn_budget: CurvePoints(vec![(0.0, 1.0), (20.0, 4.0)]),
+            strength: CurvePoints(vec![(0.0, 1.0), (40.0, 3.0)]),
+            pressure_per_threat: 0.25,
+            max_pressure: 4.0,
+            struggling_factor: 0.5,
+            health_multiplier: 1.0,
+            damage_multiplier: 1.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DifficultyError {
+    Io(std::io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for DifficultyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DifficultyError::Io(err) => write!(f, "could not read difficulty curves: {err}"),
+            DifficultyError::Parse(err) => write!(f, "could not parse difficulty curves: {err}"),
+        }
+    }
+}
+
+// Every curve in the file, by preset name, for switching between runs.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct DifficultyPresets {
+    curves: HashMap<String, DifficultyCurve>,
+}
+
+impl DifficultyPresets {
+    pub fn load(path: &Path) -> Result<Self, DifficultyError> {
+        let text = fs::read_to_string(path).map_err(DifficultyError::Io)?;
+        let curves = ron::from_str(&text).map_err(|err| DifficultyError::Parse(err.to_string()))?;
+        Ok(Self { curves })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DifficultyCurve> {
+        self.curves.get(name)
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct DifficultyConfig {
+    pub curve_path: PathBuf,
+    pub preset: String,
+}
+
+// An unknown preset or unreadable file leaves the built-in curve in place.
+fn load_difficulty_curve(mut commands: Commands, config: Res<DifficultyConfig>) {
+    let presets = match DifficultyPresets::load(&config.curve_path) {
+        Ok(presets) => presets,
+        Err(err) => {
+            warn!("no difficulty curves from {}: {err}", config.curve_path.display());
+            DifficultyPresets::default()
+        }
+    };
+    match presets.get(&config.preset) {
+        Some(curve) => commands.insert_resource(curve.clone()),
+        None => warn!("no difficulty preset named {}", config.preset),
+    }
+    commands.insert_resource(presets);
+}
+
+// A new world is a new run, so the threat starts over.
+fn reset_threat(mut regenerated: MessageReader<WorldRegenerated>, mut difficulty: ResMut<Difficulty>) {
+    if regenerated.read().count() > 0 {
+        *difficulty = Difficulty::default();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DifficultyPlugin {
+    pub curve_path: PathBuf,
+    pub preset: String,
+}
+
+impl Default for DifficultyPlugin {
+    fn default() -> Self {
+        Self {
+            curve_path: PathBuf::from(DEFAULT_DIFFICULTY_FILE),
+            preset: DEFAULT_DIFFICULTY_PRESET.to_string(),
+        }
+    }
+}
+
+impl DifficultyPlugin {
+    pub fn with_curve_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.curve_path = path.into();
+        self
+    }
+
+    pub fn with_preset(mut self, preset: impl Into<String>) -> Self {
+        self.preset = preset.into();
+        self
+    }
+}
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Difficulty::default())
+            .insert_resource(DifficultyCurve::default())
+            .insert_resource(DifficultyConfig {
+                curve_path: self.curve_path.clone(),
+                preset: self.preset.clone(),
+            })
+            .add_systems(Startup, load_difficulty_curve)
+            .add_systems(Update, reset_threat);
+    }
+}
@@ -0,0 +1,571 @@
+// hostile creatures that spawn out in the dark a little way from the player,
+// chase them along A* paths once they're close enough and hurt them on
+// contact. They notice the player by seeing them inside their vision cone,
+// which sneaking shortens, or by being right next to them, and wander over
+// to look into noises they hear. Left alone they hunt whatever their faction
+// is hostile to, such as wildlife. They move as a pack, spreading out
+// rather than piling up on the same spot. A spawn director
+// raises the pressure the longer a run goes on and eases off while the
+// player is hurt or hungry.
+use bevy::prelude::*;
+use rand::Rng;
+use std::time::Duration;
+
+use crate::combat::{AttackEvent, Health, Loot};
+use crate::difficulty::{Difficulty, DifficultyCurve};
+use crate::faction::{Faction, FactionRelations};
+use crate::flocking::Flocking;
+use crate::food::Location2D;
+use crate::light::{facing_dir, is_lit, is_visible_in_cone, LightConfig, Lightable};
+use crate::menu::AppState;
+use crate::noise::{heard_loudness, NoiseEvent};
+use crate::obstacle::Obstacle;
+use crate::pathfinding::{PathFollower, Pathfinder};
+use crate::player::{
+    solid_overlaps, DeathCause, DeathRespawnState, Facing, Player, PlayerState, RunSummary, Sneak,
+    Stats, FOOD_BAR_MAX, STATS_MAX,
+};
+use crate::world::{WorldGrid, WorldRegenerated, WORLD_TILE_SIZE};
+
+const DEFAULT_MAX_ENEMIES: usize = 4;
+const DEFAULT_SPAWN_SECONDS: f32 = 20.0;
+// Spawns land in a ring around the player, outside the light.
+const SPAWN_DISTANCE: (f32, f32) = (40.0, 80.0);
+const SPAWN_ATTEMPTS: usize = 10;
+// Spawns keep this far outside the lantern cone, since the cone swings
+// round smoothly and could otherwise catch a spawn on the next frame.
+const CONE_MARGIN_DEGREES: f32 = 30.0;
+const ENEMY_SIZE: f32 = 10.0;
+const ENEMY_HEALTH: f32 = 50.0;
+// Sight, in tiles; a chase only breaks off past CHASE_RADIUS.
+pub const VISION_RANGE: f32 = 35.0;
+pub const VISION_ANGLE_DEGREES: f32 = 70.0;
+pub const SNEAK_VISION_FACTOR: f32 = 0.6;
+const NOTICE_RADIUS: f32 = 8.0;
+const LOOK_SECONDS: (f32, f32) = (2.0, 5.0);
+const LOOK_TURN_SPEED: f32 = 1.5;
+const CHASE_RADIUS: f32 = 50.0;
+const CHASE_SPEED: f32 = 30.0;
+// Noises quieter than this where the enemy stands are ignored.
+const HEARING_THRESHOLD: f32 = 0.05;
+const INVESTIGATE_SPEED: f32 = 18.0;
+const INVESTIGATE_SECONDS: f32 = 12.0;
+const INVESTIGATE_ARRIVAL: f32 = 4.0;
+const REPLAN_SECONDS: f32 = 0.5;
+const CONTACT_RADIUS: f32 = 8.0;
+const CONTACT_DAMAGE: f32 = 10.0;
+const CONTACT_COOLDOWN_SECONDS: f32 = 1.0;
+// Enemies that keep their distance only back off once the player is closer
+// than this share of it, and then at this speed.
+const BACK_OFF_SHARE: f32 = 0.7;
+const BACK_OFF_SPEED: f32 = 20.0;
+const ENEMY_LOOT_TABLE: &str = "shade";
+const ENEMY_TINT: Color = Color::srgb(0.45, 0.15, 0.5);
+
+#[derive(Component)]
+pub struct Enemy {
+    pub chasing: bool,
+    pub contact_damage: f32,
+    // Where it's looking; idle enemies turn to look about every few seconds.
+    pub facing: Vec2,
+    look_target: Vec2,
+    look_timer: f32,
+    // The last noise it heard and is heading over to check.
+    pub investigating: Option<Vec2>,
+    // Some other creature it's chasing down instead of the player.
+    pub hunting: Option<Entity>,
+    investigate_timer: f32,
+    contact_cooldown: f32,
+}
+
+impl Enemy {
+    // Sends it over to check on something, giving up after a while.
+    pub fn investigate(&mut self, source: Vec2) {
+        self.investigating = Some(source);
+        self.investigate_timer = INVESTIGATE_SECONDS;
+    }
+}
+
+// Enemies with this hang back at about this many tiles from the player
+// instead of closing in.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct KeepDistance(pub f32);
+
+// Base numbers; the director scales them at runtime.
+#[derive(Resource)]
+pub struct EnemySpawnConfig {
+    pub max_enemies: usize,
+    pub spawn_seconds: f32,
+    pub timer: Timer,
+}
+
+// Pressure scales how often enemies spawn and how many can be about;
+// strength scales the health and damage of new spawns. Both follow the
+// difficulty curve. Bias is an outside nudge on pressure, e.g. from the LLM
+// director.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct SpawnDirector {
+    pub pressure: f32,
+    pub strength: f32,
+    pub bias: f32,
+}
+
+impl Default for SpawnDirector {
+    fn default() -> Self {
+        Self {
+            pressure: 1.0,
+            strength: 1.0,
+            bias: 1.0,
+        }
+    }
+}
+
+impl SpawnDirector {
+    pub fn max_enemies(&self, config: &EnemySpawnConfig) -> usize {
+        (config.max_enemies as f32 * self.pressure).round() as usize
+    }
+}
+
+fn update_director(
+    time: Res<Time>,
+    summary: Res<RunSummary>,
+    difficulty: Res<Difficulty>,
+    curve: Res<DifficultyCurve>,
+    mut director: ResMut<SpawnDirector>,
+    player_query: Query<&Stats, With<Player>>,
+) {
+    let minutes = (time.elapsed_secs() - summary.started_at).max(0.0) / 60.0;
+    let condition = player_query.single().map_or(1.0, |stats| {
+        (stats.health / STATS_MAX + stats.food_bar / FOOD_BAR_MAX) * 0.5
+    });
+    let ease = curve.struggling_factor.lerp(1.0, condition.clamp(0.0, 1.0));
+    let pressure = curve.spawn_budget.sample(minutes)
+        + difficulty.threat_level as f32 * curve.pressure_per_threat;
+    director.pressure = (pressure * ease * director.bias).min(curve.max_pressure);
+    director.strength = curve.strength.sample(minutes);
+}
+
+// Inside the lantern's reach and within the cone plus a margin.
+fn in_view_cone(offset: Vec2, facing: Vec2, range: f32, half_angle: f32) -> bool {
+    offset.length() <= range && offset.angle_to(facing).abs() <= half_angle
+}
+
+fn spawn_enemies(
+    mut commands: Commands,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    grid: Res<WorldGrid>,
+    light_config: Res<LightConfig>,
+    director: Res<SpawnDirector>,
+    curve: Res<DifficultyCurve>,
+    mut config: ResMut<EnemySpawnConfig>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    let interval = (config.spawn_seconds / director.pressure).max(0.1);
+    config.timer.set_duration(Duration::from_secs_f32(interval));
+    if death_state.is_dead || !config.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    if enemy_query.iter().count() >= director.max_enemies(&config) {
+        return;
+    }
+    let Ok((player_transform, state)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    if let Some(position) = find_dark_spawn(&grid, &light_config, player_pos, state.facing) {
+        let health = director.strength * curve.health_multiplier;
+        let damage = director.strength * curve.damage_multiplier;
+        spawn_scaled_enemy(&mut commands, position, health, damage);
+    }
+}
+
+// A clear, unlit spot in the spawn ring around the player, out of the
+// lantern's way; None if a few tries don't turn one up.
+pub fn find_dark_spawn(
+    grid: &WorldGrid,
+    light_config: &LightConfig,
+    player_pos: Vec2,
+    facing: Facing,
+) -> Option<Vec2> {
+    let facing = facing_dir(facing).as_vec2().normalize();
+    let range = light_config.max_range * WORLD_TILE_SIZE;
+    let half_angle = (light_config.view_angle_degrees * 0.5 + CONE_MARGIN_DEGREES).to_radians();
+    let mut rng = rand::rng();
+    (0..SPAWN_ATTEMPTS).find_map(|_| {
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        let distance = rng.random_range(SPAWN_DISTANCE.0..SPAWN_DISTANCE.1) * WORLD_TILE_SIZE;
+        let offset = Vec2::from_angle(angle) * distance;
+        let position = player_pos + offset;
+        let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+        let clear = grid.in_bounds(tile.x, tile.y)
+            && !is_lit(grid, tile.x, tile.y)
+            && !in_view_cone(offset, facing, range, half_angle)
+            && !solid_overlaps(grid, position, ENEMY_SIZE / 2.0);
+        clear.then_some(position)
+    })
+}
+
+// Also used for bosses' minions, so it doesn't check the spot is clear.
+pub fn spawn_enemy(commands: &mut Commands, position: Vec2, strength: f32) -> Entity {
+    spawn_scaled_enemy(commands, position, strength, strength)
+}
+
+// Like spawn_enemy, with health and damage scaled separately.
+pub fn spawn_scaled_enemy(
+    commands: &mut Commands,
+    position: Vec2,
+    health_scale: f32,
+    damage_scale: f32,
+) -> Entity {
+    let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+    commands.spawn((
+        Enemy {
+            chasing: false,
+            contact_damage: CONTACT_DAMAGE * damage_scale,
+            facing: Vec2::X,
+            look_target: Vec2::X,
+            look_timer: 0.0,
+            investigating: None,
+            investigate_timer: 0.0,
+            hunting: None,
+            contact_cooldown: 0.0,
+        },
+        PathFollower::new(REPLAN_SECONDS),
+        Health::new(ENEMY_HEALTH * health_scale),
+        Loot {
+            table: ENEMY_LOOT_TABLE,
+        },
+        Faction::Monster,
+        Flocking::default(),
+        Lightable { tint: ENEMY_TINT },
+        Location2D {
+            x: tile.x,
+            y: tile.y,
+        },
+        Sprite::from_color(Color::WHITE, Vec2::splat(ENEMY_SIZE)),
+        Visibility::Hidden,
+        Transform::from_translation(position.extend(0.75)),
+    ))
+    .id()
+}
+
+fn despawn_enemies(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    enemy_query: Query<Entity, With<Enemy>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &enemy_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+// An idle enemy goes to check the loudest noise it heard this frame; a
+// louder one later on draws it away again.
+fn hear_noises(
+    grid: Res<WorldGrid>,
+    mut noises: MessageReader<NoiseEvent>,
+    mut enemy_query: Query<(&mut Enemy, &Transform)>,
+) {
+    let noises: Vec<NoiseEvent> = noises.read().copied().collect();
+    if noises.is_empty() {
+        return;
+    }
+    for (mut enemy, transform) in &mut enemy_query {
+        if enemy.chasing {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let loudest = noises
+            .iter()
+            .map(|noise| (noise.position, heard_loudness(&grid, noise, position)))
+            .filter(|(_, loudness)| *loudness >= HEARING_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((source, _)) = loudest {
+            enemy.investigate(source);
+        }
+    }
+}
+
+// Enemies follow a planned path round walls and water, going straight for
+// the player when no path is known, and slide along whatever they still
+// bump into the same way animals do. The player comes first, then prey,
+// then noises.
+fn chase_player(
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    death_state: Res<DeathRespawnState>,
+    relations: Res<FactionRelations>,
+    mut pathfinder: ResMut<Pathfinder>,
+    player_query: Query<(&Transform, &Sneak), With<Player>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Enemy>>,
+    prey_query: Query<(Entity, &Transform, &Faction), (With<Health>, Without<Enemy>, Without<Player>)>,
+    mut enemy_query: Query<
+        (
+            &mut Enemy,
+            &Faction,
+            Option<&KeepDistance>,
+            Option<&mut Flocking>,
+            &mut PathFollower,
+            &mut Location2D,
+            &mut Transform,
+        ),
+        Without<Player>,
+    >,
+) {
+    let Ok((player_transform, sneak)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let dt = time.delta_secs();
+    let vision_range = if sneak.active {
+        VISION_RANGE * SNEAK_VISION_FACTOR
+    } else {
+        VISION_RANGE
+    };
+    let half_angle = (VISION_ANGLE_DEGREES * 0.5).to_radians();
+    let mut rng = rand::rng();
+    let radius = ENEMY_SIZE / 2.0;
+    let blocked = |candidate: Vec2| {
+        solid_overlaps(&grid, candidate, radius)
+            || obstacle_query.iter().any(|(obstacle_transform, obstacle)| {
+                let reach = obstacle.radius + radius;
+                obstacle_transform.translation.truncate().distance_squared(candidate) < reach * reach
+            })
+    };
+
+    for (
+        mut enemy,
+        faction,
+        keep_distance,
+        mut flocking,
+        mut follower,
+        mut location,
+        mut transform,
+    ) in &mut enemy_query
+    {
+        let position = transform.translation.truncate();
+        let distance = position.distance(player_pos);
+        let noticed = distance <= NOTICE_RADIUS * WORLD_TILE_SIZE
+            || is_visible_in_cone(player_pos, position, enemy.facing, vision_range, half_angle);
+        enemy.chasing = !death_state.is_dead
+            && distance <= CHASE_RADIUS * WORLD_TILE_SIZE
+            && (enemy.chasing || noticed);
+        if enemy.chasing {
+            enemy.investigating = None;
+        }
+        enemy.investigate_timer -= dt;
+        if let Some(source) = enemy.investigating
+            && (enemy.investigate_timer <= 0.0
+                || source.distance(position) <= INVESTIGATE_ARRIVAL * WORLD_TILE_SIZE)
+        {
+            // Arrived or gave up: look around from here.
+            enemy.investigating = None;
+            enemy.look_timer = 0.0;
+            follower.clear();
+        }
+        let prey_pos = |prey: Entity| {
+            prey_query
+                .get(prey)
+                .ok()
+                .map(|(_, prey_transform, _)| prey_transform.translation.truncate())
+        };
+        if enemy.chasing {
+            enemy.hunting = None;
+        } else if enemy.hunting.is_none() {
+            enemy.hunting = prey_query
+                .iter()
+                .filter(|(_, _, prey_faction)| relations.hostile(*faction, **prey_faction))
+                .map(|(prey, prey_transform, _)| (prey, prey_transform.translation.truncate()))
+                .filter(|(_, prey_pos)| {
+                    prey_pos.distance(position) <= NOTICE_RADIUS * WORLD_TILE_SIZE
+                        || is_visible_in_cone(*prey_pos, position, enemy.facing, VISION_RANGE, half_angle)
+                })
+                .min_by(|a, b| a.1.distance(position).total_cmp(&b.1.distance(position)))
+                .map(|(prey, _)| prey);
+        }
+        // Prey that died or got away is forgotten.
+        let hunted = enemy
+            .hunting
+            .and_then(prey_pos)
+            .filter(|prey_pos| prey_pos.distance(position) <= CHASE_RADIUS * WORLD_TILE_SIZE);
+        if hunted.is_none() {
+            enemy.hunting = None;
+        }
+        let (goal, speed) = if enemy.chasing {
+            (player_pos, CHASE_SPEED)
+        } else if let Some(prey_pos) = hunted {
+            (prey_pos, CHASE_SPEED)
+        } else if let Some(source) = enemy.investigating {
+            (source, INVESTIGATE_SPEED)
+        } else {
+            (position, 0.0)
+        };
+        if speed == 0.0 {
+            follower.clear();
+            enemy.look_timer -= dt;
+            if enemy.look_timer <= 0.0 {
+                let (min, max) = LOOK_SECONDS;
+                enemy.look_timer = rng.random_range(min..max);
+                enemy.look_target =
+                    Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU));
+            }
+            enemy.facing = enemy
+                .facing
+                .rotate_towards(enemy.look_target, LOOK_TURN_SPEED * dt);
+            continue;
+        }
+        let keep = keep_distance
+            .filter(|_| enemy.chasing)
+            .map(|keep| keep.0 * WORLD_TILE_SIZE);
+        if let Some(keep) = keep
+            && distance <= keep
+        {
+            // In range: hold still facing the player, or back off if too close.
+            enemy.facing = (player_pos - position).normalize_or(enemy.facing);
+            follower.clear();
+            if distance >= keep * BACK_OFF_SHARE {
+                continue;
+            }
+        } else if (enemy.chasing || enemy.hunting.is_some())
+            && goal.distance(position) <= CONTACT_RADIUS * WORLD_TILE_SIZE * 0.5
+        {
+            enemy.facing = (goal - position).normalize_or(enemy.facing);
+            continue;
+        }
+        let (heading, speed) = if keep.is_some_and(|keep| distance <= keep) {
+            ((position - player_pos).normalize_or_zero(), BACK_OFF_SPEED)
+        } else {
+            let waypoint = follower
+                .next_waypoint(&mut pathfinder, position, goal, dt)
+                .unwrap_or(goal);
+            let heading = (waypoint - position).normalize_or_zero();
+            enemy.facing = heading.normalize_or(enemy.facing);
+            let heading = flocking
+                .as_deref()
+                .map_or(heading, |flocking| flocking.steer(heading));
+            (heading, speed)
+        };
+        if let Some(flocking) = flocking.as_mut() {
+            flocking.heading = heading;
+        }
+        let step = heading * speed * WORLD_TILE_SIZE * dt;
+        let next = [step, Vec2::new(step.x, 0.0), Vec2::new(0.0, step.y)]
+            .into_iter()
+            .map(|step| position + step)
+            .find(|candidate| !blocked(*candidate));
+        let Some(next) = next else {
+            continue;
+        };
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+        let tile = (next / WORLD_TILE_SIZE).floor().as_ivec2();
+        location.set_if_neq(Location2D {
+            x: tile.x,
+            y: tile.y,
+        });
+    }
+}
+
+// Prey is bitten through the same attack events the player uses, so it
+// dies and drops its loot the usual way.
+fn contact_damage(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut summary: ResMut<RunSummary>,
+    mut attacks: MessageWriter<AttackEvent>,
+    mut player_query: Query<(&Transform, &mut Stats), With<Player>>,
+    prey_query: Query<&Transform, (With<Health>, Without<Enemy>, Without<Player>)>,
+    mut enemy_query: Query<(Entity, &mut Enemy, &Transform), Without<Player>>,
+) {
+    let dt = time.delta_secs();
+    let Ok((player_transform, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (entity, mut enemy, transform) in &mut enemy_query {
+        enemy.contact_cooldown = (enemy.contact_cooldown - dt).max(0.0);
+        if enemy.contact_cooldown > 0.0 {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        if let Some(prey_transform) = enemy.hunting.and_then(|prey| prey_query.get(prey).ok()) {
+            let offset = prey_transform.translation.truncate() - position;
+            if offset.length() <= CONTACT_RADIUS * WORLD_TILE_SIZE {
+                enemy.contact_cooldown = CONTACT_COOLDOWN_SECONDS;
+                attacks.write(AttackEvent {
+                    attacker: entity,
+                    origin: position,
+                    direction: offset.normalize_or(Vec2::X),
+                    range: CONTACT_RADIUS * WORLD_TILE_SIZE,
+                    damage: enemy.contact_damage,
+                });
+            }
+            continue;
+        }
+        if death_state.is_dead || position.distance(player_pos) > CONTACT_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        enemy.contact_cooldown = CONTACT_COOLDOWN_SECONDS;
+        stats.health = (stats.health - enemy.contact_damage).max(0.0);
+        summary.last_damage = Some(DeathCause::Enemy);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EnemyPlugin {
+    pub max_enemies: usize,
+    pub spawn_seconds: f32,
+}
+
+impl Default for EnemyPlugin {
+    fn default() -> Self {
+        Self {
+            max_enemies: DEFAULT_MAX_ENEMIES,
+            spawn_seconds: DEFAULT_SPAWN_SECONDS,
+        }
+    }
+}
+
+impl EnemyPlugin {
+    pub fn with_max_enemies(mut self, max_enemies: usize) -> Self {
+        self.max_enemies = max_enemies;
+        self
+    }
+
+    pub fn with_spawn_seconds(mut self, seconds: f32) -> Self {
+        self.spawn_seconds = seconds;
+        self
+    }
+}
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EnemySpawnConfig {
+            max_enemies: self.max_enemies,
+            spawn_seconds: self.spawn_seconds.max(0.1),
+            timer: Timer::new(
+                Duration::from_secs_f32(self.spawn_seconds.max(0.1)),
+                TimerMode::Repeating,
+            ),
+        })
+        .init_resource::<SpawnDirector>()
+        .add_systems(
+            Update,
+            (
+                despawn_enemies,
+                update_director,
+                spawn_enemies,
+                hear_noises,
+                chase_player,
+                contact_damage,
+            )
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,33 @@
+// rolling log of notable gameplay events, attached to bug reports
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+const EVENT_LOG_CAPACITY: usize = 64;
+
+#[derive(Resource, Default)]
+pub struct EventLog {
+    lines: VecDeque<String>,
+}
+
+impl EventLog {
+    pub fn push(&mut self, time: &Time, line: impl Into<String>) {
+        let line = format!("[{:>8.2}] {}", time.elapsed_secs(), line.into());
+        info!("{line}");
+        if self.lines.len() == EVENT_LOG_CAPACITY {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &String> {
+        self.lines.iter()
+    }
+}
+
+pub struct EventLogPlugin;
+
+impl Plugin for EventLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EventLog::default());
+    }
+}
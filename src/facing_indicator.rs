@@ -0,0 +1,99 @@
+// a small arrow just outside the player sprite pointing the way the player
+// faces, which is where the lantern turns to. With diagonal input it's easy
+// to lose track of that from the sprite alone. It's part of the HUD, so it
+// goes when the HUD is hidden.
+use bevy::prelude::*;
+use bevy::sprite_render::AlphaMode2d;
+
+use crate::hud::HudElement;
+use crate::light::facing_dir;
+use crate::player::{DeathRespawnState, Player, PlayerState};
+use crate::world::{PLAYER_SIZE, WORLD_TILE_SIZE};
+
+// From the player's centre to the arrow's, just clear of the sprite.
+const INDICATOR_DISTANCE: f32 = PLAYER_SIZE * 0.5 + 4.0;
+const INDICATOR_LENGTH: f32 = 5.0;
+const INDICATOR_WIDTH: f32 = 6.0;
+const INDICATOR_Z: f32 = 0.05;
+const INDICATOR_COLOR: Color = Color::srgba(1.0, 0.95, 0.75, 0.45);
+
+#[derive(Component)]
+struct FacingIndicator;
+
+#[derive(Resource)]
+struct FacingIndicatorAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+// The mesh points up, with its base on the origin.
+fn setup_facing_indicator(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let half_width = INDICATOR_WIDTH * 0.5;
+    let mesh = meshes.add(Triangle2d::new(
+        Vec2::new(0.0, INDICATOR_LENGTH),
+        Vec2::new(-half_width, 0.0),
+        Vec2::new(half_width, 0.0),
+    ));
+    let material = materials.add(ColorMaterial {
+        color: INDICATOR_COLOR,
+        alpha_mode: AlphaMode2d::Blend,
+        ..default()
+    });
+    commands.insert_resource(FacingIndicatorAssets { mesh, material });
+}
+
+fn attach_facing_indicator(
+    mut commands: Commands,
+    assets: Res<FacingIndicatorAssets>,
+    player_query: Query<Entity, Added<Player>>,
+) {
+    for player in &player_query {
+        commands.entity(player).with_child((
+            FacingIndicator,
+            Mesh2d(assets.mesh.clone()),
+            MeshMaterial2d(assets.material.clone()),
+            Transform::from_xyz(0.0, 0.0, INDICATOR_Z),
+            Visibility::Inherited,
+            HudElement::default(),
+        ));
+    }
+}
+
+fn update_facing_indicator(
+    death_state: Res<DeathRespawnState>,
+    player_query: Query<(&PlayerState, &Children), With<Player>>,
+    mut indicator_query: Query<(&mut Transform, &mut Visibility), With<FacingIndicator>>,
+) {
+    for (state, children) in &player_query {
+        let dir = facing_dir(state.facing).as_vec2().normalize();
+        for child in children.iter() {
+            let Ok((mut transform, mut visibility)) = indicator_query.get_mut(child) else {
+                continue;
+            };
+            let offset = dir * INDICATOR_DISTANCE * WORLD_TILE_SIZE;
+            transform.translation = offset.extend(INDICATOR_Z);
+            transform.rotation =
+                Quat::from_rotation_z(dir.to_angle() - std::f32::consts::FRAC_PI_2);
+            visibility.set_if_neq(if death_state.is_dead {
+                Visibility::Hidden
+            } else {
+                Visibility::Inherited
+            });
+        }
+    }
+}
+
+pub struct FacingIndicatorPlugin;
+
+impl Plugin for FacingIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_facing_indicator).add_systems(
+            Update,
+            (attach_facing_indicator, update_facing_indicator).chain(),
+        );
+    }
+}
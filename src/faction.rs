@@ -0,0 +1,122 @@
+// who is hostile to whom. Every creature belongs to a faction, and a
+// symmetric relation matrix decides whether two factions fight, ignore each
+// other or are on the same side. Monsters hunt wildlife, wildlife runs from
+// anything hostile that's stronger than it, and creatures that are neutral
+// to the player turn on them for a while once hit.
+use bevy::prelude::*;
+
+use crate::combat::Health;
+
+const FACTION_COUNT: usize = 4;
+const DEFAULT_PROVOKED_SECONDS: f32 = 20.0;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Faction {
+    Player,
+    Wildlife,
+    Monster,
+    Npc,
+}
+
+impl Faction {
+    fn index(self) -> usize {
+        match self {
+            Faction::Player => 0,
+            Faction::Wildlife => 1,
+            Faction::Monster => 2,
+            Faction::Npc => 3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Friendly,
+    Neutral,
+    Hostile,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct FactionRelations {
+    matrix: [[Relation; FACTION_COUNT]; FACTION_COUNT],
+    pub provoked_seconds: f32,
+}
+
+impl Default for FactionRelations {
+    fn default() -> Self {
+        let mut relations = Self {
+            matrix: [[Relation::Neutral; FACTION_COUNT]; FACTION_COUNT],
+            provoked_seconds: DEFAULT_PROVOKED_SECONDS,
+        };
+        for faction in [Faction::Player, Faction::Wildlife, Faction::Monster, Faction::Npc] {
+            relations.set(faction, faction, Relation::Friendly);
+        }
+        relations.set(Faction::Monster, Faction::Player, Relation::Hostile);
+        relations.set(Faction::Monster, Faction::Wildlife, Relation::Hostile);
+        relations.set(Faction::Monster, Faction::Npc, Relation::Hostile);
+        relations
+    }
+}
+
+impl FactionRelations {
+    pub fn relation(&self, a: Faction, b: Faction) -> Relation {
+        self.matrix[a.index()][b.index()]
+    }
+
+    pub fn set(&mut self, a: Faction, b: Faction, relation: Relation) {
+        self.matrix[a.index()][b.index()] = relation;
+        self.matrix[b.index()][a.index()] = relation;
+    }
+
+    pub fn hostile(&self, a: Faction, b: Faction) -> bool {
+        self.relation(a, b) == Relation::Hostile
+    }
+}
+
+// On a creature the player has hit; it wears off after a while.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Provoked {
+    pub remaining: f32,
+}
+
+// How tough a creature is when sizing up a fight.
+pub fn strength(health: &Health) -> f32 {
+    health.max
+}
+
+fn calm_down(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut provoked_query: Query<(Entity, &mut Provoked)>,
+) {
+    for (entity, mut provoked) in &mut provoked_query {
+        provoked.remaining -= time.delta_secs();
+        if provoked.remaining <= 0.0 {
+            commands.entity(entity).remove::<Provoked>();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FactionPlugin {
+    pub relations: FactionRelations,
+}
+
+impl FactionPlugin {
+    pub fn with_relation(mut self, a: Faction, b: Faction, relation: Relation) -> Self {
+        self.relations.set(a, b, relation);
+        self
+    }
+
+    pub fn with_provoked_seconds(mut self, seconds: f32) -> Self {
+        self.relations.provoked_seconds = seconds.max(0.0);
+        self
+    }
+}
+
+impl Plugin for FactionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.relations.clone())
+            .add_systems(Update, calm_down);
+    }
+}
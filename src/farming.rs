@@ -0,0 +1,224 @@
+// crops grown from seeds: planted in front of the player, they grow faster
+// in the light and are harvested with E once ripe
+use bevy::prelude::*;
+
+use crate::event_log::EventLog;
+use crate::food::{FoodTracker, Location2D};
+use crate::food_catalog::{FoodCatalog, FoodItem};
+use crate::interact::Interactable;
+use crate::items::{spawn_world_item, Inventory, ItemKind};
+use crate::light::{facing_dir, is_lit, Lightable};
+use crate::menu::AppState;
+use crate::obstacle::Obstacle;
+use crate::player::{solid_overlaps, DeathRespawnState, Player, PlayerState};
+use crate::settings::{Action, GameSettings};
+use crate::world::{WorldGrid, WorldRegenerated, WORLD_TILE_SIZE};
+
+const PLANT_REACH: f32 = 16.0;
+const CROP_SPACING: f32 = 10.0;
+const CROP_HARVEST_RADIUS: f32 = 20.0;
+const STAGE_SECONDS: f32 = 60.0;
+const LIT_GROWTH_FACTOR: f32 = 3.0;
+const CROP_YIELD: u32 = 2;
+const CROP_SIZES: [f32; 3] = [4.0, 7.0, 10.0];
+const SPROUT_TINT: Color = Color::srgb(0.35, 0.65, 0.3);
+
+#[derive(Message, Clone, Copy)]
+pub struct PlantSeeds {
+    pub food: FoodItem,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CropStage {
+    Sprout,
+    Growing,
+    Ripe,
+}
+
+impl CropStage {
+    fn next(self) -> Self {
+        match self {
+            CropStage::Sprout => CropStage::Growing,
+            CropStage::Growing | CropStage::Ripe => CropStage::Ripe,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            CropStage::Sprout => 0,
+            CropStage::Growing => 1,
+            CropStage::Ripe => 2,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Crop {
+    pub food: FoodItem,
+    pub stage: CropStage,
+    pub growth: f32,
+}
+
+fn crop_tint(catalog: &FoodCatalog, crop: &Crop) -> Color {
+    let ripe = catalog.get(crop.food).map_or(Color::WHITE, |def| def.tint);
+    match crop.stage {
+        CropStage::Sprout => SPROUT_TINT,
+        CropStage::Growing => SPROUT_TINT.mix(&ripe, 0.5),
+        CropStage::Ripe => ripe,
+    }
+}
+
+fn plant_seeds(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    food_tracker: Res<FoodTracker>,
+    mut plantings: MessageReader<PlantSeeds>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &PlayerState, &mut Inventory), With<Player>>,
+    crop_query: Query<&Location2D, With<Crop>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Player>>,
+) {
+    let Ok((transform, state, mut inventory)) = player_query.single_mut() else {
+        plantings.clear();
+        return;
+    };
+    for planting in plantings.read() {
+        let dir = facing_dir(state.facing).as_vec2().normalize();
+        let target = transform.translation.truncate() + dir * PLANT_REACH * WORLD_TILE_SIZE;
+        let location = Location2D {
+            x: (target.x / WORLD_TILE_SIZE).floor() as i32,
+            y: (target.y / WORLD_TILE_SIZE).floor() as i32,
+        };
+        let too_close = |other: Vec2| other.distance_squared(target) < CROP_SPACING * CROP_SPACING;
+        let tile_center = |location: &Location2D| {
+            Vec2::new(location.x as f32, location.y as f32) * WORLD_TILE_SIZE
+        };
+        let blocked = !grid.in_bounds(location.x, location.y)
+            || solid_overlaps(&grid, target, CROP_SPACING / 2.0)
+            || crop_query.iter().any(|crop| too_close(tile_center(crop)))
+            || food_tracker.query_radius(target, CROP_SPACING).next().is_some()
+            || obstacle_query.iter().any(|(obstacle_transform, obstacle)| {
+                let reach = obstacle.radius + CROP_SPACING / 2.0;
+                obstacle_transform.translation.truncate().distance_squared(target) < reach * reach
+            });
+        if blocked {
+            event_log.push(&time, "can't plant there");
+            continue;
+        }
+        if !inventory.remove(ItemKind::Seeds(planting.food), 1) {
+            continue;
+        }
+
+        commands.spawn((
+            Crop {
+                food: planting.food,
+                stage: CropStage::Sprout,
+                growth: 0.0,
+            },
+            Interactable::new("interact.harvest", CROP_HARVEST_RADIUS * WORLD_TILE_SIZE).disabled(),
+            Lightable { tint: SPROUT_TINT },
+            location,
+            Sprite::from_color(Color::WHITE, Vec2::splat(CROP_SIZES[0])),
+            Visibility::Hidden,
+            Transform::from_translation(tile_center(&location).extend(0.9)),
+        ));
+        event_log.push(&time, format!("planted {} seeds", planting.food.name.to_lowercase()));
+    }
+}
+
+fn grow_crops(
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    catalog: Res<FoodCatalog>,
+    mut crop_query: Query<(
+        &mut Crop,
+        &Location2D,
+        &mut Lightable,
+        &mut Sprite,
+        &mut Interactable,
+    )>,
+) {
+    let dt = time.delta_secs();
+    for (mut crop, location, mut lightable, mut sprite, mut interactable) in &mut crop_query {
+        if crop.stage == CropStage::Ripe {
+            continue;
+        }
+        let rate = if is_lit(&grid, location.x, location.y) {
+            LIT_GROWTH_FACTOR
+        } else {
+            1.0
+        };
+        crop.growth += dt * rate;
+        if crop.growth < STAGE_SECONDS {
+            continue;
+        }
+        crop.growth = 0.0;
+        crop.stage = crop.stage.next();
+        interactable.enabled = crop.stage == CropStage::Ripe;
+        lightable.tint = crop_tint(&catalog, &crop);
+        sprite.custom_size = Some(Vec2::splat(CROP_SIZES[crop.stage.index()]));
+    }
+}
+
+fn harvest_crops(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    crop_query: Query<(Entity, &Crop, &Location2D, &Transform), Without<Player>>,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::Interact)) {
+        return;
+    }
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    for (entity, crop, location, transform) in &crop_query {
+        if crop.stage != CropStage::Ripe {
+            continue;
+        }
+        let distance = transform.translation.truncate().distance(player_pos);
+        if distance > CROP_HARVEST_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        // Whatever doesn't fit is left on the ground where the crop stood.
+        let kind = ItemKind::Food(crop.food);
+        let left_over = inventory.add_within_limit(kind, CROP_YIELD);
+        for _ in 0..left_over {
+            spawn_world_item(&mut commands, kind, *location);
+        }
+        event_log.push(&time, format!("harvested {}", crop.food.name.to_lowercase()));
+        commands.entity(entity).despawn();
+    }
+}
+
+fn clear_crops(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    crop_query: Query<Entity, With<Crop>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &crop_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub struct FarmingPlugin;
+
+impl Plugin for FarmingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<PlantSeeds>().add_systems(
+            Update,
+            (clear_crops, plant_seeds, grow_crops, harvest_crops)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,212 @@
+// fishing from the edge of a pond: E casts a line onto the water, and E
+// again while the bobber dips lands a fish. Too early, too late or walking
+// off and it gets away.
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::event_log::EventLog;
+use crate::food::{Location2D, RandomSelectionConfig};
+use crate::food_catalog::FoodCatalog;
+use crate::items::{spawn_world_item, Inventory, ItemKind};
+use crate::light::{facing_dir, Lightable};
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player, PlayerState};
+use crate::settings::{Action, GameSettings};
+use crate::world::{WorldGrid, WorldRegenerated, WORLD_TILE_SIZE};
+
+const CAST_REACH: f32 = 20.0;
+const BITE_WAIT_SECONDS: (f32, f32) = (2.0, 6.0);
+const BITE_WINDOW_SECONDS: f32 = 0.6;
+const WALK_OFF_DISTANCE: f32 = 4.0;
+const BOBBER_SIZE: f32 = 3.0;
+const BOBBER_DIP: f32 = 1.0;
+const BOBBER_TINT: Color = Color::srgb(0.95, 0.3, 0.25);
+const BITE_TINT: Color = Color::srgb(1.0, 0.9, 0.4);
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum FishingState {
+    #[default]
+    Idle,
+    Waiting {
+        remaining: f32,
+    },
+    // The window in which E lands the fish.
+    Bite {
+        remaining: f32,
+    },
+}
+
+#[derive(Resource, Default)]
+pub struct Fishing {
+    pub state: FishingState,
+    cast_from: Vec2,
+}
+
+impl Fishing {
+    pub fn is_active(&self) -> bool {
+        self.state != FishingState::Idle
+    }
+}
+
+#[derive(Component)]
+struct Bobber {
+    rest: Vec2,
+}
+
+fn reel_in(
+    commands: &mut Commands,
+    fishing: &mut Fishing,
+    bobber_query: &Query<Entity, With<Bobber>>,
+) {
+    fishing.state = FishingState::Idle;
+    for entity in bobber_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn fish(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    grid: Res<WorldGrid>,
+    catalog: Res<FoodCatalog>,
+    mut rng: ResMut<RandomSelectionConfig>,
+    mut fishing: ResMut<Fishing>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &PlayerState, &mut Inventory), With<Player>>,
+    bobber_query: Query<Entity, With<Bobber>>,
+) {
+    let Ok((transform, state, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    if death_state.is_dead {
+        if fishing.is_active() {
+            reel_in(&mut commands, &mut fishing, &bobber_query);
+        }
+        return;
+    }
+    let position = transform.translation.truncate();
+    let pressed = input.just_pressed(settings.key(Action::Interact));
+    let dt = time.delta_secs();
+
+    if fishing.is_active() && position.distance(fishing.cast_from) > WALK_OFF_DISTANCE {
+        reel_in(&mut commands, &mut fishing, &bobber_query);
+        event_log.push(&time, "reeled in the line");
+        return;
+    }
+
+    match fishing.state {
+        FishingState::Idle => {
+            if !pressed {
+                return;
+            }
+            let dir = facing_dir(state.facing).as_vec2().normalize();
+            let target = position + dir * CAST_REACH * WORLD_TILE_SIZE;
+            let tile = (target / WORLD_TILE_SIZE).floor().as_ivec2();
+            if !grid.is_water(tile.x, tile.y) {
+                return;
+            }
+            let (min, max) = BITE_WAIT_SECONDS;
+            fishing.state = FishingState::Waiting {
+                remaining: rng.rng.random_range(min..max),
+            };
+            fishing.cast_from = position;
+            commands.spawn((
+                Bobber { rest: target },
+                Lightable { tint: BOBBER_TINT },
+                Location2D {
+                    x: tile.x,
+                    y: tile.y,
+                },
+                Sprite::from_color(Color::WHITE, Vec2::splat(BOBBER_SIZE)),
+                Visibility::Hidden,
+                Transform::from_translation(target.extend(0.9)),
+            ));
+            event_log.push(&time, "cast a line");
+        }
+        FishingState::Waiting { remaining } => {
+            if pressed {
+                reel_in(&mut commands, &mut fishing, &bobber_query);
+                event_log.push(&time, "pulled too early, nothing bit");
+                return;
+            }
+            fishing.state = if remaining <= dt {
+                FishingState::Bite {
+                    remaining: BITE_WINDOW_SECONDS,
+                }
+            } else {
+                FishingState::Waiting {
+                    remaining: remaining - dt,
+                }
+            };
+        }
+        FishingState::Bite { remaining } => {
+            if pressed {
+                reel_in(&mut commands, &mut fishing, &bobber_query);
+                let Some(def) = catalog.choose_catch(&mut rng.rng) else {
+                    event_log.push(&time, "nothing lives in these waters");
+                    return;
+                };
+                // No room means the fish flops onto the bank at the player's feet.
+                let kind = ItemKind::Food(def.item);
+                if inventory.add_within_limit(kind, 1) > 0 {
+                    let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+                    spawn_world_item(&mut commands, kind, Location2D { x: tile.x, y: tile.y });
+                }
+                event_log.push(&time, format!("caught a {}", def.item.name.to_lowercase()));
+                return;
+            }
+            if remaining <= dt {
+                reel_in(&mut commands, &mut fishing, &bobber_query);
+                event_log.push(&time, "the fish got away");
+            } else {
+                fishing.state = FishingState::Bite {
+                    remaining: remaining - dt,
+                };
+            }
+        }
+    }
+}
+
+// The bobber turns bright and dips under while a fish is biting.
+fn animate_bobber(
+    fishing: Res<Fishing>,
+    mut bobber_query: Query<(&Bobber, &mut Lightable, &mut Transform)>,
+) {
+    let biting = matches!(fishing.state, FishingState::Bite { .. });
+    for (bobber, mut lightable, mut transform) in &mut bobber_query {
+        let (tint, dip) = if biting {
+            (BITE_TINT, BOBBER_DIP)
+        } else {
+            (BOBBER_TINT, 0.0)
+        };
+        lightable.tint = tint;
+        transform.translation.y = bobber.rest.y - dip * WORLD_TILE_SIZE;
+    }
+}
+
+fn stop_fishing_on_regenerate(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    mut fishing: ResMut<Fishing>,
+    bobber_query: Query<Entity, With<Bobber>>,
+) {
+    if regenerated.read().count() > 0 {
+        reel_in(&mut commands, &mut fishing, &bobber_query);
+    }
+}
+
+pub struct FishingPlugin;
+
+impl Plugin for FishingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Fishing::default()).add_systems(
+            Update,
+            (stop_fishing_on_regenerate, fish, animate_bobber)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,136 @@
+// boids-style steering for creatures that move in packs: each one keeps
+// clear of packmates right next to it, drifts towards the middle of the
+// ones nearby and lines up with where they're heading. Whatever moves the
+// creature blends the steering into its own heading, so a pack closing in
+// fans out round its target instead of piling onto one tile.
+use bevy::prelude::*;
+
+use crate::world::WORLD_TILE_SIZE;
+
+const DEFAULT_NEIGHBOUR_RADIUS: f32 = 20.0;
+const DEFAULT_SEPARATION_RADIUS: f32 = 8.0;
+const DEFAULT_SEPARATION_WEIGHT: f32 = 1.5;
+const DEFAULT_COHESION_WEIGHT: f32 = 0.3;
+const DEFAULT_ALIGNMENT_WEIGHT: f32 = 0.4;
+// Steering never outweighs the creature's own heading, so it still gets
+// where it's going.
+const MAX_STEERING: f32 = 0.8;
+
+#[derive(Component, Debug, Default)]
+pub struct Flocking {
+    // Added to the creature's heading when it moves.
+    pub steering: Vec2,
+    // Where it went last frame, for packmates to line up with.
+    pub heading: Vec2,
+}
+
+impl Flocking {
+    // The creature's own heading with the pack's pull mixed in.
+    pub fn steer(&self, heading: Vec2) -> Vec2 {
+        (heading + self.steering).normalize_or(heading)
+    }
+}
+
+// Radii are in tiles.
+#[derive(Resource, Clone, Debug)]
+pub struct FlockConfig {
+    pub neighbour_radius: f32,
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    pub cohesion_weight: f32,
+    pub alignment_weight: f32,
+}
+
+fn steer_flocks(config: Res<FlockConfig>, mut flock_query: Query<(Entity, &Transform, &mut Flocking)>) {
+    let members: Vec<(Entity, Vec2, Vec2)> = flock_query
+        .iter()
+        .map(|(entity, transform, flocking)| {
+            (entity, transform.translation.truncate(), flocking.heading)
+        })
+        .collect();
+    let neighbour_radius = config.neighbour_radius * WORLD_TILE_SIZE;
+    let separation_radius = config.separation_radius * WORLD_TILE_SIZE;
+    for (entity, transform, mut flocking) in &mut flock_query {
+        let position = transform.translation.truncate();
+        let mut separation = Vec2::ZERO;
+        let mut centre = Vec2::ZERO;
+        let mut alignment = Vec2::ZERO;
+        let mut neighbours = 0;
+        for &(other, other_pos, other_heading) in &members {
+            let offset = position - other_pos;
+            let distance = offset.length();
+            if other == entity || distance > neighbour_radius {
+                continue;
+            }
+            neighbours += 1;
+            centre += other_pos;
+            alignment += other_heading;
+            if distance < separation_radius {
+                // Harder the closer they are; stacked ones push apart any way.
+                let away = offset
+                    .try_normalize()
+                    .unwrap_or_else(|| Vec2::from_angle(entity.to_bits() as f32));
+                separation += away * (1.0 - distance / separation_radius);
+            }
+        }
+        if neighbours == 0 {
+            flocking.steering = Vec2::ZERO;
+            continue;
+        }
+        let cohesion = (centre / neighbours as f32 - position).normalize_or_zero();
+        let alignment = alignment.normalize_or_zero();
+        flocking.steering = (separation * config.separation_weight
+            + cohesion * config.cohesion_weight
+            + alignment * config.alignment_weight)
+            .clamp_length_max(MAX_STEERING);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FlockingPlugin {
+    pub neighbour_radius: f32,
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    pub cohesion_weight: f32,
+    pub alignment_weight: f32,
+}
+
+impl Default for FlockingPlugin {
+    fn default() -> Self {
+        Self {
+            neighbour_radius: DEFAULT_NEIGHBOUR_RADIUS,
+            separation_radius: DEFAULT_SEPARATION_RADIUS,
+            separation_weight: DEFAULT_SEPARATION_WEIGHT,
+            cohesion_weight: DEFAULT_COHESION_WEIGHT,
+            alignment_weight: DEFAULT_ALIGNMENT_WEIGHT,
+        }
+    }
+}
+
+impl FlockingPlugin {
+    pub fn with_radii(mut self, neighbour: f32, separation: f32) -> Self {
+        self.neighbour_radius = neighbour;
+        self.separation_radius = separation;
+        self
+    }
+
+    pub fn with_weights(mut self, separation: f32, cohesion: f32, alignment: f32) -> Self {
+        self.separation_weight = separation;
+        self.cohesion_weight = cohesion;
+        self.alignment_weight = alignment;
+        self
+    }
+}
+
+impl Plugin for FlockingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FlockConfig {
+            neighbour_radius: self.neighbour_radius,
+            separation_radius: self.separation_radius,
+            separation_weight: self.separation_weight,
+            cohesion_weight: self.cohesion_weight,
+            alignment_weight: self.alignment_weight,
+        })
+        .add_systems(Update, steer_flocks);
+    }
+}
@@ -1,20 +1,59 @@
 use bevy::prelude::*;
-use std::{env, time::Duration};
-use std::collections::HashSet;
+use std::{env, path::PathBuf, time::Duration};
+use std::collections::HashMap;
 use rand::{Rng, SeedableRng, rngs::StdRng};
 use crate::{
-    player::{DeathRespawnState, FOOD_BAR_MAX, Player, Stats},
-    world::{WorldGrid, HEIGHT, WIDTH, WORLD_TILE_SIZE},
+    event_log::EventLog,
+    food_catalog::{
+        load_food_catalog, FoodCatalog, FoodDef, FoodEffect, FoodItem, Rarity, DEFAULT_FOODS_FILE,
+    },
+    interact::Interactable,
+    items::{Inventory, ItemKind},
+    loot::{LootTables, GROUND_FOOD_TABLE},
+    menu::AppState,
+    player::{DeathRespawnState, FOOD_BAR_MAX, Player, RunSummary, Stats, STATS_MAX},
+    progress::{Perk, PersistentProgress, XpGained, FORAGER_FOOD_FACTOR},
+    status_effect::{StatusEffectKind, StatusEffects},
+    light::Lightable,
+    obstacle::Obstacle,
+    settings::{Action, GameSettings},
+    world::{
+        reachable_tiles, Biome, TilesChanged, WorldGrid, WorldRegenerated, WORLD_TILE_SIZE,
+    },
 };
 
-const X_SPAWN_GENERATION: i32 = HEIGHT as i32 - 32;
-const Y_SPAWN_GENERATION: i32 = WIDTH as i32 - 32;
+const SPAWN_EDGE_MARGIN: i32 = 32;
+const DEFAULT_MAX_FOOD: i32 = 5;
+const DEFAULT_SPAWN_SECONDS: f32 = 5.0;
 
 const MAX_SPAWN_ATTEMPTS: i32 = 10;
-const FOOD_PICKUP_RADIUS_TILES: i32 = 32;
-const LIGHT_MAX_BRIGHTNESS: f32 = 0.93;
-const MIN_LIGHT_THRESHOLD: f32 = 0.01;
-const MIN_DARKNESS_FACTOR: f32 = 0.12;
+const OBSTACLE_CLEARANCE: f32 = 4.0;
+pub const FOOD_PICKUP_RADIUS_TILES: i32 = 32;
+const DEFAULT_MAGNET_RADIUS_TILES: i32 = 10;
+// Side of the square buckets FoodTracker sorts food into.
+const FOOD_BUCKET_TILES: i32 = 16;
+const WELL_FED_THRESHOLD: f32 = 90.0;
+const WELL_FED_SECONDS: f32 = 30.0;
+const FOOD_XP: u32 = 5;
+const SEED_DROP_CHANCE: f64 = 0.35;
+// Food left lying around goes stale over its shelf life, giving less and
+// less back, then rots. Rotten food poisons whoever eats it and is cleared
+// away after FoodSpawnConfig::rotten_seconds more, blinking for the last
+// few so the player can see it going.
+const STALE_REGEN_FACTOR: f32 = 0.25;
+const DEFAULT_ROTTEN_SECONDS: f32 = 45.0;
+const EXPIRY_BLINK_SECONDS: f32 = 5.0;
+const EXPIRY_BLINK_PERIOD: f32 = 0.5;
+const EXPIRY_BLINK_ALPHA: f32 = 0.2;
+const ROTTEN_POISON_SECONDS: f32 = 10.0;
+const ROTTEN_TINT: Color = Color::srgb(0.45, 0.5, 0.25);
+// Quality odds shift from mostly poor near the spawn point to mostly fresh
+// at the far edge of the map; the shore nudges them up and rubble down.
+const QUALITY_NORMAL_WEIGHT: f32 = 0.5;
+const QUALITY_POOR_WEIGHT: (f32, f32) = (0.45, 0.1);
+const QUALITY_FRESH_WEIGHT: (f32, f32) = (0.05, 0.45);
+const QUALITY_TERRAIN_FACTOR: f32 = 1.5;
+const POOR_TINT: Color = Color::srgb(0.45, 0.42, 0.38);
 
 
 #[derive(Component)]
@@ -22,7 +61,111 @@ pub struct Food;
 
 #[derive(Component)]
 pub struct FoodStats {
+    pub food: FoodItem,
     pub food_bar_regen: f32,
+    pub health_regen: f32,
+    pub stamina_regen: f32,
+    pub effect: Option<FoodEffect>,
+}
+
+impl FoodStats {
+    fn meal(&self, food: Option<FoodItem>) -> EatFood {
+        EatFood {
+            food_bar_regen: self.food_bar_regen,
+            health_regen: self.health_regen,
+            stamina_regen: self.stamina_regen,
+            effect: self.effect,
+            food,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoodQuality {
+    Poor,
+    Normal,
+    Fresh,
+}
+
+impl FoodQuality {
+    pub fn name(self) -> &'static str {
+        match self {
+            FoodQuality::Poor => "poor",
+            FoodQuality::Normal => "normal",
+            FoodQuality::Fresh => "fresh",
+        }
+    }
+
+    pub fn regen_factor(self) -> f32 {
+        match self {
+            FoodQuality::Poor => 0.6,
+            FoodQuality::Normal => 1.0,
+            FoodQuality::Fresh => 1.4,
+        }
+    }
+
+    // Poor food looks dull, fresh food a little brighter.
+    pub fn tint(self, base: Color) -> Color {
+        match self {
+            FoodQuality::Poor => base.mix(&POOR_TINT, 0.4),
+            FoodQuality::Normal => base,
+            FoodQuality::Fresh => base.mix(&Color::WHITE, 0.2),
+        }
+    }
+
+    fn roll(grid: &WorldGrid, location: Location2D, rng: &mut StdRng) -> Self {
+        let tile = IVec2::new(location.x, location.y);
+        let center = (grid.center() / WORLD_TILE_SIZE).as_ivec2();
+        let half_extent = (grid.width.min(grid.height) / 2).max(1) as f32;
+        let far = (tile.as_vec2().distance(center.as_vec2()) / half_extent).clamp(0.0, 1.0);
+        let lerp = |(near, distant): (f32, f32)| near + (distant - near) * far;
+        let mut poor = lerp(QUALITY_POOR_WEIGHT);
+        let mut fresh = lerp(QUALITY_FRESH_WEIGHT);
+        match grid.biome(tile) {
+            Biome::Shore => fresh *= QUALITY_TERRAIN_FACTOR,
+            Biome::Rubble => poor *= QUALITY_TERRAIN_FACTOR,
+            Biome::Plain => {}
+        }
+        let roll = rng.random_range(0.0..poor + QUALITY_NORMAL_WEIGHT + fresh);
+        if roll < poor {
+            FoodQuality::Poor
+        } else if roll < poor + QUALITY_NORMAL_WEIGHT {
+            FoodQuality::Normal
+        } else {
+            FoodQuality::Fresh
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Freshness {
+    pub age: f32,
+    pub shelf_life: f32,
+}
+
+impl Freshness {
+    // 0 when fresh, 1 once rotten.
+    pub fn staleness(&self) -> f32 {
+        (self.age / self.shelf_life).clamp(0.0, 1.0)
+    }
+
+    pub fn is_rotten(&self) -> bool {
+        self.age >= self.shelf_life
+    }
+
+    // Seconds left on the ground before the food is cleared away.
+    pub fn time_left(&self, rotten_seconds: f32) -> f32 {
+        self.shelf_life + rotten_seconds - self.age
+    }
+
+    pub fn describe_key(&self) -> &'static str {
+        match self.staleness() {
+            s if s >= 1.0 => "freshness.rotten",
+            s if s >= 0.5 => "freshness.stale",
+            s if s >= 0.2 => "freshness.going_stale",
+            _ => "freshness.fresh",
+        }
+    }
 }
 
 #[derive(Component, Hash, Eq, PartialEq, Clone, Copy)]
@@ -31,53 +174,211 @@ pub struct Location2D {
     pub y: i32,
 }
 
-#[derive(Resource)]
+// Every food on the map by tile, with a coarse grid of buckets on top so
+// nearby food can be found without scanning it all.
+#[derive(Resource, Default)]
 pub struct FoodTracker {
-    food_spawn_location: HashSet<Location2D>,
+    food_spawn_location: HashMap<Location2D, Entity>,
+    buckets: HashMap<IVec2, Vec<Location2D>>,
     pub food_amount: i32,
 }
 
+// Read as food spawns, so anything (difficulty presets, debug tools) can
+// change it while the game runs.
 #[derive(Resource)]
 pub struct FoodSpawnConfig {
     pub timer: Timer,
+    pub max_food: i32,
+    // Timed spawns land at least this many tiles from the player.
+    pub min_player_distance: i32,
+    // Relative odds of timed spawns landing in each biome; biomes left out
+    // count as 1.
+    pub biome_weights: HashMap<Biome, f32>,
+    // Only spawn where the player can walk to, not in sealed-off pockets.
+    pub reachable_only: bool,
+    // How long rotten food lies around before it's removed.
+    pub rotten_seconds: f32,
+}
+
+impl FoodSpawnConfig {
+    pub fn set_spawn_interval(&mut self, seconds: f32) {
+        self.timer.set_duration(Duration::from_secs_f32(seconds.max(0.1)));
+    }
+
+    pub fn biome_weight(&self, biome: Biome) -> f32 {
+        self.biome_weights.get(&biome).copied().unwrap_or(1.0).max(0.0)
+    }
+
+    // The heaviest biome always accepts a spawn; the rest in proportion.
+    fn spawn_chance(&self, biome: Biome) -> f64 {
+        let heaviest = [Biome::Plain, Biome::Rubble, Biome::Shore]
+            .into_iter()
+            .map(|biome| self.biome_weight(biome))
+            .fold(0.0, f32::max);
+        if heaviest <= 0.0 {
+            return 0.0;
+        }
+        (self.biome_weight(biome) / heaviest) as f64
+    }
+}
+
+// Off by default: food is picked up with E.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PickupSettings {
+    // Walking near food collects it without pressing E.
+    pub auto_pickup: bool,
+    pub magnet_radius_tiles: i32,
+}
+
+// Tiles reachable from the player, worked out again on the next spawn after
+// the map changes.
+#[derive(Resource, Default)]
+struct ReachableTiles {
+    tiles: Option<Vec<Vec<bool>>>,
 }
 
 #[derive(Resource)]
 pub struct RandomSelectionConfig {
     pub rng: StdRng,
+    pub seed: u64,
+}
+
+// Eating from the inventory or straight off the ground.
+#[derive(Message, Clone, Copy)]
+pub struct EatFood {
+    pub food_bar_regen: f32,
+    pub health_regen: f32,
+    pub stamina_regen: f32,
+    pub effect: Option<FoodEffect>,
+    // What was eaten, for seeds; None for rotten food.
+    pub food: Option<FoodItem>,
+}
+
+impl EatFood {
+    pub fn from_def(def: &FoodDef) -> Self {
+        Self {
+            food_bar_regen: def.food_bar_regen,
+            health_regen: def.health_regen,
+            stamina_regen: def.stamina_regen,
+            effect: def.effect,
+            food: Some(def.item),
+        }
+    }
+}
+
+#[derive(Message, Clone, Copy)]
+pub struct SpawnFoodCache {
+    pub count: usize,
+    pub radius_tiles: i32,
 }
 
 impl FoodTracker {
+    fn bucket(location: &Location2D) -> IVec2 {
+        IVec2::new(location.x, location.y).div_euclid(IVec2::splat(FOOD_BUCKET_TILES))
+    }
+
     pub fn iter_locations(&self) -> impl Iterator<Item = &Location2D> {
-        self.food_spawn_location.iter()
+        self.food_spawn_location.keys()
+    }
+
+    pub fn contains(&self, location: &Location2D) -> bool {
+        self.food_spawn_location.contains_key(location)
+    }
+
+    // Food within `radius` (world units) of `position`, as (tile, entity).
+    pub fn query_radius(
+        &self,
+        position: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Location2D, Entity)> + '_ {
+        let to_bucket = |point: Vec2| {
+            let tile = (point / WORLD_TILE_SIZE).floor().as_ivec2();
+            Self::bucket(&Location2D {
+                x: tile.x,
+                y: tile.y,
+            })
+        };
+        let min = to_bucket(position - Vec2::splat(radius));
+        let max = to_bucket(position + Vec2::splat(radius));
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|bucket| self.buckets.get(&bucket))
+            .flatten()
+            .filter(move |location| {
+                let food = Vec2::new(location.x as f32, location.y as f32) * WORLD_TILE_SIZE;
+                food.distance_squared(position) <= radius * radius
+            })
+            .filter_map(|location| {
+                let entity = self.food_spawn_location.get(location)?;
+                Some((*location, *entity))
+            })
+    }
+
+    // Call alongside spawning the food entity.
+    pub fn insert(&mut self, location: Location2D, entity: Entity) {
+        if self.food_spawn_location.insert(location, entity).is_none() {
+            self.buckets.entry(Self::bucket(&location)).or_default().push(location);
+            self.food_amount += 1;
+        }
     }
 
     pub fn clear(&mut self) {
         self.food_spawn_location.clear();
+        self.buckets.clear();
         self.food_amount = 0;
     }
+
+    // Call alongside despawning the food entity.
+    pub fn remove(&mut self, location: &Location2D) {
+        if self.food_spawn_location.remove(location).is_none() {
+            return;
+        }
+        self.food_amount = self.food_amount.saturating_sub(1);
+        if let Some(bucket) = self.buckets.get_mut(&Self::bucket(location)) {
+            bucket.retain(|other| other != location);
+        }
+    }
 }
 
+// The loot file's ground_food table, when it has one, overrides the spawn
+// weights in the food table.
+fn choose_ground_food<'a>(
+    catalog: &'a FoodCatalog,
+    loot_tables: Option<&LootTables>,
+    rng: &mut impl Rng,
+) -> &'a FoodDef {
+    let picked = loot_tables
+        .and_then(|tables| tables.get(GROUND_FOOD_TABLE))
+        .and_then(|table| table.pick(rng))
+        .and_then(|entry| match entry.item {
+            Some(ItemKind::Food(food)) => catalog.get(food),
+            _ => None,
+        });
+    picked.unwrap_or_else(|| catalog.choose(rng))
+}
 
 fn spawn_food(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     time: Res<Time>,
     death_state: Res<DeathRespawnState>,
+    grid: Res<WorldGrid>,
+    catalog: Res<FoodCatalog>,
+    loot_tables: Option<Res<LootTables>>,
     mut config: ResMut<FoodSpawnConfig>,
     mut food_stats: ResMut<FoodTracker>,
+    mut reachable: ResMut<ReachableTiles>,
     player_query: Query<&Transform, With<Player>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Player>>,
     mut rng: ResMut<RandomSelectionConfig>,
 ) {
     if death_state.is_dead {
         return;
     }
 
-    let texture: Handle<Image> = asset_server.load("apple.png");
-
     config.timer.tick(time.delta());
 
-    let food_spawn_flag = food_stats.food_amount < 5;
+    let food_spawn_flag = food_stats.food_amount < config.max_food;
 
     if config.timer.is_finished() && food_spawn_flag {
         let Ok(player_transform) = player_query.single() else {
@@ -87,69 +388,214 @@ fn spawn_food(
             (player_transform.translation.x / WORLD_TILE_SIZE).floor() as i32;
         let player_tile_y =
             (player_transform.translation.y / WORLD_TILE_SIZE).floor() as i32;
-        if let Some(location) =
-            food_generate_location(food_stats.as_mut(), player_tile_x, player_tile_y, &mut rng.rng)
-        {
-            let Location2D { x, y } = location;
-            let world_x = x as f32 * WORLD_TILE_SIZE;
-            let world_y = y as f32 * WORLD_TILE_SIZE;
-            commands.spawn((
-                Food,
-                location,
-                Sprite {
-                    custom_size: Some(Vec2::new(16.0, 16.0)),
-                    ..Sprite::from_image(texture)
-                },
-                Visibility::Hidden,
-                Transform::from_translation(Vec3::new(world_x, world_y, 1.0)),
-                FoodStats { food_bar_regen: 20.0 },
-            ));
-            food_stats.food_amount += 1;
-        }
-    }
-}
-
-fn setup_food_spawning(
+        let limits = spawn_limits(&grid);
+        let player_tile = IVec2::new(player_tile_x, player_tile_y);
+        let reachable = reachable_area(&grid, &config, &mut reachable, player_tile);
+        let min_distance_sq = config.min_player_distance * config.min_player_distance;
+        let tile_ok = |x, y| {
+            IVec2::new(x, y).distance_squared(player_tile) >= min_distance_sq
+                && can_hold_food(&grid, reachable, &obstacle_query, x, y)
+        };
+        let spawn_chance = |x, y| config.spawn_chance(grid.biome(IVec2::new(x, y)));
+        if let Some(location) = food_generate_location(
+            &food_stats,
+            tile_ok,
+            spawn_chance,
+            limits,
+            player_tile_x,
+            player_tile_y,
+            &mut rng.rng,
+        ) {
+            let food = choose_ground_food(&catalog, loot_tables.as_deref(), &mut rng.rng);
+            let quality = FoodQuality::roll(&grid, location, &mut rng.rng);
+            let entity = spawn_food_entity(&mut commands, &asset_server, location, food, quality);
+            food_stats.insert(location, entity);
+        }
+    }
+}
+
+fn spawn_food_cache(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut requests: MessageReader<SpawnFoodCache>,
+    grid: Res<WorldGrid>,
+    catalog: Res<FoodCatalog>,
+    loot_tables: Option<Res<LootTables>>,
+    config: Res<FoodSpawnConfig>,
+    mut food_stats: ResMut<FoodTracker>,
+    mut reachable: ResMut<ReachableTiles>,
+    player_query: Query<&Transform, With<Player>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Player>>,
+    mut rng: ResMut<RandomSelectionConfig>,
 ) {
-    commands.insert_resource(FoodSpawnConfig {
-        timer: Timer::new(Duration::from_secs(5), TimerMode::Repeating),
-    });
-    commands.insert_resource(FoodTracker {
-        food_spawn_location: HashSet::new(),
-        food_amount: 0,
-    });
+    if requests.is_empty() {
+        return;
+    }
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_tile_x =
+        (player_transform.translation.x / WORLD_TILE_SIZE).floor() as i32;
+    let player_tile_y =
+        (player_transform.translation.y / WORLD_TILE_SIZE).floor() as i32;
 
-    let seed_key = "SPAWN_SEED_KEY";
-    let seed_value: Option<u64> = env::var(seed_key)
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok());
+    let limits = spawn_limits(&grid);
+    let player_tile = IVec2::new(player_tile_x, player_tile_y);
+    let reachable = reachable_area(&grid, &config, &mut reachable, player_tile);
+    let tile_ok = |x, y| can_hold_food(&grid, reachable, &obstacle_query, x, y);
+    for request in requests.read() {
+        for _ in 0..request.count {
+            let Some(location) = food_generate_location_near(
+                &food_stats,
+                tile_ok,
+                limits,
+                player_tile_x,
+                player_tile_y,
+                request.radius_tiles,
+                &mut rng.rng,
+            ) else {
+                continue;
+            };
+            let food = choose_ground_food(&catalog, loot_tables.as_deref(), &mut rng.rng);
+            let quality = FoodQuality::roll(&grid, location, &mut rng.rng);
+            let entity = spawn_food_entity(&mut commands, &asset_server, location, food, quality);
+            food_stats.insert(location, entity);
+        }
+    }
+}
 
-    let rng = match seed_value {
-        Some(seed) => StdRng::seed_from_u64(seed),
-        None => StdRng::from_os_rng(),
-    };
-    commands.insert_resource(RandomSelectionConfig {
-        rng
+fn spawn_food_entity(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    location: Location2D,
+    food: &FoodDef,
+    quality: FoodQuality,
+) -> Entity {
+    let Location2D { x, y } = location;
+    let world_x = x as f32 * WORLD_TILE_SIZE;
+    let world_y = y as f32 * WORLD_TILE_SIZE;
+    commands
+        .spawn((
+            Food,
+            Interactable::new(
+                "interact.pick_up",
+                FOOD_PICKUP_RADIUS_TILES as f32 * WORLD_TILE_SIZE,
+            ),
+            Lightable {
+                tint: quality.tint(food.tint),
+            },
+            location,
+            Sprite {
+                custom_size: Some(Vec2::new(16.0, 16.0)),
+                ..Sprite::from_image(asset_server.load(food.item.texture))
+            },
+            Visibility::Hidden,
+            Transform::from_translation(Vec3::new(world_x, world_y, 1.0)),
+            FoodStats {
+                food: food.item,
+                food_bar_regen: food.food_bar_regen * quality.regen_factor(),
+                health_regen: food.health_regen * quality.regen_factor(),
+                stamina_regen: food.stamina_regen * quality.regen_factor(),
+                effect: food.effect,
+            },
+            quality,
+            Freshness {
+                age: 0.0,
+                shelf_life: food.shelf_life,
+            },
+        ))
+        .id()
+}
+
+// Food put down by the player rather than spawned, e.g. thrown as bait.
+// Fails if food already lies on the tile.
+pub fn place_food(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    tracker: &mut FoodTracker,
+    location: Location2D,
+    food: &FoodDef,
+) -> Option<Entity> {
+    if tracker.contains(&location) {
+        return None;
+    }
+    let entity = spawn_food_entity(commands, asset_server, location, food, FoodQuality::Normal);
+    tracker.insert(location, entity);
+    Some(entity)
+}
+
+fn pickup_settings(configured: bool, magnet_radius_tiles: i32) -> PickupSettings {
+    // AUTO_PICKUP switches the magnet on without a rebuild.
+    let auto_key = "AUTO_PICKUP";
+    let from_env = env::var(auto_key)
+        .map(|value| matches!(value.as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    PickupSettings {
+        auto_pickup: configured || from_env,
+        magnet_radius_tiles,
+    }
+}
+
+fn random_selection_config(configured: Option<u64>) -> RandomSelectionConfig {
+    let seed_key = "SPAWN_SEED_KEY";
+    let seed_value: Option<u64> = configured.or_else(|| {
+        env::var(seed_key)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
     });
+
+    let seed = seed_value.unwrap_or_else(rand::random);
+    RandomSelectionConfig {
+        rng: StdRng::seed_from_u64(seed),
+        seed,
+    }
+}
+
+// Food stays a little way in from the map edge.
+fn spawn_limits(grid: &WorldGrid) -> IVec2 {
+    IVec2::new(
+        grid.width as i32 - SPAWN_EDGE_MARGIN,
+        grid.height as i32 - SPAWN_EDGE_MARGIN,
+    )
 }
 
 fn food_generate_location(
-    food_stats: &mut FoodTracker,
+    food_stats: &FoodTracker,
+    tile_ok: impl Fn(i32, i32) -> bool,
+    spawn_chance: impl Fn(i32, i32) -> f64,
+    limits: IVec2,
     player_x: i32,
     player_y: i32,
     rng: &mut StdRng,
 ) -> Option<Location2D> {
 
     for _ in 0..MAX_SPAWN_ATTEMPTS {
-        let x: i32 = rng.random_range(1..X_SPAWN_GENERATION);
-        let y: i32 = rng.random_range(1..Y_SPAWN_GENERATION);
-        if check_allowed_generation(&food_stats.food_spawn_location, player_x, player_y, x, y) {
-            let location = Location2D { x, y };
-            food_stats
-                .food_spawn_location
-                .insert(location);
-            return Some(location);
+        let x: i32 = rng.random_range(1..limits.x);
+        let y: i32 = rng.random_range(1..limits.y);
+        if tile_ok(x, y)
+            && check_allowed_generation(food_stats, player_x, player_y, x, y)
+            && rng.random_bool(spawn_chance(x, y).clamp(0.0, 1.0))
+        {
+            return Some(Location2D { x, y });
+        }
+    }
+    None
+}
+
+fn food_generate_location_near(
+    food_stats: &FoodTracker,
+    tile_ok: impl Fn(i32, i32) -> bool,
+    limits: IVec2,
+    center_x: i32,
+    center_y: i32,
+    radius: i32,
+    rng: &mut StdRng,
+) -> Option<Location2D> {
+    for _ in 0..MAX_SPAWN_ATTEMPTS {
+        let x = (center_x + rng.random_range(-radius..=radius)).clamp(1, limits.x - 1);
+        let y = (center_y + rng.random_range(-radius..=radius)).clamp(1, limits.y - 1);
+        if tile_ok(x, y) && check_allowed_generation(food_stats, center_x, center_y, x, y) {
+            return Some(Location2D { x, y });
         }
     }
     None
@@ -158,18 +604,26 @@ fn food_generate_location(
 fn food_pickup(
     mut commands: Commands,
     input: Res<ButtonInput<KeyCode>>,
+    game_settings: Res<GameSettings>,
+    time: Res<Time>,
     death_state: Res<DeathRespawnState>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    mut meals: MessageWriter<EatFood>,
+    mut event_log: ResMut<EventLog>,
+    settings: Res<PickupSettings>,
     mut food_stats: ResMut<FoodTracker>,
-    mut player_query: Query<(&Transform, &mut Stats), With<Player>>,
-    food_query: Query<(Entity, &FoodStats, &Location2D, &Visibility), With<Food>>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    food_query: Query<(Entity, &FoodStats, &Freshness, &Location2D, &Visibility), With<Food>>,
 ) {
     if death_state.is_dead {
         return;
     }
-    if !input.just_pressed(KeyCode::KeyE) {
+    let pressed = input.just_pressed(game_settings.key(Action::Interact));
+    if !pressed && !settings.auto_pickup {
         return;
     }
-    let Ok((player_transform, mut stats)) = player_query.single_mut() else {
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else {
         return;
     };
     let player_tile_x =
@@ -178,75 +632,345 @@ fn food_pickup(
         (player_transform.translation.y / WORLD_TILE_SIZE).floor() as i32;
 
     let max_dist_sq = FOOD_PICKUP_RADIUS_TILES * FOOD_PICKUP_RADIUS_TILES;
-    for (entity, food, location, visibility) in &food_query {
+    let magnet_dist_sq = settings.magnet_radius_tiles * settings.magnet_radius_tiles;
+    let reach_tiles = if pressed {
+        FOOD_PICKUP_RADIUS_TILES
+    } else {
+        settings.magnet_radius_tiles
+    };
+    let player_tile = Vec2::new(player_tile_x as f32, player_tile_y as f32) * WORLD_TILE_SIZE;
+    let nearby: Vec<Entity> = food_stats
+        .query_radius(player_tile, reach_tiles as f32 * WORLD_TILE_SIZE)
+        .map(|(_, entity)| entity)
+        .collect();
+    for (entity, food, freshness, location, visibility) in food_query.iter_many(&nearby) {
         if !matches!(*visibility, Visibility::Visible) {
             continue;
         }
         let dx = location.x - player_tile_x;
         let dy = location.y - player_tile_y;
         let dist_sq = dx * dx + dy * dy;
-        if dist_sq > 0 && dist_sq <= max_dist_sq {
-            stats.food_bar =
-                (stats.food_bar + food.food_bar_regen).min(FOOD_BAR_MAX);
-            food_stats.food_amount = food_stats.food_amount.saturating_sub(1);
-            food_stats.food_spawn_location.remove(location);
+        // The magnet leaves rotten food alone rather than force-feeding it.
+        let pulled = settings.auto_pickup && !freshness.is_rotten() && dist_sq <= magnet_dist_sq;
+        let reached = pressed && dist_sq > 0 && dist_sq <= max_dist_sq;
+        if pulled || reached {
+            // Food goes into the inventory; with no room left (or once it
+            // has rotted) it is eaten on the spot.
+            if freshness.is_rotten() {
+                let name = progress.food_name(food.food).to_lowercase();
+                event_log.push(&time, format!("ate rotten {name}"));
+                meals.write(food.meal(None));
+            } else if inventory.add_within_limit(ItemKind::Food(food.food), 1) > 0 {
+                meals.write(food.meal(Some(food.food)));
+            }
+            // Rarity would give a disguised food away.
+            if let Some(def) = catalog.get(food.food)
+                && def.rarity != Rarity::Common
+                && progress.identifies(def.item)
+            {
+                event_log.push(
+                    &time,
+                    format!("found {} {}", def.rarity.name(), def.item.name.to_lowercase()),
+                );
+            }
+            food_stats.remove(location);
             commands.entity(entity).despawn();
         }
     }
 }
 
-fn update_food_lighting(
-    grid: Res<WorldGrid>,
-    mut food_query: Query<(&Location2D, &mut Visibility, &mut Sprite), With<Food>>,
+// The first taste of a disguised food identifies it for good.
+fn eat_food(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut progress: ResMut<PersistentProgress>,
+    mut event_log: ResMut<EventLog>,
+    mut meals: MessageReader<EatFood>,
+    mut xp: MessageWriter<XpGained>,
+    mut summary: ResMut<RunSummary>,
+    mut rng: ResMut<RandomSelectionConfig>,
+    mut player_query: Query<(&mut Stats, &mut StatusEffects, &mut Inventory), With<Player>>,
 ) {
-    for (location, mut visibility, mut sprite) in &mut food_query {
-        let x = location.x as usize;
-        let y = location.y as usize;
-        let in_bounds = x < WIDTH && y < HEIGHT;
-        if !in_bounds {
-            *visibility = Visibility::Hidden;
-            continue;
+    if death_state.is_dead {
+        meals.clear();
+        return;
+    }
+    let Ok((mut stats, mut effects, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let regen_factor = if progress.has_perk(Perk::Forager) {
+        FORAGER_FOOD_FACTOR
+    } else {
+        1.0
+    };
+    for meal in meals.read() {
+        stats.food_bar = (stats.food_bar + meal.food_bar_regen * regen_factor).min(FOOD_BAR_MAX);
+        stats.health = (stats.health + meal.health_regen).clamp(0.0, STATS_MAX);
+        stats.stamina = (stats.stamina + meal.stamina_regen).clamp(0.0, STATS_MAX);
+        if stats.food_bar >= WELL_FED_THRESHOLD {
+            effects.apply(StatusEffectKind::WellFed, WELL_FED_SECONDS);
         }
+        if let Some(effect) = meal.effect {
+            effects.apply(effect.kind, effect.seconds);
+        }
+        if let Some(food) = meal.food
+            && !progress.identifies(food)
+        {
+            progress.identified_foods.insert(food);
+            let (seemed, was) = (food.looks_like.to_lowercase(), food.name.to_lowercase());
+            event_log.push(&time, format!("that {seemed} was a {was}"));
+        }
+        if let Some(food) = meal.food
+            && rng.rng.random_bool(SEED_DROP_CHANCE)
+        {
+            inventory.add_within_limit(ItemKind::Seeds(food), 1);
+        }
+        summary.food_eaten += 1;
+        xp.write(XpGained {
+            amount: FOOD_XP,
+            reason: "ate food",
+        });
+    }
+}
 
-        let brightness = grid.brightness[y][x];
-        let normalized = if LIGHT_MAX_BRIGHTNESS > 0.0 {
-            (brightness / LIGHT_MAX_BRIGHTNESS).clamp(0.0, 1.0)
-        } else {
-            0.0
-        };
-
-        if brightness <= MIN_LIGHT_THRESHOLD {
-            *visibility = Visibility::Hidden;
+fn spoil_food(
+    mut commands: Commands,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    catalog: Res<FoodCatalog>,
+    config: Res<FoodSpawnConfig>,
+    mut food_stats: ResMut<FoodTracker>,
+    mut food_query: Query<
+        (
+            Entity,
+            &mut Freshness,
+            &mut FoodStats,
+            &FoodQuality,
+            &mut Lightable,
+            &Location2D,
+        ),
+        With<Food>,
+    >,
+) {
+    if death_state.is_dead {
+        return;
+    }
+    let dt = time.delta_secs();
+    for (entity, mut freshness, mut food, quality, mut lightable, location) in &mut food_query {
+        freshness.age += dt;
+        let time_left = freshness.time_left(config.rotten_seconds);
+        if time_left <= 0.0 {
+            food_stats.remove(location);
+            commands.entity(entity).despawn();
             continue;
         }
+        let Some(def) = catalog.get(food.food) else {
+            continue;
+        };
+        let staleness = freshness.staleness();
+        lightable.tint = quality.tint(def.tint).mix(&ROTTEN_TINT, staleness);
+        // Fade rather than hide, since lighting owns the visibility.
+        if time_left < EXPIRY_BLINK_SECONDS
+            && (time_left / EXPIRY_BLINK_PERIOD).fract() < 0.5
+        {
+            lightable.tint = lightable.tint.with_alpha(EXPIRY_BLINK_ALPHA);
+        }
+        if freshness.is_rotten() {
+            food.food_bar_regen = 0.0;
+            food.health_regen = 0.0;
+            food.stamina_regen = 0.0;
+            food.effect = Some(FoodEffect {
+                kind: StatusEffectKind::Poison,
+                seconds: ROTTEN_POISON_SECONDS,
+            });
+        } else {
+            let factor = 1.0 - (1.0 - STALE_REGEN_FACTOR) * staleness;
+            let scale = quality.regen_factor() * factor;
+            food.food_bar_regen = def.food_bar_regen * scale;
+            food.health_regen = def.health_regen * scale;
+            food.stamina_regen = def.stamina_regen * scale;
+        }
+    }
+}
 
-        *visibility = Visibility::Visible;
+// The flood fill only runs when reachable_only is set and the map has
+// changed since the last one.
+fn reachable_area<'a>(
+    grid: &WorldGrid,
+    config: &FoodSpawnConfig,
+    reachable: &'a mut ReachableTiles,
+    player_tile: IVec2,
+) -> Option<&'a Vec<Vec<bool>>> {
+    if !config.reachable_only {
+        return None;
+    }
+    Some(
+        reachable
+            .tiles
+            .get_or_insert_with(|| reachable_tiles(grid, player_tile)),
+    )
+}
+
+fn invalidate_reachable_tiles(
+    mut tiles_changed: MessageReader<TilesChanged>,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    mut reachable: ResMut<ReachableTiles>,
+) {
+    let changed = tiles_changed.read().count() > 0;
+    if regenerated.read().count() > 0 || changed {
+        reachable.tiles = None;
+    }
+}
 
-        // Keep fruit visible but dark when near the edge of the light.
-        let darkness_factor =
-            MIN_DARKNESS_FACTOR + (1.0 - MIN_DARKNESS_FACTOR) * normalized;
-        sprite.color = Color::srgb(darkness_factor, darkness_factor, darkness_factor);
+// Open ground, clear of trees and rocks and, if required, reachable.
+fn can_hold_food(
+    grid: &WorldGrid,
+    reachable: Option<&Vec<Vec<bool>>>,
+    obstacle_query: &Query<(&Transform, &Obstacle), Without<Player>>,
+    x: i32,
+    y: i32,
+) -> bool {
+    if grid.is_solid(x, y) {
+        return false;
     }
+    if let Some(reachable) = reachable
+        && !reachable[y as usize][x as usize]
+    {
+        return false;
+    }
+    let position = Vec2::new(x as f32, y as f32) * WORLD_TILE_SIZE;
+    !obstacle_query.iter().any(|(transform, obstacle)| {
+        let reach = obstacle.radius + OBSTACLE_CLEARANCE;
+        transform.translation.truncate().distance_squared(position) < reach * reach
+    })
 }
 
 fn check_allowed_generation(
-    occupied: &HashSet<Location2D>,
+    occupied: &FoodTracker,
     player_x: i32,
     player_y: i32,
     x: i32,
     y: i32,
 ) -> bool {
     let is_player_tile = player_x == x && player_y == y;
-    let is_free = !occupied.contains(&Location2D{ x, y });
+    let is_free = !occupied.contains(&Location2D { x, y });
     is_free && !is_player_tile
 }
 
-pub struct FoodPlugin;
+// The seed falls back to SPAWN_SEED_KEY and then a random one; food types
+// come from the table at catalog_path.
+#[derive(Clone, Debug)]
+pub struct FoodPlugin {
+    pub max_food: i32,
+    pub spawn_interval: Duration,
+    pub catalog_path: PathBuf,
+    pub seed: Option<u64>,
+    pub reachable_only: bool,
+    pub rotten_seconds: f32,
+    pub auto_pickup: bool,
+    pub magnet_radius_tiles: i32,
+    pub min_player_distance: i32,
+    pub biome_weights: HashMap<Biome, f32>,
+}
+
+impl Default for FoodPlugin {
+    fn default() -> Self {
+        Self {
+            max_food: DEFAULT_MAX_FOOD,
+            spawn_interval: Duration::from_secs_f32(DEFAULT_SPAWN_SECONDS),
+            catalog_path: PathBuf::from(DEFAULT_FOODS_FILE),
+            seed: None,
+            reachable_only: false,
+            rotten_seconds: DEFAULT_ROTTEN_SECONDS,
+            auto_pickup: false,
+            magnet_radius_tiles: DEFAULT_MAGNET_RADIUS_TILES,
+            min_player_distance: 0,
+            biome_weights: HashMap::new(),
+        }
+    }
+}
+
+impl FoodPlugin {
+    pub fn with_max_food(mut self, max_food: i32) -> Self {
+        self.max_food = max_food.max(0);
+        self
+    }
+
+    pub fn with_spawn_interval(mut self, seconds: f32) -> Self {
+        self.spawn_interval = Duration::from_secs_f32(seconds.max(0.1));
+        self
+    }
+
+    pub fn with_catalog_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.catalog_path = path.into();
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // Keeps food out of sealed-off pockets, at the cost of a flood fill
+    // over the map whenever it changes.
+    pub fn with_reachable_only(mut self, reachable_only: bool) -> Self {
+        self.reachable_only = reachable_only;
+        self
+    }
+
+    // Zero clears food away the moment it rots.
+    pub fn with_rotten_seconds(mut self, seconds: f32) -> Self {
+        self.rotten_seconds = seconds.max(0.0);
+        self
+    }
+
+    // Collects food the player walks near; E still works alongside.
+    pub fn with_auto_pickup(mut self, auto_pickup: bool) -> Self {
+        self.auto_pickup = auto_pickup;
+        self
+    }
+
+    pub fn with_magnet_radius(mut self, tiles: i32) -> Self {
+        self.magnet_radius_tiles = tiles.clamp(0, FOOD_PICKUP_RADIUS_TILES);
+        self
+    }
+
+    pub fn with_min_player_distance(mut self, tiles: i32) -> Self {
+        self.min_player_distance = tiles.max(0);
+        self
+    }
+
+    // Zero keeps timed spawns out of the biome entirely.
+    pub fn with_biome_weight(mut self, biome: Biome, weight: f32) -> Self {
+        self.biome_weights.insert(biome, weight.max(0.0));
+        self
+    }
+}
 
 impl Plugin for FoodPlugin {
     fn build(&self, app: &mut App){
-        app.add_systems(Startup, setup_food_spawning)
-            .add_systems(Update, (spawn_food, food_pickup))
-            .add_systems(PostUpdate, update_food_lighting);
+        app.add_message::<SpawnFoodCache>()
+            .add_message::<EatFood>()
+            .insert_resource(FoodSpawnConfig {
+                timer: Timer::new(self.spawn_interval, TimerMode::Repeating),
+                max_food: self.max_food,
+                min_player_distance: self.min_player_distance,
+                biome_weights: self.biome_weights.clone(),
+                reachable_only: self.reachable_only,
+                rotten_seconds: self.rotten_seconds,
+            })
+            .insert_resource(ReachableTiles::default())
+            .insert_resource(pickup_settings(self.auto_pickup, self.magnet_radius_tiles))
+            .insert_resource(load_food_catalog(&self.catalog_path))
+            .insert_resource(FoodTracker::default())
+            .insert_resource(random_selection_config(self.seed))
+            .add_systems(
+                Update,
+                (
+                    (invalidate_reachable_tiles, spawn_food, spawn_food_cache).chain(),
+                    (spoil_food, food_pickup, eat_food).chain(),
+                )
+                    .run_if(in_state(AppState::Playing)),
+            );
     }
 }
@@ -0,0 +1,294 @@
+// food types read from foods.ron, so adding a food needs no code changes
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::{fmt, fs, path::Path};
+
+use crate::locale::Locale;
+use crate::status_effect::StatusEffectKind;
+
+pub const DEFAULT_FOODS_FILE: &str = "assets/foods.ron";
+const FALLBACK_FOOD_BAR_REGEN: f32 = 20.0;
+const DEFAULT_SHELF_LIFE_SECONDS: f32 = 150.0;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Rarity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Rarity::Common => "common",
+            Rarity::Uncommon => "uncommon",
+            Rarity::Rare => "rare",
+        }
+    }
+
+    // Drawn around uncommon and rare things lying on the ground.
+    pub fn outline(self) -> Option<Color> {
+        match self {
+            Rarity::Common => None,
+            Rarity::Uncommon => Some(Color::srgba(0.35, 0.85, 0.4, 0.8)),
+            Rarity::Rare => Some(Color::srgba(1.0, 0.8, 0.25, 0.9)),
+        }
+    }
+}
+
+// A status effect applied when the food is eaten, e.g. poison from a bad
+// mushroom or regeneration from a herb.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FoodEffect {
+    pub kind: StatusEffectKind,
+    pub seconds: f32,
+}
+
+// Tooltip lines for what eating something does; health and stamina only
+// show up when the food touches them.
+pub fn payload_lines(
+    locale: &Locale,
+    food_bar: f32,
+    health: f32,
+    stamina: f32,
+    effect: Option<FoodEffect>,
+) -> Vec<String> {
+    let amount_line =
+        |key: &str, amount: f32| locale.format(key, &[("amount", &format!("{amount:+.0}"))]);
+    let mut lines = vec![amount_line("tooltip.food", food_bar)];
+    for (amount, key) in [(health, "tooltip.health"), (stamina, "tooltip.stamina")] {
+        if amount != 0.0 {
+            lines.push(amount_line(key, amount));
+        }
+    }
+    if let Some(effect) = effect {
+        lines.push(format!("{} {:.0}s", effect.kind.name(), effect.seconds));
+    }
+    lines
+}
+
+fn untinted() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
+
+fn default_shelf_life() -> f32 {
+    DEFAULT_SHELF_LIFE_SECONDS
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct FoodRecord {
+    name: String,
+    texture: String,
+    // Multiplied into the sprite colour, so one greyscale texture can serve
+    // several foods.
+    #[serde(default = "untinted")]
+    tint: (f32, f32, f32),
+    food_bar_regen: f32,
+    // Restored straight away on eating, for herbs and energy foods.
+    #[serde(default)]
+    health_regen: f32,
+    #[serde(default)]
+    stamina_regen: f32,
+    #[serde(default)]
+    effect: Option<FoodEffect>,
+    #[serde(default)]
+    rarity: Rarity,
+    spawn_weight: f32,
+    // Relative chance of being caught when fishing; zero for land food.
+    #[serde(default)]
+    catch_weight: f32,
+    // Seconds on the ground before it rots.
+    #[serde(default = "default_shelf_life")]
+    shelf_life: f32,
+    // Shown instead of the real name until the food has been tasted, so a
+    // bad mushroom can pass for a good one.
+    #[serde(default)]
+    looks_like: Option<String>,
+}
+
+// How a food travels as an item. Names and textures are leaked once when the
+// catalog loads so the item can stay Copy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FoodItem {
+    pub id: u16,
+    pub name: &'static str,
+    pub texture: &'static str,
+    // Same as name unless the food is disguised.
+    pub looks_like: &'static str,
+}
+
+impl FoodItem {
+    pub fn is_disguised(self) -> bool {
+        self.looks_like != self.name
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FoodDef {
+    pub item: FoodItem,
+    pub tint: Color,
+    pub food_bar_regen: f32,
+    pub health_regen: f32,
+    pub stamina_regen: f32,
+    pub effect: Option<FoodEffect>,
+    pub rarity: Rarity,
+    pub spawn_weight: f32,
+    pub catch_weight: f32,
+    pub shelf_life: f32,
+}
+
+#[derive(Debug)]
+pub enum FoodCatalogError {
+    Io(std::io::Error),
+    Parse(String),
+    Empty,
+    Weight(String),
+}
+
+impl fmt::Display for FoodCatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FoodCatalogError::Io(err) => write!(f, "could not read food table: {err}"),
+            FoodCatalogError::Parse(err) => write!(f, "could not parse food table: {err}"),
+            FoodCatalogError::Empty => write!(f, "food table has no foods that can spawn"),
+            FoodCatalogError::Weight(name) => write!(f, "{name} has a negative weight"),
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct FoodCatalog {
+    foods: Vec<FoodDef>,
+    total_weight: f32,
+}
+
+impl Default for FoodCatalog {
+    // Just the apple, for when foods.ron is missing or broken.
+    fn default() -> Self {
+        let apple = FoodRecord {
+            name: "Apple".to_string(),
+            texture: "apple.png".to_string(),
+            tint: untinted(),
+            food_bar_regen: FALLBACK_FOOD_BAR_REGEN,
+            health_regen: 0.0,
+            stamina_regen: 0.0,
+            effect: None,
+            rarity: Rarity::Common,
+            spawn_weight: 1.0,
+            catch_weight: 0.0,
+            shelf_life: DEFAULT_SHELF_LIFE_SECONDS,
+            looks_like: None,
+        };
+        Self::from_records(vec![apple]).expect("the fallback food table is valid")
+    }
+}
+
+impl FoodCatalog {
+    pub fn load(path: &Path) -> Result<Self, FoodCatalogError> {
+        let text = fs::read_to_string(path).map_err(FoodCatalogError::Io)?;
+        let records: Vec<FoodRecord> =
+            ron::from_str(&text).map_err(|err| FoodCatalogError::Parse(err.to_string()))?;
+        Self::from_records(records)
+    }
+
+    fn from_records(records: Vec<FoodRecord>) -> Result<Self, FoodCatalogError> {
+        let negative = |record: &&FoodRecord| record.spawn_weight < 0.0 || record.catch_weight < 0.0;
+        if let Some(record) = records.iter().find(negative) {
+            return Err(FoodCatalogError::Weight(record.name.clone()));
+        }
+        let total_weight: f32 = records.iter().map(|record| record.spawn_weight).sum();
+        if total_weight <= 0.0 {
+            return Err(FoodCatalogError::Empty);
+        }
+        let foods = records
+            .into_iter()
+            .enumerate()
+            .map(|(index, record)| {
+                let name: &'static str = Box::leak(record.name.into_boxed_str());
+                let looks_like = match record.looks_like {
+                    Some(looks_like) => Box::leak(looks_like.into_boxed_str()),
+                    None => name,
+                };
+                FoodDef {
+                    item: FoodItem {
+                        id: index as u16,
+                        name,
+                        texture: Box::leak(record.texture.into_boxed_str()),
+                        looks_like,
+                    },
+                    tint: Color::srgb(record.tint.0, record.tint.1, record.tint.2),
+                    food_bar_regen: record.food_bar_regen,
+                    health_regen: record.health_regen,
+                    stamina_regen: record.stamina_regen,
+                    effect: record.effect,
+                    rarity: record.rarity,
+                    spawn_weight: record.spawn_weight,
+                    catch_weight: record.catch_weight,
+                    shelf_life: record.shelf_life.max(1.0),
+                }
+            })
+            .collect();
+        Ok(Self {
+            foods,
+            total_weight,
+        })
+    }
+
+    pub fn get(&self, item: FoodItem) -> Option<&FoodDef> {
+        self.foods.get(item.id as usize)
+    }
+
+    pub fn find(&self, name: &str) -> Option<&FoodDef> {
+        self.foods.iter().find(|food| food.item.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FoodDef> {
+        self.foods.iter()
+    }
+
+    // Picks a food at random in proportion to its spawn weight.
+    pub fn choose(&self, rng: &mut impl Rng) -> &FoodDef {
+        let mut roll = rng.random_range(0.0..self.total_weight);
+        for food in &self.foods {
+            if roll < food.spawn_weight {
+                return food;
+            }
+            roll -= food.spawn_weight;
+        }
+        self.foods
+            .iter()
+            .rfind(|food| food.spawn_weight > 0.0)
+            .expect("a catalog always has a food that can spawn")
+    }
+
+    // Picks a fish in proportion to its catch weight, or None when the table
+    // has nothing to catch.
+    pub fn choose_catch(&self, rng: &mut impl Rng) -> Option<&FoodDef> {
+        let total: f32 = self.foods.iter().map(|food| food.catch_weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = rng.random_range(0.0..total);
+        for food in &self.foods {
+            if roll < food.catch_weight {
+                return Some(food);
+            }
+            roll -= food.catch_weight;
+        }
+        self.foods.iter().rfind(|food| food.catch_weight > 0.0)
+    }
+}
+
+// A missing or broken table falls back to apples rather than refusing to
+// start.
+pub fn load_food_catalog(path: &Path) -> FoodCatalog {
+    match FoodCatalog::load(path) {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            warn!("using the built-in food table instead of {}: {err}", path.display());
+            FoodCatalog::default()
+        }
+    }
+}
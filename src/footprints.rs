@@ -0,0 +1,193 @@
+// fading footprints left behind the player, drawn as one decal mesh
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::NoFrustumCulling;
+use bevy::mesh::{Indices, Mesh};
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use bevy::sprite_render::AlphaMode2d;
+use std::collections::VecDeque;
+
+use crate::player::{DeathRespawnState, MovementTracker, Player, Sneak};
+use crate::world::WorldRegenerated;
+
+const FOOTPRINT_SPACING: f32 = 10.0;
+const FOOTPRINT_SIDE_OFFSET: f32 = 2.5;
+const FOOTPRINT_SIZE: Vec2 = Vec2::new(2.5, 4.0);
+const FOOTPRINT_LIFETIME_SECONDS: f32 = 90.0;
+const MAX_FOOTPRINTS: usize = 400;
+const FOOTPRINT_Z: f32 = 0.2;
+// Pale dust, faint enough to sit under the lighting but still readable on
+// unlit ground.
+const FOOTPRINT_COLOR: [f32; 3] = [0.55, 0.5, 0.42];
+const FOOTPRINT_ALPHA: f32 = 0.35;
+const SNEAK_FOOTPRINT_ALPHA: f32 = 0.15;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Footprint {
+    pub position: Vec2,
+    pub direction: Vec2,
+    pub left_at: f32,
+    pub faint: bool,
+}
+
+// Oldest first. Tracking creatures can follow these as well as the player.
+#[derive(Resource, Default)]
+pub struct Footprints {
+    prints: VecDeque<Footprint>,
+}
+
+impl Footprints {
+    pub fn iter(&self) -> impl Iterator<Item = &Footprint> {
+        self.prints.iter()
+    }
+
+    // The most recent footprint within radius of the position.
+    pub fn freshest_near(&self, position: Vec2, radius: f32) -> Option<&Footprint> {
+        self.prints
+            .iter()
+            .rev()
+            .find(|print| print.position.distance_squared(position) <= radius * radius)
+    }
+
+    pub fn clear(&mut self) {
+        self.prints.clear();
+    }
+}
+
+#[derive(Resource)]
+struct FootprintMesh(Handle<Mesh>);
+
+#[derive(Default)]
+struct TrailState {
+    last_print: Option<Vec2>,
+    left_foot: bool,
+}
+
+fn setup_footprints(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mesh = meshes.add(build_footprint_mesh(&Footprints::default(), 0.0));
+    let material = materials.add(ColorMaterial {
+        color: Color::WHITE,
+        alpha_mode: AlphaMode2d::Blend,
+        ..default()
+    });
+    // The trail moves with the player, so a cached bounding box would cull it.
+    commands.spawn((
+        Mesh2d(mesh.clone()),
+        MeshMaterial2d(material),
+        Transform::from_translation(Vec3::new(0.0, 0.0, FOOTPRINT_Z)),
+        NoFrustumCulling,
+    ));
+    commands.insert_resource(FootprintMesh(mesh));
+    commands.insert_resource(Footprints::default());
+}
+
+fn leave_footprints(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut trail: Local<TrailState>,
+    mut footprints: ResMut<Footprints>,
+    player_query: Query<(&Transform, &MovementTracker, &Sneak), With<Player>>,
+) {
+    let Ok((transform, tracker, sneak)) = player_query.single() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    if death_state.is_dead || !tracker.is_moving {
+        trail.last_print = Some(position);
+        return;
+    }
+    let last = *trail.last_print.get_or_insert(position);
+    let step = position - last;
+    if step.length() < FOOTPRINT_SPACING {
+        return;
+    }
+
+    let direction = step.normalize();
+    let side = if trail.left_foot { 1.0 } else { -1.0 };
+    trail.left_foot = !trail.left_foot;
+    trail.last_print = Some(position);
+    if footprints.prints.len() == MAX_FOOTPRINTS {
+        footprints.prints.pop_front();
+    }
+    footprints.prints.push_back(Footprint {
+        position: position + direction.perp() * FOOTPRINT_SIDE_OFFSET * side,
+        direction,
+        left_at: time.elapsed_secs(),
+        faint: sneak.active,
+    });
+}
+
+fn fade_footprints(
+    time: Res<Time>,
+    mut footprints: ResMut<Footprints>,
+    footprint_mesh: Res<FootprintMesh>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let now = time.elapsed_secs();
+    while footprints
+        .prints
+        .front()
+        .is_some_and(|print| now - print.left_at > FOOTPRINT_LIFETIME_SECONDS)
+    {
+        footprints.prints.pop_front();
+    }
+    let _ = meshes.insert(footprint_mesh.0.id(), build_footprint_mesh(&footprints, now));
+}
+
+fn build_footprint_mesh(footprints: &Footprints, now: f32) -> Mesh {
+    let count = footprints.prints.len();
+    let mut positions = Vec::with_capacity(count * 4);
+    let mut colors = Vec::with_capacity(count * 4);
+    let mut indices = Vec::with_capacity(count * 6);
+
+    for print in &footprints.prints {
+        let age = ((now - print.left_at) / FOOTPRINT_LIFETIME_SECONDS).clamp(0.0, 1.0);
+        let base_alpha = if print.faint { SNEAK_FOOTPRINT_ALPHA } else { FOOTPRINT_ALPHA };
+        let alpha = base_alpha * (1.0 - age);
+        // Quads are oriented along the walking direction.
+        let forward = print.direction * FOOTPRINT_SIZE.y * 0.5;
+        let across = print.direction.perp() * FOOTPRINT_SIZE.x * 0.5;
+        let corners = [
+            print.position - forward - across,
+            print.position - forward + across,
+            print.position + forward + across,
+            print.position + forward - across,
+        ];
+
+        let base = positions.len() as u32;
+        positions.extend(corners.iter().map(|corner| [corner.x, corner.y, 0.0]));
+        let [red, green, blue] = FOOTPRINT_COLOR;
+        colors.extend_from_slice(&[[red, green, blue, alpha]; 4]);
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+fn clear_footprints(
+    mut regenerated: MessageReader<WorldRegenerated>,
+    mut footprints: ResMut<Footprints>,
+) {
+    if regenerated.read().count() > 0 {
+        footprints.clear();
+    }
+}
+
+pub struct FootprintPlugin;
+
+impl Plugin for FootprintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_footprints).add_systems(
+            Update,
+            (clear_footprints, leave_footprints, fade_footprints).chain(),
+        );
+    }
+}
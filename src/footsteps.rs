@@ -0,0 +1,113 @@
+// footstep sounds while walking, varied by the surface underfoot
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::noise::NoiseEvent;
+use crate::player::{MovementTracker, Player, Sneak};
+use crate::settings::GameSettings;
+use crate::world::{TileKind, WorldGrid, WORLD_TILE_SIZE};
+
+const STRIDE_LENGTH: f32 = 18.0;
+const STEP_SECONDS: f32 = 0.05;
+// Footsteps sit under the other effects; sfx_volume scales them from here.
+const FOOTSTEP_VOLUME: f32 = 0.35;
+const GROUND_STEP_LOUDNESS: f32 = 0.3;
+const RUBBLE_STEP_LOUDNESS: f32 = 0.45;
+const SNEAK_NOISE_FACTOR: f32 = 0.25;
+
+// Two pitches per surface so left and right feet don't sound identical.
+#[derive(Resource)]
+struct FootstepSounds {
+    ground: [Handle<Pitch>; 2],
+    rubble: [Handle<Pitch>; 2],
+}
+
+impl FootstepSounds {
+    fn for_tile(&self, kind: TileKind, foot: usize) -> Handle<Pitch> {
+        match kind {
+            TileKind::Ground | TileKind::Water => self.ground[foot].clone(),
+            TileKind::Rubble => self.rubble[foot].clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct StrideState {
+    last_position: Option<Vec2>,
+    distance: f32,
+    foot: usize,
+}
+
+fn setup_footsteps(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    let step = Duration::from_secs_f32(STEP_SECONDS);
+    commands.insert_resource(FootstepSounds {
+        ground: [
+            pitches.add(Pitch::new(92.0, step)),
+            pitches.add(Pitch::new(104.0, step)),
+        ],
+        rubble: [
+            pitches.add(Pitch::new(180.0, step)),
+            pitches.add(Pitch::new(205.0, step)),
+        ],
+    });
+}
+
+// Steps are emitted per distance walked, so cadence follows movement speed.
+fn play_footsteps(
+    mut commands: Commands,
+    mut stride: Local<StrideState>,
+    settings: Res<GameSettings>,
+    sounds: Res<FootstepSounds>,
+    grid: Res<WorldGrid>,
+    mut noises: MessageWriter<NoiseEvent>,
+    player_query: Query<(&Transform, &MovementTracker, &Sneak), With<Player>>,
+) {
+    let Ok((transform, tracker, sneak)) = player_query.single() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let previous = stride.last_position.replace(position).unwrap_or(position);
+    if !tracker.is_moving {
+        stride.distance = 0.0;
+        return;
+    }
+
+    stride.distance += position.distance(previous);
+    if stride.distance < STRIDE_LENGTH * WORLD_TILE_SIZE {
+        return;
+    }
+    stride.distance = 0.0;
+    stride.foot = 1 - stride.foot;
+
+    let tile_x = (position.x / WORLD_TILE_SIZE).floor() as i32;
+    let tile_y = (position.y / WORLD_TILE_SIZE).floor() as i32;
+    let kind = grid.tile_kind(tile_x, tile_y).unwrap_or(TileKind::Ground);
+    let loudness = match kind {
+        TileKind::Ground | TileKind::Water => GROUND_STEP_LOUDNESS,
+        TileKind::Rubble => RUBBLE_STEP_LOUDNESS,
+    };
+    let sneak_factor = if sneak.active { SNEAK_NOISE_FACTOR } else { 1.0 };
+    noises.write(NoiseEvent {
+        position,
+        loudness: loudness * sneak_factor,
+    });
+
+    let volume = FOOTSTEP_VOLUME * settings.sfx_volume * sneak_factor;
+    if volume <= 0.0 {
+        return;
+    }
+    commands.spawn((
+        AudioPlayer(sounds.for_tile(kind, stride.foot)),
+        PlaybackSettings::DESPAWN.with_volume(Volume::Linear(volume)),
+    ));
+}
+
+pub struct FootstepPlugin;
+
+impl Plugin for FootstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_footsteps)
+            .add_systems(Update, play_footsteps);
+    }
+}
@@ -0,0 +1,110 @@
+// small world-space health bars over anything with Health that has taken
+// damage; the bar fades out a few seconds after the last hit. As children
+// of the entity they hide with it in the dark.
+use bevy::prelude::*;
+
+use crate::combat::Health;
+
+const BAR_WIDTH: f32 = 12.0;
+const BAR_HEIGHT: f32 = 1.5;
+const BAR_GAP: f32 = 2.0;
+const BAR_Z: f32 = 0.1;
+const SHOW_SECONDS: f32 = 3.0;
+const FADE_SECONDS: f32 = 1.0;
+const TRACK_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.8);
+const FULL_COLOR: Color = Color::srgb(0.3, 0.85, 0.3);
+const EMPTY_COLOR: Color = Color::srgb(0.85, 0.15, 0.1);
+
+// On the damaged entity while its bar is up.
+#[derive(Component)]
+pub struct HealthBar {
+    track: Entity,
+    fill: Entity,
+    remaining: f32,
+}
+
+// For things with a bar of their own elsewhere, like bosses.
+#[derive(Component)]
+pub struct NoHealthBar;
+
+#[derive(Component)]
+struct HealthBarTrack;
+
+#[derive(Component)]
+struct HealthBarFill;
+
+fn show_health_bars(
+    mut commands: Commands,
+    mut damaged_query: Query<
+        (Entity, &Health, Option<&Sprite>, Option<&mut HealthBar>),
+        (Changed<Health>, Without<NoHealthBar>),
+    >,
+) {
+    for (entity, health, sprite, bar) in &mut damaged_query {
+        if health.current >= health.max {
+            continue;
+        }
+        if let Some(mut bar) = bar {
+            bar.remaining = SHOW_SECONDS;
+            continue;
+        }
+        let height = sprite.and_then(|sprite| sprite.custom_size).map_or(0.0, |size| size.y);
+        let offset = Vec3::new(0.0, height / 2.0 + BAR_GAP, BAR_Z);
+        let fill = commands
+            .spawn((
+                HealthBarFill,
+                Sprite::from_color(FULL_COLOR, Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                Transform::from_translation(Vec3::Z * 0.01),
+            ))
+            .id();
+        let track = commands
+            .spawn((
+                HealthBarTrack,
+                Sprite::from_color(TRACK_COLOR, Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                Transform::from_translation(offset),
+            ))
+            .add_child(fill)
+            .id();
+        commands.entity(entity).add_child(track).insert(HealthBar {
+            track,
+            fill,
+            remaining: SHOW_SECONDS,
+        });
+    }
+}
+
+// The fill shrinks toward the left edge of the track.
+fn update_health_bars(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bar_query: Query<(Entity, &Health, &mut HealthBar)>,
+    mut track_query: Query<&mut Sprite, (With<HealthBarTrack>, Without<HealthBarFill>)>,
+    mut fill_query: Query<(&mut Sprite, &mut Transform), With<HealthBarFill>>,
+) {
+    for (entity, health, mut bar) in &mut bar_query {
+        bar.remaining -= time.delta_secs();
+        if bar.remaining <= 0.0 || health.current >= health.max {
+            commands.entity(bar.track).despawn();
+            commands.entity(entity).remove::<HealthBar>();
+            continue;
+        }
+        let alpha = (bar.remaining / FADE_SECONDS).min(1.0);
+        let ratio = (health.current / health.max).clamp(0.0, 1.0);
+        if let Ok(mut sprite) = track_query.get_mut(bar.track) {
+            sprite.color = TRACK_COLOR.with_alpha(TRACK_COLOR.alpha() * alpha);
+        }
+        if let Ok((mut sprite, mut transform)) = fill_query.get_mut(bar.fill) {
+            sprite.custom_size = Some(Vec2::new(BAR_WIDTH * ratio, BAR_HEIGHT));
+            sprite.color = EMPTY_COLOR.mix(&FULL_COLOR, ratio).with_alpha(alpha);
+            transform.translation.x = -BAR_WIDTH * (1.0 - ratio) / 2.0;
+        }
+    }
+}
+
+pub struct HealthBarPlugin;
+
+impl Plugin for HealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (show_health_bars, update_health_bars).chain());
+    }
+}
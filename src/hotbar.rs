@@ -0,0 +1,256 @@
+// hotbar along the bottom of the screen: the first inventory slots, picked
+// with 1-5 and used with the UseItem key, G by default
+use bevy::prelude::*;
+
+use crate::event_log::EventLog;
+use crate::farming::PlantSeeds;
+use crate::food::EatFood;
+use crate::food_catalog::FoodCatalog;
+use crate::hud::HudElement;
+use crate::items::{Inventory, ItemKind, BOTTLE_SIPS};
+use crate::light::facing_dir;
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player, PlayerState, Stats, STATS_MAX};
+use crate::progress::PersistentProgress;
+use crate::settings::{Action, GameSettings};
+use crate::tools::ToolBelt;
+use crate::tooltip::{Tooltip, TooltipAnchor};
+use crate::world::{WorldGrid, WORLD_TILE_SIZE};
+
+const HOTBAR_KEYS: [KeyCode; 5] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+];
+const HOTBAR_SLOT_SIZE: f32 = 36.0;
+const HOTBAR_ICON_SIZE: f32 = 22.0;
+const SLOT_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.7);
+const SELECTED_BORDER: Color = Color::srgb(0.95, 0.85, 0.45);
+const IDLE_BORDER: Color = Color::srgba(0.4, 0.4, 0.4, 0.7);
+const FILL_REACH: f32 = 12.0;
+const SIP_STAMINA: f32 = 25.0;
+
+#[derive(Resource, Default)]
+pub struct Hotbar {
+    pub selected: usize,
+}
+
+#[derive(Component)]
+struct HotbarSlot(usize);
+
+#[derive(Component)]
+struct HotbarIcon(usize);
+
+#[derive(Component)]
+struct HotbarCount(usize);
+
+fn setup_hotbar(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                bottom: px(12.0),
+                width: percent(100.0),
+                display: Display::Flex,
+                justify_content: JustifyContent::Center,
+                column_gap: px(4.0),
+                ..default()
+            },
+            HudElement::default(),
+        ))
+        .with_children(|bar| {
+            for index in 0..HOTBAR_KEYS.len() {
+                bar.spawn((
+                    Node {
+                        width: px(HOTBAR_SLOT_SIZE),
+                        height: px(HOTBAR_SLOT_SIZE),
+                        border: UiRect::all(px(2.0)),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    BackgroundColor(SLOT_COLOR),
+                    BorderColor::all(IDLE_BORDER),
+                    // Hovering a slot names what's in it, shown above the bar.
+                    Interaction::default(),
+                    Tooltip::default(),
+                    TooltipAnchor::default(),
+                    HotbarSlot(index),
+                ))
+                .with_children(|slot| {
+                    slot.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: px(2.0),
+                            top: px(0.0),
+                            ..default()
+                        },
+                        Text::new((index + 1).to_string()),
+                        TextFont::from_font_size(10.0),
+                        TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                    ));
+                    slot.spawn((
+                        Node {
+                            width: px(HOTBAR_ICON_SIZE),
+                            height: px(HOTBAR_ICON_SIZE),
+                            ..default()
+                        },
+                        ImageNode::default(),
+                        Visibility::Hidden,
+                        HotbarIcon(index),
+                    ));
+                    slot.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            right: px(2.0),
+                            bottom: px(0.0),
+                            ..default()
+                        },
+                        Text::new(""),
+                        TextFont::from_font_size(11.0),
+                        TextColor(Color::srgb(0.95, 0.95, 0.95)),
+                        HotbarCount(index),
+                    ));
+                });
+            }
+        });
+}
+
+// The death screen's leaderboard takes the digit keys while the player is
+// dead.
+fn select_hotbar_slot(
+    input: Res<ButtonInput<KeyCode>>,
+    death_state: Res<DeathRespawnState>,
+    mut hotbar: ResMut<Hotbar>,
+) {
+    if death_state.is_dead {
+        return;
+    }
+    if let Some(index) = HOTBAR_KEYS.iter().position(|key| input.just_pressed(*key)) {
+        hotbar.selected = index;
+    }
+}
+
+// Food is eaten, seeds are planted and tools are equipped. Bottles are
+// filled when facing water and drunk from otherwise. Anything else has no
+// use yet.
+fn use_hotbar_item(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    grid: Res<WorldGrid>,
+    hotbar: Res<Hotbar>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    locale: Res<Locale>,
+    mut meals: MessageWriter<EatFood>,
+    mut plantings: MessageWriter<PlantSeeds>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<
+        (&Transform, &PlayerState, &mut Stats, &mut Inventory, &mut ToolBelt),
+        With<Player>,
+    >,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::UseItem)) {
+        return;
+    }
+    let Ok((transform, state, mut stats, mut inventory, mut belt)) = player_query.single_mut()
+    else {
+        return;
+    };
+    let Some((kind, _)) = inventory.slot(hotbar.selected) else {
+        return;
+    };
+    if let ItemKind::Food(food) = kind
+        && let Some(def) = catalog.get(food)
+    {
+        inventory.remove_from_slot(hotbar.selected, 1);
+        meals.write(EatFood::from_def(def));
+    } else if let ItemKind::Seeds(food) = kind {
+        plantings.write(PlantSeeds { food });
+    } else if let ItemKind::Bottle(sips) = kind {
+        let dir = facing_dir(state.facing).as_vec2().normalize();
+        let target = transform.translation.truncate() + dir * FILL_REACH * WORLD_TILE_SIZE;
+        let tile = (target / WORLD_TILE_SIZE).floor().as_ivec2();
+        let (refilled, message) = if grid.is_water(tile.x, tile.y) {
+            (BOTTLE_SIPS, "filled the bottle")
+        } else if sips > 0 {
+            stats.stamina = (stats.stamina + SIP_STAMINA).min(STATS_MAX);
+            (sips - 1, "drank some water")
+        } else {
+            (0, "the bottle is empty, fill it at water")
+        };
+        inventory.set_slot(hotbar.selected, ItemKind::Bottle(refilled));
+        event_log.push(&time, message);
+    } else if kind.is_tool() {
+        belt.equipped = Some(kind);
+    } else {
+        let name = kind.display_name(&progress, &locale).to_lowercase();
+        event_log.push(&time, format!("nothing to do with {name}"));
+    }
+}
+
+fn update_hotbar(
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    hotbar: Res<Hotbar>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut slot_query: Query<(&HotbarSlot, &mut BorderColor, &mut Tooltip)>,
+    mut icon_query: Query<(&HotbarIcon, &mut ImageNode, &mut Visibility)>,
+    mut count_query: Query<(&HotbarCount, &mut Text)>,
+) {
+    let Ok(inventory) = player_query.single() else {
+        return;
+    };
+    for (slot, mut border, mut tooltip) in &mut slot_query {
+        let color = if slot.0 == hotbar.selected {
+            SELECTED_BORDER
+        } else {
+            IDLE_BORDER
+        };
+        border.set_if_neq(BorderColor::all(color));
+        let text = inventory
+            .slot(slot.0)
+            .map(|(kind, _)| kind.describe(&catalog, &progress, &locale))
+            .unwrap_or_default();
+        if tooltip.0 != text {
+            tooltip.0 = text;
+        }
+    }
+    for (icon, mut image, mut visibility) in &mut icon_query {
+        let Some((kind, _)) = inventory.slot(icon.0) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        *image = kind.icon(&asset_server, &catalog);
+    }
+    for (count, mut text) in &mut count_query {
+        text.0 = match inventory.slot(count.0) {
+            Some((_, amount)) if amount > 1 => amount.to_string(),
+            _ => String::new(),
+        };
+    }
+}
+
+pub struct HotbarPlugin;
+
+impl Plugin for HotbarPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Hotbar::default())
+            .add_systems(Startup, setup_hotbar)
+            .add_systems(
+                Update,
+                (select_hotbar_slot, use_hotbar_item, update_hotbar)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,622 @@
+// the HUD: the player's health, food and stamina, shown as pips, bars or
+// both, each in a screen corner of its own choosing, plus the key that hides
+// it all. Everything tagged HudElement (the status panels, the minimap,
+// narration) hides together on that key, for screenshots or to play without
+// it. Screens and menus aren't tagged and still open as usual.
+use bevy::camera::visibility::VisibilitySystems;
+use bevy::prelude::*;
+
+use crate::food::EatFood;
+use crate::menu::AppState;
+use crate::player::{Player, Stats, STATS_MAX};
+use crate::settings::{GameSettings, StatusPalette};
+
+const DEFAULT_TOGGLE_KEY: KeyCode = KeyCode::F1;
+// From the screen edges to the widgets in each corner.
+const HUD_MARGIN: f32 = 16.0;
+const WIDGET_GAP: f32 = 8.0;
+const STATUS_PIPS: usize = 4;
+const STATUS_CHUNK: f32 = 25.0;
+const STATUS_ICON_SIZE: f32 = 24.0;
+const STATUS_PANEL_PADDING: f32 = 6.0;
+const STATUS_ROW_GAP: f32 = 6.0;
+const STATUS_PIP_GAP: f32 = 4.0;
+const STATUS_PANEL_ALPHA: f32 = 1.0;
+const STATUS_ROW_ALPHA: f32 = 1.0;
+const STATUS_ROW_COLOR: Color = Color::srgba(0.93, 0.93, 0.93, STATUS_ROW_ALPHA);
+// A row flashes when a meal raises or lowers its bar, with a + or - beside
+// it so the direction doesn't rest on colour alone.
+const STATUS_FLASH_SECONDS: f32 = 0.8;
+const STATUS_MARKER_WIDTH: f32 = 12.0;
+const STATUS_BAR_ICON_SIZE: f32 = 16.0;
+// As wide as a row of pips, less the icon in front.
+const STATUS_BAR_WIDTH: f32 = STATUS_PIPS as f32 * (STATUS_ICON_SIZE + STATUS_PIP_GAP)
+    - STATUS_PIP_GAP
+    - STATUS_BAR_ICON_SIZE;
+const STATUS_BAR_HEIGHT: f32 = 10.0;
+const STATUS_BAR_TRACK: Color = Color::srgb(0.25, 0.25, 0.25);
+
+// What the element was showing before the HUD was hidden. Systems that
+// show and hide their own parts of the HUD keep working while it's hidden;
+// their last say is what comes back.
+#[derive(Component, Default)]
+pub struct HudElement {
+    restore: Option<Visibility>,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct HudVisibility {
+    pub shown: bool,
+    pub toggle_key: KeyCode,
+}
+
+// The minimap keeps the bottom right and the hotbar the bottom centre.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HudAnchor {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HudAnchor {
+    const ALL: [HudAnchor; 4] = [
+        HudAnchor::TopLeft,
+        HudAnchor::TopRight,
+        HudAnchor::BottomLeft,
+        HudAnchor::BottomRight,
+    ];
+
+    // Widgets sharing a corner stack away from it, outermost first.
+    fn corner_node(self) -> Node {
+        let margin = px(HUD_MARGIN);
+        let (left, right) = match self {
+            HudAnchor::TopLeft | HudAnchor::BottomLeft => (margin, Val::Auto),
+            HudAnchor::TopRight | HudAnchor::BottomRight => (Val::Auto, margin),
+        };
+        let (top, bottom, direction) = match self {
+            HudAnchor::TopLeft | HudAnchor::TopRight => (margin, Val::Auto, FlexDirection::Column),
+            HudAnchor::BottomLeft | HudAnchor::BottomRight => {
+                (Val::Auto, margin, FlexDirection::ColumnReverse)
+            }
+        };
+        let align_items = match self {
+            HudAnchor::TopLeft | HudAnchor::BottomLeft => AlignItems::FlexStart,
+            HudAnchor::TopRight | HudAnchor::BottomRight => AlignItems::FlexEnd,
+        };
+        Node {
+            position_type: PositionType::Absolute,
+            left,
+            right,
+            top,
+            bottom,
+            display: Display::Flex,
+            flex_direction: direction,
+            align_items,
+            row_gap: px(WIDGET_GAP),
+            ..default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatDisplay {
+    #[default]
+    Pips,
+    Bars,
+    Both,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HudWidget {
+    StatusPips,
+    StatusBars,
+    Compass,
+    Clock,
+    Objectives,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct HudLayout {
+    pub stats: StatDisplay,
+    pub pips_anchor: HudAnchor,
+    pub bars_anchor: HudAnchor,
+    pub compass_anchor: HudAnchor,
+    pub clock_anchor: HudAnchor,
+    pub objectives_anchor: HudAnchor,
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self {
+            stats: StatDisplay::Pips,
+            pips_anchor: HudAnchor::TopLeft,
+            bars_anchor: HudAnchor::TopLeft,
+            compass_anchor: HudAnchor::TopLeft,
+            clock_anchor: HudAnchor::TopRight,
+            objectives_anchor: HudAnchor::TopRight,
+        }
+    }
+}
+
+impl HudLayout {
+    pub fn anchor(&self, widget: HudWidget) -> HudAnchor {
+        match widget {
+            HudWidget::StatusPips => self.pips_anchor,
+            HudWidget::StatusBars => self.bars_anchor,
+            HudWidget::Compass => self.compass_anchor,
+            HudWidget::Clock => self.clock_anchor,
+            HudWidget::Objectives => self.objectives_anchor,
+        }
+    }
+
+    // The stat widgets to show, in the order they stack.
+    fn status_widgets(&self) -> Vec<(HudWidget, HudAnchor)> {
+        let pips = (HudWidget::StatusPips, self.pips_anchor);
+        let bars = (HudWidget::StatusBars, self.bars_anchor);
+        match self.stats {
+            StatDisplay::Pips => vec![pips],
+            StatDisplay::Bars => vec![bars],
+            StatDisplay::Both => vec![pips, bars],
+        }
+    }
+}
+
+// Holds the widgets anchored to one corner of the screen, stacked in the
+// order they're added. Widgets from other modules go in with the corner
+// their HudLayout anchor names.
+#[derive(Component)]
+pub struct HudCorner(pub HudAnchor);
+
+// The first status widget shown; other HUD parts can add rows of their own
+// under its stat rows.
+#[derive(Component)]
+pub struct StatusPanel;
+
+#[derive(Component)]
+struct StatusPip {
+    kind: StatusKind,
+    index: usize,
+}
+
+#[derive(Component)]
+struct StatusBarFill {
+    kind: StatusKind,
+}
+
+#[derive(Component)]
+struct StatusBarIcon {
+    kind: StatusKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StatusKind {
+    Food,
+    Health,
+    Stamina,
+}
+
+impl StatusKind {
+    const ALL: [StatusKind; 3] = [StatusKind::Food, StatusKind::Health, StatusKind::Stamina];
+}
+
+#[derive(Component)]
+struct StatusRow {
+    kind: StatusKind,
+    // Seconds of flash left and whether it was a gain.
+    flash: f32,
+    gained: bool,
+}
+
+#[derive(Component)]
+struct StatusFlashMarker;
+
+// Tints the grey pip icons; empty pips are left untinted. Bars fill in the
+// same colour.
+fn status_pip_tint(palette: StatusPalette, kind: StatusKind) -> Color {
+    match (palette, kind) {
+        (StatusPalette::Classic, StatusKind::Health) => Color::srgb(0.9, 0.25, 0.25),
+        (StatusPalette::Classic, StatusKind::Food) => Color::srgb(0.35, 0.8, 0.3),
+        (StatusPalette::Classic, StatusKind::Stamina) => Color::srgb(0.3, 0.5, 0.95),
+        (StatusPalette::ColorSafe, StatusKind::Health) => Color::srgb(0.84, 0.37, 0.0),
+        (StatusPalette::ColorSafe, StatusKind::Food) => Color::srgb(0.94, 0.89, 0.26),
+        (StatusPalette::ColorSafe, StatusKind::Stamina) => Color::srgb(0.0, 0.45, 0.7),
+        (StatusPalette::Monochrome, _) => Color::WHITE,
+    }
+}
+
+// (gain, loss) flash colours.
+fn status_flash_colors(palette: StatusPalette) -> (Color, Color) {
+    match palette {
+        StatusPalette::Classic => (
+            Color::srgba(0.65, 0.95, 0.6, STATUS_ROW_ALPHA),
+            Color::srgba(0.98, 0.6, 0.55, STATUS_ROW_ALPHA),
+        ),
+        StatusPalette::ColorSafe => (
+            Color::srgba(0.6, 0.8, 0.95, STATUS_ROW_ALPHA),
+            Color::srgba(0.98, 0.75, 0.45, STATUS_ROW_ALPHA),
+        ),
+        StatusPalette::Monochrome => (
+            Color::srgba(1.0, 1.0, 1.0, STATUS_ROW_ALPHA),
+            Color::srgba(0.7, 0.7, 0.7, STATUS_ROW_ALPHA),
+        ),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PipState {
+    Empty,
+    Half,
+    Full,
+}
+
+#[derive(Resource, Clone)]
+struct StatusIconHandles {
+    food_empty: Handle<Image>,
+    food_half: Handle<Image>,
+    food_full: Handle<Image>,
+    health_empty: Handle<Image>,
+    health_half: Handle<Image>,
+    health_full: Handle<Image>,
+    stamina_empty: Handle<Image>,
+    stamina_half: Handle<Image>,
+    stamina_full: Handle<Image>,
+}
+
+impl StatusIconHandles {
+    fn new(asset_server: &AssetServer) -> Self {
+        Self {
+            food_empty: asset_server.load("food_empty.png"),
+            food_half: asset_server.load("food_half.png"),
+            food_full: asset_server.load("food_full.png"),
+            health_empty: asset_server.load("health_empty.png"),
+            health_half: asset_server.load("health_half.png"),
+            health_full: asset_server.load("health_full.png"),
+            stamina_empty: asset_server.load("stamina_empty.png"),
+            stamina_half: asset_server.load("stamina_half.png"),
+            stamina_full: asset_server.load("stamina_full.png"),
+        }
+    }
+
+    fn handle_for(&self, kind: StatusKind, state: PipState) -> Handle<Image> {
+        match (kind, state) {
+            // Swap empty/full visuals so "full" reads as brighter.
+            (StatusKind::Food, PipState::Empty) => self.food_full.clone(),
+            (StatusKind::Food, PipState::Half) => self.food_half.clone(),
+            (StatusKind::Food, PipState::Full) => self.food_empty.clone(),
+            (StatusKind::Health, PipState::Empty) => self.health_full.clone(),
+            (StatusKind::Health, PipState::Half) => self.health_half.clone(),
+            (StatusKind::Health, PipState::Full) => self.health_empty.clone(),
+            (StatusKind::Stamina, PipState::Empty) => self.stamina_full.clone(),
+            (StatusKind::Stamina, PipState::Half) => self.stamina_half.clone(),
+            (StatusKind::Stamina, PipState::Full) => self.stamina_empty.clone(),
+        }
+    }
+}
+
+fn toggle_hud(input: Res<ButtonInput<KeyCode>>, mut hud: ResMut<HudVisibility>) {
+    if input.just_pressed(hud.toggle_key) {
+        hud.shown = !hud.shown;
+    }
+}
+
+fn apply_hud_visibility(
+    hud: Res<HudVisibility>,
+    mut element_query: Query<(&mut HudElement, &mut Visibility)>,
+) {
+    for (mut element, mut visibility) in &mut element_query {
+        if hud.shown {
+            if let Some(restore) = element.restore.take() {
+                *visibility = restore;
+            }
+        } else if *visibility != Visibility::Hidden {
+            element.restore = Some(*visibility);
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+// Widgets anchored to the same corner stack rather than overlap.
+fn setup_status_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    layout: Res<HudLayout>,
+) {
+    let icon_handles = StatusIconHandles::new(&asset_server);
+    commands.insert_resource(icon_handles.clone());
+
+    let corners = HudAnchor::ALL.map(|anchor| {
+        let corner = commands.spawn((
+            anchor.corner_node(),
+            HudElement::default(),
+            HudCorner(anchor),
+        ));
+        (anchor, corner.id())
+    });
+    for (index, (widget, anchor)) in layout.status_widgets().into_iter().enumerate() {
+        let Some(&(_, corner)) = corners.iter().find(|(corner, _)| *corner == anchor) else {
+            continue;
+        };
+        let spawn_row = match widget {
+            HudWidget::StatusPips => spawn_status_row,
+            HudWidget::StatusBars => spawn_status_bar,
+            HudWidget::Compass | HudWidget::Clock | HudWidget::Objectives => continue,
+        };
+        let mut panel = commands.spawn((
+            Node {
+                padding: UiRect::all(px(STATUS_PANEL_PADDING)),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                row_gap: px(STATUS_ROW_GAP),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.86, 0.86, 0.86, STATUS_PANEL_ALPHA)),
+            BorderColor::all(Color::srgb(0.25, 0.25, 0.25)),
+            ChildOf(corner),
+        ));
+        if index == 0 {
+            panel.insert(StatusPanel);
+        }
+        panel.with_children(|panel| {
+            for kind in StatusKind::ALL {
+                spawn_row(panel, &icon_handles, kind);
+            }
+        });
+    }
+}
+
+fn update_status_ui(
+    player_query: Query<&Stats, With<Player>>,
+    icon_handles: Res<StatusIconHandles>,
+    settings: Res<GameSettings>,
+    mut pip_query: Query<(&StatusPip, &mut ImageNode), Without<StatusBarIcon>>,
+    mut bar_icon_query: Query<(&StatusBarIcon, &mut ImageNode), Without<StatusPip>>,
+    mut bar_query: Query<(&StatusBarFill, &mut Node, &mut BackgroundColor)>,
+) {
+    let Ok(stats) = player_query.single() else {
+        return;
+    };
+
+    for (pip, mut image) in &mut pip_query {
+        let value = status_value(stats, pip.kind);
+        let state = pip_state(value, pip.index);
+        image.image = icon_handles.handle_for(pip.kind, state);
+        image.color = match state {
+            PipState::Empty => Color::WHITE,
+            PipState::Half | PipState::Full => status_pip_tint(settings.palette, pip.kind),
+        };
+    }
+    for (icon, mut image) in &mut bar_icon_query {
+        image.color = status_pip_tint(settings.palette, icon.kind);
+    }
+    for (bar, mut node, mut background) in &mut bar_query {
+        let fill = (status_value(stats, bar.kind) / STATS_MAX).clamp(0.0, 1.0);
+        node.width = percent(fill * 100.0);
+        background.set_if_neq(BackgroundColor(status_pip_tint(settings.palette, bar.kind)));
+    }
+}
+
+fn flash_status_rows(
+    time: Res<Time>,
+    settings: Res<GameSettings>,
+    mut meals: MessageReader<EatFood>,
+    mut row_query: Query<(&mut StatusRow, &mut BackgroundColor, &Children)>,
+    mut marker_query: Query<&mut Text, With<StatusFlashMarker>>,
+) {
+    for meal in meals.read() {
+        for (mut row, _, _) in &mut row_query {
+            let amount = match row.kind {
+                StatusKind::Food => meal.food_bar_regen,
+                StatusKind::Health => meal.health_regen,
+                StatusKind::Stamina => meal.stamina_regen,
+            };
+            if amount == 0.0 {
+                continue;
+            }
+            row.flash = STATUS_FLASH_SECONDS;
+            row.gained = amount > 0.0;
+        }
+    }
+    let (gain_color, loss_color) = status_flash_colors(settings.palette);
+    for (mut row, mut background, children) in &mut row_query {
+        row.flash = (row.flash - time.delta_secs()).max(0.0);
+        let fade = row.flash / STATUS_FLASH_SECONDS;
+        let flash_color = if row.gained { gain_color } else { loss_color };
+        background.set_if_neq(BackgroundColor(STATUS_ROW_COLOR.mix(&flash_color, fade)));
+        let marker = match (row.flash > 0.0, row.gained) {
+            (false, _) => "",
+            (true, true) => "+",
+            (true, false) => "-",
+        };
+        for child in children {
+            if let Ok(mut text) = marker_query.get_mut(*child)
+                && text.0 != marker
+            {
+                text.0 = marker.to_string();
+            }
+        }
+    }
+}
+
+fn status_row(kind: StatusKind) -> impl Bundle {
+    (
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: px(STATUS_PIP_GAP),
+            padding: UiRect::all(px(4.0)),
+            ..default()
+        },
+        BackgroundColor(STATUS_ROW_COLOR),
+        StatusRow {
+            kind,
+            flash: 0.0,
+            gained: true,
+        },
+    )
+}
+
+fn spawn_flash_marker(row: &mut ChildSpawnerCommands) {
+    row.spawn((
+        Node {
+            width: px(STATUS_MARKER_WIDTH),
+            align_self: AlignSelf::Center,
+            ..default()
+        },
+        Text::new(""),
+        TextFont::from_font_size(18.0),
+        TextColor(Color::srgb(0.15, 0.15, 0.15)),
+        StatusFlashMarker,
+    ));
+}
+
+fn spawn_status_row(
+    parent: &mut ChildSpawnerCommands,
+    icon_handles: &StatusIconHandles,
+    kind: StatusKind,
+) {
+    parent.spawn(status_row(kind)).with_children(|row| {
+        for index in 0..STATUS_PIPS {
+            row.spawn((
+                Node {
+                    width: px(STATUS_ICON_SIZE),
+                    height: px(STATUS_ICON_SIZE),
+                    ..default()
+                },
+                ImageNode::new(icon_handles.handle_for(kind, PipState::Full)),
+                StatusPip { kind, index },
+            ));
+        }
+        spawn_flash_marker(row);
+    });
+}
+
+// The full pip icon names the bar, which matters with the monochrome
+// palette.
+fn spawn_status_bar(
+    parent: &mut ChildSpawnerCommands,
+    icon_handles: &StatusIconHandles,
+    kind: StatusKind,
+) {
+    parent.spawn(status_row(kind)).with_children(|row| {
+        row.spawn((
+            Node {
+                width: px(STATUS_BAR_ICON_SIZE),
+                height: px(STATUS_BAR_ICON_SIZE),
+                ..default()
+            },
+            ImageNode::new(icon_handles.handle_for(kind, PipState::Full)),
+            StatusBarIcon { kind },
+        ));
+        row.spawn((
+            Node {
+                width: px(STATUS_BAR_WIDTH),
+                height: px(STATUS_BAR_HEIGHT),
+                ..default()
+            },
+            BackgroundColor(STATUS_BAR_TRACK),
+        ))
+        .with_child((
+            Node {
+                width: percent(100.0),
+                height: percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::WHITE),
+            StatusBarFill { kind },
+        ));
+        spawn_flash_marker(row);
+    });
+}
+
+fn status_value(stats: &Stats, kind: StatusKind) -> f32 {
+    match kind {
+        StatusKind::Food => stats.food_bar,
+        StatusKind::Health => stats.health,
+        StatusKind::Stamina => stats.stamina,
+    }
+}
+
+fn pip_state(value: f32, index: usize) -> PipState {
+    let clamped_value = value.clamp(0.0, 100.0);
+    let start = index as f32 * STATUS_CHUNK;
+    let fill = ((clamped_value - start) / STATUS_CHUNK).clamp(0.0, 1.0);
+
+    if fill > 0.5 {
+        PipState::Full
+    } else if fill > 0.0 {
+        PipState::Half
+    } else {
+        PipState::Empty
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HudPlugin {
+    pub toggle_key: KeyCode,
+    // Starts with the HUD hidden.
+    pub immersive: bool,
+    pub layout: HudLayout,
+}
+
+impl Default for HudPlugin {
+    fn default() -> Self {
+        Self {
+            toggle_key: DEFAULT_TOGGLE_KEY,
+            immersive: false,
+            layout: HudLayout::default(),
+        }
+    }
+}
+
+impl HudPlugin {
+    pub fn with_toggle_key(mut self, key: KeyCode) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    pub fn immersive(mut self) -> Self {
+        self.immersive = true;
+        self
+    }
+
+    pub fn with_stat_display(mut self, display: StatDisplay) -> Self {
+        self.layout.stats = display;
+        self
+    }
+
+    pub fn with_anchor(mut self, widget: HudWidget, anchor: HudAnchor) -> Self {
+        match widget {
+            HudWidget::StatusPips => self.layout.pips_anchor = anchor,
+            HudWidget::StatusBars => self.layout.bars_anchor = anchor,
+            HudWidget::Compass => self.layout.compass_anchor = anchor,
+            HudWidget::Clock => self.layout.clock_anchor = anchor,
+            HudWidget::Objectives => self.layout.objectives_anchor = anchor,
+        }
+        self
+    }
+}
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HudVisibility {
+            shown: !self.immersive,
+            toggle_key: self.toggle_key,
+        })
+        .insert_resource(self.layout.clone())
+        .add_systems(Startup, setup_status_ui)
+        .add_systems(
+            Update,
+            (
+                toggle_hud,
+                (update_status_ui, flash_status_rows).run_if(in_state(AppState::Playing)),
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            apply_hud_visibility.before(VisibilitySystems::VisibilityPropagate),
+        );
+    }
+}
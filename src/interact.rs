@@ -0,0 +1,137 @@
+// interaction prompt: anything the interact key does something with carries
+// an Interactable, and the closest one in reach gets a "Press E to ..."
+// prompt under it. The modules that own those things keep enabled up to
+// date and still handle the key press themselves.
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::hud::HudVisibility;
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player};
+use crate::settings::{key_label, Action, GameSettings};
+use crate::MainCamera;
+
+// Screen pixels between the thing and the top of the prompt.
+const PROMPT_GAP: f32 = 14.0;
+
+#[derive(Component, Clone, Debug)]
+pub struct Interactable {
+    // Locale key for what the key does, e.g. "interact.open".
+    pub verb: &'static str,
+    // In world units from the player.
+    pub reach: f32,
+    // Off while there's nothing to do, like a bush that's been picked.
+    pub enabled: bool,
+}
+
+impl Interactable {
+    pub fn new(verb: &'static str, reach: f32) -> Self {
+        Self {
+            verb,
+            reach,
+            enabled: true,
+        }
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+}
+
+#[derive(Component)]
+struct InteractPrompt;
+
+#[derive(Component)]
+struct InteractPromptText;
+
+// Under the tooltip, which describes the same thing from above.
+fn setup_interact_prompt(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::axes(px(6.0), px(3.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.06, 0.06, 0.07, 0.8)),
+            GlobalZIndex(150),
+            Visibility::Hidden,
+            InteractPrompt,
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont::from_font_size(14.0),
+            TextColor(Color::srgb(0.95, 0.9, 0.7)),
+            InteractPromptText,
+        ));
+}
+
+// Things out of sight in the dark don't get a prompt. It's part of the HUD,
+// so it goes when the HUD is hidden.
+fn update_interact_prompt(
+    state: Res<State<AppState>>,
+    death_state: Res<DeathRespawnState>,
+    hud: Res<HudVisibility>,
+    (locale, settings): (Res<Locale>, Res<GameSettings>),
+    ui_scale: Res<UiScale>,
+    player_query: Query<&Transform, With<Player>>,
+    interactable_query: Query<(&Interactable, &GlobalTransform, &InheritedVisibility)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut prompt_query: Query<(&mut Node, &mut Visibility, &ComputedNode), With<InteractPrompt>>,
+    mut text_query: Query<&mut Text, With<InteractPromptText>>,
+) {
+    let Ok((mut node, mut visibility, panel)) = prompt_query.single_mut() else {
+        return;
+    };
+    let playing = *state.get() == AppState::Playing && !death_state.is_dead && hud.shown;
+    let closest = player_query.single().ok().filter(|_| playing).and_then(|player| {
+        let player_pos = player.translation.truncate();
+        interactable_query
+            .iter()
+            .filter(|(interactable, _, shown)| interactable.enabled && shown.get())
+            .map(|(interactable, transform, _)| {
+                let position = transform.translation().truncate();
+                (interactable, position, position.distance(player_pos))
+            })
+            .filter(|(interactable, _, distance)| *distance <= interactable.reach)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    });
+    let placed = closest.and_then(|(interactable, position, _)| {
+        let (camera, camera_transform) = camera_query.single().ok()?;
+        let viewport = camera.world_to_viewport(camera_transform, position.extend(0.0)).ok()?;
+        Some((interactable.verb, viewport))
+    });
+    let (Some((verb, viewport)), Ok(window)) = (placed, window_query.single()) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    let scale = ui_scale.0.max(f32::EPSILON);
+    let screen = Vec2::new(window.width(), window.height()) / scale;
+    let size = panel.size() * panel.inverse_scale_factor();
+    let position = Vec2::new(viewport.x / scale - size.x * 0.5, viewport.y / scale + PROMPT_GAP);
+    let position = position.min(screen - size).max(Vec2::ZERO);
+    visibility.set_if_neq(Visibility::Visible);
+    node.left = px(position.x);
+    node.top = px(position.y);
+
+    let key = key_label(settings.key(Action::Interact));
+    let prompt = locale.format("interact.prompt", &[("key", &key), ("verb", locale.get(verb))]);
+    if let Ok(mut text) = text_query.single_mut()
+        && text.0 != prompt
+    {
+        text.0 = prompt;
+    }
+}
+
+pub struct InteractPlugin;
+
+impl Plugin for InteractPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_interact_prompt)
+            .add_systems(Update, update_interact_prompt);
+    }
+}
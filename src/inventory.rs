@@ -0,0 +1,481 @@
+// inventory screen: a grid of carried stacks with eat, drop, split and equip
+// actions, driven by mouse, keyboard or a gamepad
+use bevy::prelude::*;
+
+use crate::event_log::EventLog;
+use crate::food::{EatFood, Location2D};
+use crate::food_catalog::FoodCatalog;
+use crate::items::{spawn_world_item, Inventory, ItemKind, INVENTORY_SLOTS};
+use crate::locale::{Locale, LocalizedText};
+use crate::menu::{AppState, MenuFocus, MenuNav};
+use crate::player::{DeathRespawnState, Player};
+use crate::progress::PersistentProgress;
+use crate::settings::{key_label, Action, GameSettings};
+use crate::tools::ToolBelt;
+use crate::tooltip::{Tooltip, TooltipAnchor};
+use crate::world::WORLD_TILE_SIZE;
+
+const SLOT_COLUMNS: u16 = 4;
+const SLOT_SIZE: f32 = 56.0;
+const ICON_SIZE: f32 = 32.0;
+const SLOT_COLOR: Color = Color::srgb(0.22, 0.22, 0.24);
+const SELECTED_SLOT_COLOR: Color = Color::srgb(0.42, 0.4, 0.3);
+const BUTTON_COLOR: Color = Color::srgb(0.3, 0.3, 0.32);
+
+#[derive(Resource, Default)]
+pub struct InventoryScreen {
+    pub open: bool,
+    pub selected: usize,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum InventoryAction {
+    Consume,
+    Drop,
+    Split,
+    Equip,
+}
+
+impl InventoryAction {
+    const ALL: [InventoryAction; 4] = [
+        InventoryAction::Consume,
+        InventoryAction::Drop,
+        InventoryAction::Split,
+        InventoryAction::Equip,
+    ];
+
+    fn key_action(self) -> Action {
+        match self {
+            InventoryAction::Consume => Action::EatItem,
+            InventoryAction::Drop => Action::DropItem,
+            InventoryAction::Split => Action::SplitStack,
+            InventoryAction::Equip => Action::EquipItem,
+        }
+    }
+
+    fn label_key(self) -> &'static str {
+        match self {
+            InventoryAction::Consume => "inventory.eat",
+            InventoryAction::Drop => "inventory.drop",
+            InventoryAction::Split => "inventory.split",
+            InventoryAction::Equip => "inventory.equip",
+        }
+    }
+
+    // Split has no gamepad button.
+    fn pad_button(self) -> Option<GamepadButton> {
+        match self {
+            InventoryAction::Consume => Some(GamepadButton::South),
+            InventoryAction::Drop => Some(GamepadButton::West),
+            InventoryAction::Split => None,
+            InventoryAction::Equip => Some(GamepadButton::North),
+        }
+    }
+}
+
+#[derive(Component)]
+struct InventoryOverlay;
+
+#[derive(Component)]
+struct InventorySlot(usize);
+
+#[derive(Component)]
+struct InventorySlotIcon(usize);
+
+#[derive(Component)]
+struct InventorySlotCount(usize);
+
+#[derive(Component)]
+struct InventoryDetailsText;
+
+// An action button's label, which names its key.
+#[derive(Component)]
+struct InventoryActionLabel(InventoryAction);
+
+fn setup_inventory_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(0.0),
+                width: percent(100.0),
+                height: percent(100.0),
+                display: Display::Flex,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            GlobalZIndex(150),
+            Visibility::Hidden,
+            InventoryOverlay,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(px(16.0)),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        row_gap: px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.12, 0.12, 0.13)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(24.0),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        LocalizedText("inventory.title"),
+                    ));
+                    panel
+                        .spawn(Node {
+                            display: Display::Grid,
+                            grid_template_columns: RepeatedGridTrack::px(SLOT_COLUMNS, SLOT_SIZE),
+                            row_gap: px(6.0),
+                            column_gap: px(6.0),
+                            ..default()
+                        })
+                        .with_children(|grid| {
+                            for index in 0..INVENTORY_SLOTS {
+                                spawn_slot(grid, index);
+                            }
+                        });
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(16.0),
+                        TextColor(Color::srgb(0.85, 0.85, 0.8)),
+                        InventoryDetailsText,
+                    ));
+                    panel
+                        .spawn(Node {
+                            display: Display::Flex,
+                            column_gap: px(8.0),
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            for action in InventoryAction::ALL {
+                                spawn_action_button(row, action);
+                            }
+                        });
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(14.0),
+                        TextColor(Color::srgb(0.55, 0.55, 0.55)),
+                        LocalizedText("inventory.hint"),
+                    ));
+                });
+        });
+}
+
+fn spawn_slot(grid: &mut ChildSpawnerCommands, index: usize) {
+    grid.spawn((
+        Button,
+        Node {
+            width: px(SLOT_SIZE),
+            height: px(SLOT_SIZE),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(SLOT_COLOR),
+        Tooltip::default(),
+        TooltipAnchor::default(),
+        MenuFocus::default(),
+        InventorySlot(index),
+    ))
+    .with_children(|slot| {
+        slot.spawn((
+            Node {
+                width: px(ICON_SIZE),
+                height: px(ICON_SIZE),
+                ..default()
+            },
+            ImageNode::default(),
+            Visibility::Hidden,
+            InventorySlotIcon(index),
+        ));
+        slot.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: px(4.0),
+                bottom: px(2.0),
+                ..default()
+            },
+            Text::new(""),
+            TextFont::from_font_size(14.0),
+            TextColor(Color::srgb(0.95, 0.95, 0.95)),
+            InventorySlotCount(index),
+        ));
+    });
+}
+
+fn spawn_action_button(row: &mut ChildSpawnerCommands, action: InventoryAction) {
+    row.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(px(12.0), px(6.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+        action,
+    ))
+    .with_child((
+        Text::new(""),
+        TextFont::from_font_size(16.0),
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        InventoryActionLabel(action),
+    ));
+}
+
+// Select on a gamepad opens and closes it, East backs out.
+fn toggle_inventory_screen(
+    input: Res<ButtonInput<KeyCode>>,
+    nav: Res<MenuNav>,
+    settings: Res<GameSettings>,
+    death_state: Res<DeathRespawnState>,
+    mut screen: ResMut<InventoryScreen>,
+    gamepads: Query<&Gamepad>,
+) {
+    if death_state.is_dead {
+        screen.open = false;
+        return;
+    }
+    let pad = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+    if input.just_pressed(settings.key(Action::Inventory)) || pad(GamepadButton::Select) {
+        screen.open = !screen.open;
+    } else if screen.open && nav.back {
+        screen.open = false;
+    }
+}
+
+// Moves round the grid, wrapping at the ends of rows and columns. Empty
+// slots can be selected too, so the cursor goes where it's pointed.
+fn navigate_inventory(nav: Res<MenuNav>, mut screen: ResMut<InventoryScreen>) {
+    if !screen.open {
+        return;
+    }
+    let columns = SLOT_COLUMNS as usize;
+    let (column, row) = (screen.selected % columns, screen.selected / columns);
+    let rows = INVENTORY_SLOTS.div_ceil(columns);
+    let (column, row) = if nav.left {
+        ((column + columns - 1) % columns, row)
+    } else if nav.right {
+        ((column + 1) % columns, row)
+    } else if nav.up {
+        (column, (row + rows - 1) % rows)
+    } else if nav.down {
+        (column, (row + 1) % rows)
+    } else {
+        return;
+    };
+    screen.selected = (row * columns + column).min(INVENTORY_SLOTS - 1);
+}
+
+fn select_inventory_slot(
+    mut screen: ResMut<InventoryScreen>,
+    slot_query: Query<(&Interaction, &InventorySlot), Changed<Interaction>>,
+) {
+    for (interaction, slot) in &slot_query {
+        if *interaction == Interaction::Pressed {
+            screen.selected = slot.0;
+        }
+    }
+}
+
+fn use_inventory_item(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    screen: Res<InventoryScreen>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    locale: Res<Locale>,
+    settings: Res<GameSettings>,
+    mut meals: MessageWriter<EatFood>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory, &mut ToolBelt), With<Player>>,
+    button_query: Query<(&Interaction, &InventoryAction), Changed<Interaction>>,
+    gamepads: Query<&Gamepad>,
+) {
+    if !screen.open {
+        return;
+    }
+    let pressed = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, action)| *action);
+    let pad = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+    let Some(action) = InventoryAction::ALL
+        .into_iter()
+        .find(|action| {
+            input.just_pressed(settings.key(action.key_action()))
+                || action.pad_button().is_some_and(pad)
+        })
+        .or(pressed)
+    else {
+        return;
+    };
+    let Ok((transform, mut inventory, mut belt)) = player_query.single_mut() else {
+        return;
+    };
+    let Some((kind, _)) = inventory.slot(screen.selected) else {
+        return;
+    };
+
+    match action {
+        InventoryAction::Consume => {
+            let ItemKind::Food(food) = kind else {
+                let name = kind.display_name(&progress, &locale).to_lowercase();
+                event_log.push(&time, format!("can't eat {name}"));
+                return;
+            };
+            let Some(def) = catalog.get(food) else {
+                return;
+            };
+            inventory.remove_from_slot(screen.selected, 1);
+            meals.write(EatFood::from_def(def));
+        }
+        InventoryAction::Drop => {
+            inventory.remove_from_slot(screen.selected, 1);
+            let tile = (transform.translation.truncate() / WORLD_TILE_SIZE).floor().as_ivec2();
+            spawn_world_item(&mut commands, kind, Location2D { x: tile.x, y: tile.y });
+        }
+        InventoryAction::Split => {
+            if !inventory.split(screen.selected) {
+                event_log.push(&time, "can't split that stack");
+            }
+        }
+        // Only the tools go on the belt; they stay in the bag as well.
+        InventoryAction::Equip => {
+            let name = kind.display_name(&progress, &locale).to_lowercase();
+            if kind.is_tool() {
+                belt.equipped = Some(kind);
+                event_log.push(&time, format!("equipped the {name}"));
+            } else {
+                event_log.push(&time, format!("can't equip {name}"));
+            }
+        }
+    }
+}
+
+fn update_inventory_screen(
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    settings: Res<GameSettings>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    screen: Res<InventoryScreen>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut overlay_query: Query<&mut Visibility, (With<InventoryOverlay>, Without<InventorySlotIcon>)>,
+    mut slot_query: Query<(
+        &InventorySlot,
+        &mut BackgroundColor,
+        &mut Tooltip,
+        &mut TooltipAnchor,
+        &mut MenuFocus,
+    )>,
+    mut icon_query: Query<(&InventorySlotIcon, &mut ImageNode, &mut Visibility)>,
+    mut count_query: Query<
+        (&InventorySlotCount, &mut Text),
+        (Without<InventoryDetailsText>, Without<InventoryActionLabel>),
+    >,
+    mut details_query: Query<
+        &mut Text,
+        (With<InventoryDetailsText>, Without<InventoryActionLabel>),
+    >,
+    mut label_query: Query<(&InventoryActionLabel, &mut Text)>,
+) {
+    if let Ok(mut visibility) = overlay_query.single_mut() {
+        visibility.set_if_neq(if screen.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !screen.open {
+        return;
+    }
+    let Ok(inventory) = player_query.single() else {
+        return;
+    };
+    for (slot, mut color, mut tooltip, mut anchor, mut focus) in &mut slot_query {
+        let selected = slot.0 == screen.selected;
+        color.set_if_neq(BackgroundColor(if selected {
+            SELECTED_SLOT_COLOR
+        } else {
+            SLOT_COLOR
+        }));
+        if anchor.focused != selected {
+            anchor.focused = selected;
+        }
+        if focus.focused != selected {
+            focus.focused = selected;
+        }
+        let text = inventory
+            .slot(slot.0)
+            .map(|(kind, _)| kind.describe(&catalog, &progress, &locale))
+            .unwrap_or_default();
+        if tooltip.0 != text {
+            tooltip.0 = text;
+        }
+    }
+    for (icon, mut image, mut visibility) in &mut icon_query {
+        let Some((kind, _)) = inventory.slot(icon.0) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        *image = kind.icon(&asset_server, &catalog);
+    }
+    for (count, mut text) in &mut count_query {
+        text.0 = match inventory.slot(count.0) {
+            Some((_, amount)) if amount > 1 => amount.to_string(),
+            _ => String::new(),
+        };
+    }
+    for (label, mut text) in &mut label_query {
+        let key = key_label(settings.key(label.0.key_action()));
+        let line = locale.format(label.0.label_key(), &[("key", &key)]);
+        if text.0 != line {
+            text.0 = line;
+        }
+    }
+    if let Ok(mut text) = details_query.single_mut() {
+        text.0 = match inventory.slot(screen.selected) {
+            Some((kind, amount)) => locale.format(
+                "inventory.details",
+                &[
+                    ("name", &kind.display_name(&progress, &locale)),
+                    ("count", &amount.to_string()),
+                    ("limit", &kind.stack_limit().to_string()),
+                    ("weight", &format!("{:.0}", kind.weight() * amount as f32)),
+                ],
+            ),
+            None => locale.get("inventory.empty").to_string(),
+        };
+    }
+}
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InventoryScreen::default())
+            .add_systems(Startup, setup_inventory_screen)
+            .add_systems(
+                Update,
+                (
+                    toggle_inventory_screen,
+                    navigate_inventory,
+                    select_inventory_slot,
+                    use_inventory_item,
+                    update_inventory_screen,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
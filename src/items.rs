@@ -0,0 +1,632 @@
+// carried items and gathered resources
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::event_log::EventLog;
+use crate::food::Location2D;
+use crate::food_catalog::{payload_lines, FoodCatalog, FoodItem, Rarity};
+use crate::hud::HudElement;
+use crate::interact::Interactable;
+use crate::light::Lightable;
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player};
+use crate::progress::PersistentProgress;
+use crate::settings::{Action, GameSettings};
+use crate::world::{WorldRegenerated, WORLD_TILE_SIZE};
+
+pub const ITEM_PICKUP_RADIUS: f32 = 20.0;
+const WORLD_ITEM_SIZE: f32 = 6.0;
+// Carrying more than CARRY_CAPACITY slows the player down; nothing more can
+// be picked up past MAX_CARRY_WEIGHT.
+pub const CARRY_CAPACITY: f32 = 20.0;
+pub const MAX_CARRY_WEIGHT: f32 = 45.0;
+pub const INVENTORY_SLOTS: usize = 12;
+// Drinks in a full bottle.
+pub const BOTTLE_SIPS: u8 = 3;
+const BOTTLE_EMPTY_TINT: Color = Color::srgb(0.72, 0.75, 0.78);
+const BOTTLE_FULL_TINT: Color = Color::srgb(0.25, 0.5, 0.95);
+const WEIGHT_METER_WIDTH: f32 = 140.0;
+const WEIGHT_METER_HEIGHT: f32 = 8.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Axe,
+    Pickaxe,
+    Wood,
+    Stone,
+    Fiber,
+    Torch,
+    Campfire,
+    // Points the way to food; see the compass module.
+    Compass,
+    Food(FoodItem),
+    // Grows into the food it came from.
+    Seeds(FoodItem),
+    // Holds how many sips of water are left, up to BOTTLE_SIPS.
+    Bottle(u8),
+}
+
+// Every item that isn't tied to a food, for looking items up by name.
+const PLAIN_ITEMS: [ItemKind; 8] = [
+    ItemKind::Axe,
+    ItemKind::Pickaxe,
+    ItemKind::Wood,
+    ItemKind::Stone,
+    ItemKind::Fiber,
+    ItemKind::Torch,
+    ItemKind::Campfire,
+    ItemKind::Compass,
+];
+
+impl ItemKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemKind::Axe => "Axe",
+            ItemKind::Pickaxe => "Pickaxe",
+            ItemKind::Wood => "Wood",
+            ItemKind::Stone => "Stone",
+            ItemKind::Fiber => "Fiber",
+            ItemKind::Torch => "Torch",
+            ItemKind::Campfire => "Campfire",
+            ItemKind::Compass => "Compass",
+            ItemKind::Food(food) => food.looks_like,
+            ItemKind::Seeds(_) => "Seeds",
+            ItemKind::Bottle(_) => "Bottle",
+        }
+    }
+
+    pub fn weight(self) -> f32 {
+        match self {
+            ItemKind::Axe => 3.0,
+            ItemKind::Pickaxe => 4.0,
+            ItemKind::Wood => 2.0,
+            ItemKind::Stone => 3.0,
+            ItemKind::Fiber => 0.5,
+            ItemKind::Torch => 1.0,
+            ItemKind::Campfire => 6.0,
+            ItemKind::Compass => 0.5,
+            ItemKind::Food(_) => 1.0,
+            ItemKind::Seeds(_) => 0.2,
+            ItemKind::Bottle(sips) => 0.5 + 0.5 * sips as f32,
+        }
+    }
+
+    // Seeds say what they grow into; disguised food shows its real name
+    // once identified. Other items go by item.<name> in the locale.
+    pub fn display_name(self, progress: &PersistentProgress, locale: &Locale) -> String {
+        match self {
+            ItemKind::Food(food) => progress.food_name(food).to_string(),
+            ItemKind::Seeds(food) => {
+                locale.format("item.seeds", &[("food", progress.food_name(food))])
+            }
+            ItemKind::Bottle(0) => locale.get("item.bottle_empty").to_string(),
+            ItemKind::Bottle(sips) => locale.format(
+                "item.bottle_water",
+                &[("sips", &sips.to_string()), ("max", &BOTTLE_SIPS.to_string())],
+            ),
+            _ => locale.get(&format!("item.{}", self.name().to_lowercase())).to_string(),
+        }
+    }
+
+    // Tooltip text. Disguised food gives nothing away until identified.
+    pub fn describe(
+        self,
+        catalog: &FoodCatalog,
+        progress: &PersistentProgress,
+        locale: &Locale,
+    ) -> String {
+        let mut lines = vec![self.display_name(progress, locale)];
+        if let ItemKind::Food(food) = self
+            && progress.identifies(food)
+            && let Some(def) = catalog.get(food)
+        {
+            lines.extend(payload_lines(
+                locale,
+                def.food_bar_regen,
+                def.health_regen,
+                def.stamina_regen,
+                def.effect,
+            ));
+        }
+        let weight = format!("{:.1}", self.weight());
+        lines.push(locale.format("tooltip.weight", &[("weight", &weight)]));
+        lines.join("\n")
+    }
+
+    pub fn stack_limit(self) -> u32 {
+        match self {
+            ItemKind::Axe | ItemKind::Pickaxe => 1,
+            ItemKind::Wood | ItemKind::Stone => 20,
+            ItemKind::Fiber => 30,
+            ItemKind::Torch => 10,
+            ItemKind::Campfire => 2,
+            ItemKind::Compass => 1,
+            ItemKind::Food(_) => 10,
+            ItemKind::Seeds(_) => 30,
+            // Each bottle keeps its own fill level.
+            ItemKind::Bottle(_) => 1,
+        }
+    }
+
+    // Stable across runs: food is saved by its real name, not its id.
+    pub fn save_key(self) -> String {
+        match self {
+            ItemKind::Food(food) => format!("Food:{}", food.name),
+            ItemKind::Seeds(food) => format!("Seeds:{}", food.name),
+            ItemKind::Bottle(sips) => format!("Bottle:{sips}"),
+            _ => self.name().to_string(),
+        }
+    }
+
+    pub fn from_save_key(key: &str, catalog: &FoodCatalog) -> Option<Self> {
+        if let Some(name) = key.strip_prefix("Food:") {
+            return catalog.find(name).map(|def| ItemKind::Food(def.item));
+        }
+        if let Some(name) = key.strip_prefix("Seeds:") {
+            return catalog.find(name).map(|def| ItemKind::Seeds(def.item));
+        }
+        if let Some(sips) = key.strip_prefix("Bottle:") {
+            return sips.parse().ok().map(|sips: u8| ItemKind::Bottle(sips.min(BOTTLE_SIPS)));
+        }
+        PLAIN_ITEMS.into_iter().find(|kind| kind.name() == key)
+    }
+
+    // Food takes its rarity from the food table; everything else is common
+    // apart from tools.
+    pub fn rarity(self, catalog: &FoodCatalog) -> Rarity {
+        match self {
+            ItemKind::Food(food) | ItemKind::Seeds(food) => {
+                catalog.get(food).map_or(Rarity::Common, |def| def.rarity)
+            }
+            ItemKind::Axe | ItemKind::Pickaxe => Rarity::Uncommon,
+            _ => Rarity::Common,
+        }
+    }
+
+    pub fn is_tool(self) -> bool {
+        matches!(self, ItemKind::Axe | ItemKind::Pickaxe)
+    }
+
+    // Items without their own texture are drawn as a plain tinted square;
+    // a bottle's colour shows how full it is.
+    pub fn icon(self, asset_server: &AssetServer, catalog: &FoodCatalog) -> ImageNode {
+        match self {
+            ItemKind::Food(food) => {
+                let tint = catalog.get(food).map_or(Color::WHITE, |def| def.tint);
+                ImageNode::new(asset_server.load(food.texture)).with_color(tint)
+            }
+            _ => ImageNode::solid_color(self.tint()),
+        }
+    }
+
+    pub fn tint(self) -> Color {
+        match self {
+            ItemKind::Axe | ItemKind::Pickaxe => Color::srgb(0.7, 0.7, 0.75),
+            ItemKind::Wood => Color::srgb(0.55, 0.38, 0.2),
+            ItemKind::Stone => Color::srgb(0.6, 0.6, 0.62),
+            ItemKind::Fiber => Color::srgb(0.6, 0.7, 0.35),
+            ItemKind::Torch => Color::srgb(0.95, 0.7, 0.3),
+            ItemKind::Campfire => Color::srgb(0.85, 0.4, 0.15),
+            ItemKind::Compass => Color::srgb(0.8, 0.7, 0.45),
+            ItemKind::Food(_) => Color::srgb(0.8, 0.2, 0.15),
+            ItemKind::Seeds(_) => Color::srgb(0.75, 0.65, 0.4),
+            ItemKind::Bottle(sips) => {
+                BOTTLE_EMPTY_TINT.mix(&BOTTLE_FULL_TINT, sips as f32 / BOTTLE_SIPS as f32)
+            }
+        }
+    }
+}
+
+// An item lying on the ground, picked up with E.
+#[derive(Component)]
+pub struct WorldItem {
+    pub kind: ItemKind,
+}
+
+pub fn spawn_world_item(commands: &mut Commands, kind: ItemKind, location: Location2D) {
+    commands.spawn((
+        WorldItem { kind },
+        Interactable::new("interact.pick_up", ITEM_PICKUP_RADIUS * WORLD_TILE_SIZE),
+        Lightable { tint: kind.tint() },
+        location,
+        Sprite::from_color(Color::WHITE, Vec2::splat(WORLD_ITEM_SIZE)),
+        Visibility::Hidden,
+        Transform::from_translation(Vec3::new(
+            location.x as f32 * WORLD_TILE_SIZE,
+            location.y as f32 * WORLD_TILE_SIZE,
+            0.8,
+        )),
+    ));
+}
+
+// Everything the player carries, as stacks in the order they were first
+// filled. One kind of item can span several stacks once it passes its stack
+// limit or a stack is split.
+#[derive(Component, Default)]
+pub struct Inventory {
+    slots: Vec<(ItemKind, u32)>,
+}
+
+// How a stack is written to a save: the item by its save key, so food stays
+// valid as long as foods.ron still has an entry of that name.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SavedStack {
+    pub item: String,
+    pub count: u32,
+}
+
+impl Inventory {
+    // Tops up existing stacks first, then opens new ones while slots are
+    // free, and returns whatever didn't fit.
+    pub fn add(&mut self, kind: ItemKind, amount: u32) -> u32 {
+        let limit = kind.stack_limit();
+        let mut left = amount;
+        for (slot, count) in &mut self.slots {
+            if left == 0 {
+                break;
+            }
+            if *slot == kind && *count < limit {
+                let moved = left.min(limit - *count);
+                *count += moved;
+                left -= moved;
+            }
+        }
+        while left > 0 && self.slots.len() < INVENTORY_SLOTS {
+            let moved = left.min(limit);
+            self.slots.push((kind, moved));
+            left -= moved;
+        }
+        left
+    }
+
+    // Takes from the last stacks of the kind first, so the earlier (hotbar)
+    // stacks stay full.
+    pub fn remove(&mut self, kind: ItemKind, amount: u32) -> bool {
+        if self.count(kind) < amount {
+            return false;
+        }
+        let mut left = amount;
+        for index in (0..self.slots.len()).rev() {
+            if left == 0 {
+                break;
+            }
+            if self.slots[index].0 != kind {
+                continue;
+            }
+            let taken = left.min(self.slots[index].1);
+            self.slots[index].1 -= taken;
+            left -= taken;
+        }
+        self.slots.retain(|(_, count)| *count > 0);
+        true
+    }
+
+    pub fn remove_from_slot(&mut self, index: usize, amount: u32) -> bool {
+        let Some((_, count)) = self.slots.get_mut(index) else {
+            return false;
+        };
+        if *count < amount {
+            return false;
+        }
+        *count -= amount;
+        if *count == 0 {
+            self.slots.remove(index);
+        }
+        true
+    }
+
+    // Moves half of a stack (rounded down) into a new slot right after it.
+    pub fn split(&mut self, index: usize) -> bool {
+        let Some((kind, count)) = self.slot(index) else {
+            return false;
+        };
+        if count < 2 || self.slots.len() >= INVENTORY_SLOTS {
+            return false;
+        }
+        let half = count / 2;
+        self.slots[index].1 -= half;
+        self.slots.insert(index + 1, (kind, half));
+        true
+    }
+
+    pub fn count(&self, kind: ItemKind) -> u32 {
+        self.slots
+            .iter()
+            .filter(|(slot, _)| *slot == kind)
+            .map(|(_, count)| *count)
+            .sum()
+    }
+
+    pub fn slots(&self) -> &[(ItemKind, u32)] {
+        &self.slots
+    }
+
+    pub fn slot(&self, index: usize) -> Option<(ItemKind, u32)> {
+        self.slots.get(index).copied()
+    }
+
+    // Swaps what a slot holds in place, e.g. a bottle as it's filled or
+    // drunk from.
+    pub fn set_slot(&mut self, index: usize, kind: ItemKind) {
+        if let Some((slot, _)) = self.slots.get_mut(index) {
+            *slot = kind;
+        }
+    }
+
+    // How many more of the kind fit in its stacks and the free slots.
+    fn stack_room(&self, kind: ItemKind) -> u32 {
+        let limit = kind.stack_limit();
+        let in_stacks: u32 = self
+            .slots
+            .iter()
+            .filter(|(slot, _)| *slot == kind)
+            .map(|(_, count)| limit.saturating_sub(*count))
+            .sum();
+        let free_slots = (INVENTORY_SLOTS - self.slots.len()) as u32;
+        in_stacks + free_slots * limit
+    }
+
+    // Adds as many as fit under MAX_CARRY_WEIGHT (and in the stacks) and
+    // returns the rest.
+    pub fn add_within_limit(&mut self, kind: ItemKind, amount: u32) -> u32 {
+        let room = ((MAX_CARRY_WEIGHT - self.weight()) / kind.weight()).floor().max(0.0) as u32;
+        let taken = amount.min(room).min(self.stack_room(kind));
+        if taken > 0 {
+            self.add(kind, taken);
+        }
+        amount - taken
+    }
+
+    pub fn weight(&self) -> f32 {
+        self.slots
+            .iter()
+            .map(|(kind, count)| kind.weight() * *count as f32)
+            .sum()
+    }
+
+    // 0 up to CARRY_CAPACITY, rising to 1 at MAX_CARRY_WEIGHT.
+    pub fn burden(&self) -> f32 {
+        ((self.weight() - CARRY_CAPACITY) / (MAX_CARRY_WEIGHT - CARRY_CAPACITY)).clamp(0.0, 1.0)
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.clear();
+    }
+
+    // Empties the inventory, returning what it held.
+    pub fn take_all(&mut self) -> Vec<(ItemKind, u32)> {
+        std::mem::take(&mut self.slots)
+    }
+
+    pub fn to_saved(&self) -> Vec<SavedStack> {
+        self.slots
+            .iter()
+            .map(|(kind, count)| SavedStack {
+                item: kind.save_key(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    // Unknown items are skipped and oversized stacks are re-stacked, so an
+    // old or hand-edited save still loads.
+    pub fn from_saved(saved: &[SavedStack], catalog: &FoodCatalog) -> Self {
+        let mut inventory = Self::default();
+        for stack in saved {
+            match ItemKind::from_save_key(&stack.item, catalog) {
+                Some(kind) => {
+                    inventory.add(kind, stack.count);
+                }
+                None => warn!("dropping unknown saved item {}", stack.item),
+            }
+        }
+        inventory
+    }
+}
+
+fn pickup_world_items(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    item_query: Query<(Entity, &WorldItem, &Transform, &Visibility), Without<Player>>,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::Interact)) {
+        return;
+    }
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let mut too_heavy = false;
+    for (entity, item, transform, visibility) in &item_query {
+        if !matches!(*visibility, Visibility::Visible) {
+            continue;
+        }
+        let distance = transform.translation.truncate().distance(player_pos);
+        if distance > ITEM_PICKUP_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        if inventory.add_within_limit(item.kind, 1) > 0 {
+            too_heavy = true;
+            continue;
+        }
+        commands.entity(entity).despawn();
+    }
+    if too_heavy {
+        event_log.push(&time, "too heavy to carry any more");
+    }
+}
+
+#[derive(Component)]
+struct WeightMeterText;
+
+#[derive(Component)]
+struct WeightMeterFill;
+
+fn setup_weight_meter(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: px(16.0),
+                top: px(44.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                row_gap: px(4.0),
+                ..default()
+            },
+            HudElement::default(),
+        ))
+        .with_children(|column| {
+            column.spawn((
+                Text::new(""),
+                TextFont::from_font_size(14.0),
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                WeightMeterText,
+            ));
+            column
+                .spawn((
+                    Node {
+                        width: px(WEIGHT_METER_WIDTH),
+                        height: px(WEIGHT_METER_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+                ))
+                .with_children(|track| {
+                    track.spawn((
+                        Node {
+                            width: px(0.0),
+                            height: percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.5, 0.7, 0.4)),
+                        WeightMeterFill,
+                    ));
+                });
+        });
+}
+
+fn update_weight_meter(
+    locale: Res<Locale>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut text_query: Query<&mut Text, With<WeightMeterText>>,
+    mut fill_query: Query<(&mut Node, &mut BackgroundColor), With<WeightMeterFill>>,
+) {
+    let Ok(inventory) = player_query.single() else {
+        return;
+    };
+    let weight = inventory.weight();
+    if let Ok(mut text) = text_query.single_mut() {
+        text.0 = locale.format(
+            "hud.load",
+            &[
+                ("weight", &format!("{weight:.0}")),
+                ("max", &format!("{MAX_CARRY_WEIGHT:.0}")),
+            ],
+        );
+    }
+    let Ok((mut node, mut color)) = fill_query.single_mut() else {
+        return;
+    };
+    node.width = px((weight / MAX_CARRY_WEIGHT).clamp(0.0, 1.0) * WEIGHT_METER_WIDTH);
+    color.0 = if weight >= MAX_CARRY_WEIGHT {
+        Color::srgb(0.85, 0.2, 0.15)
+    } else if weight > CARRY_CAPACITY {
+        Color::srgb(0.9, 0.6, 0.2)
+    } else {
+        Color::srgb(0.5, 0.7, 0.4)
+    };
+}
+
+fn clear_world_items(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    item_query: Query<Entity, With<WorldItem>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &item_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_weight_meter).add_systems(
+            Update,
+            (clear_world_items, pickup_world_items, update_weight_meter)
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(item: &str, count: u32) -> SavedStack {
+        SavedStack {
+            item: item.to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn saved_inventory_round_trips() {
+        let catalog = FoodCatalog::default();
+        let apple = catalog.find("Apple").unwrap().item;
+        let mut inventory = Inventory::default();
+        inventory.add(ItemKind::Axe, 1);
+        inventory.add(ItemKind::Food(apple), 4);
+        inventory.add(ItemKind::Seeds(apple), 7);
+        inventory.add(ItemKind::Bottle(2), 1);
+        inventory.add(ItemKind::Wood, 25);
+
+        let restored = Inventory::from_saved(&inventory.to_saved(), &catalog);
+        assert_eq!(restored.slots(), inventory.slots());
+        assert_eq!(restored.count(ItemKind::Wood), 25);
+    }
+
+    #[test]
+    fn oversized_stacks_are_restacked() {
+        let catalog = FoodCatalog::default();
+        let restored = Inventory::from_saved(&[saved("Wood", 45), saved("Axe", 2)], &catalog);
+        assert_eq!(
+            restored.slots(),
+            [
+                (ItemKind::Wood, 20),
+                (ItemKind::Wood, 20),
+                (ItemKind::Wood, 5),
+                (ItemKind::Axe, 1),
+                (ItemKind::Axe, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_items_are_skipped() {
+        let catalog = FoodCatalog::default();
+        let saved = [
+            saved("Food:Dragonfruit", 3),
+            saved("Laser", 1),
+            saved("Stone", 2),
+            saved("Seeds:", 4),
+            saved("Bottle:9", 1),
+        ];
+        let restored = Inventory::from_saved(&saved, &catalog);
+        assert_eq!(
+            restored.slots(),
+            [(ItemKind::Stone, 2), (ItemKind::Bottle(BOTTLE_SIPS), 1)]
+        );
+    }
+}
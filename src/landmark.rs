@@ -0,0 +1,375 @@
+// landmarks placed at generation; shrines act as checkpoints, chests hold a
+// roll of the chest loot table, a lair is home to the boss and a camp to the
+// trader, and dens and nests breed shades and rabbits
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::event_log::EventLog;
+use crate::food::{FoodTracker, Location2D};
+use crate::food_catalog::FoodCatalog;
+use crate::interact::Interactable;
+use crate::light::Lightable;
+use crate::loot::{drop_loot, LootTables};
+use crate::menu::AppState;
+use crate::player::{solid_overlaps, DeathRespawnState, Player, Stats, FOOD_BAR_MAX, STATS_MAX};
+use crate::progress::XpGained;
+use crate::settings::{Action, GameSettings};
+use crate::world::{world_rng, WorldGrid, WorldRegenerated, WorldSeed, WORLD_TILE_SIZE};
+use crate::world_file::ImportedWorld;
+
+const SHRINE_COUNT: usize = 8;
+const SHRINE_SIZE: f32 = 14.0;
+const SHRINE_ACTIVATE_RADIUS: f32 = 24.0;
+const EDGE_MARGIN: i32 = 40;
+const LANDMARK_SEED_SALT: u64 = 0x5421;
+const SHRINE_XP: u32 = 25;
+const CHEST_COUNT: usize = 6;
+const CHEST_SIZE: f32 = 10.0;
+const CHEST_OPEN_RADIUS: f32 = 16.0;
+const CHEST_LOOT_TABLE: &str = "chest";
+const LAIR_COUNT: usize = 1;
+const LAIR_SIZE: f32 = 20.0;
+// Open ground around a lair, so its boss isn't spawned stuck in a wall.
+const LAIR_CLEARING: f32 = 16.0;
+// Per landmark, so a crowded map skips one rather than hanging.
+const PLACEMENT_ATTEMPTS: usize = 1000;
+const LAIR_TINT: Color = Color::srgb(0.25, 0.12, 0.2);
+const CAMP_COUNT: usize = 1;
+const CAMP_SIZE: f32 = 8.0;
+const CAMP_TINT: Color = Color::srgb(0.95, 0.55, 0.2);
+const DEN_COUNT: usize = 4;
+const NEST_COUNT: usize = 5;
+const DEN_SIZE: f32 = 12.0;
+// Room for whatever comes out of a den or nest.
+const DEN_CLEARING: f32 = 8.0;
+const DEN_TINT: Color = Color::srgb(0.3, 0.18, 0.28);
+const NEST_TINT: Color = Color::srgb(0.5, 0.42, 0.28);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LandmarkKind {
+    Shrine,
+    Chest,
+    Lair,
+    Camp,
+    Den,
+    Nest,
+}
+
+#[derive(Component)]
+pub struct Landmark {
+    pub kind: LandmarkKind,
+}
+
+#[derive(Component)]
+pub struct Shrine {
+    pub activated: bool,
+}
+
+#[derive(Component)]
+pub struct Chest {
+    pub opened: bool,
+}
+
+// The boss module spawns a boss at each lair as it appears.
+#[derive(Component)]
+pub struct Lair;
+
+// Likewise the trader module puts a trader at each camp.
+#[derive(Component)]
+pub struct Camp;
+
+// And the den module has each den or nest breed its creature.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Den {
+    Shade,
+    Rabbit,
+}
+
+fn chest_tint(opened: bool) -> Color {
+    if opened {
+        Color::srgb(0.35, 0.25, 0.15)
+    } else {
+        Color::srgb(0.7, 0.48, 0.2)
+    }
+}
+
+fn shrine_tint(activated: bool) -> Color {
+    if activated {
+        Color::srgb(0.95, 0.8, 0.35)
+    } else {
+        Color::srgb(0.45, 0.5, 0.85)
+    }
+}
+
+fn spawn_landmarks(
+    mut commands: Commands,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    imported: Option<Res<ImportedWorld>>,
+) {
+    place_landmarks(&mut commands, &grid, *seed, imported.as_deref());
+}
+
+fn respawn_landmarks(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    imported: Option<Res<ImportedWorld>>,
+    landmark_query: Query<Entity, With<Landmark>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &landmark_query {
+        commands.entity(entity).despawn();
+    }
+    place_landmarks(&mut commands, &grid, *seed, imported.as_deref());
+}
+
+fn place_landmarks(
+    commands: &mut Commands,
+    grid: &WorldGrid,
+    seed: WorldSeed,
+    imported: Option<&ImportedWorld>,
+) {
+    if let Some(imported) = imported {
+        for record in &imported.0.landmarks {
+            spawn_landmark(commands, record.kind, Location2D { x: record.x, y: record.y });
+        }
+        return;
+    }
+
+    // Newer kinds come last so existing seeds keep their older landmarks.
+    let mut rng = world_rng(seed, LANDMARK_SEED_SALT);
+    let kinds = std::iter::repeat_n(LandmarkKind::Shrine, SHRINE_COUNT)
+        .chain(std::iter::repeat_n(LandmarkKind::Chest, CHEST_COUNT))
+        .chain(std::iter::repeat_n(LandmarkKind::Lair, LAIR_COUNT))
+        .chain(std::iter::repeat_n(LandmarkKind::Camp, CAMP_COUNT))
+        .chain(std::iter::repeat_n(LandmarkKind::Den, DEN_COUNT))
+        .chain(std::iter::repeat_n(LandmarkKind::Nest, NEST_COUNT));
+    for kind in kinds {
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let x = rng.random_range(EDGE_MARGIN..grid.width as i32 - EDGE_MARGIN);
+            let y = rng.random_range(EDGE_MARGIN..grid.height as i32 - EDGE_MARGIN);
+            if grid.is_solid(x, y) {
+                continue;
+            }
+            let position = Vec2::new(x as f32, y as f32) * WORLD_TILE_SIZE;
+            let clearing = match kind {
+                LandmarkKind::Lair => LAIR_CLEARING,
+                LandmarkKind::Den | LandmarkKind::Nest => DEN_CLEARING,
+                _ => 0.0,
+            };
+            if clearing > 0.0 && solid_overlaps(grid, position, clearing) {
+                continue;
+            }
+            spawn_landmark(commands, kind, Location2D { x, y });
+            break;
+        }
+    }
+}
+
+fn spawn_landmark(commands: &mut Commands, kind: LandmarkKind, location: Location2D) {
+    let translation = Vec3::new(
+        location.x as f32 * WORLD_TILE_SIZE,
+        location.y as f32 * WORLD_TILE_SIZE,
+        0.6,
+    );
+    match kind {
+        LandmarkKind::Shrine => {
+            commands.spawn((
+                Landmark { kind },
+                Shrine { activated: false },
+                Interactable::new("interact.shrine", SHRINE_ACTIVATE_RADIUS * WORLD_TILE_SIZE),
+                Lightable {
+                    tint: shrine_tint(false),
+                },
+                location,
+                Sprite::from_color(Color::WHITE, Vec2::splat(SHRINE_SIZE)),
+                Visibility::Hidden,
+                Transform::from_translation(translation),
+            ));
+        }
+        LandmarkKind::Chest => {
+            commands.spawn((
+                Landmark { kind },
+                Chest { opened: false },
+                Interactable::new("interact.open", CHEST_OPEN_RADIUS * WORLD_TILE_SIZE),
+                Lightable {
+                    tint: chest_tint(false),
+                },
+                location,
+                Sprite::from_color(Color::WHITE, Vec2::splat(CHEST_SIZE)),
+                Visibility::Hidden,
+                Transform::from_translation(translation),
+            ));
+        }
+        LandmarkKind::Lair => {
+            commands.spawn((
+                Landmark { kind },
+                Lair,
+                Lightable { tint: LAIR_TINT },
+                location,
+                Sprite::from_color(Color::WHITE, Vec2::splat(LAIR_SIZE)),
+                Visibility::Hidden,
+                Transform::from_translation(translation.with_z(0.55)),
+            ));
+        }
+        LandmarkKind::Camp => {
+            commands.spawn((
+                Landmark { kind },
+                Camp,
+                Lightable { tint: CAMP_TINT },
+                location,
+                Sprite::from_color(Color::WHITE, Vec2::splat(CAMP_SIZE)),
+                Visibility::Hidden,
+                Transform::from_translation(translation),
+            ));
+        }
+        LandmarkKind::Den | LandmarkKind::Nest => {
+            let (den, tint) = if kind == LandmarkKind::Den {
+                (Den::Shade, DEN_TINT)
+            } else {
+                (Den::Rabbit, NEST_TINT)
+            };
+            commands.spawn((
+                Landmark { kind },
+                den,
+                Lightable { tint },
+                location,
+                Sprite::from_color(Color::WHITE, Vec2::splat(DEN_SIZE)),
+                Visibility::Hidden,
+                Transform::from_translation(translation.with_z(0.55)),
+            ));
+        }
+    }
+}
+
+// Chests spill their loot on the ground rather than into the bag.
+fn open_chests(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    grid: Res<WorldGrid>,
+    catalog: Res<FoodCatalog>,
+    mut food_tracker: ResMut<FoodTracker>,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    loot_tables: Option<Res<LootTables>>,
+    mut event_log: ResMut<EventLog>,
+    player_query: Query<&Transform, With<Player>>,
+    mut chest_query: Query<
+        (&mut Chest, &mut Interactable, &mut Lightable, &Location2D, &Transform),
+        Without<Player>,
+    >,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::Interact)) {
+        return;
+    }
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (mut chest, mut interactable, mut lightable, location, transform) in &mut chest_query {
+        if chest.opened {
+            continue;
+        }
+        let distance = transform.translation.truncate().distance(player_pos);
+        if distance > CHEST_OPEN_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        chest.opened = true;
+        interactable.enabled = false;
+        lightable.tint = chest_tint(true);
+        let drops = loot_tables
+            .as_deref()
+            .map(|tables| tables.roll(CHEST_LOOT_TABLE, &mut rand::rng()))
+            .unwrap_or_default();
+        drop_loot(
+            &mut commands,
+            &asset_server,
+            &grid,
+            &catalog,
+            &mut food_tracker,
+            &drops,
+            *location,
+        );
+        let message = if drops.is_empty() {
+            "opened a chest, it was empty"
+        } else {
+            "opened a chest"
+        };
+        event_log.push(&time, message);
+        break;
+    }
+}
+
+// Shrines restore everything and become the respawn point, but every
+// activation permanently raises the threat level.
+fn activate_shrines(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    mut event_log: ResMut<EventLog>,
+    mut death_state: ResMut<DeathRespawnState>,
+    mut difficulty: ResMut<Difficulty>,
+    mut xp: MessageWriter<XpGained>,
+    mut player_query: Query<(&Transform, &mut Stats), With<Player>>,
+    mut shrine_query: Query<
+        (&Landmark, &mut Shrine, &mut Interactable, &mut Lightable, &Transform),
+        Without<Player>,
+    >,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::Interact)) {
+        return;
+    }
+    let Ok((player_transform, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (landmark, mut shrine, mut interactable, mut lightable, transform) in &mut shrine_query {
+        if landmark.kind != LandmarkKind::Shrine || shrine.activated {
+            continue;
+        }
+        let shrine_pos = transform.translation.truncate();
+        if shrine_pos.distance(player_pos) > SHRINE_ACTIVATE_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        shrine.activated = true;
+        interactable.enabled = false;
+        lightable.tint = shrine_tint(true);
+        stats.health = STATS_MAX;
+        stats.stamina = STATS_MAX;
+        stats.food_bar = FOOD_BAR_MAX;
+        death_state.respawn_point = Some(shrine_pos);
+        difficulty.raise_threat();
+        xp.write(XpGained {
+            amount: SHRINE_XP,
+            reason: "activated a shrine",
+        });
+        event_log.push(
+            &time,
+            format!("shrine activated, threat level {}", difficulty.threat_level),
+        );
+        break;
+    }
+}
+
+pub struct LandmarkPlugin;
+
+impl Plugin for LandmarkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_landmarks)
+            .add_systems(
+                Update,
+                (respawn_landmarks, activate_shrines, open_chests)
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
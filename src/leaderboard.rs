@@ -0,0 +1,280 @@
+// local leaderboard: the longest runs, with the world seed and what ended
+// them, kept in leaderboard.ron. The list shows on the death screen and the
+// title menu, where a number key replays that run's world.
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, fmt, fs, path::Path, path::PathBuf};
+
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, RunSummary};
+use crate::world::WorldSeed;
+use crate::world_file::ImportedWorld;
+
+pub const DEFAULT_LEADERBOARD_FILE: &str = "leaderboard.ron";
+const LEADERBOARD_SIZE: usize = 10;
+const RETRY_KEYS: [KeyCode; LEADERBOARD_SIZE] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Digit0,
+];
+
+// Imported worlds have no seed to go back to. The cause is kept in English
+// and shown through the locale's "cause." keys.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub survived_secs: u32,
+    pub seed: Option<u64>,
+    pub cause: String,
+}
+
+// Longest first.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct Leaderboard {
+    entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    pub fn load(path: &Path) -> Result<Self, LeaderboardError> {
+        let text = fs::read_to_string(path).map_err(LeaderboardError::Io)?;
+        let mut board: Self =
+            ron::from_str(&text).map_err(|err| LeaderboardError::Parse(err.to_string()))?;
+        board.entries.sort_by_key(|entry| Reverse(entry.survived_secs));
+        board.entries.truncate(LEADERBOARD_SIZE);
+        Ok(board)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), LeaderboardError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| LeaderboardError::Parse(err.to_string()))?;
+        fs::write(path, text).map_err(LeaderboardError::Io)
+    }
+
+    // Whether the run made the list.
+    pub fn record(&mut self, entry: LeaderboardEntry) -> bool {
+        let at = self
+            .entries
+            .iter()
+            .position(|other| entry.survived_secs > other.survived_secs)
+            .unwrap_or(self.entries.len());
+        if at >= LEADERBOARD_SIZE {
+            return false;
+        }
+        self.entries.insert(at, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+        true
+    }
+
+    pub fn entries(&self) -> &[LeaderboardEntry] {
+        &self.entries
+    }
+}
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Io(std::io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaderboardError::Io(err) => write!(f, "could not access the leaderboard: {err}"),
+            LeaderboardError::Parse(err) => write!(f, "could not read the leaderboard: {err}"),
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct LeaderboardConfig {
+    pub leaderboard_path: PathBuf,
+}
+
+// A listed seed the player asked to play again. The death screen and the
+// title menu each take it from here and start the run their own way.
+#[derive(Resource, Default)]
+pub struct SeedRetry {
+    pub seed: Option<u64>,
+}
+
+#[derive(Component)]
+struct LeaderboardPanel;
+
+#[derive(Component)]
+struct LeaderboardText;
+
+// No file yet just means no runs have ended.
+fn load_leaderboard(mut commands: Commands, config: Res<LeaderboardConfig>) {
+    match Leaderboard::load(&config.leaderboard_path) {
+        Ok(board) => commands.insert_resource(board),
+        Err(LeaderboardError::Io(_)) => {}
+        Err(err) => warn!("ignoring {}: {err}", config.leaderboard_path.display()),
+    }
+}
+
+// Above the death overlay and the title menu.
+fn setup_leaderboard_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: px(32.0),
+                top: percent(20.0),
+                padding: UiRect::all(px(12.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.06, 0.85)),
+            GlobalZIndex(205),
+            Visibility::Hidden,
+            LeaderboardPanel,
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont::from_font_size(16.0),
+            TextColor(Color::srgb(0.88, 0.85, 0.75)),
+            LeaderboardText,
+        ));
+}
+
+// Notes the run the moment the player dies.
+fn record_run(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    summary: Res<RunSummary>,
+    seed: Res<WorldSeed>,
+    imported: Option<Res<ImportedWorld>>,
+    config: Res<LeaderboardConfig>,
+    mut board: ResMut<Leaderboard>,
+    mut was_dead: Local<bool>,
+) {
+    let died = death_state.is_dead && !*was_dead;
+    *was_dead = death_state.is_dead;
+    if !died {
+        return;
+    }
+    let entry = LeaderboardEntry {
+        survived_secs: (time.elapsed_secs() - summary.started_at).max(0.0) as u32,
+        seed: imported.is_none().then_some(seed.0),
+        cause: summary.last_damage.map_or("unknown", |cause| cause.name()).to_string(),
+    };
+    if board.record(entry)
+        && let Err(err) = board.save(&config.leaderboard_path)
+    {
+        warn!("could not save the leaderboard: {err}");
+    }
+}
+
+fn leaderboard_shown(state: &AppState, death_state: &DeathRespawnState) -> bool {
+    match state {
+        AppState::MainMenu => true,
+        AppState::Playing => death_state.is_dead,
+        AppState::Paused | AppState::PhotoMode => false,
+    }
+}
+
+fn retry_input(
+    input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<AppState>>,
+    death_state: Res<DeathRespawnState>,
+    board: Res<Leaderboard>,
+    mut retry: ResMut<SeedRetry>,
+) {
+    if !leaderboard_shown(state.get(), &death_state) {
+        return;
+    }
+    let Some(index) = RETRY_KEYS.iter().position(|key| input.just_pressed(*key)) else {
+        return;
+    };
+    if let Some(seed) = board.entries().get(index).and_then(|entry| entry.seed) {
+        retry.seed = Some(seed);
+    }
+}
+
+fn update_leaderboard_panel(
+    state: Res<State<AppState>>,
+    death_state: Res<DeathRespawnState>,
+    board: Res<Leaderboard>,
+    locale: Res<Locale>,
+    mut panel_query: Query<&mut Visibility, With<LeaderboardPanel>>,
+    mut text_query: Query<&mut Text, With<LeaderboardText>>,
+) {
+    let shown = leaderboard_shown(state.get(), &death_state);
+    if let Ok(mut visibility) = panel_query.single_mut() {
+        visibility.set_if_neq(if shown {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !shown {
+        return;
+    }
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let mut lines = vec![locale.get("leaderboard.title").to_string(), String::new()];
+    if board.entries().is_empty() {
+        lines.push(locale.get("leaderboard.empty").to_string());
+    }
+    for (index, entry) in board.entries().iter().enumerate() {
+        let key = (index + 1) % 10;
+        let seed = entry.seed.map_or(locale.get("leaderboard.imported").to_string(), |seed| {
+            locale.format("leaderboard.seed", &[("seed", &seed.to_string())])
+        });
+        let cause_key = format!("cause.{}", entry.cause);
+        lines.push(format!(
+            "{key}. {}:{:02}  {}  ({seed})",
+            entry.survived_secs / 60,
+            entry.survived_secs % 60,
+            locale.get(&cause_key),
+        ));
+    }
+    if board.entries().iter().any(|entry| entry.seed.is_some()) {
+        lines.push(String::new());
+        lines.push(locale.get("leaderboard.hint").to_string());
+    }
+    let joined = lines.join("\n");
+    if text.0 != joined {
+        text.0 = joined;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LeaderboardPlugin {
+    pub leaderboard_path: PathBuf,
+}
+
+impl Default for LeaderboardPlugin {
+    fn default() -> Self {
+        Self {
+            leaderboard_path: PathBuf::from(DEFAULT_LEADERBOARD_FILE),
+        }
+    }
+}
+
+impl LeaderboardPlugin {
+    pub fn with_leaderboard_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.leaderboard_path = path.into();
+        self
+    }
+}
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Leaderboard::default())
+            .insert_resource(SeedRetry::default())
+            .insert_resource(LeaderboardConfig {
+                leaderboard_path: self.leaderboard_path.clone(),
+            })
+            .add_systems(Startup, (load_leaderboard, setup_leaderboard_panel))
+            .add_systems(Update, (record_run, retry_input, update_leaderboard_panel).chain());
+    }
+}
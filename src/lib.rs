@@ -0,0 +1,336 @@
+// The game as a library: every plugin, plus GamePlugin to assemble them.
+// Bevy systems routinely take many params and nested query filters.
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub mod player;
+pub mod light;
+pub mod world;
+pub mod food;
+pub mod food_catalog;
+pub mod script;
+pub mod boss_bar;
+pub mod items;
+pub mod obstacle;
+pub mod tools;
+pub mod difficulty;
+pub mod landmark;
+pub mod combat;
+pub mod world_file;
+pub mod noise;
+pub mod throw;
+pub mod event_log;
+pub mod bug_report;
+pub mod perf;
+pub mod status_effect;
+pub mod footsteps;
+pub mod props;
+pub mod character;
+pub mod minimap;
+pub mod light_dump;
+pub mod rules;
+pub mod progress;
+pub mod footprints;
+pub mod inventory;
+pub mod hotbar;
+pub mod farming;
+pub mod fishing;
+pub mod wildlife;
+pub mod crafting;
+pub mod tooltip;
+pub mod berries;
+pub mod loot;
+pub mod enemy;
+pub mod pathfinding;
+pub mod health_bar;
+pub mod boss;
+pub mod trader;
+pub mod llm_director;
+pub mod pet;
+pub mod vision_cone;
+pub mod faction;
+pub mod day_night;
+pub mod night_monster;
+pub mod den;
+pub mod behavior;
+pub mod taming;
+pub mod lurker;
+pub mod projectile;
+pub mod spitter;
+pub mod carcass;
+pub mod flocking;
+pub mod menu;
+pub mod settings;
+pub mod hud;
+pub mod leaderboard;
+pub mod locale;
+pub mod debug_overlay;
+pub mod interact;
+pub mod facing_indicator;
+pub mod tutorial;
+pub mod compass;
+pub mod clock;
+pub mod photo_mode;
+pub mod objectives;
+
+pub mod prelude {
+    pub use crate::berries::{BerryBush, BerryPlugin};
+    pub use crate::boss::{Boss, BossPlugin};
+    pub use crate::boss_bar::{BossBarPlugin, BossEncounterEvent};
+    pub use crate::bug_report::BugReportPlugin;
+    pub use crate::character::{CharacterKind, CharacterPlugin, PlayerProfile};
+    pub use crate::combat::{AttackEvent, AttackState, CombatPlugin, Health, Loot, Shrouded};
+    pub use crate::crafting::{CraftingPlugin, CraftingScreen, Recipe, RecipeBook};
+    pub use crate::difficulty::{Difficulty, DifficultyCurve, DifficultyPlugin, DifficultyPresets};
+    pub use crate::enemy::{Enemy, EnemyPlugin, EnemySpawnConfig, SpawnDirector};
+    pub use crate::event_log::{EventLog, EventLogPlugin};
+    pub use crate::farming::{Crop, CropStage, FarmingPlugin, PlantSeeds};
+    pub use crate::fishing::{Fishing, FishingPlugin, FishingState};
+    pub use crate::food::{
+        EatFood, Food, FoodPlugin, FoodQuality, FoodSpawnConfig, FoodTracker, Location2D,
+        PickupSettings, SpawnFoodCache,
+    };
+    pub use crate::food_catalog::{FoodCatalog, FoodDef, FoodEffect, FoodItem, Rarity};
+    pub use crate::footprints::{Footprint, FootprintPlugin, Footprints};
+    pub use crate::footsteps::FootstepPlugin;
+    pub use crate::health_bar::{HealthBar, HealthBarPlugin, NoHealthBar};
+    pub use crate::hotbar::{Hotbar, HotbarPlugin};
+    pub use crate::inventory::{InventoryPlugin, InventoryScreen};
+    pub use crate::items::{Inventory, ItemKind, ItemsPlugin, SavedStack, WorldItem};
+    pub use crate::landmark::{Camp, Chest, Den, Lair, Landmark, LandmarkKind, LandmarkPlugin};
+    pub use crate::light::{LightConfig, LightPlugin, LightQuality, Lightable, LightingSystems};
+    pub use crate::light_dump::LightDumpPlugin;
+    pub use crate::loot::{LootPlugin, LootTable, LootTables};
+    pub use crate::minimap::MinimapPlugin;
+    pub use crate::noise::{NoiseEvent, NoisePlugin};
+    pub use crate::obstacle::{Obstacle, ObstacleKind, ObstaclePlugin};
+    pub use crate::pathfinding::{NavGrid, PathFollower, Pathfinder, PathfindingPlugin};
+    pub use crate::perf::{PerfGovernor, PerfPlugin};
+    pub use crate::player::{
+        DeathCause, DeathRespawnState, Exhausted, Facing, MovementConfig, MovementTracker, Player,
+        PlayerPlugin, PlayerState, RunSummary, Sneak, StaminaMessages, StaminaSpent, StaminaUse,
+        Stats, Velocity,
+    };
+    pub use crate::progress::{Perk, PersistentProgress, ProgressPlugin, XpGained};
+    pub use crate::props::{Prop, PropPlugin};
+    pub use crate::rules::{DeathMode, GameRules, RulesPlugin};
+    pub use crate::script::{ScriptAction, ScriptPlugin, ScriptRunner, ScriptTrigger, ScriptedEvent};
+    pub use crate::status_effect::{StatusEffectKind, StatusEffectPlugin, StatusEffects};
+    pub use crate::throw::ThrowPlugin;
+    pub use crate::tools::{ToolBelt, ToolPlugin};
+    pub use crate::wildlife::{
+        Animal, AnimalAction, AnimalBehaviors, AnimalCondition, AnimalKind, WildlifePlugin,
+    };
+    pub use crate::tooltip::{Tooltip, TooltipAnchor, TooltipConfig, TooltipPlugin, WorldTooltip};
+    pub use crate::trader::{TradeOffers, TradeScreen, Trader, TraderPlugin};
+    pub use crate::llm_director::{DirectorCommand, LlmDirector, LlmDirectorPlugin};
+    pub use crate::pet::{Pet, PetPlugin};
+    pub use crate::vision_cone::VisionConePlugin;
+    pub use crate::faction::{Faction, FactionPlugin, FactionRelations, Provoked, Relation};
+    pub use crate::day_night::{DayNightCycle, DayNightPlugin, DayPhaseChanged};
+    pub use crate::night_monster::{NightMonster, NightMonsterPlugin};
+    pub use crate::den::{DenBrood, DenConfig, DenPlugin};
+    pub use crate::behavior::{Behavior, BehaviorConfig, BehaviorError, BehaviorNode, BehaviorPlugin};
+    pub use crate::taming::{Bait, SavedTame, Tamed, Taming, TamingPlugin};
+    pub use crate::lurker::{Lurker, LurkerConfig, LurkerPlugin};
+    pub use crate::projectile::{Projectile, ProjectileHit, ProjectilePlugin};
+    pub use crate::spitter::{Spitter, SpitterConfig, SpitterPlugin};
+    pub use crate::carcass::{Carcass, CarcassPlugin, LeavesCarcass};
+    pub use crate::flocking::{FlockConfig, Flocking, FlockingPlugin};
+    pub use crate::menu::{AppState, MenuFocus, MenuNav, MenuPlugin};
+    pub use crate::hud::{
+        HudAnchor, HudElement, HudLayout, HudPlugin, HudVisibility, HudWidget, StatDisplay,
+    };
+    pub use crate::leaderboard::{Leaderboard, LeaderboardEntry, LeaderboardPlugin, SeedRetry};
+    pub use crate::locale::{Locale, LocalePlugin, LocalizedText};
+    pub use crate::debug_overlay::{DebugOverlay, DebugOverlayPlugin};
+    pub use crate::interact::{Interactable, InteractPlugin};
+    pub use crate::facing_indicator::FacingIndicatorPlugin;
+    pub use crate::tutorial::{TutorialHint, TutorialPlugin};
+    pub use crate::compass::CompassPlugin;
+    pub use crate::clock::ClockPlugin;
+    pub use crate::photo_mode::{PhotoModeConfig, PhotoModePlugin};
+    pub use crate::objectives::{
+        Objective, ObjectiveGoal, ObjectiveProgress, ObjectiveTracker, Objectives, ObjectivesPlugin,
+    };
+    pub use crate::settings::{
+        Action, DitherMode, GameSettings, SettingsPlugin, SettingsScreen, StatusPalette,
+    };
+    pub use crate::world::{
+        Biome, RegenerateWorld, TileKind, TilesChanged, WorldGrid, WorldPlugin, WorldRegenerated,
+        WorldRegeneration, WorldSeed, DEFAULT_WORLD_HEIGHT, DEFAULT_WORLD_WIDTH, WORLD_TILE_SIZE,
+    };
+    pub use crate::world_file::{WorldChoice, WorldFile, WorldFilePlugin};
+    pub use crate::{CameraSettings, GamePlugin, MainCamera};
+}
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::prelude::*;
+use crate::world::PLAYER_SIZE;
+
+// Everything except DefaultPlugins, so callers can pick their own window,
+// renderer or headless setup.
+// Plugins that take configuration start from default() and are adjusted
+// with builder methods, e.g.
+// `WorldPlugin::default().with_size(200, 200).with_seed(7)` or
+// `HudPlugin::default().with_toggle_key(KeyCode::F10).immersive()`.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraSettings>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(
+                Update,
+                follow_player_camera.run_if(not(in_state(AppState::PhotoMode))),
+            )
+            .add_plugins(MenuPlugin)
+            .add_plugins(SettingsPlugin::default())
+            .add_plugins(HudPlugin::default())
+            .add_plugins(LeaderboardPlugin::default())
+            .add_plugins(LocalePlugin::default())
+            .add_plugins(DebugOverlayPlugin::default())
+            .add_plugins(InteractPlugin)
+            .add_plugins(FacingIndicatorPlugin)
+            .add_plugins(TutorialPlugin)
+            .add_plugins(CompassPlugin)
+            .add_plugins(ClockPlugin)
+            .add_plugins(PhotoModePlugin::default())
+            .add_plugins(ObjectivesPlugin::default())
+            .add_plugins(PlayerPlugin::default())
+            .add_plugins(WorldPlugin::default())
+            .add_plugins(LightPlugin::default())
+            .add_plugins(FoodPlugin::default())
+            .add_plugins(ScriptPlugin)
+            .add_plugins(BossBarPlugin)
+            .add_plugins(ObstaclePlugin)
+            .add_plugins(ToolPlugin)
+            .add_plugins(DifficultyPlugin::default())
+            .add_plugins(LandmarkPlugin)
+            .add_plugins(CombatPlugin)
+            .add_plugins(WorldFilePlugin)
+            .add_plugins(ItemsPlugin)
+            .add_plugins(NoisePlugin)
+            .add_plugins(ThrowPlugin)
+            .add_plugins(EventLogPlugin)
+            .add_plugins(BugReportPlugin)
+            .add_plugins(PerfPlugin)
+            .add_plugins(StatusEffectPlugin)
+            .add_plugins(FootstepPlugin)
+            .add_plugins(PropPlugin)
+            .add_plugins(CharacterPlugin)
+            .add_plugins(MinimapPlugin)
+            .add_plugins(LightDumpPlugin)
+            .add_plugins(RulesPlugin)
+            .add_plugins(ProgressPlugin)
+            .add_plugins(FootprintPlugin)
+            .add_plugins(InventoryPlugin)
+            .add_plugins(HotbarPlugin)
+            .add_plugins(FarmingPlugin)
+            .add_plugins(FishingPlugin)
+            .add_plugins(WildlifePlugin)
+            .add_plugins(CraftingPlugin::default())
+            .add_plugins(TooltipPlugin::default())
+            .add_plugins(BerryPlugin)
+            .add_plugins(LootPlugin::default())
+            .add_plugins(PathfindingPlugin)
+            .add_plugins(EnemyPlugin::default())
+            .add_plugins(HealthBarPlugin)
+            .add_plugins(BossPlugin)
+            .add_plugins(TraderPlugin::default())
+            .add_plugins(LlmDirectorPlugin::default())
+            .add_plugins(PetPlugin)
+            .add_plugins(VisionConePlugin)
+            .add_plugins(FactionPlugin::default())
+            .add_plugins(DayNightPlugin::default())
+            .add_plugins(NightMonsterPlugin::default())
+            .add_plugins(DenPlugin::default())
+            .add_plugins(BehaviorPlugin::default())
+            .add_plugins(TamingPlugin)
+            .add_plugins(LurkerPlugin::default())
+            .add_plugins(ProjectilePlugin)
+            .add_plugins(SpitterPlugin::default())
+            .add_plugins(CarcassPlugin)
+            .add_plugins(FlockingPlugin::default());
+    }
+}
+
+#[derive(Component)]
+pub struct MainCamera;
+
+// How the camera keeps up with the player: it stays put while they move
+// about inside the deadzone, a rectangle round the middle of the screen,
+// and eases after them once they leave it. Smoothing of 0 snaps. Either
+// way it stops short of showing anything past the edge of the map.
+#[derive(Resource, Clone, Debug)]
+pub struct CameraSettings {
+    // Half the deadzone's width and height, in world units: screen pixels at
+    // the default zoom.
+    pub deadzone: Vec2,
+    // How quickly the camera closes the gap, per second.
+    pub smoothing: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: Vec2::new(3.0, 2.0) * PLAYER_SIZE,
+            smoothing: 8.0,
+        }
+    }
+}
+
+fn setup_camera(mut commands: Commands, grid: Res<WorldGrid>) {
+    commands.spawn((
+        Camera2d,
+        MainCamera,
+        Transform::from_translation(grid.center().extend(10.0)),
+    ));
+}
+
+// Keeps half the view between the camera and each edge of the world. On a
+// map smaller than the view, that axis stays centred on the map.
+fn clamp_to_world(position: Vec2, world: Rect, half_view: Vec2) -> Vec2 {
+    let min = world.min + half_view;
+    let max = world.max - half_view;
+    let center = world.center();
+    Vec2::new(
+        if min.x <= max.x { position.x.clamp(min.x, max.x) } else { center.x },
+        if min.y <= max.y { position.y.clamp(min.y, max.y) } else { center.y },
+    )
+}
+
+fn follow_player_camera(
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    grid: Res<WorldGrid>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut camera_query: Query<(&mut Transform, &Projection), (With<MainCamera>, Without<Player>)>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let Ok((mut camera_transform, projection)) = camera_query.single_mut() else {
+        return;
+    };
+    let camera = camera_transform.translation.truncate();
+    let offset = player_transform.translation.truncate() - camera;
+    let deadzone = settings.deadzone.abs();
+    // Only the part of the offset beyond the deadzone moves the camera.
+    let target = camera + offset - offset.clamp(-deadzone, deadzone);
+    let mut next = if settings.smoothing > 0.0 {
+        camera.lerp(target, 1.0 - (-settings.smoothing * time.delta_secs()).exp())
+    } else {
+        target
+    };
+    if let Ok(window) = window_query.single() {
+        let zoom = match projection {
+            Projection::Orthographic(ortho) => ortho.scale,
+            _ => 1.0,
+        };
+        let half_view = Vec2::new(window.width(), window.height()) * 0.5 * zoom;
+        next = clamp_to_world(next, grid.world_rect(), half_view);
+    }
+    camera_transform.translation.x = next.x;
+    camera_transform.translation.y = next.y;
+}
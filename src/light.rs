@@ -1,8 +1,10 @@
 use bevy::mesh::Mesh;
 use bevy::prelude::*;
 
-use crate::player::{Facing, Player, PlayerState};
-use crate::world::{set_chunk_tile_color, WorldChunks, WorldGrid, HEIGHT, WIDTH, WORLD_TILE_SIZE};
+use crate::food::Location2D;
+use crate::menu::AppState;
+use crate::player::{Facing, Player, PlayerState, Sneak};
+use crate::world::{set_chunk_tile_color, WorldChunks, WorldGrid, WORLD_TILE_SIZE};
 
 const MAX_DISTANCE: usize = 124;
 const VIEW_ANGLE_DEGREES: f32 = 120.0;
@@ -10,23 +12,91 @@ const RENDER_PADDING_TILES: i32 = 8;
 const PIXEL_LEVELS: f32 = 6.0;
 const DITHER_STRENGTH: f32 = 0.8;
 const LIGHT_SNAP: f32 = 1.0;
+const LIGHT_MAX_BRIGHTNESS: f32 = 0.93;
+const MIN_LIGHT_THRESHOLD: f32 = 0.01;
+const MIN_DARKNESS_FACTOR: f32 = 0.12;
 
-fn in_bounds(x: i32, y: i32) -> bool {
-    let lower_bound = x >= 0 && y >= 0;
-    let upper_bound = x < WIDTH as i32 && y < HEIGHT as i32;
+const RANGE_SHRINK_PER_SEC: f32 = 60.0;
+const SNEAK_RANGE_FACTOR: f32 = 0.6;
+const SNEAK_SPREAD_FACTOR: f32 = 0.7;
+const TURN_SPEED_RADIANS: f32 = 7.0;
+const EDGE_SOFTNESS_DEGREES: f32 = 12.0;
 
-    lower_bound && upper_bound
+// Runtime lighting knobs; the performance governor trades these down when
+// frames run long.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct LightQuality {
+    pub range: f32,
+    pub update_interval: u32,
+    pub dither_strength: f32,
 }
 
-fn set_visible(field: &mut Vec<Vec<bool>>, x: i32, y: i32, visible: bool) {
-    if in_bounds(x, y) {
-        let ux = x as usize;
-        let uy = y as usize;
-        field[uy][ux] = visible;
+// Lantern shape; the perf governor only ever scales LightQuality below
+// max_range. The player's settings set the brightness floor, the least lit
+// ground ever shows, and whether the bands are dithered.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct LightConfig {
+    pub max_range: f32,
+    pub view_angle_degrees: f32,
+    pub brightness_floor: f32,
+    pub dithered: bool,
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        Self {
+            max_range: MAX_DISTANCE as f32,
+            view_angle_degrees: VIEW_ANGLE_DEGREES,
+            brightness_floor: 0.0,
+            dithered: true,
+        }
     }
 }
 
-fn facing_dir(facing: Facing) -> IVec2 {
+impl Default for LightQuality {
+    fn default() -> Self {
+        Self {
+            range: MAX_DISTANCE as f32,
+            update_interval: 1,
+            dither_strength: DITHER_STRENGTH,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LightPassState {
+    frames_skipped: u32,
+    pending_dt: f32,
+    swept_range: f32,
+    light_angle: Option<f32>,
+}
+
+// World sprites tagged with this are hidden in the dark and their base tint
+// is darkened by the brightness of the tile they sit on.
+#[derive(Component)]
+pub struct Lightable {
+    pub tint: Color,
+}
+
+impl Default for Lightable {
+    fn default() -> Self {
+        Self { tint: Color::WHITE }
+    }
+}
+
+fn set_visible(field: &mut [Vec<bool>], x: i32, y: i32, visible: bool) {
+    if x < 0 || y < 0 {
+        return;
+    }
+    if let Some(tile) = field
+        .get_mut(y as usize)
+        .and_then(|row| row.get_mut(x as usize))
+    {
+        *tile = visible;
+    }
+}
+
+pub fn facing_dir(facing: Facing) -> IVec2 {
     match facing {
         Facing::Up => IVec2::new(0, 1),
         Facing::UpRight => IVec2::new(1, 1),
@@ -39,29 +109,49 @@ fn facing_dir(facing: Facing) -> IVec2 {
     }
 }
 
-fn is_visible_in_cone(
+// Angular cone test: a tile is lit when it is within range (Euclidean) and
+// the angle between it and the light direction is at most the cone's half
+// angle, so every direction covers the same shape. Enemy sight uses the
+// same test.
+fn cone_angle(delta: Vec2, dir: Vec2) -> f32 {
+    let distance = delta.length();
+    if distance <= f32::EPSILON {
+        return 0.0;
+    }
+    let cos_angle = (delta.dot(dir) / distance).clamp(-1.0, 1.0);
+    cos_angle.acos()
+}
+
+pub fn is_visible_in_cone(
     tile_center: Vec2,
     player_pos: Vec2,
-    facing: Facing,
+    dir: Vec2,
     range: f32,
-    spread: f32,
+    half_angle: f32,
 ) -> bool {
     let delta = (tile_center - player_pos) / WORLD_TILE_SIZE;
-    let dir = facing_dir(facing).as_vec2();
-
-    let forward = delta.dot(dir);
-    if forward <= 0.0 {
+    if delta.length() > range {
         return false;
     }
+    cone_angle(delta, dir) <= half_angle
+}
 
-    let forward_scale = (dir.x.abs() + dir.y.abs()).max(1.0);
-    let forward_steps = forward / forward_scale;
-    if forward_steps > range {
-        return false;
-    }
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
 
-    let side = delta.x * -dir.y + delta.y * dir.x;
-    side.abs() <= forward_steps * spread
+// Turns the light towards the facing at a limited rate, taking the short
+// way round, so it sweeps instead of snapping between the eight facings.
+fn turn_light(current: Option<f32>, facing: Facing, dt: f32) -> f32 {
+    let target = facing_dir(facing).as_vec2().to_angle();
+    let Some(current) = current else {
+        return target;
+    };
+    let diff = (target - current + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+        - std::f32::consts::PI;
+    let max_step = TURN_SPEED_RADIANS * dt;
+    current + diff.clamp(-max_step, max_step)
 }
 
 fn bayer_4x4(x: usize, y: usize) -> f32 {
@@ -90,14 +180,26 @@ fn bayer_4x4(x: usize, y: usize) -> f32 {
 fn update_visibility(
     mut grid: ResMut<WorldGrid>,
     time: Res<Time>,
-    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+    config: Res<LightConfig>,
+    quality: Res<LightQuality>,
+    mut pass: Local<LightPassState>,
+    player_query: Query<(&Transform, &PlayerState, &Sneak), With<Player>>,
     mut meshes: ResMut<Assets<Mesh>>,
     chunks: Res<WorldChunks>,
 ) {
-    let Ok((player_transform, player_state)) = player_query.single() else {
+    let Ok((player_transform, player_state, sneak)) = player_query.single() else {
         return;
     };
 
+    pass.pending_dt += time.delta_secs();
+    pass.frames_skipped += 1;
+    if pass.frames_skipped < quality.update_interval.max(1) {
+        return;
+    }
+    let dt = pass.pending_dt;
+    pass.frames_skipped = 0;
+    pass.pending_dt = 0.0;
+
     let raw_pos = player_transform.translation.truncate();
     let light_pos = if LIGHT_SNAP > 0.0 {
         (raw_pos / LIGHT_SNAP).round() * LIGHT_SNAP
@@ -106,8 +208,20 @@ fn update_visibility(
     };
     let player_tile_x = (light_pos.x / WORLD_TILE_SIZE).floor() as i32;
     let player_tile_y = (light_pos.y / WORLD_TILE_SIZE).floor() as i32;
-    let range = MAX_DISTANCE as f32;
-    let spread = (VIEW_ANGLE_DEGREES.to_radians() * 0.5).tan();
+    // Sneaking shades the lantern to a shorter, narrower cone.
+    let (range_factor, spread_factor) = if sneak.active {
+        (SNEAK_RANGE_FACTOR, SNEAK_SPREAD_FACTOR)
+    } else {
+        (1.0, 1.0)
+    };
+    let range = (quality.range * range_factor).clamp(1.0, config.max_range.max(1.0));
+    // When the range shrinks, keep sweeping the old area until it fades out.
+    pass.swept_range = (pass.swept_range - RANGE_SHRINK_PER_SEC * dt).max(range);
+    let half_angle = config.view_angle_degrees.to_radians() * 0.5 * spread_factor;
+    let softness = EDGE_SOFTNESS_DEGREES.to_radians() * spread_factor;
+    let light_angle = turn_light(pass.light_angle, player_state.facing, dt);
+    pass.light_angle = Some(light_angle);
+    let light_dir = Vec2::from_angle(light_angle);
 
     let max_brightness = 0.93;
     let hidden_brightness = 0.0;
@@ -115,14 +229,14 @@ fn update_visibility(
     let distance_bias = 1.05;
     let side_bias = 1.15;
     let smooth_speed = 60.0;
-    let lerp_alpha = (smooth_speed * time.delta_secs()).clamp(0.0, 1.0);
+    let lerp_alpha = (smooth_speed * dt).clamp(0.0, 1.0);
 
-    let inner_bound = range.ceil() as i32 + 2;
+    let inner_bound = pass.swept_range.ceil() as i32 + 2;
     let outer_bound = inner_bound + RENDER_PADDING_TILES;
     let min_x = (player_tile_x - outer_bound).max(0);
-    let max_x = (player_tile_x + outer_bound).min(WIDTH as i32 - 1);
+    let max_x = (player_tile_x + outer_bound).min(grid.width as i32 - 1);
     let min_y = (player_tile_y - outer_bound).max(0);
-    let max_y = (player_tile_y + outer_bound).min(HEIGHT as i32 - 1);
+    let max_y = (player_tile_y + outer_bound).min(grid.height as i32 - 1);
 
     for y in min_y..=max_y {
         for x in min_x..=max_x {
@@ -143,9 +257,9 @@ fn update_visibility(
                 is_visible_in_cone(
                     tile_center,
                     light_pos,
-                    player_state.facing,
+                    light_dir,
                     range,
-                    spread,
+                    half_angle + softness,
                 )
             } else {
                 false
@@ -156,19 +270,16 @@ fn update_visibility(
                 let distance = delta.length();
                 let t_distance = (distance / range).clamp(0.0, 1.0).powf(distance_bias);
 
-                let dir = facing_dir(player_state.facing).as_vec2();
-                let forward = delta.dot(dir);
-                let forward_scale = (dir.x.abs() + dir.y.abs()).max(1.0);
-                let forward_steps = forward / forward_scale;
-                let side = delta.x * -dir.y + delta.y * dir.x;
-                let side_denom = (forward_steps * spread).abs().max(0.0001);
-                let side_ratio = (side.abs() / side_denom)
+                let angle = cone_angle(delta, light_dir);
+                let side_ratio = (angle / (half_angle + softness).max(0.0001))
                     .clamp(0.0, 1.0)
                     .powf(side_bias);
+                // Fade out across a band around the nominal cone edge.
+                let edge = 1.0 - smoothstep(half_angle - softness, half_angle + softness, angle);
 
                 let t = t_distance.max(side_ratio).clamp(0.0, 1.0);
                 let falloff = (1.0 - t).clamp(0.0, 1.0).powf(brightness_curve);
-                max_brightness * falloff
+                max_brightness * falloff * edge
             } else {
                 hidden_brightness
             };
@@ -183,10 +294,22 @@ fn update_visibility(
                 };
                 let dx = (x - player_tile_x).rem_euclid(4) as usize;
                 let dy = (y - player_tile_y).rem_euclid(4) as usize;
-                let dither = bayer_4x4(dx, dy) * DITHER_STRENGTH;
-                let stepped = ((normalized * PIXEL_LEVELS) + dither).floor() / PIXEL_LEVELS;
-                let display = max_brightness * stepped.clamp(0.0, 1.0);
-                let color = Color::srgb(display, display, display).to_linear();
+                let dither = if config.dithered {
+                    bayer_4x4(dx, dy) * quality.dither_strength
+                } else {
+                    0.0
+                };
+                let stepped = (((normalized * PIXEL_LEVELS) + dither).floor() / PIXEL_LEVELS)
+                    .clamp(0.0, 1.0);
+                // Unlit ground stays dark whatever the floor.
+                let floor = if next > MIN_LIGHT_THRESHOLD {
+                    config.brightness_floor
+                } else {
+                    0.0
+                };
+                let display = max_brightness * (floor + (1.0 - floor) * stepped);
+                let [r, g, b] = grid.tiles[uy][ux].light_tint();
+                let color = Color::srgb(display * r, display * g, display * b).to_linear();
                 let color = [color.red, color.green, color.blue, color.alpha];
                 set_chunk_tile_color(&mut meshes, &chunks, ux, uy, color);
             }
@@ -194,10 +317,170 @@ fn update_visibility(
     }
 }
 
-pub struct LightPlugin;
+pub fn is_lit(grid: &WorldGrid, x: i32, y: i32) -> bool {
+    grid.in_bounds(x, y) && grid.brightness[y as usize][x as usize] > MIN_LIGHT_THRESHOLD
+}
+
+// Tint for something sitting on a tile of the given brightness, or None
+// when the tile is too dark for it to be seen.
+pub fn lit_tint(grid: &WorldGrid, x: i32, y: i32, tint: Color) -> Option<Color> {
+    if !is_lit(grid, x, y) {
+        return None;
+    }
+    let brightness = grid.brightness[y as usize][x as usize];
+    let normalized = if LIGHT_MAX_BRIGHTNESS > 0.0 {
+        (brightness / LIGHT_MAX_BRIGHTNESS).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // Keep things visible but dark when near the edge of the light.
+    let darkness_factor = MIN_DARKNESS_FACTOR + (1.0 - MIN_DARKNESS_FACTOR) * normalized;
+    let tint = tint.to_srgba();
+    Some(Color::srgba(
+        tint.red * darkness_factor,
+        tint.green * darkness_factor,
+        tint.blue * darkness_factor,
+        tint.alpha,
+    ))
+}
+
+fn update_sprite_lighting(
+    grid: Res<WorldGrid>,
+    mut sprite_query: Query<(&Location2D, &Lightable, &mut Visibility, &mut Sprite)>,
+) {
+    for (location, lightable, mut visibility, mut sprite) in &mut sprite_query {
+        match lit_tint(&grid, location.x, location.y, lightable.tint) {
+            Some(color) => {
+                *visibility = Visibility::Visible;
+                sprite.color = color;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+// Systems that read the brightness grid should run after this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LightingSystems;
+
+#[derive(Clone, Debug, Default)]
+pub struct LightPlugin {
+    pub config: LightConfig,
+    pub quality: LightQuality,
+}
+
+impl LightPlugin {
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.config.max_range = range.max(1.0);
+        self.quality.range = self.config.max_range;
+        self
+    }
+
+    pub fn with_view_angle(mut self, degrees: f32) -> Self {
+        self.config.view_angle_degrees = degrees.clamp(1.0, 360.0);
+        self
+    }
+
+    pub fn with_update_interval(mut self, frames: u32) -> Self {
+        self.quality.update_interval = frames.max(1);
+        self
+    }
+
+    pub fn with_dither_strength(mut self, strength: f32) -> Self {
+        self.quality.dither_strength = strength.max(0.0);
+        self
+    }
+}
 
 impl Plugin for LightPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostUpdate, update_visibility);
+        app.insert_resource(self.config)
+            .insert_resource(self.quality)
+            .add_systems(
+                PostUpdate,
+                (update_visibility, update_sprite_lighting)
+                    .chain()
+                    .in_set(LightingSystems)
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const RANGE: f32 = 20.0;
+    const HALF_ANGLE_DEGREES: f32 = 30.0;
+    const CARDINALS: [Facing; 4] = [Facing::Up, Facing::Right, Facing::Down, Facing::Left];
+    const DIAGONALS: [Facing; 4] = [
+        Facing::UpRight,
+        Facing::DownRight,
+        Facing::DownLeft,
+        Facing::UpLeft,
+    ];
+
+    // Offsets of the lit tiles from the light, which sits on a tile centre.
+    fn lit_tiles(facing: Facing) -> HashSet<IVec2> {
+        let dir = facing_dir(facing).as_vec2().normalize();
+        let reach = RANGE.ceil() as i32;
+        let mut lit = HashSet::new();
+        for y in -reach..=reach {
+            for x in -reach..=reach {
+                let offset = IVec2::new(x, y);
+                let center = offset.as_vec2() * WORLD_TILE_SIZE;
+                let half_angle = HALF_ANGLE_DEGREES.to_radians();
+                if is_visible_in_cone(center, Vec2::ZERO, dir, RANGE, half_angle) {
+                    lit.insert(offset);
+                }
+            }
+        }
+        lit
+    }
+
+    fn mirrored(tiles: &HashSet<IVec2>, mirror: impl Fn(IVec2) -> IVec2) -> HashSet<IVec2> {
+        tiles.iter().map(|tile| mirror(*tile)).collect()
+    }
+
+    // The grid has no 45 degree symmetry, so a diagonal cone can only match a
+    // cardinal one up to the few tiles sitting on its edges.
+    #[test]
+    fn every_facing_lights_the_same_area() {
+        let cardinal = lit_tiles(Facing::Up).len();
+        let diagonal = lit_tiles(Facing::UpRight).len();
+        for facing in CARDINALS {
+            assert_eq!(lit_tiles(facing).len(), cardinal, "{facing:?}");
+        }
+        for facing in DIAGONALS {
+            assert_eq!(lit_tiles(facing).len(), diagonal, "{facing:?}");
+        }
+        assert!(
+            cardinal.abs_diff(diagonal) * 50 <= cardinal,
+            "cardinal {cardinal} tiles, diagonal {diagonal}"
+        );
+    }
+
+    #[test]
+    fn cones_are_mirror_images() {
+        let flip_x = |tile: IVec2| IVec2::new(-tile.x, tile.y);
+        let flip_y = |tile: IVec2| IVec2::new(tile.x, -tile.y);
+        let swap = |tile: IVec2| IVec2::new(tile.y, tile.x);
+
+        let up = lit_tiles(Facing::Up);
+        assert_eq!(mirrored(&up, flip_x), up);
+        assert_eq!(mirrored(&up, flip_y), lit_tiles(Facing::Down));
+        assert_eq!(mirrored(&up, swap), lit_tiles(Facing::Right));
+        assert_eq!(mirrored(&lit_tiles(Facing::Right), flip_x), lit_tiles(Facing::Left));
+
+        let up_right = lit_tiles(Facing::UpRight);
+        assert_eq!(mirrored(&up_right, swap), up_right);
+        assert_eq!(mirrored(&up_right, flip_x), lit_tiles(Facing::UpLeft));
+        assert_eq!(mirrored(&up_right, flip_y), lit_tiles(Facing::DownRight));
+        assert_eq!(
+            mirrored(&lit_tiles(Facing::UpLeft), flip_y),
+            lit_tiles(Facing::DownLeft)
+        );
     }
 }
@@ -0,0 +1,130 @@
+// debug dump of the lighting grids: F9 writes brightness and visibility PNGs,
+// Shift+F9 adds a CSV of every lit tile
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::{fs, io};
+
+use crate::event_log::EventLog;
+use crate::world::WorldGrid;
+
+const DUMP_DIR: &str = "debug";
+const BRIGHTNESS_SCALE: f32 = 0.93;
+
+// Rows are flipped so north is at the top of the image, like the screen.
+fn grid_image(grid: &WorldGrid, pixel: impl Fn(usize, usize) -> [u8; 4]) -> Image {
+    let mut data = Vec::with_capacity(grid.width * grid.height * 4);
+    for y in (0..grid.height).rev() {
+        for x in 0..grid.width {
+            data.extend_from_slice(&pixel(x, y));
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: grid.width as u32,
+            height: grid.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+fn save_png(image: Image, path: &Path) -> io::Result<()> {
+    let dynamic = image
+        .try_into_dynamic()
+        .map_err(|err| io::Error::other(err.to_string()))?;
+    dynamic
+        .save(path)
+        .map_err(|err| io::Error::other(err.to_string()))
+}
+
+fn light_csv(grid: &WorldGrid) -> String {
+    let mut csv = String::from("x,y,brightness,visible,wall\n");
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            let brightness = grid.brightness[y][x];
+            let visible = grid.field[y][x];
+            if brightness <= 0.0 && !visible {
+                continue;
+            }
+            let _ = writeln!(
+                csv,
+                "{x},{y},{brightness:.4},{},{}",
+                visible as u8,
+                grid.walls[y][x] as u8,
+            );
+        }
+    }
+    csv
+}
+
+// Writes brightness.png, visibility.png and optionally light.csv into dir
+// and returns the paths written.
+pub fn write_light_dump(grid: &WorldGrid, dir: &Path, with_csv: bool) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+
+    let brightness = grid_image(grid, |x, y| {
+        let value = (grid.brightness[y][x] / BRIGHTNESS_SCALE).clamp(0.0, 1.0);
+        let level = (value * 255.0).round() as u8;
+        [level, level, level, 255]
+    });
+    let path = dir.join("brightness.png");
+    save_png(brightness, &path)?;
+    written.push(path);
+
+    let visibility = grid_image(grid, |x, y| {
+        if grid.walls[y][x] {
+            [80, 80, 80, 255]
+        } else if grid.field[y][x] {
+            [255, 255, 255, 255]
+        } else {
+            [0, 0, 0, 255]
+        }
+    });
+    let path = dir.join("visibility.png");
+    save_png(visibility, &path)?;
+    written.push(path);
+
+    if with_csv {
+        let path = dir.join("light.csv");
+        fs::write(&path, light_csv(grid))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn dump_light_on_key(
+    input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    mut event_log: ResMut<EventLog>,
+) {
+    if !input.just_pressed(KeyCode::F9) {
+        return;
+    }
+    let with_csv = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let stamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let dir = Path::new(DUMP_DIR).join(format!("light-{stamp}"));
+    match write_light_dump(&grid, &dir, with_csv) {
+        Ok(_) => event_log.push(&time, format!("dumped lighting to {}", dir.display())),
+        Err(err) => warn!("could not dump lighting to {}: {err}", dir.display()),
+    }
+}
+
+pub struct LightDumpPlugin;
+
+impl Plugin for LightDumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, dump_light_on_key);
+    }
+}
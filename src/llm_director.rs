@@ -0,0 +1,332 @@
+// optional pacing director backed by an LLM. Every so often the run is
+// summed up in a short prompt and piped to an external command (set with
+// LLM_DIRECTOR_CMD or the plugin builder), which is expected to ask a model
+// and print its answer. The answer is a RON list of DirectorCommand values;
+// each is clamped to a safe range before it touches the game, and anything
+// that doesn't parse is logged and dropped. Without a command the director
+// does nothing.
+use bevy::prelude::*;
+use bevy::tasks::{futures::check_ready, AsyncComputeTaskPool, Task};
+use serde::Deserialize;
+use std::{
+    env, fmt,
+    io::Write,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use crate::difficulty::Difficulty;
+use crate::enemy::{Enemy, SpawnDirector};
+use crate::event_log::EventLog;
+use crate::food::{FoodSpawnConfig, FoodTracker, SpawnFoodCache};
+use crate::player::{DeathRespawnState, Player, RunSummary, Stats};
+
+const DEFAULT_INTERVAL_SECONDS: f32 = 120.0;
+const MAX_COMMANDS: usize = 4;
+const FACTOR_RANGE: (f32, f32) = (0.5, 2.0);
+const MAX_CACHE_FOOD: usize = 5;
+const MAX_CACHE_RADIUS: i32 = 40;
+const MAX_MESSAGE_CHARS: usize = 80;
+
+// Everything the model is allowed to change.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub enum DirectorCommand {
+    // Scales enemy spawn pressure; 1.0 leaves it alone.
+    EnemyPressure(f32),
+    // Scales how often food spawns; above 1.0 means more food.
+    FoodSpawnRate(f32),
+    // Drops a few pieces of food around the player.
+    FoodCache { count: usize, radius_tiles: i32 },
+    RaiseThreat,
+    // A line for the event log, to foreshadow what's coming.
+    Message(String),
+}
+
+impl DirectorCommand {
+    fn clamped(self) -> Self {
+        let factor = |value: f32| {
+            if value.is_finite() {
+                value.clamp(FACTOR_RANGE.0, FACTOR_RANGE.1)
+            } else {
+                1.0
+            }
+        };
+        match self {
+            DirectorCommand::EnemyPressure(value) => DirectorCommand::EnemyPressure(factor(value)),
+            DirectorCommand::FoodSpawnRate(value) => DirectorCommand::FoodSpawnRate(factor(value)),
+            DirectorCommand::FoodCache {
+                count,
+                radius_tiles,
+            } => DirectorCommand::FoodCache {
+                count: count.min(MAX_CACHE_FOOD),
+                radius_tiles: radius_tiles.clamp(1, MAX_CACHE_RADIUS),
+            },
+            DirectorCommand::RaiseThreat => DirectorCommand::RaiseThreat,
+            DirectorCommand::Message(text) => {
+                DirectorCommand::Message(text.chars().take(MAX_MESSAGE_CHARS).collect())
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DirectorError {
+    Spawn(std::io::Error),
+    Failed(String),
+    Parse(String),
+}
+
+impl fmt::Display for DirectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectorError::Spawn(err) => write!(f, "could not run the director command: {err}"),
+            DirectorError::Failed(status) => write!(f, "the director command failed: {status}"),
+            DirectorError::Parse(err) => write!(f, "could not parse the director's answer: {err}"),
+        }
+    }
+}
+
+// Accepts the list on its own or wrapped in other text, since models like
+// to explain themselves.
+pub fn parse_commands(answer: &str) -> Result<Vec<DirectorCommand>, DirectorError> {
+    let start = answer.find('[').unwrap_or(0);
+    let end = answer.rfind(']').map_or(answer.len(), |end| end + 1);
+    let list = answer.get(start..end).unwrap_or(answer);
+    let commands: Vec<DirectorCommand> =
+        ron::from_str(list).map_err(|err| DirectorError::Parse(err.to_string()))?;
+    Ok(commands
+        .into_iter()
+        .take(MAX_COMMANDS)
+        .map(DirectorCommand::clamped)
+        .collect())
+}
+
+fn ask(command: &str, prompt: String) -> Result<String, DirectorError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(DirectorError::Spawn)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(prompt.as_bytes())
+            .map_err(DirectorError::Spawn)?;
+    }
+    let output = child.wait_with_output().map_err(DirectorError::Spawn)?;
+    if !output.status.success() {
+        return Err(DirectorError::Failed(output.status.to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// What the last summary saw, for trends.
+#[derive(Clone, Copy, Debug, Default)]
+struct RunSnapshot {
+    health: f32,
+    stamina: f32,
+    food_bar: f32,
+    food_eaten: u32,
+}
+
+#[derive(Resource)]
+pub struct LlmDirector {
+    pub command: Option<String>,
+    pub timer: Timer,
+    pending: Option<Task<Result<String, DirectorError>>>,
+    last: Option<RunSnapshot>,
+    deaths: u32,
+    was_dead: bool,
+    // The food spawn interval before the director touched it.
+    base_food_interval: Option<f32>,
+}
+
+impl LlmDirector {
+    fn prompt(&self, now: RunSnapshot, facts: &[String]) -> String {
+        let trend = |label: &str, now: f32, then: Option<f32>| match then {
+            Some(then) => format!("{label}: {now:.0} (was {then:.0})"),
+            None => format!("{label}: {now:.0}"),
+        };
+        let mut lines = vec![
+            "You pace a survival game. The player forages in the dark by lantern light."
+                .to_string(),
+            "Keep it tense but fair: ease off when they struggle, push when they coast."
+                .to_string(),
+            String::new(),
+            trend("health", now.health, self.last.map(|last| last.health)),
+            trend("stamina", now.stamina, self.last.map(|last| last.stamina)),
+            trend(
+                "food bar",
+                now.food_bar,
+                self.last.map(|last| last.food_bar),
+            ),
+            format!(
+                "food eaten since last report: {}",
+                now.food_eaten
+                    .saturating_sub(self.last.map_or(0, |last| last.food_eaten))
+            ),
+            format!("deaths this session: {}", self.deaths),
+        ];
+        lines.extend(facts.iter().cloned());
+        lines.extend([
+            String::new(),
+            "Answer with a RON list of up to four commands, for example:".to_string(),
+            "[EnemyPressure(1.2), FoodSpawnRate(0.8), Message(\"The dark stirs.\")]".to_string(),
+            "Commands: EnemyPressure(0.5..2.0), FoodSpawnRate(0.5..2.0),".to_string(),
+            "FoodCache(count: 1..5, radius_tiles: 1..40), RaiseThreat, Message(text).".to_string(),
+        ]);
+        lines.join("\n")
+    }
+}
+
+fn count_deaths(death_state: Res<DeathRespawnState>, mut director: ResMut<LlmDirector>) {
+    if death_state.is_dead && !director.was_dead {
+        director.deaths += 1;
+    }
+    director.was_dead = death_state.is_dead;
+}
+
+fn request_direction(
+    time: Res<Time>,
+    summary: Res<RunSummary>,
+    difficulty: Res<Difficulty>,
+    food_config: Res<FoodSpawnConfig>,
+    food_tracker: Res<FoodTracker>,
+    mut director: ResMut<LlmDirector>,
+    player_query: Query<&Stats, With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    let Some(command) = director.command.clone() else {
+        return;
+    };
+    if director.pending.is_some() || !director.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Ok(stats) = player_query.single() else {
+        return;
+    };
+    let now = RunSnapshot {
+        health: stats.health,
+        stamina: stats.stamina,
+        food_bar: stats.food_bar,
+        food_eaten: summary.food_eaten,
+    };
+    let survived = (time.elapsed_secs() - summary.started_at).max(0.0) as u32;
+    let facts = [
+        format!("survived this life: {}:{:02}", survived / 60, survived % 60),
+        format!(
+            "food on the ground: {} of at most {}",
+            food_tracker.food_amount, food_config.max_food
+        ),
+        format!("enemies about: {}", enemy_query.iter().count()),
+        format!("threat level: {}", difficulty.threat_level),
+    ];
+    let prompt = director.prompt(now, &facts);
+    director.last = Some(now);
+    let task = AsyncComputeTaskPool::get().spawn(async move { ask(&command, prompt) });
+    director.pending = Some(task);
+}
+
+fn apply_direction(
+    time: Res<Time>,
+    mut director: ResMut<LlmDirector>,
+    mut spawn_director: ResMut<SpawnDirector>,
+    mut food_config: ResMut<FoodSpawnConfig>,
+    mut difficulty: ResMut<Difficulty>,
+    mut event_log: ResMut<EventLog>,
+    mut caches: MessageWriter<SpawnFoodCache>,
+) {
+    let Some(task) = director.pending.as_mut() else {
+        return;
+    };
+    let Some(answer) = check_ready(task) else {
+        return;
+    };
+    director.pending = None;
+    let commands = match answer.and_then(|answer| parse_commands(&answer)) {
+        Ok(commands) => commands,
+        Err(err) => {
+            warn!("{err}");
+            return;
+        }
+    };
+    for command in commands {
+        match command {
+            DirectorCommand::EnemyPressure(factor) => spawn_director.bias = factor,
+            DirectorCommand::FoodSpawnRate(factor) => {
+                let base = *director
+                    .base_food_interval
+                    .get_or_insert(food_config.timer.duration().as_secs_f32());
+                food_config.set_spawn_interval(base / factor);
+            }
+            DirectorCommand::FoodCache {
+                count,
+                radius_tiles,
+            } => {
+                caches.write(SpawnFoodCache {
+                    count,
+                    radius_tiles,
+                });
+            }
+            DirectorCommand::RaiseThreat => difficulty.raise_threat(),
+            DirectorCommand::Message(text) => event_log.push(&time, text),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LlmDirectorPlugin {
+    pub command: Option<String>,
+    pub interval_seconds: f32,
+}
+
+impl Default for LlmDirectorPlugin {
+    fn default() -> Self {
+        Self {
+            command: None,
+            interval_seconds: DEFAULT_INTERVAL_SECONDS,
+        }
+    }
+}
+
+impl LlmDirectorPlugin {
+    pub fn with_command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+
+    pub fn with_interval(mut self, seconds: f32) -> Self {
+        self.interval_seconds = seconds;
+        self
+    }
+}
+
+impl Plugin for LlmDirectorPlugin {
+    fn build(&self, app: &mut App) {
+        // LLM_DIRECTOR_CMD switches the director on without a rebuild.
+        let command_key = "LLM_DIRECTOR_CMD";
+        let command = self.command.clone().or_else(|| {
+            env::var(command_key)
+                .ok()
+                .filter(|command| !command.is_empty())
+        });
+        app.insert_resource(LlmDirector {
+            command,
+            timer: Timer::new(
+                Duration::from_secs_f32(self.interval_seconds.max(1.0)),
+                TimerMode::Repeating,
+            ),
+            pending: None,
+            last: None,
+            deaths: 0,
+            was_dead: false,
+            base_food_interval: None,
+        })
+        .add_systems(
+            Update,
+            (count_deaths, request_direction, apply_direction).chain(),
+        );
+    }
+}
@@ -0,0 +1,192 @@
+// UI text by language. Each language is a file in assets/locales/ mapping
+// keys such as "menu.new_game" to text; anything a language leaves out
+// falls back to English, and then to the key itself. The language comes
+// from the player's settings and can be changed while playing.
+use bevy::prelude::*;
+use std::{collections::HashMap, fmt, fs, path::Path, path::PathBuf};
+
+use crate::settings::GameSettings;
+
+pub const DEFAULT_LOCALES_DIR: &str = "assets/locales";
+pub const DEFAULT_LANGUAGE: &str = "en";
+// Every language file names itself under this key.
+const LANGUAGE_NAME_KEY: &str = "language.name";
+
+#[derive(Debug)]
+pub enum LocaleError {
+    Io(std::io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocaleError::Io(err) => write!(f, "could not read language file: {err}"),
+            LocaleError::Parse(err) => write!(f, "could not parse language file: {err}"),
+        }
+    }
+}
+
+fn load_strings(dir: &Path, language: &str) -> Result<HashMap<String, String>, LocaleError> {
+    let path = dir.join(format!("{language}.ron"));
+    let text = fs::read_to_string(path).map_err(LocaleError::Io)?;
+    ron::from_str(&text).map_err(|err| LocaleError::Parse(err.to_string()))
+}
+
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Locale {
+    language: String,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+    // (code, name in that language) for every file found, sorted by code.
+    languages: Vec<(String, String)>,
+}
+
+impl Locale {
+    // Looks through the directory for languages; an unreadable file is
+    // left out of the list rather than stopping the game.
+    pub fn load(dir: &Path, language: &str) -> Self {
+        let mut languages = Vec::new();
+        let entries = fs::read_dir(dir)
+            .map_err(|err| warn!("no languages in {}: {err}", dir.display()))
+            .into_iter()
+            .flatten()
+            .flatten();
+        for entry in entries {
+            let path = entry.path();
+            if path.extension().is_none_or(|extension| extension != "ron") {
+                continue;
+            }
+            let Some(code) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            match load_strings(dir, code) {
+                Ok(strings) => {
+                    let name = strings.get(LANGUAGE_NAME_KEY).cloned();
+                    languages.push((code.to_string(), name.unwrap_or_else(|| code.to_string())));
+                }
+                Err(err) => warn!("skipping {}: {err}", path.display()),
+            }
+        }
+        languages.sort();
+
+        let fallback = load_strings(dir, DEFAULT_LANGUAGE).unwrap_or_default();
+        let mut locale = Self {
+            language: DEFAULT_LANGUAGE.to_string(),
+            strings: fallback.clone(),
+            fallback,
+            languages,
+        };
+        locale.switch(dir, language);
+        locale
+    }
+
+    // Keeps the current language if the new one can't be read.
+    pub fn switch(&mut self, dir: &Path, language: &str) {
+        if language == DEFAULT_LANGUAGE {
+            self.language = DEFAULT_LANGUAGE.to_string();
+            self.strings = self.fallback.clone();
+            return;
+        }
+        match load_strings(dir, language) {
+            Ok(strings) => {
+                self.language = language.to_string();
+                self.strings = strings;
+            }
+            Err(err) => warn!("staying with {}: {language}: {err}", self.language),
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn languages(&self) -> &[(String, String)] {
+        &self.languages
+    }
+
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    // Fills in {name} placeholders, e.g.
+    // `locale.format("death.survived", &[("time", "3:07")])`.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        text
+    }
+}
+
+// Text that's only ever the one string; it's redone when the language
+// changes. Text built from live values asks the Locale each time instead.
+#[derive(Component, Clone, Debug)]
+pub struct LocalizedText(pub &'static str);
+
+#[derive(Resource, Clone, Debug)]
+pub struct LocaleConfig {
+    pub locales_dir: PathBuf,
+}
+
+fn follow_language_setting(
+    config: Res<LocaleConfig>,
+    settings: Res<GameSettings>,
+    mut locale: ResMut<Locale>,
+) {
+    if settings.language != locale.language() {
+        locale.switch(&config.locales_dir, &settings.language);
+    }
+}
+
+fn refresh_localized_text(locale: Res<Locale>, mut text_query: Query<(&LocalizedText, &mut Text)>) {
+    for (localized, mut text) in &mut text_query {
+        let wanted = locale.get(localized.0);
+        if text.0 != wanted {
+            text.0 = wanted.to_string();
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LocalePlugin {
+    pub locales_dir: PathBuf,
+}
+
+impl Default for LocalePlugin {
+    fn default() -> Self {
+        Self {
+            locales_dir: PathBuf::from(DEFAULT_LOCALES_DIR),
+        }
+    }
+}
+
+impl LocalePlugin {
+    pub fn with_locales_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.locales_dir = dir.into();
+        self
+    }
+}
+
+// Loaded while building, so UI spawned at Startup already has its text;
+// the player's saved language takes over once the settings are read.
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Locale::load(&self.locales_dir, DEFAULT_LANGUAGE))
+            .insert_resource(LocaleConfig {
+                locales_dir: self.locales_dir.clone(),
+            })
+            .add_systems(
+                Update,
+                (
+                    follow_language_setting.run_if(resource_changed::<GameSettings>),
+                    refresh_localized_text.run_if(resource_changed::<Locale>),
+                )
+                    .chain(),
+            );
+    }
+}
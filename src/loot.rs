@@ -0,0 +1,291 @@
+// weighted loot tables from loot.ron, rolled for animal and enemy drops,
+// chests and, if the file has a ground_food table, the food that spawns on
+// the ground.
+// Uncommon and rare things on the ground get a coloured outline.
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt, fs, path::Path, path::PathBuf};
+
+use crate::food::{place_food, Food, FoodStats, FoodTracker, Location2D};
+use crate::food_catalog::FoodCatalog;
+use crate::items::{spawn_world_item, ItemKind, WorldItem};
+use crate::world::WorldGrid;
+
+pub const DEFAULT_LOOT_FILE: &str = "assets/loot.ron";
+pub const GROUND_FOOD_TABLE: &str = "ground_food";
+const OUTLINE_MARGIN: f32 = 3.0;
+const OUTLINE_Z: f32 = -0.05;
+// How far from the drop point food looks for a free tile.
+const DROP_SPREAD_TILES: i32 = 2;
+
+fn one() -> f32 {
+    1.0
+}
+
+fn one_roll() -> u32 {
+    1
+}
+
+fn single() -> (u32, u32) {
+    (1, 1)
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct LootEntryRecord {
+    // Left out for an entry that drops nothing.
+    #[serde(default)]
+    item: Option<String>,
+    #[serde(default = "one")]
+    weight: f32,
+    // Inclusive range.
+    #[serde(default = "single")]
+    count: (u32, u32),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct LootTableRecord {
+    #[serde(default = "one_roll")]
+    rolls: u32,
+    entries: Vec<LootEntryRecord>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LootEntry {
+    pub item: Option<ItemKind>,
+    pub weight: f32,
+    pub count: (u32, u32),
+}
+
+#[derive(Clone, Debug)]
+pub struct LootTable {
+    pub rolls: u32,
+    entries: Vec<LootEntry>,
+    total_weight: f32,
+}
+
+impl LootTable {
+    // One weighted pick, ignoring the count.
+    pub fn pick(&self, rng: &mut impl Rng) -> Option<&LootEntry> {
+        if self.total_weight <= 0.0 {
+            return None;
+        }
+        let mut roll = rng.random_range(0.0..self.total_weight);
+        for entry in &self.entries {
+            if roll < entry.weight {
+                return Some(entry);
+            }
+            roll -= entry.weight;
+        }
+        self.entries.iter().rfind(|entry| entry.weight > 0.0)
+    }
+
+    // Everything dropped over all the rolls.
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<(ItemKind, u32)> {
+        let mut drops = Vec::new();
+        for _ in 0..self.rolls {
+            let Some(entry) = self.pick(rng) else {
+                break;
+            };
+            let Some(item) = entry.item else {
+                continue;
+            };
+            let (min, max) = entry.count;
+            let count = rng.random_range(min..=max.max(min));
+            if count > 0 {
+                drops.push((item, count));
+            }
+        }
+        drops
+    }
+}
+
+#[derive(Debug)]
+pub enum LootError {
+    Io(std::io::Error),
+    Parse(String),
+    UnknownItem(String),
+    Weight(String),
+}
+
+impl fmt::Display for LootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LootError::Io(err) => write!(f, "could not read loot tables: {err}"),
+            LootError::Parse(err) => write!(f, "could not parse loot tables: {err}"),
+            LootError::UnknownItem(item) => write!(f, "loot tables mention unknown item {item}"),
+            LootError::Weight(table) => write!(f, "loot table {table} has a negative weight"),
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default)]
+pub struct LootTables {
+    tables: HashMap<String, LootTable>,
+}
+
+impl LootTables {
+    // Items are named by their save keys, as in recipes.
+    pub fn load(path: &Path, catalog: &FoodCatalog) -> Result<Self, LootError> {
+        let text = fs::read_to_string(path).map_err(LootError::Io)?;
+        let records: HashMap<String, LootTableRecord> =
+            ron::from_str(&text).map_err(|err| LootError::Parse(err.to_string()))?;
+        let mut tables = HashMap::with_capacity(records.len());
+        for (name, record) in records {
+            let mut entries = Vec::with_capacity(record.entries.len());
+            for entry in record.entries {
+                if entry.weight < 0.0 {
+                    return Err(LootError::Weight(name));
+                }
+                let item = match entry.item {
+                    Some(key) => Some(
+                        ItemKind::from_save_key(&key, catalog)
+                            .ok_or(LootError::UnknownItem(key))?,
+                    ),
+                    None => None,
+                };
+                entries.push(LootEntry {
+                    item,
+                    weight: entry.weight,
+                    count: entry.count,
+                });
+            }
+            let total_weight = entries.iter().map(|entry| entry.weight).sum();
+            let table = LootTable {
+                rolls: record.rolls,
+                entries,
+                total_weight,
+            };
+            tables.insert(name, table);
+        }
+        Ok(Self { tables })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LootTable> {
+        self.tables.get(name)
+    }
+
+    // A missing table drops nothing.
+    pub fn roll(&self, name: &str, rng: &mut impl Rng) -> Vec<(ItemKind, u32)> {
+        self.get(name).map_or_else(Vec::new, |table| table.roll(rng))
+    }
+}
+
+// Food drops become real food, registered with the food tracker, on the drop
+// tile or the nearest free one; everything else, and food with nowhere to go,
+// lands as world items.
+pub fn drop_loot(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    grid: &WorldGrid,
+    catalog: &FoodCatalog,
+    tracker: &mut FoodTracker,
+    drops: &[(ItemKind, u32)],
+    location: Location2D,
+) {
+    let spots: Vec<Location2D> = (0..=DROP_SPREAD_TILES)
+        .flat_map(|ring| {
+            (-ring..=ring).flat_map(move |dy| {
+                (-ring..=ring)
+                    .filter(move |dx| dx.abs() == ring || dy.abs() == ring)
+                    .map(move |dx| Location2D {
+                        x: location.x + dx,
+                        y: location.y + dy,
+                    })
+            })
+        })
+        .filter(|spot| !grid.is_solid(spot.x, spot.y))
+        .collect();
+    for &(kind, count) in drops {
+        for _ in 0..count {
+            let placed = match kind {
+                ItemKind::Food(food) => catalog.get(food).is_some_and(|def| {
+                    spots
+                        .iter()
+                        .any(|&spot| place_food(commands, asset_server, tracker, spot, def).is_some())
+                }),
+                _ => false,
+            };
+            if !placed {
+                spawn_world_item(commands, kind, location);
+            }
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct LootConfig {
+    pub loot_path: PathBuf,
+}
+
+#[derive(Component)]
+struct RarityOutline;
+
+// Needs the food catalog, so this waits for Startup like the recipe book.
+fn load_loot_tables(mut commands: Commands, config: Res<LootConfig>, catalog: Res<FoodCatalog>) {
+    let tables = match LootTables::load(&config.loot_path, &catalog) {
+        Ok(tables) => tables,
+        Err(err) => {
+            warn!("no loot tables from {}: {err}", config.loot_path.display());
+            LootTables::default()
+        }
+    };
+    commands.insert_resource(tables);
+}
+
+// A square behind the item; as a child it shows and hides with the item's
+// lighting.
+fn outline_rare_things(
+    mut commands: Commands,
+    catalog: Res<FoodCatalog>,
+    item_query: Query<(Entity, &WorldItem, &Sprite), Added<WorldItem>>,
+    food_query: Query<(Entity, &FoodStats, &Sprite), Added<Food>>,
+) {
+    let items = item_query
+        .iter()
+        .map(|(entity, item, sprite)| (entity, item.kind, sprite));
+    let foods = food_query
+        .iter()
+        .map(|(entity, stats, sprite)| (entity, ItemKind::Food(stats.food), sprite));
+    for (entity, kind, sprite) in items.chain(foods) {
+        let Some(color) = kind.rarity(&catalog).outline() else {
+            continue;
+        };
+        let size = sprite.custom_size.unwrap_or(Vec2::ONE) + Vec2::splat(OUTLINE_MARGIN);
+        commands.entity(entity).with_child((
+            RarityOutline,
+            Sprite::from_color(color, size),
+            Transform::from_translation(Vec3::Z * OUTLINE_Z),
+        ));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LootPlugin {
+    pub loot_path: PathBuf,
+}
+
+impl Default for LootPlugin {
+    fn default() -> Self {
+        Self {
+            loot_path: PathBuf::from(DEFAULT_LOOT_FILE),
+        }
+    }
+}
+
+impl LootPlugin {
+    pub fn with_loot_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.loot_path = path.into();
+        self
+    }
+}
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LootConfig {
+            loot_path: self.loot_path.clone(),
+        })
+        .add_systems(Startup, load_loot_tables)
+        .add_systems(Update, outline_rare_things);
+    }
+}
@@ -0,0 +1,245 @@
+// lurkers: ambush predators that wait in the dark. They go by the
+// brightness grid itself rather than Lightable, so they only show, and can
+// only be hit, once the lantern shines properly on them; dim edges of the
+// light don't give them away. Pass close to a hidden one and it pounces;
+// caught in the light, it slinks back into the dark.
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::combat::{Health, Loot, Shrouded};
+use crate::faction::Faction;
+use crate::food::Location2D;
+use crate::light::{lit_tint, LightingSystems};
+use crate::obstacle::Obstacle;
+use crate::player::{solid_overlaps, DeathCause, DeathRespawnState, Player, RunSummary, Stats};
+use crate::world::{world_rng, WorldGrid, WorldRegenerated, WorldSeed, WORLD_TILE_SIZE};
+
+const DEFAULT_LURKER_COUNT: usize = 10;
+const LURKER_SEED_SALT: u64 = 0x1a2c;
+const EDGE_MARGIN: i32 = 32;
+const SPAWN_CLEARING_RADIUS: i32 = 80;
+const LURKER_SIZE: f32 = 9.0;
+const LURKER_HEALTH: f32 = 40.0;
+const LURKER_TINT: Color = Color::srgb(0.4, 0.33, 0.28);
+const LURKER_LOOT_TABLE: &str = "shade";
+// How bright its tile has to be before it shows.
+const REVEAL_BRIGHTNESS: f32 = 0.25;
+const POUNCE_RADIUS: f32 = 14.0;
+const POUNCE_SPEED: f32 = 80.0;
+const POUNCE_SECONDS: f32 = 0.6;
+// Before it can pounce again.
+const POUNCE_COOLDOWN_SECONDS: f32 = 3.0;
+const RETREAT_SPEED: f32 = 40.0;
+const BITE_RADIUS: f32 = 6.0;
+const BITE_DAMAGE: f32 = 20.0;
+
+#[derive(Component)]
+pub struct Lurker {
+    pub revealed: bool,
+    // Direction and time left of the pounce under way.
+    pounce: Option<(Vec2, f32)>,
+    cooldown: f32,
+}
+
+#[derive(Resource)]
+pub struct LurkerConfig {
+    pub count: usize,
+}
+
+fn brightness_at(grid: &WorldGrid, location: &Location2D) -> f32 {
+    if !grid.in_bounds(location.x, location.y) {
+        return 0.0;
+    }
+    grid.brightness[location.y as usize][location.x as usize]
+}
+
+fn spawn_lurkers(
+    mut commands: Commands,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    config: Res<LurkerConfig>,
+) {
+    place_lurkers(&mut commands, &grid, *seed, config.count);
+}
+
+fn respawn_lurkers(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    config: Res<LurkerConfig>,
+    lurker_query: Query<Entity, With<Lurker>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &lurker_query {
+        commands.entity(entity).despawn();
+    }
+    place_lurkers(&mut commands, &grid, *seed, config.count);
+}
+
+fn place_lurkers(commands: &mut Commands, grid: &WorldGrid, seed: WorldSeed, count: usize) {
+    if grid.width as i32 <= EDGE_MARGIN * 2 || grid.height as i32 <= EDGE_MARGIN * 2 {
+        return;
+    }
+    let mut rng = world_rng(seed, LURKER_SEED_SALT);
+    let center = IVec2::new(grid.width as i32 / 2, grid.height as i32 / 2);
+    for _ in 0..count {
+        let tile = IVec2::new(
+            rng.random_range(EDGE_MARGIN..grid.width as i32 - EDGE_MARGIN),
+            rng.random_range(EDGE_MARGIN..grid.height as i32 - EDGE_MARGIN),
+        );
+        if tile.distance_squared(center) < SPAWN_CLEARING_RADIUS * SPAWN_CLEARING_RADIUS {
+            continue;
+        }
+        let position = tile.as_vec2() * WORLD_TILE_SIZE;
+        if solid_overlaps(grid, position, LURKER_SIZE / 2.0) {
+            continue;
+        }
+        commands.spawn((
+            Lurker {
+                revealed: false,
+                pounce: None,
+                cooldown: 0.0,
+            },
+            Shrouded,
+            Health::new(LURKER_HEALTH),
+            Loot {
+                table: LURKER_LOOT_TABLE,
+            },
+            Faction::Monster,
+            Location2D {
+                x: tile.x,
+                y: tile.y,
+            },
+            Sprite::from_color(LURKER_TINT, Vec2::splat(LURKER_SIZE)),
+            Visibility::Hidden,
+            Transform::from_translation(position.extend(0.74)),
+        ));
+    }
+}
+
+// Lurkers hold still until the player comes close, then lunge in a straight
+// line and bite once on contact. A revealed one backs away instead.
+fn move_lurkers(
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    death_state: Res<DeathRespawnState>,
+    mut summary: ResMut<RunSummary>,
+    mut player_query: Query<(&Transform, &mut Stats), With<Player>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Lurker>>,
+    mut lurker_query: Query<(&mut Lurker, &mut Location2D, &mut Transform), Without<Player>>,
+) {
+    let Ok((player_transform, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let dt = time.delta_secs();
+    let radius = LURKER_SIZE / 2.0;
+    let blocked = |candidate: Vec2| {
+        solid_overlaps(&grid, candidate, radius)
+            || obstacle_query.iter().any(|(obstacle_transform, obstacle)| {
+                let reach = obstacle.radius + radius;
+                obstacle_transform.translation.truncate().distance_squared(candidate) < reach * reach
+            })
+    };
+
+    for (mut lurker, mut location, mut transform) in &mut lurker_query {
+        lurker.cooldown = (lurker.cooldown - dt).max(0.0);
+        let position = transform.translation.truncate();
+        let offset = player_pos - position;
+        let step = if lurker.revealed {
+            lurker.pounce = None;
+            -offset.normalize_or_zero() * RETREAT_SPEED
+        } else if let Some((direction, remaining)) = lurker.pounce {
+            if !death_state.is_dead && offset.length() <= BITE_RADIUS * WORLD_TILE_SIZE {
+                stats.health = (stats.health - BITE_DAMAGE).max(0.0);
+                summary.last_damage = Some(DeathCause::Ambush);
+                lurker.pounce = None;
+                continue;
+            }
+            lurker.pounce = (remaining > dt).then_some((direction, remaining - dt));
+            direction * POUNCE_SPEED
+        } else if !death_state.is_dead
+            && lurker.cooldown <= 0.0
+            && offset.length() <= POUNCE_RADIUS * WORLD_TILE_SIZE
+        {
+            lurker.pounce = Some((offset.normalize_or_zero(), POUNCE_SECONDS));
+            lurker.cooldown = POUNCE_COOLDOWN_SECONDS;
+            continue;
+        } else {
+            continue;
+        };
+        let next = position + step * WORLD_TILE_SIZE * dt;
+        if blocked(next) {
+            lurker.pounce = None;
+            continue;
+        }
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+        let tile = (next / WORLD_TILE_SIZE).floor().as_ivec2();
+        location.set_if_neq(Location2D {
+            x: tile.x,
+            y: tile.y,
+        });
+    }
+}
+
+// Shown, and open to attack, only on a bright enough tile.
+fn reveal_lurkers(
+    mut commands: Commands,
+    grid: Res<WorldGrid>,
+    mut lurker_query: Query<(Entity, &mut Lurker, &Location2D, &mut Visibility, &mut Sprite)>,
+) {
+    for (entity, mut lurker, location, mut visibility, mut sprite) in &mut lurker_query {
+        let tint = lit_tint(&grid, location.x, location.y, LURKER_TINT)
+            .filter(|_| brightness_at(&grid, location) >= REVEAL_BRIGHTNESS);
+        let revealed = tint.is_some();
+        match tint {
+            Some(color) => {
+                *visibility = Visibility::Visible;
+                sprite.color = color;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+        if revealed == lurker.revealed {
+            continue;
+        }
+        lurker.revealed = revealed;
+        if revealed {
+            commands.entity(entity).remove::<Shrouded>();
+        } else {
+            commands.entity(entity).insert(Shrouded);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LurkerPlugin {
+    pub count: usize,
+}
+
+impl Default for LurkerPlugin {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_LURKER_COUNT,
+        }
+    }
+}
+
+impl LurkerPlugin {
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+}
+
+impl Plugin for LurkerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LurkerConfig { count: self.count })
+            .add_systems(Startup, spawn_lurkers)
+            .add_systems(Update, (respawn_lurkers, move_lurkers).chain())
+            .add_systems(PostUpdate, reveal_lurkers.after(LightingSystems));
+    }
+}
@@ -0,0 +1,545 @@
+// title screen shown at launch and the pause menu behind Escape. The world,
+// the player and food only appear once a game is started from the title;
+// Continue picks up a run already under way, and the World entry picks what
+// new games are played on. Game time stands still
+// whenever the game isn't being played, and systems gated on Playing stop.
+// Menus and screens move focus with the arrows, a d-pad or the left stick,
+// and whatever has focus is outlined.
+use bevy::input::InputSystems;
+use bevy::prelude::*;
+
+use crate::bug_report::{open_bug_report, BugReportForm, BugReportInput};
+use crate::character::PlayerProfile;
+use crate::crafting::CraftingScreen;
+use crate::inventory::InventoryScreen;
+use crate::leaderboard::SeedRetry;
+use crate::locale::Locale;
+use crate::player::{DeathRespawnState, Player, RunSummary};
+use crate::progress::PersistentProgress;
+use crate::settings::SettingsScreen;
+use crate::trader::TradeScreen;
+use crate::world::{RegenerateWorld, WorldGrid, WorldRegeneration};
+use crate::world_file::{ImportedWorld, WorldChoice, WorldFile};
+
+// The left stick counts as a press once pushed past this.
+const STICK_THRESHOLD: f32 = 0.5;
+const FOCUS_OUTLINE: Color = Color::srgb(0.95, 0.8, 0.35);
+const ROW_COLOR: Color = Color::srgb(0.9, 0.85, 0.7);
+const UNAVAILABLE_ROW_COLOR: Color = Color::srgb(0.45, 0.43, 0.38);
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    Playing,
+    Paused,
+    // The game stands still while the camera roams; see photo_mode.
+    PhotoMode,
+}
+
+// This frame's menu presses, from the keyboard or any gamepad, so every
+// screen navigates the same way. Each stick push counts once, like a key.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct MenuNav {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+    pub confirm: bool,
+    pub back: bool,
+}
+
+// Something keyboard or gamepad focus can land on; the focused one gets
+// the outline.
+#[derive(Component, Default)]
+pub struct MenuFocus {
+    pub focused: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MenuItem {
+    NewGame,
+    World,
+    Continue,
+    Resume,
+    Settings,
+    ReportBug,
+    QuitToTitle,
+    Quit,
+}
+
+const TITLE_ITEMS: &[MenuItem] = &[
+    MenuItem::NewGame,
+    MenuItem::World,
+    MenuItem::Continue,
+    MenuItem::Settings,
+    MenuItem::Quit,
+];
+const PAUSE_ITEMS: &[MenuItem] = &[
+    MenuItem::Resume,
+    MenuItem::Settings,
+    MenuItem::ReportBug,
+    MenuItem::QuitToTitle,
+];
+
+impl MenuItem {
+    fn label_key(self) -> &'static str {
+        match self {
+            MenuItem::NewGame => "menu.new_game",
+            MenuItem::World => "menu.world",
+            MenuItem::Continue => "menu.continue",
+            MenuItem::Resume => "menu.resume",
+            MenuItem::Settings => "menu.settings",
+            MenuItem::ReportBug => "menu.report_bug",
+            MenuItem::QuitToTitle => "menu.quit_to_title",
+            MenuItem::Quit => "menu.quit",
+        }
+    }
+
+    // Why it can't be picked right now, if it can't, as a locale key.
+    fn unavailable(self, run_in_progress: bool) -> Option<&'static str> {
+        match self {
+            MenuItem::Continue if !run_in_progress => Some("menu.no_run"),
+            _ => None,
+        }
+    }
+}
+
+// Whichever menu is up; there's never more than one. The title is a locale
+// key.
+#[derive(Resource)]
+struct Menu {
+    title: &'static str,
+    items: &'static [MenuItem],
+    selected: usize,
+}
+
+#[derive(Component)]
+struct MenuRoot;
+
+#[derive(Component)]
+struct MenuTitle;
+
+#[derive(Component)]
+struct MenuRow(usize);
+
+#[derive(Component)]
+struct MenuRowText(usize);
+
+#[derive(Component)]
+struct MenuHint;
+
+fn spawn_menu(commands: &mut Commands, menu: Menu, background: Color) {
+    let rows = menu.items.len();
+    commands.insert_resource(menu);
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(0.0),
+                width: percent(100.0),
+                height: percent(100.0),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: px(6.0),
+                ..default()
+            },
+            BackgroundColor(background),
+            GlobalZIndex(200),
+            MenuRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    margin: UiRect::bottom(px(18.0)),
+                    ..default()
+                },
+                Text::new(""),
+                TextFont::from_font_size(28.0),
+                TextColor(ROW_COLOR),
+                MenuTitle,
+            ));
+            for index in 0..rows {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            padding: UiRect::axes(px(16.0), px(4.0)),
+                            ..default()
+                        },
+                        MenuFocus::default(),
+                        MenuRow(index),
+                    ))
+                    .with_child((
+                        Text::new(""),
+                        TextFont::from_font_size(28.0),
+                        TextColor(ROW_COLOR),
+                        MenuRowText(index),
+                    ));
+            }
+            parent.spawn((
+                Node {
+                    margin: UiRect::top(px(18.0)),
+                    ..default()
+                },
+                Text::new(""),
+                TextFont::from_font_size(18.0),
+                TextColor(ROW_COLOR),
+                MenuHint,
+            ));
+        });
+}
+
+fn setup_main_menu(mut commands: Commands, player_query: Query<(), With<Player>>) {
+    // Start on Continue when there's a run to go back to.
+    let selected = if player_query.is_empty() { 0 } else { 2 };
+    let menu = Menu {
+        title: "menu.title.main",
+        items: TITLE_ITEMS,
+        selected,
+    };
+    spawn_menu(&mut commands, menu, Color::BLACK);
+}
+
+// See-through, so the frozen game shows behind it.
+fn setup_pause_menu(mut commands: Commands) {
+    let menu = Menu {
+        title: "menu.title.paused",
+        items: PAUSE_ITEMS,
+        selected: 0,
+    };
+    spawn_menu(&mut commands, menu, Color::srgba(0.0, 0.0, 0.0, 0.75));
+}
+
+fn close_menu(mut commands: Commands, root_query: Query<Entity, With<MenuRoot>>) {
+    commands.remove_resource::<Menu>();
+    for entity in &root_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn freeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unfreeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn read_menu_nav(
+    input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut stick_held: Local<IVec2>,
+    mut nav: ResMut<MenuNav>,
+) {
+    let pad = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+    let stick = gamepads
+        .iter()
+        .map(|gamepad| gamepad.left_stick())
+        .find(|stick| stick.length() > STICK_THRESHOLD)
+        .unwrap_or(Vec2::ZERO);
+    let held = if stick == Vec2::ZERO {
+        IVec2::ZERO
+    } else if stick.x.abs() > stick.y.abs() {
+        IVec2::new(stick.x.signum() as i32, 0)
+    } else {
+        IVec2::new(0, stick.y.signum() as i32)
+    };
+    let pushed = if held != *stick_held { held } else { IVec2::ZERO };
+    *stick_held = held;
+    *nav = MenuNav {
+        up: input.just_pressed(KeyCode::ArrowUp) || pad(GamepadButton::DPadUp) || pushed.y > 0,
+        down: input.just_pressed(KeyCode::ArrowDown)
+            || pad(GamepadButton::DPadDown)
+            || pushed.y < 0,
+        left: input.just_pressed(KeyCode::ArrowLeft)
+            || pad(GamepadButton::DPadLeft)
+            || pushed.x < 0,
+        right: input.just_pressed(KeyCode::ArrowRight)
+            || pad(GamepadButton::DPadRight)
+            || pushed.x > 0,
+        confirm: input.just_pressed(KeyCode::Enter) || pad(GamepadButton::South),
+        back: input.just_pressed(KeyCode::Escape) || pad(GamepadButton::East),
+    };
+}
+
+fn highlight_menu_focus(
+    mut commands: Commands,
+    focus_query: Query<(Entity, &MenuFocus), Changed<MenuFocus>>,
+) {
+    for (entity, focus) in &focus_query {
+        let color = if focus.focused { FOCUS_OUTLINE } else { Color::NONE };
+        commands
+            .entity(entity)
+            .insert(Outline::new(px(2.0), px(0.0), color));
+    }
+}
+
+// Runs right after input collection, so an open screen gets Escape to close
+// itself first and a pause doesn't also reach gameplay. Start on a gamepad
+// pauses too, and East backs out of the pause menu.
+fn toggle_pause(
+    mut input: ResMut<ButtonInput<KeyCode>>,
+    mut nav: ResMut<MenuNav>,
+    gamepads: Query<&Gamepad>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    screens: (Res<InventoryScreen>, Res<CraftingScreen>, Res<TradeScreen>),
+    bug_report: Res<BugReportForm>,
+    settings: Res<SettingsScreen>,
+    player_query: Query<(), With<Player>>,
+) {
+    let start = gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::Start));
+    let back = match state.get() {
+        AppState::Playing => input.just_pressed(KeyCode::Escape),
+        _ => nav.back,
+    };
+    if !(back || start) || settings.open {
+        return;
+    }
+    let (inventory, crafting, trade) = screens;
+    if inventory.open || crafting.open || trade.open || bug_report.open || player_query.is_empty() {
+        return;
+    }
+    match state.get() {
+        AppState::Playing => next_state.set(AppState::Paused),
+        AppState::Paused | AppState::PhotoMode => next_state.set(AppState::Playing),
+        AppState::MainMenu => return,
+    }
+    input.clear_just_pressed(KeyCode::Escape);
+    nav.back = false;
+}
+
+// A new game over one still under way throws the old one away: the player
+// goes, so the character picker comes up again, and the world is rebuilt.
+// A seed from the leaderboard, or a change between generated and imported
+// worlds, rebuilds the world even with no run going.
+fn start_new_game(
+    commands: &mut Commands,
+    regenerate: &mut MessageWriter<RegenerateWorld>,
+    player_query: &Query<Entity, With<Player>>,
+    seed: Option<u64>,
+    (world, was_imported): (Option<WorldFile>, bool),
+) {
+    let swaps_world = world.is_some() || was_imported;
+    match world {
+        Some(file) => commands.insert_resource(ImportedWorld(file)),
+        None => commands.remove_resource::<ImportedWorld>(),
+    }
+    if player_query.is_empty() {
+        if seed.is_some() || swaps_world {
+            regenerate.write(RegenerateWorld { seed });
+        }
+        return;
+    }
+    for entity in player_query {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<PlayerProfile>();
+    commands.insert_resource(RunSummary::default());
+    commands.insert_resource(DeathRespawnState::default());
+    commands.insert_resource(PersistentProgress::default());
+    regenerate.write(RegenerateWorld { seed });
+}
+
+// Unavailable entries are skipped over. The mouse moves focus too, and a
+// click picks the entry; Left/Right or picking World steps through the
+// worlds. The settings screen and bug report form open on top and take the
+// keys until they're closed.
+fn menu_input(
+    mut commands: Commands,
+    nav: Res<MenuNav>,
+    mut menu: ResMut<Menu>,
+    mut settings: ResMut<SettingsScreen>,
+    mut bug_report: ResMut<BugReportForm>,
+    mut time: ResMut<Time<Virtual>>,
+    grid: Res<WorldGrid>,
+    mut world_choice: ResMut<WorldChoice>,
+    imported: Option<Res<ImportedWorld>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut regenerate: MessageWriter<RegenerateWorld>,
+    mut exit: MessageWriter<AppExit>,
+    player_query: Query<Entity, With<Player>>,
+    row_query: Query<(&Interaction, &MenuRow), Changed<Interaction>>,
+) {
+    if settings.open || bug_report.open {
+        return;
+    }
+    let run_in_progress = !player_query.is_empty();
+    let mut clicked = false;
+    for (interaction, row) in &row_query {
+        let available = menu
+            .items
+            .get(row.0)
+            .is_some_and(|item| item.unavailable(run_in_progress).is_none());
+        if *interaction == Interaction::None || !available {
+            continue;
+        }
+        menu.selected = row.0;
+        clicked |= *interaction == Interaction::Pressed;
+    }
+    let count = menu.items.len();
+    let step = if nav.up {
+        count - 1
+    } else if nav.down {
+        1
+    } else {
+        0
+    };
+    if step > 0 {
+        for _ in 0..count {
+            menu.selected = (menu.selected + step) % count;
+            if menu.items[menu.selected].unavailable(run_in_progress).is_none() {
+                break;
+            }
+        }
+    }
+
+    let item = menu.items[menu.selected];
+    if item == MenuItem::World && (nav.left || nav.right) {
+        world_choice.step(if nav.left { -1 } else { 1 });
+    }
+    if !nav.confirm && !clicked {
+        return;
+    }
+    if item.unavailable(run_in_progress).is_some() {
+        return;
+    }
+    match item {
+        MenuItem::NewGame => {
+            let world = world_choice.load(grid.width, grid.height);
+            let swap = (world, imported.is_some());
+            start_new_game(&mut commands, &mut regenerate, &player_query, None, swap);
+            next_state.set(AppState::Playing);
+        }
+        MenuItem::World => world_choice.step(1),
+        MenuItem::Continue | MenuItem::Resume => next_state.set(AppState::Playing),
+        MenuItem::Settings => settings.show(),
+        MenuItem::ReportBug => open_bug_report(&mut commands, &mut bug_report, &mut time, &grid),
+        MenuItem::QuitToTitle => next_state.set(AppState::MainMenu),
+        MenuItem::Quit => {
+            exit.write(AppExit::Success);
+        }
+    }
+}
+
+// Leaderboard seeds are always for generated worlds.
+fn retry_seed(
+    mut commands: Commands,
+    settings: Res<SettingsScreen>,
+    mut retry: ResMut<SeedRetry>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut regenerate: MessageWriter<RegenerateWorld>,
+    imported: Option<Res<ImportedWorld>>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    // Left for later while the settings screen is up, rather than dropped.
+    if settings.open {
+        return;
+    }
+    let Some(seed) = retry.seed.take() else {
+        return;
+    };
+    let swap = (None, imported.is_some());
+    start_new_game(&mut commands, &mut regenerate, &player_query, Some(seed), swap);
+    next_state.set(AppState::Playing);
+}
+
+// The menu steps aside while a bug report is being written, so it stays
+// out of the report's screenshot.
+fn update_menu(
+    menu: Res<Menu>,
+    locale: Res<Locale>,
+    world_choice: Res<WorldChoice>,
+    bug_report: Res<BugReportForm>,
+    mut root_query: Query<&mut Visibility, With<MenuRoot>>,
+    player_query: Query<(), With<Player>>,
+    mut focus_query: Query<(&MenuRow, &mut MenuFocus)>,
+    mut row_text_query: Query<(&MenuRowText, &mut Text, &mut TextColor)>,
+    mut title_query: Query<&mut Text, (With<MenuTitle>, Without<MenuRowText>)>,
+    mut hint_query: Query<&mut Text, (With<MenuHint>, Without<MenuTitle>, Without<MenuRowText>)>,
+) {
+    if let Ok(mut visibility) = root_query.single_mut() {
+        visibility.set_if_neq(if bug_report.open {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+    let run_in_progress = !player_query.is_empty();
+    for (row, mut focus) in &mut focus_query {
+        let focused = row.0 == menu.selected;
+        if focus.focused != focused {
+            focus.focused = focused;
+        }
+    }
+    for (row, mut text, mut color) in &mut row_text_query {
+        let Some(item) = menu.items.get(row.0) else {
+            continue;
+        };
+        let label = match item {
+            MenuItem::World => {
+                let name = world_choice
+                    .name()
+                    .unwrap_or_else(|| locale.get("menu.world.generated").to_string());
+                locale.format(item.label_key(), &[("world", &name)])
+            }
+            _ => locale.get(item.label_key()).to_string(),
+        };
+        let (line, tint) = match item.unavailable(run_in_progress) {
+            Some(reason) => (format!("{label} ({})", locale.get(reason)), UNAVAILABLE_ROW_COLOR),
+            None => (label, ROW_COLOR),
+        };
+        if text.0 != line {
+            text.0 = line;
+        }
+        color.set_if_neq(TextColor(tint));
+    }
+    if let Ok(mut text) = title_query.single_mut() {
+        text.0 = locale.get(menu.title).to_string();
+    }
+    if let Ok(mut text) = hint_query.single_mut() {
+        text.0 = locale.get("menu.hint").to_string();
+    }
+}
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AppState>()
+            .init_resource::<MenuNav>()
+            .add_systems(OnEnter(AppState::MainMenu), setup_main_menu)
+            .add_systems(OnExit(AppState::MainMenu), close_menu)
+            .add_systems(OnEnter(AppState::Paused), setup_pause_menu)
+            .add_systems(OnExit(AppState::Paused), close_menu)
+            .add_systems(OnEnter(AppState::Playing), unfreeze_time)
+            .add_systems(OnExit(AppState::Playing), freeze_time)
+            .add_systems(
+                PreUpdate,
+                (read_menu_nav, toggle_pause)
+                    .chain()
+                    .after(InputSystems)
+                    .after(BugReportInput),
+            )
+            .add_systems(PostUpdate, highlight_menu_focus)
+            .add_systems(
+                Update,
+                (menu_input, update_menu)
+                    .chain()
+                    .before(WorldRegeneration)
+                    .run_if(resource_exists::<Menu>),
+            )
+            .add_systems(
+                Update,
+                retry_seed
+                    .before(WorldRegeneration)
+                    .run_if(in_state(AppState::MainMenu)),
+            );
+    }
+}
@@ -0,0 +1,382 @@
+// corner minimap composited from cached per-chunk mini-images. Explored
+// ground is drawn from the world grid, whatever the lantern is lighting
+// right now shows warmer, and the player marker carries a pointer for the
+// way they're facing.
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::hud::HudElement;
+use crate::light::{facing_dir, LightConfig};
+use crate::player::{Player, PlayerState};
+use crate::progress::PersistentProgress;
+use crate::settings::{Action, GameSettings};
+use crate::world::{TileKind, TilesChanged, WorldGrid, WORLD_TILE_SIZE};
+
+const TILES_PER_PIXEL: usize = 5;
+const MINIMAP_CHUNK_TILES: usize = 50;
+const CHUNK_PIXELS: usize = MINIMAP_CHUNK_TILES / TILES_PER_PIXEL;
+const MINIMAP_DISPLAY_SIZE: f32 = 200.0;
+const PLAYER_MARKER_SIZE: f32 = 5.0;
+const FACING_POINTER_LENGTH: f32 = 7.0;
+const FACING_POINTER_WIDTH: f32 = 2.0;
+const LIT_THRESHOLD: f32 = 0.05;
+const LIT_REFRESH_SECONDS: f32 = 0.25;
+const LIT_STRENGTH: f32 = 0.45;
+
+const GROUND_COLOR: [u8; 4] = [38, 44, 36, 255];
+const RUBBLE_COLOR: [u8; 4] = [92, 80, 64, 255];
+const WATER_COLOR: [u8; 4] = [40, 70, 130, 255];
+const WALL_COLOR: [u8; 4] = [150, 150, 150, 255];
+const UNEXPLORED_COLOR: [u8; 4] = [0, 0, 0, 255];
+const LIT_COLOR: [u8; 4] = [235, 205, 130, 255];
+
+// Each chunk keeps its own small RGBA image; only dirty chunks are
+// regenerated and blitted into the shared minimap texture.
+#[derive(Resource)]
+struct MinimapCache {
+    image: Handle<Image>,
+    map_pixels: UVec2,
+    cols: usize,
+    explored_cells: usize,
+    chunk_pixels: Vec<Vec<u8>>,
+    dirty: Vec<bool>,
+    // Chunks under the lantern at the last refresh, redrawn next time so
+    // the glow fades where it's moved away.
+    lit_chunks: Vec<usize>,
+    lit_timer: Timer,
+}
+
+#[derive(Component)]
+struct MinimapRoot;
+
+#[derive(Component)]
+struct MinimapPlayerMarker;
+
+#[derive(Component)]
+struct MinimapFacingPointer;
+
+fn mix(from: [u8; 4], to: [u8; 4], t: f32) -> [u8; 4] {
+    let mut color = [0; 4];
+    for (channel, value) in color.iter_mut().enumerate() {
+        let mixed = from[channel] as f32 + (to[channel] as f32 - from[channel] as f32) * t;
+        *value = mixed.round() as u8;
+    }
+    color
+}
+
+fn shade_pixel(
+    grid: &WorldGrid,
+    progress: &PersistentProgress,
+    pixel_x: usize,
+    pixel_y: usize,
+) -> [u8; 4] {
+    let start_x = pixel_x * TILES_PER_PIXEL;
+    let start_y = pixel_y * TILES_PER_PIXEL;
+    if !progress.is_explored(start_x as i32, start_y as i32) {
+        return UNEXPLORED_COLOR;
+    }
+    let end_x = (start_x + TILES_PER_PIXEL).min(grid.width);
+    let end_y = (start_y + TILES_PER_PIXEL).min(grid.height);
+
+    let mut walls = 0;
+    let mut rubble = 0;
+    let mut water = 0;
+    let mut total = 0;
+    let mut lit = 0;
+    for y in start_y..end_y {
+        for x in start_x..end_x {
+            total += 1;
+            if grid.brightness[y][x] > LIT_THRESHOLD {
+                lit += 1;
+            }
+            if grid.walls[y][x] {
+                walls += 1;
+            } else if grid.tiles[y][x] == TileKind::Rubble {
+                rubble += 1;
+            } else if grid.tiles[y][x] == TileKind::Water {
+                water += 1;
+            }
+        }
+    }
+    if total == 0 {
+        return [0, 0, 0, 255];
+    }
+
+    let floor = if water > 0 {
+        WATER_COLOR
+    } else if rubble > 0 {
+        RUBBLE_COLOR
+    } else {
+        GROUND_COLOR
+    };
+    let color = mix(floor, WALL_COLOR, walls as f32 / total as f32);
+    mix(color, LIT_COLOR, LIT_STRENGTH * lit as f32 / total as f32)
+}
+
+fn map_pixels(grid: &WorldGrid) -> UVec2 {
+    UVec2::new(
+        grid.width.div_ceil(TILES_PER_PIXEL) as u32,
+        grid.height.div_ceil(TILES_PER_PIXEL) as u32,
+    )
+}
+
+fn render_chunk(
+    grid: &WorldGrid,
+    progress: &PersistentProgress,
+    chunk_x: usize,
+    chunk_y: usize,
+) -> Vec<u8> {
+    let map = map_pixels(grid);
+    let mut pixels = Vec::with_capacity(CHUNK_PIXELS * CHUNK_PIXELS * 4);
+    for local_y in 0..CHUNK_PIXELS {
+        for local_x in 0..CHUNK_PIXELS {
+            let pixel_x = chunk_x * CHUNK_PIXELS + local_x;
+            let pixel_y = chunk_y * CHUNK_PIXELS + local_y;
+            let color = if pixel_x < map.x as usize && pixel_y < map.y as usize {
+                shade_pixel(grid, progress, pixel_x, pixel_y)
+            } else {
+                [0, 0, 0, 255]
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+    pixels
+}
+
+// Image rows run top to bottom while world y grows upwards, so the chunk is
+// flipped vertically on the way in.
+fn blit_chunk(data: &mut [u8], map: UVec2, pixels: &[u8], chunk_x: usize, chunk_y: usize) {
+    let (map_x, map_y) = (map.x as usize, map.y as usize);
+    for local_y in 0..CHUNK_PIXELS {
+        let pixel_y = chunk_y * CHUNK_PIXELS + local_y;
+        if pixel_y >= map_y {
+            break;
+        }
+        let row = map_y - 1 - pixel_y;
+        let start_x = chunk_x * CHUNK_PIXELS;
+        let width = CHUNK_PIXELS.min(map_x - start_x);
+        let dst = (row * map_x + start_x) * 4;
+        let src = local_y * CHUNK_PIXELS * 4;
+        data[dst..dst + width * 4].copy_from_slice(&pixels[src..src + width * 4]);
+    }
+}
+
+fn setup_minimap(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    grid: Res<WorldGrid>,
+) {
+    let map = map_pixels(&grid);
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: map.x,
+            height: map.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    let handle = images.add(image);
+
+    let cols = (map.x as usize).div_ceil(CHUNK_PIXELS);
+    let rows = (map.y as usize).div_ceil(CHUNK_PIXELS);
+    commands.insert_resource(MinimapCache {
+        image: handle.clone(),
+        map_pixels: map,
+        explored_cells: 0,
+        cols,
+        chunk_pixels: vec![Vec::new(); cols * rows],
+        dirty: vec![true; cols * rows],
+        lit_chunks: Vec::new(),
+        lit_timer: Timer::from_seconds(LIT_REFRESH_SECONDS, TimerMode::Repeating),
+    });
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: px(16.0),
+                bottom: px(16.0),
+                width: px(MINIMAP_DISPLAY_SIZE),
+                height: px(MINIMAP_DISPLAY_SIZE),
+                border: UiRect::all(px(2.0)),
+                ..default()
+            },
+            BorderColor::all(Color::srgb(0.25, 0.25, 0.25)),
+            ImageNode::new(handle),
+            HudElement::default(),
+            MinimapRoot,
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: px(PLAYER_MARKER_SIZE),
+                    height: px(PLAYER_MARKER_SIZE),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.95, 0.85, 0.3)),
+                MinimapPlayerMarker,
+            ));
+            root.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: px(FACING_POINTER_WIDTH),
+                    height: px(FACING_POINTER_LENGTH),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.95, 0.85, 0.3)),
+                MinimapFacingPointer,
+            ));
+        });
+}
+
+fn mark_dirty_chunks(
+    mut changes: MessageReader<TilesChanged>,
+    time: Res<Time>,
+    progress: Res<PersistentProgress>,
+    light: Res<LightConfig>,
+    mut cache: ResMut<MinimapCache>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    // Explored cells are only ever forgotten all at once, on death.
+    if progress.explored.len() < cache.explored_cells {
+        cache.dirty.fill(true);
+    }
+    cache.explored_cells = progress.explored.len();
+
+    let chunk_span = (CHUNK_PIXELS * TILES_PER_PIXEL) as i32;
+    let rows = cache.dirty.len() / cache.cols;
+    for change in changes.read() {
+        let min_x = (change.min.x.max(0) / chunk_span) as usize;
+        let min_y = (change.min.y.max(0) / chunk_span) as usize;
+        let max_x = ((change.max.x.max(0) / chunk_span) as usize).min(cache.cols - 1);
+        let max_y = ((change.max.y.max(0) / chunk_span) as usize).min(rows - 1);
+        for chunk_y in min_y..=max_y {
+            for chunk_x in min_x..=max_x {
+                let index = chunk_y * cache.cols + chunk_x;
+                cache.dirty[index] = true;
+            }
+        }
+    }
+
+    // The brightness grid changes every frame, so the lantern's patch is
+    // redrawn on a timer rather than chasing it.
+    if !cache.lit_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let cache = &mut *cache;
+    for index in cache.lit_chunks.drain(..) {
+        cache.dirty[index] = true;
+    }
+    let Ok(transform) = player_query.single() else {
+        return;
+    };
+    let tile = (transform.translation.truncate() / WORLD_TILE_SIZE).as_ivec2();
+    let reach = light.max_range.ceil() as i32;
+    let min_x = ((tile.x - reach).max(0) / chunk_span) as usize;
+    let min_y = ((tile.y - reach).max(0) / chunk_span) as usize;
+    let max_x = (((tile.x + reach).max(0) / chunk_span) as usize).min(cache.cols - 1);
+    let max_y = (((tile.y + reach).max(0) / chunk_span) as usize).min(rows - 1);
+    for chunk_y in min_y..=max_y {
+        for chunk_x in min_x..=max_x {
+            let index = chunk_y * cache.cols + chunk_x;
+            cache.dirty[index] = true;
+            cache.lit_chunks.push(index);
+        }
+    }
+}
+
+fn refresh_minimap(
+    grid: Res<WorldGrid>,
+    progress: Res<PersistentProgress>,
+    mut cache: ResMut<MinimapCache>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if !cache.dirty.contains(&true) {
+        return;
+    }
+    let Some(image) = images.get_mut(&cache.image) else {
+        return;
+    };
+    let Some(data) = image.data.as_mut() else {
+        return;
+    };
+
+    let cache = &mut *cache;
+    let mut refreshed = Vec::new();
+    for (index, dirty) in cache.dirty.iter_mut().enumerate() {
+        if *dirty {
+            cache.chunk_pixels[index] =
+                render_chunk(&grid, &progress, index % cache.cols, index / cache.cols);
+            *dirty = false;
+            refreshed.push(index);
+        }
+    }
+    for index in refreshed {
+        let pixels = &cache.chunk_pixels[index];
+        blit_chunk(data, cache.map_pixels, pixels, index % cache.cols, index / cache.cols);
+    }
+}
+
+fn update_minimap_marker(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    grid: Res<WorldGrid>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+    mut root_query: Query<&mut Visibility, With<MinimapRoot>>,
+    mut marker_query: Query<&mut Node, With<MinimapPlayerMarker>>,
+    mut pointer_query: Query<
+        (&mut Node, &mut UiTransform),
+        (With<MinimapFacingPointer>, Without<MinimapPlayerMarker>),
+    >,
+) {
+    if input.just_pressed(settings.key(Action::Map))
+        && let Ok(mut visibility) = root_query.single_mut()
+    {
+        visibility.toggle_inherited_hidden();
+    }
+
+    let Ok((transform, state)) = player_query.single() else {
+        return;
+    };
+    let Ok(mut marker) = marker_query.single_mut() else {
+        return;
+    };
+    let tile = transform.translation.truncate() / WORLD_TILE_SIZE;
+    let u = (tile.x / grid.width as f32).clamp(0.0, 1.0);
+    let v = 1.0 - (tile.y / grid.height as f32).clamp(0.0, 1.0);
+    let center = Vec2::new(u, v) * MINIMAP_DISPLAY_SIZE;
+    let half = PLAYER_MARKER_SIZE * 0.5;
+    marker.left = px(center.x - half);
+    marker.top = px(center.y - half);
+
+    // The pointer sticks out of the marker the way the player faces; the
+    // map's y runs down the screen, so the facing is flipped to match.
+    let Ok((mut pointer, mut pointer_transform)) = pointer_query.single_mut() else {
+        return;
+    };
+    let facing = facing_dir(state.facing).as_vec2().normalize();
+    let screen_dir = Vec2::new(facing.x, -facing.y);
+    let tip = center + screen_dir * (half + FACING_POINTER_LENGTH * 0.5);
+    pointer.left = px(tip.x - FACING_POINTER_WIDTH * 0.5);
+    pointer.top = px(tip.y - FACING_POINTER_LENGTH * 0.5);
+    // Upright is pointing up the screen; turn clockwise from there.
+    pointer_transform.rotation = Rot2::radians(facing.x.atan2(facing.y));
+}
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_minimap).add_systems(
+            Update,
+            (mark_dirty_chunks, refresh_minimap, update_minimap_marker).chain(),
+        );
+    }
+}
@@ -0,0 +1,135 @@
+// wraiths: enemies that only come out at night. They spawn in the dark like
+// other enemies, burn away while standing on lit tiles and are gone at dawn,
+// so the lantern is a weapon against them.
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::combat::Health;
+use crate::day_night::{DayNightCycle, DayPhaseChanged};
+use crate::enemy::{find_dark_spawn, spawn_enemy};
+use crate::event_log::EventLog;
+use crate::food::Location2D;
+use crate::light::{is_lit, LightConfig, Lightable};
+use crate::player::{DeathRespawnState, Player, PlayerState};
+use crate::world::WorldGrid;
+
+const DEFAULT_MAX_WRAITHS: usize = 3;
+const DEFAULT_SPAWN_SECONDS: f32 = 15.0;
+const WRAITH_STRENGTH: f32 = 1.2;
+const WRAITH_TINT: Color = Color::srgb(0.55, 0.65, 0.8);
+const LIGHT_BURN_PER_SECOND: f32 = 30.0;
+
+#[derive(Component)]
+pub struct NightMonster;
+
+#[derive(Resource)]
+pub struct NightMonsterConfig {
+    pub max_monsters: usize,
+    pub timer: Timer,
+}
+
+fn spawn_night_monsters(
+    mut commands: Commands,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    cycle: Res<DayNightCycle>,
+    grid: Res<WorldGrid>,
+    light_config: Res<LightConfig>,
+    mut config: ResMut<NightMonsterConfig>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+    monster_query: Query<(), With<NightMonster>>,
+) {
+    if death_state.is_dead || !cycle.is_night() || !config.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    if monster_query.iter().count() >= config.max_monsters {
+        return;
+    }
+    let Ok((player_transform, state)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let Some(position) = find_dark_spawn(&grid, &light_config, player_pos, state.facing) else {
+        return;
+    };
+    let wraith = spawn_enemy(&mut commands, position, WRAITH_STRENGTH);
+    commands
+        .entity(wraith)
+        .insert((NightMonster, Lightable { tint: WRAITH_TINT }));
+}
+
+// Light eats away at them; one that burns out leaves nothing behind.
+fn burn_in_light(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    mut event_log: ResMut<EventLog>,
+    mut monster_query: Query<(Entity, &Location2D, &mut Health), With<NightMonster>>,
+) {
+    for (entity, location, mut health) in &mut monster_query {
+        if !is_lit(&grid, location.x, location.y) {
+            continue;
+        }
+        health.current = (health.current - LIGHT_BURN_PER_SECOND * time.delta_secs()).max(0.0);
+        if health.current <= 0.0 {
+            event_log.push(&time, "a wraith burned away in the light");
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn despawn_at_dawn(
+    mut commands: Commands,
+    mut phases: MessageReader<DayPhaseChanged>,
+    monster_query: Query<Entity, With<NightMonster>>,
+) {
+    if !phases.read().any(|phase| *phase == DayPhaseChanged::Dawn) {
+        return;
+    }
+    for entity in &monster_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NightMonsterPlugin {
+    pub max_monsters: usize,
+    pub spawn_seconds: f32,
+}
+
+impl Default for NightMonsterPlugin {
+    fn default() -> Self {
+        Self {
+            max_monsters: DEFAULT_MAX_WRAITHS,
+            spawn_seconds: DEFAULT_SPAWN_SECONDS,
+        }
+    }
+}
+
+impl NightMonsterPlugin {
+    pub fn with_max_monsters(mut self, max_monsters: usize) -> Self {
+        self.max_monsters = max_monsters;
+        self
+    }
+
+    pub fn with_spawn_seconds(mut self, seconds: f32) -> Self {
+        self.spawn_seconds = seconds;
+        self
+    }
+}
+
+impl Plugin for NightMonsterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(NightMonsterConfig {
+            max_monsters: self.max_monsters,
+            timer: Timer::new(
+                Duration::from_secs_f32(self.spawn_seconds.max(0.1)),
+                TimerMode::Repeating,
+            ),
+        })
+        .add_systems(
+            Update,
+            (spawn_night_monsters, burn_in_light, despawn_at_dawn).chain(),
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// sounds made in the world, for creatures that listen. A noise carries
+// further the louder it is and loses some of its strength through each wall
+// between it and the listener; water doesn't muffle it.
+use bevy::prelude::*;
+
+use crate::world::{WorldGrid, WORLD_TILE_SIZE};
+
+// Tiles a noise of loudness 1.0 carries through open ground.
+const TILES_PER_LOUDNESS: f32 = 60.0;
+// Share of the loudness left after passing through one wall tile.
+const WALL_DAMPING: f32 = 0.6;
+// Past this many walls nothing gets through anyway.
+const MAX_WALLS: i32 = 8;
+
+#[derive(Message, Debug, Clone, Copy)]
+pub struct NoiseEvent {
+    pub position: Vec2,
+    pub loudness: f32,
+}
+
+fn walls_between(grid: &WorldGrid, from: Vec2, to: Vec2) -> i32 {
+    let steps = (from.distance(to) / WORLD_TILE_SIZE).ceil() as i32;
+    let mut walls = 0;
+    let mut last_tile = None;
+    for step in 1..steps {
+        let point = from.lerp(to, step as f32 / steps as f32);
+        let tile = (point / WORLD_TILE_SIZE).floor().as_ivec2();
+        if last_tile == Some(tile) {
+            continue;
+        }
+        last_tile = Some(tile);
+        if grid.in_bounds(tile.x, tile.y) && grid.walls[tile.y as usize][tile.x as usize] {
+            walls += 1;
+            if walls >= MAX_WALLS {
+                break;
+            }
+        }
+    }
+    walls
+}
+
+// How loud the noise is where the listener stands, fading to zero at the
+// edge of its reach; 0.0 means it isn't heard at all.
+pub fn heard_loudness(grid: &WorldGrid, noise: &NoiseEvent, listener: Vec2) -> f32 {
+    let distance = noise.position.distance(listener) / WORLD_TILE_SIZE;
+    if distance > noise.loudness * TILES_PER_LOUDNESS {
+        return 0.0;
+    }
+    let walls = walls_between(grid, noise.position, listener);
+    let loudness = noise.loudness * WALL_DAMPING.powi(walls);
+    let reach = loudness * TILES_PER_LOUDNESS;
+    if reach <= 0.0 || distance > reach {
+        return 0.0;
+    }
+    loudness * (1.0 - distance / reach)
+}
+
+pub struct NoisePlugin;
+
+impl Plugin for NoisePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<NoiseEvent>();
+    }
+}
@@ -0,0 +1,300 @@
+// objectives: a short list of goals for the run, each counted up from
+// ObjectiveProgress messages until it's done, and a HUD panel listing them
+// with their progress, e.g. "Eat 3 apples 1/3". Meals and dawns are fed in
+// here; crafting sends its own. The panel's key folds it down to its
+// heading, and a new world starts the list over.
+use bevy::prelude::*;
+
+use crate::day_night::DayPhaseChanged;
+use crate::food::EatFood;
+use crate::hud::{HudCorner, HudLayout, HudWidget};
+use crate::items::ItemKind;
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::script::ScriptRunner;
+use crate::settings::key_label;
+use crate::world::WorldRegenerated;
+
+const DEFAULT_TOGGLE_KEY: KeyCode = KeyCode::F4;
+const PANEL_WIDTH: f32 = 200.0;
+const HEADING_COLOR: Color = Color::srgb(0.95, 0.9, 0.7);
+const OBJECTIVE_COLOR: Color = Color::srgb(0.9, 0.9, 0.88);
+const DONE_COLOR: Color = Color::srgb(0.5, 0.75, 0.5);
+
+// What counts towards an objective. Food goes by its name in foods.ron.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectiveGoal {
+    Eat(&'static str),
+    Craft(ItemKind),
+    SurviveNight,
+}
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct ObjectiveProgress {
+    pub goal: ObjectiveGoal,
+    pub amount: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Objective {
+    // The locale key describing it, e.g. "Eat 3 apples"; the count follows.
+    pub text_key: &'static str,
+    pub goal: ObjectiveGoal,
+    pub target: u32,
+    progress: u32,
+}
+
+impl Objective {
+    pub fn new(text_key: &'static str, goal: ObjectiveGoal, target: u32) -> Self {
+        Self {
+            text_key,
+            goal,
+            target: target.max(1),
+            progress: 0,
+        }
+    }
+
+    pub fn progress(&self) -> u32 {
+        self.progress
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.progress >= self.target
+    }
+}
+
+// The run's objectives, plus the list a new world starts from.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Objectives {
+    list: Vec<Objective>,
+    starting: Vec<Objective>,
+}
+
+impl Objectives {
+    // Also comes back with every new world.
+    pub fn add(&mut self, objective: Objective) {
+        self.starting.push(objective.clone());
+        self.list.push(objective);
+    }
+
+    pub fn list(&self) -> &[Objective] {
+        &self.list
+    }
+
+    pub fn reset(&mut self) {
+        self.list = self.starting.clone();
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct ObjectiveTracker {
+    pub collapsed: bool,
+    pub toggle_key: KeyCode,
+}
+
+#[derive(Component)]
+struct ObjectiveHeading;
+
+#[derive(Component)]
+struct ObjectiveList;
+
+fn default_objectives() -> Vec<Objective> {
+    vec![
+        Objective::new("objective.eat_apples", ObjectiveGoal::Eat("Apple"), 3),
+        Objective::new("objective.craft_torch", ObjectiveGoal::Craft(ItemKind::Torch), 1),
+        Objective::new("objective.survive_night", ObjectiveGoal::SurviveNight, 1),
+    ]
+}
+
+fn attach_objectives(
+    mut commands: Commands,
+    layout: Res<HudLayout>,
+    corner_query: Query<(Entity, &HudCorner), Added<HudCorner>>,
+) {
+    let anchor = layout.anchor(HudWidget::Objectives);
+    for (corner, _) in corner_query.iter().filter(|(_, corner)| corner.0 == anchor) {
+        commands.entity(corner).with_children(|corner| {
+            corner
+                .spawn((
+                    Node {
+                        width: px(PANEL_WIDTH),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::axes(px(8.0), px(6.0)),
+                        row_gap: px(2.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.06, 0.06, 0.07, 0.7)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(15.0),
+                        TextColor(HEADING_COLOR),
+                        ObjectiveHeading,
+                    ));
+                    panel.spawn((
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: px(2.0),
+                            ..default()
+                        },
+                        ObjectiveList,
+                    ));
+                });
+        });
+    }
+}
+
+fn feed_objectives(
+    mut meals: MessageReader<EatFood>,
+    mut phases: MessageReader<DayPhaseChanged>,
+    mut progress: MessageWriter<ObjectiveProgress>,
+) {
+    for meal in meals.read() {
+        if let Some(food) = meal.food {
+            progress.write(ObjectiveProgress {
+                goal: ObjectiveGoal::Eat(food.name),
+                amount: 1,
+            });
+        }
+    }
+    // Making it to dawn is surviving the night.
+    for phase in phases.read() {
+        if *phase == DayPhaseChanged::Dawn {
+            progress.write(ObjectiveProgress {
+                goal: ObjectiveGoal::SurviveNight,
+                amount: 1,
+            });
+        }
+    }
+}
+
+fn track_objectives(
+    locale: Res<Locale>,
+    mut objectives: ResMut<Objectives>,
+    mut runner: ResMut<ScriptRunner>,
+    mut progress: MessageReader<ObjectiveProgress>,
+    mut regenerated: MessageReader<WorldRegenerated>,
+) {
+    if regenerated.read().count() > 0 {
+        objectives.reset();
+    }
+    for step in progress.read() {
+        for objective in &mut objectives.list {
+            if objective.goal != step.goal || objective.is_done() {
+                continue;
+            }
+            objective.progress = (objective.progress + step.amount).min(objective.target);
+            if objective.is_done() {
+                let text = locale.get(objective.text_key);
+                runner.narrate(locale.format("objective.complete", &[("objective", text)]));
+            }
+        }
+    }
+}
+
+fn toggle_objectives(input: Res<ButtonInput<KeyCode>>, mut tracker: ResMut<ObjectiveTracker>) {
+    if input.just_pressed(tracker.toggle_key) {
+        tracker.collapsed = !tracker.collapsed;
+    }
+}
+
+// The rows are rebuilt whenever anything on them changes.
+fn update_objective_panel(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    objectives: Res<Objectives>,
+    tracker: Res<ObjectiveTracker>,
+    mut heading_query: Query<&mut Text, With<ObjectiveHeading>>,
+    list_query: Query<Entity, With<ObjectiveList>>,
+) {
+    let Ok(list) = list_query.single() else {
+        return;
+    };
+    if !(objectives.is_changed() || tracker.is_changed() || locale.is_changed()) {
+        return;
+    }
+    let left = objectives.list.iter().filter(|objective| !objective.is_done()).count();
+    if let Ok(mut heading) = heading_query.single_mut() {
+        let key = key_label(tracker.toggle_key);
+        heading.0 = if tracker.collapsed {
+            locale.format(
+                "objective.heading_collapsed",
+                &[("left", &left.to_string()), ("key", &key)],
+            )
+        } else {
+            locale.format("objective.heading", &[("key", &key)])
+        };
+    }
+    commands.entity(list).despawn_related::<Children>();
+    if tracker.collapsed {
+        return;
+    }
+    commands.entity(list).with_children(|list| {
+        for objective in &objectives.list {
+            let line = format!(
+                "{} {}/{}",
+                locale.get(objective.text_key),
+                objective.progress,
+                objective.target
+            );
+            let color = if objective.is_done() { DONE_COLOR } else { OBJECTIVE_COLOR };
+            list.spawn((Text::new(line), TextFont::from_font_size(14.0), TextColor(color)));
+        }
+    });
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectivesPlugin {
+    pub toggle_key: KeyCode,
+    // Starts folded down to the heading.
+    pub collapsed: bool,
+}
+
+impl Default for ObjectivesPlugin {
+    fn default() -> Self {
+        Self {
+            toggle_key: DEFAULT_TOGGLE_KEY,
+            collapsed: false,
+        }
+    }
+}
+
+impl ObjectivesPlugin {
+    pub fn with_toggle_key(mut self, key: KeyCode) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    pub fn collapsed(mut self) -> Self {
+        self.collapsed = true;
+        self
+    }
+}
+
+impl Plugin for ObjectivesPlugin {
+    fn build(&self, app: &mut App) {
+        let mut objectives = Objectives::default();
+        for objective in default_objectives() {
+            objectives.add(objective);
+        }
+        app.add_message::<ObjectiveProgress>()
+            .insert_resource(objectives)
+            .insert_resource(ObjectiveTracker {
+                collapsed: self.collapsed,
+                toggle_key: self.toggle_key,
+            })
+            .add_systems(
+                Update,
+                (
+                    attach_objectives,
+                    (feed_objectives, track_objectives).chain(),
+                    toggle_objectives.run_if(in_state(AppState::Playing)),
+                    update_objective_panel
+                        .after(attach_objectives)
+                        .after(track_objectives)
+                        .after(toggle_objectives),
+                ),
+            );
+    }
+}
@@ -0,0 +1,132 @@
+// trees and rocks scattered over the map at startup
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::food::Location2D;
+use crate::props::Prop;
+use crate::world_file::ImportedWorld;
+use crate::world::{world_rng, WorldGrid, WorldRegenerated, WorldSeed, WORLD_TILE_SIZE};
+
+const TREE_COUNT: usize = 900;
+const ROCK_COUNT: usize = 600;
+const TREE_RADIUS: f32 = 8.0;
+const ROCK_RADIUS: f32 = 6.0;
+const EDGE_MARGIN: i32 = 16;
+const SPAWN_CLEARING_RADIUS: i32 = 48;
+const OBSTACLE_SEED_SALT: u64 = 0x0b57;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObstacleKind {
+    Tree,
+    Rock,
+}
+
+impl ObstacleKind {
+    fn radius(self) -> f32 {
+        match self {
+            ObstacleKind::Tree => TREE_RADIUS,
+            ObstacleKind::Rock => ROCK_RADIUS,
+        }
+    }
+
+    fn tint(self) -> Color {
+        match self {
+            ObstacleKind::Tree => Color::srgb(0.2, 0.55, 0.25),
+            ObstacleKind::Rock => Color::srgb(0.55, 0.55, 0.6),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Obstacle {
+    pub kind: ObstacleKind,
+    pub radius: f32,
+}
+
+fn spawn_obstacles(
+    mut commands: Commands,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    imported: Option<Res<ImportedWorld>>,
+) {
+    place_obstacles(&mut commands, &grid, *seed, imported.as_deref());
+}
+
+fn respawn_obstacles(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    imported: Option<Res<ImportedWorld>>,
+    obstacle_query: Query<Entity, With<Obstacle>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &obstacle_query {
+        commands.entity(entity).despawn();
+    }
+    place_obstacles(&mut commands, &grid, *seed, imported.as_deref());
+}
+
+fn place_obstacles(
+    commands: &mut Commands,
+    grid: &WorldGrid,
+    seed: WorldSeed,
+    imported: Option<&ImportedWorld>,
+) {
+    if let Some(imported) = imported {
+        for record in &imported.0.obstacles {
+            let location = Location2D { x: record.x, y: record.y };
+            spawn_obstacle(commands, record.kind, location);
+        }
+        return;
+    }
+
+    let mut rng = world_rng(seed, OBSTACLE_SEED_SALT);
+
+    let center_x = grid.width as i32 / 2;
+    let center_y = grid.height as i32 / 2;
+    let kinds = std::iter::repeat_n(ObstacleKind::Tree, TREE_COUNT)
+        .chain(std::iter::repeat_n(ObstacleKind::Rock, ROCK_COUNT));
+    for kind in kinds {
+        let x = rng.random_range(EDGE_MARGIN..grid.width as i32 - EDGE_MARGIN);
+        let y = rng.random_range(EDGE_MARGIN..grid.height as i32 - EDGE_MARGIN);
+        let dx = x - center_x;
+        let dy = y - center_y;
+        if dx * dx + dy * dy < SPAWN_CLEARING_RADIUS * SPAWN_CLEARING_RADIUS {
+            continue;
+        }
+        if grid.is_solid(x, y) {
+            continue;
+        }
+        spawn_obstacle(commands, kind, Location2D { x, y });
+    }
+}
+
+fn spawn_obstacle(commands: &mut Commands, kind: ObstacleKind, location: Location2D) {
+    let radius = kind.radius();
+    commands.spawn((
+        Obstacle { kind, radius },
+        Prop {
+            size: Vec2::splat(radius * 2.0),
+            tint: kind.tint(),
+        },
+        location,
+        Transform::from_translation(Vec3::new(
+            location.x as f32 * WORLD_TILE_SIZE,
+            location.y as f32 * WORLD_TILE_SIZE,
+            0.5,
+        )),
+    ));
+}
+
+pub struct ObstaclePlugin;
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_obstacles)
+            .add_systems(Update, respawn_obstacles);
+    }
+}
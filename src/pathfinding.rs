@@ -0,0 +1,274 @@
+// A* over a coarse nav grid built from the world's walls and water. Cells
+// are a few tiles wide so the search stays small and a path leaves room for
+// something bigger than a single tile; found paths are cached until the
+// tiles under them change.
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::world::{TilesChanged, WorldGrid, WORLD_TILE_SIZE};
+
+pub const NAV_CELL_TILES: i32 = 8;
+// Gives up on searches that open more cells than this; far-off goals are
+// planned again as the follower closes in.
+const MAX_EXPANDED_CELLS: usize = 4000;
+const PATH_CACHE_CAPACITY: usize = 256;
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+// A cell is blocked if any tile in it is solid.
+#[derive(Default, Debug, Clone)]
+pub struct NavGrid {
+    pub cols: i32,
+    pub rows: i32,
+    blocked: Vec<bool>,
+}
+
+impl NavGrid {
+    pub fn cell_of(position: Vec2) -> IVec2 {
+        (position / (WORLD_TILE_SIZE * NAV_CELL_TILES as f32)).floor().as_ivec2()
+    }
+
+    pub fn cell_center(cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + Vec2::splat(0.5)) * NAV_CELL_TILES as f32 * WORLD_TILE_SIZE
+    }
+
+    fn in_bounds(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.cols && cell.y < self.rows
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.in_bounds(cell) && !self.blocked[(cell.y * self.cols + cell.x) as usize]
+    }
+
+    fn fits(&self, grid: &WorldGrid) -> bool {
+        self.cols == (grid.width as i32 + NAV_CELL_TILES - 1) / NAV_CELL_TILES
+            && self.rows == (grid.height as i32 + NAV_CELL_TILES - 1) / NAV_CELL_TILES
+    }
+
+    fn rebuild(&mut self, grid: &WorldGrid) {
+        self.cols = (grid.width as i32 + NAV_CELL_TILES - 1) / NAV_CELL_TILES;
+        self.rows = (grid.height as i32 + NAV_CELL_TILES - 1) / NAV_CELL_TILES;
+        self.blocked = vec![false; (self.cols * self.rows) as usize];
+        self.refresh(grid, IVec2::ZERO, IVec2::new(self.cols - 1, self.rows - 1));
+    }
+
+    // Recomputes the inclusive rectangle of cells.
+    fn refresh(&mut self, grid: &WorldGrid, min_cell: IVec2, max_cell: IVec2) {
+        let min_cell = min_cell.max(IVec2::ZERO);
+        let max_cell = max_cell.min(IVec2::new(self.cols - 1, self.rows - 1));
+        for cy in min_cell.y..=max_cell.y {
+            for cx in min_cell.x..=max_cell.x {
+                let x0 = cx * NAV_CELL_TILES;
+                let y0 = cy * NAV_CELL_TILES;
+                let blocked = (y0..y0 + NAV_CELL_TILES)
+                    .any(|y| (x0..x0 + NAV_CELL_TILES).any(|x| grid.is_solid(x, y)));
+                self.blocked[(cy * self.cols + cx) as usize] = blocked;
+            }
+        }
+    }
+
+    // Cells from just after `start` up to and including `goal`. The start and
+    // goal may themselves be blocked, since whoever stands there is smaller
+    // than a cell. Diagonal steps can't cut a blocked corner.
+    pub fn find_path(&self, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+        if !self.in_bounds(start) || !self.in_bounds(goal) {
+            return None;
+        }
+        if start == goal {
+            return Some(Vec::new());
+        }
+        let heuristic = |cell: IVec2| {
+            let d = (cell - goal).abs();
+            STRAIGHT_COST * d.max_element() as u32
+                + (DIAGONAL_COST - STRAIGHT_COST) * d.min_element() as u32
+        };
+        let passable = |cell: IVec2| cell == goal || self.is_walkable(cell);
+
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+        let mut cost: HashMap<IVec2, u32> = HashMap::from([(start, 0)]);
+        open.push(Reverse((heuristic(start), start.x, start.y)));
+        let mut expanded = 0;
+
+        while let Some(Reverse((_, x, y))) = open.pop() {
+            let cell = IVec2::new(x, y);
+            if cell == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(&previous) = came_from.get(&current) {
+                    if previous == start {
+                        break;
+                    }
+                    path.push(previous);
+                    current = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            expanded += 1;
+            if expanded > MAX_EXPANDED_CELLS {
+                return None;
+            }
+            let cell_cost = cost[&cell];
+            for step in NEIGHBOURS {
+                let next = cell + step;
+                if !passable(next) {
+                    continue;
+                }
+                let diagonal = step.x != 0 && step.y != 0;
+                if diagonal
+                    && !(passable(cell + IVec2::new(step.x, 0))
+                        && passable(cell + IVec2::new(0, step.y)))
+                {
+                    continue;
+                }
+                let next_cost = cell_cost + if diagonal { DIAGONAL_COST } else { STRAIGHT_COST };
+                if cost.get(&next).is_some_and(|&known| known <= next_cost) {
+                    continue;
+                }
+                cost.insert(next, next_cost);
+                came_from.insert(next, cell);
+                open.push(Reverse((next_cost + heuristic(next), next.x, next.y)));
+            }
+        }
+        None
+    }
+}
+
+// The shared service: the nav grid plus a cache of recent searches, misses
+// included, so several followers chasing the same target don't each search.
+#[derive(Resource, Default)]
+pub struct Pathfinder {
+    pub nav: NavGrid,
+    cache: HashMap<(IVec2, IVec2), Option<Vec<IVec2>>>,
+    // Bumped whenever the nav grid changes, so followers know to replan.
+    generation: u32,
+}
+
+impl Pathfinder {
+    fn invalidate(&mut self) {
+        self.cache.clear();
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    // Waypoints in world space, ending at the middle of the goal's cell.
+    pub fn find(&mut self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let key = (NavGrid::cell_of(from), NavGrid::cell_of(to));
+        if !self.cache.contains_key(&key) {
+            if self.cache.len() >= PATH_CACHE_CAPACITY {
+                self.cache.clear();
+            }
+            let path = self.nav.find_path(key.0, key.1);
+            self.cache.insert(key, path);
+        }
+        self.cache[&key]
+            .as_ref()
+            .map(|cells| cells.iter().map(|&cell| NavGrid::cell_center(cell)).collect())
+    }
+}
+
+// Something walking a planned path. The plan is redone when the target
+// moves into another cell or the walls change, but no more often than every
+// `replan_seconds`.
+#[derive(Component, Debug, Clone)]
+pub struct PathFollower {
+    pub waypoints: Vec<Vec2>,
+    pub goal_cell: Option<IVec2>,
+    pub replan_seconds: f32,
+    replan_timer: f32,
+    generation: u32,
+}
+
+impl PathFollower {
+    pub fn new(replan_seconds: f32) -> Self {
+        Self {
+            waypoints: Vec::new(),
+            goal_cell: None,
+            replan_seconds,
+            replan_timer: 0.0,
+            generation: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.waypoints.clear();
+        self.goal_cell = None;
+    }
+
+    // Where to head next on the way from `position` to `target`, or None when
+    // there's no known path. Waypoints are dropped once reached; the last
+    // stretch inside the goal's cell goes straight for the target.
+    pub fn next_waypoint(
+        &mut self,
+        pathfinder: &mut Pathfinder,
+        position: Vec2,
+        target: Vec2,
+        dt: f32,
+    ) -> Option<Vec2> {
+        self.replan_timer = (self.replan_timer - dt).max(0.0);
+        let goal_cell = NavGrid::cell_of(target);
+        let stale = self.goal_cell != Some(goal_cell) || self.generation != pathfinder.generation;
+        if stale && self.replan_timer <= 0.0 {
+            self.replan_timer = self.replan_seconds;
+            self.goal_cell = Some(goal_cell);
+            self.generation = pathfinder.generation;
+            self.waypoints = pathfinder.find(position, target).unwrap_or_default();
+            if self.waypoints.is_empty() && NavGrid::cell_of(position) != goal_cell {
+                self.goal_cell = None;
+                return None;
+            }
+        }
+        self.goal_cell?;
+        let reach = NAV_CELL_TILES as f32 * WORLD_TILE_SIZE * 0.5;
+        while self.waypoints.len() > 1 && self.waypoints[0].distance(position) < reach {
+            self.waypoints.remove(0);
+        }
+        match self.waypoints.as_slice() {
+            [] | [_] => Some(target),
+            [next, ..] => Some(*next),
+        }
+    }
+}
+
+fn refresh_nav_grid(
+    grid: Res<WorldGrid>,
+    mut tiles_changed: MessageReader<TilesChanged>,
+    mut pathfinder: ResMut<Pathfinder>,
+) {
+    if !pathfinder.nav.fits(&grid) {
+        tiles_changed.clear();
+        pathfinder.nav.rebuild(&grid);
+        pathfinder.invalidate();
+        return;
+    }
+    let mut changed = false;
+    for change in tiles_changed.read() {
+        let min_cell = change.min.div_euclid(IVec2::splat(NAV_CELL_TILES));
+        let max_cell = change.max.div_euclid(IVec2::splat(NAV_CELL_TILES));
+        pathfinder.nav.refresh(&grid, min_cell, max_cell);
+        changed = true;
+    }
+    if changed {
+        pathfinder.invalidate();
+    }
+}
+
+pub struct PathfindingPlugin;
+
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Pathfinder>()
+            .add_systems(PreUpdate, refresh_nav_grid);
+    }
+}
@@ -0,0 +1,98 @@
+// frame-time governor that trades lighting quality for frame rate
+use bevy::prelude::*;
+
+use crate::light::LightQuality;
+use crate::settings::GameSettings;
+
+const TARGET_FPS: f32 = 55.0;
+const SAMPLE_SECONDS: f32 = 1.0;
+const ADJUST_COOLDOWN_SECONDS: f32 = 3.0;
+const RESTORE_HEADROOM: f32 = 1.25;
+
+// Quality levels from best to cheapest, relative to the configured
+// lighting: (range factor, update interval, dither factor).
+const QUALITY_LEVELS: [(f32, u32, f32); 4] = [
+    (1.0, 1, 1.0),
+    (0.84, 1, 0.75),
+    (0.71, 2, 0.5),
+    (0.58, 3, 0.0),
+];
+
+#[derive(Resource)]
+pub struct PerfGovernor {
+    pub level: usize,
+    base: LightQuality,
+    sample_time: f32,
+    sample_frames: u32,
+    cooldown: f32,
+}
+
+fn setup_perf_governor(mut commands: Commands, quality: Res<LightQuality>) {
+    commands.insert_resource(PerfGovernor {
+        level: 0,
+        base: *quality,
+        sample_time: 0.0,
+        sample_frames: 0,
+        cooldown: ADJUST_COOLDOWN_SECONDS,
+    });
+}
+
+fn govern_performance(
+    time: Res<Time<Real>>,
+    settings: Res<GameSettings>,
+    mut governor: ResMut<PerfGovernor>,
+    mut quality: ResMut<LightQuality>,
+) {
+    // Turning the setting off hands back the configured lighting at once.
+    if !settings.auto_perf_scaling {
+        if governor.level > 0 {
+            governor.level = 0;
+            *quality = governor.base;
+        }
+        governor.sample_time = 0.0;
+        governor.sample_frames = 0;
+        return;
+    }
+    let dt = time.delta_secs();
+    governor.cooldown = (governor.cooldown - dt).max(0.0);
+    governor.sample_time += dt;
+    governor.sample_frames += 1;
+    if governor.sample_time < SAMPLE_SECONDS {
+        return;
+    }
+
+    let fps = governor.sample_frames as f32 / governor.sample_time;
+    governor.sample_time = 0.0;
+    governor.sample_frames = 0;
+    if governor.cooldown > 0.0 {
+        return;
+    }
+
+    let previous = governor.level;
+    if fps < TARGET_FPS && governor.level + 1 < QUALITY_LEVELS.len() {
+        governor.level += 1;
+    } else if fps > TARGET_FPS * RESTORE_HEADROOM && governor.level > 0 {
+        governor.level -= 1;
+    }
+    if governor.level == previous {
+        return;
+    }
+
+    governor.cooldown = ADJUST_COOLDOWN_SECONDS;
+    let (range_factor, update_interval, dither_factor) = QUALITY_LEVELS[governor.level];
+    *quality = LightQuality {
+        range: governor.base.range * range_factor,
+        update_interval: governor.base.update_interval.max(update_interval),
+        dither_strength: governor.base.dither_strength * dither_factor,
+    };
+    info!("performance governor: {fps:.0} fps, quality level {}", governor.level);
+}
+
+pub struct PerfPlugin;
+
+impl Plugin for PerfPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_perf_governor)
+            .add_systems(Update, govern_performance);
+    }
+}
@@ -0,0 +1,381 @@
+// a stray fox waits somewhere near the spawn point. Feeding it the food in
+// the selected hotbar slot (H by default) tames it; from then on it follows the player,
+// sniffs out food around them and carries it back along the nav grid. It
+// gets hungry too, shown by a small bar over its head, and a fox left
+// starving for too long goes stray again.
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::event_log::EventLog;
+use crate::faction::Faction;
+use crate::food::{place_food, Food, FoodStats, FoodTracker, Freshness, Location2D};
+use crate::food_catalog::{FoodCatalog, FoodItem};
+use crate::hotbar::Hotbar;
+use crate::items::{Inventory, ItemKind};
+use crate::light::Lightable;
+use crate::menu::AppState;
+use crate::obstacle::Obstacle;
+use crate::pathfinding::{PathFollower, Pathfinder};
+use crate::player::{solid_overlaps, DeathRespawnState, Player};
+use crate::progress::PersistentProgress;
+use crate::settings::{Action, GameSettings};
+use crate::world::{world_rng, WorldGrid, WorldRegenerated, WorldSeed, WORLD_TILE_SIZE};
+
+const PET_NAME: &str = "fox";
+const PET_SIZE: f32 = 7.0;
+const PET_TINT: Color = Color::srgb(0.85, 0.45, 0.2);
+const PET_SEED_SALT: u64 = 0xf0c5;
+// The stray sits in a ring this far from the world centre.
+const STRAY_DISTANCE: (f32, f32) = (24.0, 48.0);
+const PLACEMENT_ATTEMPTS: usize = 100;
+const FEED_RADIUS: f32 = 12.0;
+const FOLLOW_DISTANCE: f32 = 10.0;
+const FETCH_RADIUS: f32 = 50.0;
+const PICKUP_REACH: f32 = 3.0;
+const DELIVER_REACH: f32 = 6.0;
+const WALK_SPEED: f32 = 30.0;
+const RUN_SPEED: f32 = 55.0;
+const REPLAN_SECONDS: f32 = 0.5;
+const HUNGER_MAX: f32 = 100.0;
+const MEAL: f32 = 40.0;
+// Full to empty in about four minutes.
+const HUNGER_PER_SECOND: f32 = HUNGER_MAX / 240.0;
+const STRAY_AFTER_SECONDS: f32 = 60.0;
+const BAR_WIDTH: f32 = 8.0;
+const BAR_HEIGHT: f32 = 1.0;
+const BAR_GAP: f32 = 2.0;
+const BAR_TRACK_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.8);
+const BAR_FULL_COLOR: Color = Color::srgb(0.9, 0.7, 0.25);
+const BAR_EMPTY_COLOR: Color = Color::srgb(0.6, 0.25, 0.1);
+
+#[derive(Component)]
+pub struct Pet {
+    pub tamed: bool,
+    pub hunger: f32,
+    pub carrying: Option<FoodItem>,
+    // Food it's heading for and the tile it lies on.
+    target: Option<(Entity, Location2D)>,
+    starving_for: f32,
+}
+
+#[derive(Component)]
+struct PetHungerBar;
+
+#[derive(Component)]
+struct PetHungerFill;
+
+fn spawn_pet(mut commands: Commands, grid: Res<WorldGrid>, seed: Res<WorldSeed>) {
+    place_pet(&mut commands, &grid, *seed);
+}
+
+fn respawn_pet(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    pet_query: Query<Entity, With<Pet>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &pet_query {
+        commands.entity(entity).despawn();
+    }
+    place_pet(&mut commands, &grid, *seed);
+}
+
+fn place_pet(commands: &mut Commands, grid: &WorldGrid, seed: WorldSeed) {
+    let mut rng = world_rng(seed, PET_SEED_SALT);
+    let spot = (0..PLACEMENT_ATTEMPTS)
+        .map(|_| {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let distance = rng.random_range(STRAY_DISTANCE.0..STRAY_DISTANCE.1);
+            grid.center() + Vec2::from_angle(angle) * distance * WORLD_TILE_SIZE
+        })
+        .find(|spot| !solid_overlaps(grid, *spot, PET_SIZE / 2.0));
+    let Some(position) = spot else {
+        return;
+    };
+    let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+    commands
+        .spawn((
+            Pet {
+                tamed: false,
+                hunger: 0.0,
+                carrying: None,
+                target: None,
+                starving_for: 0.0,
+            },
+            PathFollower::new(REPLAN_SECONDS),
+            Faction::Player,
+            Lightable { tint: PET_TINT },
+            Location2D {
+                x: tile.x,
+                y: tile.y,
+            },
+            Sprite::from_color(Color::WHITE, Vec2::splat(PET_SIZE)),
+            Visibility::Hidden,
+            Transform::from_translation(position.extend(0.72)),
+        ))
+        .with_child((
+            PetHungerBar,
+            Sprite::from_color(BAR_TRACK_COLOR, Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+            Transform::from_xyz(0.0, PET_SIZE / 2.0 + BAR_GAP, 0.1),
+            Visibility::Hidden,
+            children![(
+                PetHungerFill,
+                Sprite::from_color(BAR_FULL_COLOR, Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                Transform::from_xyz(0.0, 0.0, 0.01),
+            )],
+        ));
+}
+
+// The first meal tames it; after that meals just fill it up.
+fn feed_pet(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    hotbar: Res<Hotbar>,
+    progress: Res<PersistentProgress>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut pet_query: Query<(&mut Pet, &Transform), Without<Player>>,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::FeedPet)) {
+        return;
+    }
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let Some((mut pet, _)) = pet_query.iter_mut().find(|(_, transform)| {
+        transform.translation.truncate().distance(player_pos) <= FEED_RADIUS * WORLD_TILE_SIZE
+    }) else {
+        return;
+    };
+    let Some((ItemKind::Food(food), _)) = inventory.slot(hotbar.selected) else {
+        event_log.push(&time, format!("the {PET_NAME} wants food from your hotbar"));
+        return;
+    };
+    inventory.remove_from_slot(hotbar.selected, 1);
+    pet.hunger = (pet.hunger + MEAL).min(HUNGER_MAX);
+    pet.starving_for = 0.0;
+    let name = progress.food_name(food).to_lowercase();
+    if pet.tamed {
+        event_log.push(&time, format!("the {PET_NAME} wolfs down the {name}"));
+    } else {
+        pet.tamed = true;
+        event_log.push(&time, format!("the {PET_NAME} takes the {name} and follows you now"));
+    }
+}
+
+fn pet_hunger(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut event_log: ResMut<EventLog>,
+    mut pet_query: Query<(&mut Pet, &mut PathFollower)>,
+) {
+    if death_state.is_dead {
+        return;
+    }
+    let dt = time.delta_secs();
+    for (mut pet, mut follower) in &mut pet_query {
+        if !pet.tamed {
+            continue;
+        }
+        pet.hunger = (pet.hunger - HUNGER_PER_SECOND * dt).max(0.0);
+        if pet.hunger > 0.0 {
+            pet.starving_for = 0.0;
+            continue;
+        }
+        pet.starving_for += dt;
+        if pet.starving_for >= STRAY_AFTER_SECONDS {
+            pet.tamed = false;
+            pet.target = None;
+            pet.starving_for = 0.0;
+            follower.clear();
+            event_log.push(&time, format!("your {PET_NAME} got too hungry and wandered off"));
+        }
+    }
+}
+
+// Of the fresh food lying around the player, whatever's closest to the fox.
+fn nearest_food(
+    tracker: &FoodTracker,
+    food_query: &Query<(&FoodStats, &Freshness), With<Food>>,
+    player_pos: Vec2,
+    pet_pos: Vec2,
+) -> Option<(Entity, Location2D)> {
+    tracker
+        .query_radius(player_pos, FETCH_RADIUS * WORLD_TILE_SIZE)
+        .filter(|(_, entity)| {
+            food_query
+                .get(*entity)
+                .is_ok_and(|(_, freshness)| !freshness.is_rotten())
+        })
+        .min_by(|a, b| {
+            let distance = |location: &Location2D| {
+                (IVec2::new(location.x, location.y).as_vec2() * WORLD_TILE_SIZE).distance(pet_pos)
+            };
+            distance(&a.0).total_cmp(&distance(&b.0))
+        })
+        .map(|(location, entity)| (entity, location))
+}
+
+// A hungry-enough fox fetches; one that's carrying something brings it to
+// the player, and otherwise it trots along behind. Fetched food goes
+// straight into the inventory, or onto the ground if there's no room.
+fn move_pet(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    death_state: Res<DeathRespawnState>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    asset_server: Res<AssetServer>,
+    mut pathfinder: ResMut<Pathfinder>,
+    mut tracker: ResMut<FoodTracker>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    food_query: Query<(&FoodStats, &Freshness), With<Food>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Pet>>,
+    mut pet_query: Query<
+        (&mut Pet, &mut PathFollower, &mut Location2D, &mut Transform),
+        Without<Player>,
+    >,
+) {
+    if death_state.is_dead {
+        return;
+    }
+    let Ok((player_transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let dt = time.delta_secs();
+    let radius = PET_SIZE / 2.0;
+    let blocked = |candidate: Vec2| {
+        solid_overlaps(&grid, candidate, radius)
+            || obstacle_query.iter().any(|(obstacle_transform, obstacle)| {
+                let reach = obstacle.radius + radius;
+                obstacle_transform.translation.truncate().distance_squared(candidate) < reach * reach
+            })
+    };
+
+    for (mut pet, mut follower, mut location, mut transform) in &mut pet_query {
+        if !pet.tamed {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        // Food can vanish under it: eaten, picked up or rotted away.
+        if let Some((_, food_location)) = pet.target
+            && !tracker.contains(&food_location)
+        {
+            pet.target = None;
+        }
+        if pet.carrying.is_none() && pet.target.is_none() && pet.hunger > 0.0 {
+            pet.target = nearest_food(&tracker, &food_query, player_pos, position);
+        }
+
+        let (goal, speed) = if let Some(food) = pet.carrying {
+            if position.distance(player_pos) <= DELIVER_REACH * WORLD_TILE_SIZE {
+                pet.carrying = None;
+                let name = progress.food_name(food).to_lowercase();
+                if inventory.add_within_limit(ItemKind::Food(food), 1) > 0
+                    && let Some(def) = catalog.get(food)
+                {
+                    let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+                    let spot = Location2D {
+                        x: tile.x,
+                        y: tile.y,
+                    };
+                    place_food(&mut commands, &asset_server, &mut tracker, spot, def);
+                }
+                event_log.push(&time, format!("your {PET_NAME} brought back some {name}"));
+                continue;
+            }
+            (player_pos, RUN_SPEED)
+        } else if let Some((entity, food_location)) = pet.target {
+            let food_pos = IVec2::new(food_location.x, food_location.y).as_vec2() * WORLD_TILE_SIZE;
+            if position.distance(food_pos) <= PICKUP_REACH * WORLD_TILE_SIZE {
+                pet.target = None;
+                if let Ok((stats, _)) = food_query.get(entity) {
+                    pet.carrying = Some(stats.food);
+                    tracker.remove(&food_location);
+                    commands.entity(entity).despawn();
+                }
+                continue;
+            }
+            (food_pos, RUN_SPEED)
+        } else if position.distance(player_pos) > FOLLOW_DISTANCE * WORLD_TILE_SIZE {
+            (player_pos, WALK_SPEED)
+        } else {
+            follower.clear();
+            continue;
+        };
+
+        let waypoint = follower
+            .next_waypoint(&mut pathfinder, position, goal, dt)
+            .unwrap_or(goal);
+        let heading = (waypoint - position).normalize_or_zero();
+        if heading == Vec2::ZERO {
+            continue;
+        }
+        let step = heading * speed * WORLD_TILE_SIZE * dt;
+        let next = [step, Vec2::new(step.x, 0.0), Vec2::new(0.0, step.y)]
+            .into_iter()
+            .map(|step| position + step)
+            .find(|candidate| !blocked(*candidate));
+        let Some(next) = next else {
+            continue;
+        };
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+        let tile = (next / WORLD_TILE_SIZE).floor().as_ivec2();
+        location.set_if_neq(Location2D {
+            x: tile.x,
+            y: tile.y,
+        });
+    }
+}
+
+// The bar only shows once the fox is tamed.
+fn update_hunger_bars(
+    pet_query: Query<(&Pet, &Children)>,
+    mut bar_query: Query<(&mut Visibility, &Children), With<PetHungerBar>>,
+    mut fill_query: Query<(&mut Sprite, &mut Transform), With<PetHungerFill>>,
+) {
+    for (pet, children) in &pet_query {
+        for child in children.iter() {
+            let Ok((mut visibility, fills)) = bar_query.get_mut(child) else {
+                continue;
+            };
+            visibility.set_if_neq(if pet.tamed {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+            let ratio = (pet.hunger / HUNGER_MAX).clamp(0.0, 1.0);
+            for fill in fills.iter() {
+                if let Ok((mut sprite, mut transform)) = fill_query.get_mut(fill) {
+                    sprite.custom_size = Some(Vec2::new(BAR_WIDTH * ratio, BAR_HEIGHT));
+                    sprite.color = BAR_EMPTY_COLOR.mix(&BAR_FULL_COLOR, ratio);
+                    transform.translation.x = -BAR_WIDTH * (1.0 - ratio) / 2.0;
+                }
+            }
+        }
+    }
+}
+
+pub struct PetPlugin;
+
+impl Plugin for PetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_pet).add_systems(
+            Update,
+            (respawn_pet, feed_pet, pet_hunger, move_pet, update_hunger_bars)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
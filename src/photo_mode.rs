@@ -0,0 +1,306 @@
+// photo mode: the game stands still, the HUD goes, and the camera comes loose
+// to roam and zoom over the frozen scene, so the dithered lighting can be
+// framed and saved as a screenshot under photos/. The toggle key or Escape
+// goes back to the game, with the camera back on the player.
+use bevy::input::mouse::AccumulatedMouseScroll;
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{save_to_disk, Screenshot};
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::bug_report::BugReportForm;
+use crate::crafting::CraftingScreen;
+use crate::hud::HudVisibility;
+use crate::inventory::InventoryScreen;
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player};
+use crate::settings::{key_label, Action, GameSettings, SettingsScreen};
+use crate::trader::TradeScreen;
+use crate::world::WORLD_TILE_SIZE;
+use crate::MainCamera;
+
+const DEFAULT_TOGGLE_KEY: KeyCode = KeyCode::F2;
+const DEFAULT_PHOTOS_DIR: &str = "photos";
+const SAVE_KEY: KeyCode = KeyCode::Enter;
+// In tiles a second at normal zoom; zoomed out it pans faster.
+const PAN_SPEED: f32 = 12.0;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 4.0;
+// Per second with the zoom keys held, and per line of the mouse wheel.
+const KEY_ZOOM_RATE: f32 = 2.0;
+const SCROLL_ZOOM_STEP: f32 = 1.1;
+const SAVED_NOTICE_SECONDS: f32 = 2.5;
+
+#[derive(Resource, Clone, Debug)]
+pub struct PhotoModeConfig {
+    pub toggle_key: KeyCode,
+    pub photos_dir: PathBuf,
+}
+
+// What photo mode changed, to put back on the way out.
+#[derive(Resource, Default)]
+struct PhotoSession {
+    hud_was_shown: bool,
+    zoom: f32,
+    // The hint stays out of the frame a screenshot is taken in.
+    shot_pending: bool,
+    saved_notice: Option<(String, f32)>,
+}
+
+#[derive(Component)]
+struct PhotoHint;
+
+fn photo_stamp() -> u128 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0)
+}
+
+fn toggle_photo_mode(
+    input: Res<ButtonInput<KeyCode>>,
+    config: Res<PhotoModeConfig>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    death_state: Res<DeathRespawnState>,
+    screens: (Res<InventoryScreen>, Res<CraftingScreen>, Res<TradeScreen>),
+    bug_report: Res<BugReportForm>,
+    settings: Res<SettingsScreen>,
+    player_query: Query<(), With<Player>>,
+) {
+    if !input.just_pressed(config.toggle_key) {
+        return;
+    }
+    match state.get() {
+        AppState::PhotoMode => next_state.set(AppState::Playing),
+        AppState::Playing => {
+            let (inventory, crafting, trade) = screens;
+            let busy = inventory.open || crafting.open || trade.open || bug_report.open;
+            if !busy && !settings.open && !death_state.is_dead && !player_query.is_empty() {
+                next_state.set(AppState::PhotoMode);
+            }
+        }
+        AppState::MainMenu | AppState::Paused => {}
+    }
+}
+
+fn enter_photo_mode(
+    mut commands: Commands,
+    mut hud: ResMut<HudVisibility>,
+    camera_query: Query<&Projection, With<MainCamera>>,
+) {
+    let zoom = match camera_query.single() {
+        Ok(Projection::Orthographic(ortho)) => ortho.scale,
+        _ => 1.0,
+    };
+    commands.insert_resource(PhotoSession {
+        hud_was_shown: hud.shown,
+        zoom,
+        ..default()
+    });
+    hud.shown = false;
+    // Not part of the HUD, as that's hidden; it's kept out of the photos.
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(12.0),
+                width: percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            GlobalZIndex(150),
+            PhotoHint,
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont::from_font_size(14.0),
+            TextColor(Color::srgba(0.95, 0.9, 0.7, 0.85)),
+            TextLayout::new_with_justify(Justify::Center),
+        ));
+}
+
+fn exit_photo_mode(
+    mut commands: Commands,
+    session: Res<PhotoSession>,
+    mut hud: ResMut<HudVisibility>,
+    mut camera_query: Query<&mut Projection, With<MainCamera>>,
+    hint_query: Query<Entity, With<PhotoHint>>,
+) {
+    hud.shown = session.hud_was_shown;
+    if let Ok(mut projection) = camera_query.single_mut()
+        && let Projection::Orthographic(ortho) = projection.as_mut()
+    {
+        ortho.scale = session.zoom;
+    }
+    for entity in &hint_query {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<PhotoSession>();
+}
+
+// Game time is frozen, so the camera runs on real time.
+fn move_photo_camera(
+    time: Res<Time<Real>>,
+    input: Res<ButtonInput<KeyCode>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    settings: Res<GameSettings>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<MainCamera>>,
+) {
+    let Ok((mut transform, mut projection)) = camera_query.single_mut() else {
+        return;
+    };
+    let Projection::Orthographic(ortho) = projection.as_mut() else {
+        return;
+    };
+    let dt = time.delta_secs();
+    let held = |action| input.pressed(settings.key(action));
+    let mut dir = Vec2::ZERO;
+    if held(Action::MoveUp) {
+        dir.y += 1.0;
+    }
+    if held(Action::MoveDown) {
+        dir.y -= 1.0;
+    }
+    if held(Action::MoveLeft) {
+        dir.x -= 1.0;
+    }
+    if held(Action::MoveRight) {
+        dir.x += 1.0;
+    }
+    let pan = dir.normalize_or_zero() * PAN_SPEED * WORLD_TILE_SIZE * ortho.scale * dt;
+    transform.translation += pan.extend(0.0);
+
+    let mut zoom = ortho.scale;
+    if input.pressed(KeyCode::Minus) {
+        zoom *= KEY_ZOOM_RATE.powf(dt);
+    }
+    if input.pressed(KeyCode::Equal) {
+        zoom /= KEY_ZOOM_RATE.powf(dt);
+    }
+    // Wheel up zooms in.
+    zoom /= SCROLL_ZOOM_STEP.powf(scroll.delta.y);
+    ortho.scale = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+}
+
+fn take_photo(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    config: Res<PhotoModeConfig>,
+    mut session: ResMut<PhotoSession>,
+) {
+    if !input.just_pressed(SAVE_KEY) {
+        return;
+    }
+    if let Err(err) = fs::create_dir_all(&config.photos_dir) {
+        warn!("could not create {}: {err}", config.photos_dir.display());
+        return;
+    }
+    let path = config
+        .photos_dir
+        .join(format!("photo-{}.png", photo_stamp()));
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path.clone()));
+    session.shot_pending = true;
+    session.saved_notice = Some((path.display().to_string(), SAVED_NOTICE_SECONDS));
+}
+
+fn update_photo_hint(
+    time: Res<Time<Real>>,
+    config: Res<PhotoModeConfig>,
+    (locale, settings): (Res<Locale>, Res<GameSettings>),
+    mut session: ResMut<PhotoSession>,
+    mut hint_query: Query<(&mut Visibility, &Children), With<PhotoHint>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let Ok((mut visibility, children)) = hint_query.single_mut() else {
+        return;
+    };
+    visibility.set_if_neq(if session.shot_pending {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    });
+    // Hidden for just the frame the screenshot is taken from.
+    session.shot_pending = false;
+    if let Some((_, left)) = session.saved_notice.as_mut() {
+        *left -= time.delta_secs();
+        if *left <= 0.0 {
+            session.saved_notice = None;
+        }
+    }
+    let line = match &session.saved_notice {
+        Some((path, _)) => locale.format("photo.saved", &[("path", path)]),
+        None => {
+            let key = |action| key_label(settings.key(action));
+            locale.format(
+                "photo.hint",
+                &[
+                    ("up", &key(Action::MoveUp)),
+                    ("left", &key(Action::MoveLeft)),
+                    ("down", &key(Action::MoveDown)),
+                    ("right", &key(Action::MoveRight)),
+                    ("key", &key_label(config.toggle_key)),
+                ],
+            )
+        }
+    };
+    for child in children.iter() {
+        if let Ok(mut text) = text_query.get_mut(child)
+            && text.0 != line
+        {
+            text.0 = line.clone();
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PhotoModePlugin {
+    pub toggle_key: KeyCode,
+    pub photos_dir: PathBuf,
+}
+
+impl Default for PhotoModePlugin {
+    fn default() -> Self {
+        Self {
+            toggle_key: DEFAULT_TOGGLE_KEY,
+            photos_dir: PathBuf::from(DEFAULT_PHOTOS_DIR),
+        }
+    }
+}
+
+impl PhotoModePlugin {
+    pub fn with_toggle_key(mut self, key: KeyCode) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    pub fn with_photos_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.photos_dir = dir.into();
+        self
+    }
+}
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PhotoModeConfig {
+            toggle_key: self.toggle_key,
+            photos_dir: self.photos_dir.clone(),
+        })
+        .add_systems(OnEnter(AppState::PhotoMode), enter_photo_mode)
+        .add_systems(OnExit(AppState::PhotoMode), exit_photo_mode)
+        .add_systems(
+            Update,
+            (
+                toggle_photo_mode,
+                (move_photo_camera, take_photo, update_photo_hint)
+                    .chain()
+                    .run_if(in_state(AppState::PhotoMode)),
+            ),
+        );
+    }
+}
@@ -1,104 +1,121 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-
-use crate::food::{Food, FoodTracker};
-use crate::world::{HEIGHT, PLAYER_SIZE, WIDTH, WORLD_TILE_SIZE};
-const MOVE_SPEED: f32 = 140.0;
+use rand::Rng;
+
+use crate::character::PlayerProfile;
+use crate::combat::AttackState;
+use crate::difficulty::Difficulty;
+use crate::event_log::EventLog;
+use crate::faction::Faction;
+use crate::food::{Food, FoodTracker, Location2D};
+use crate::inventory::InventoryScreen;
+use crate::items::{spawn_world_item, Inventory};
+use crate::leaderboard::SeedRetry;
+use crate::locale::{Locale, LocalizedText};
+use crate::menu::AppState;
+use crate::obstacle::Obstacle;
+use crate::progress::{Perk, PersistentProgress, PATHFINDER_SPEED_FACTOR};
+use crate::rules::{DeathMode, GameRules};
+use crate::settings::{Action, GameSettings};
+use crate::status_effect::StatusEffects;
+use crate::tools::ToolBelt;
+use crate::world::{RegenerateWorld, TileKind, WorldGrid, PLAYER_SIZE, WORLD_TILE_SIZE};
 const LOW_STAMINA_SPEED_FACTOR: f32 = 1.0 / 3.0;
+const SNEAK_SPEED_FACTOR: f32 = 0.5;
+const OVERBURDENED_SPEED_FACTOR: f32 = 0.5;
+const OVERBURDENED_STAMINA_FACTOR: f32 = 2.0;
 const ATLAS_COLUMNS: u32 = 8;
+const ATLAS_ROWS: u32 = 4;
+const BLINK_ROW: usize = 2;
+const SWAY_ROW: usize = 3;
+const IDLE_WAIT_SECONDS: (f32, f32) = (2.0, 5.0);
+const BLINK_SECONDS: f32 = 0.15;
+const SWAY_SECONDS: f32 = 0.6;
+const BLINK_CHANCE: f64 = 0.7;
 const FOOD_COLLISION_RADIUS: f32 = 12.0;
+pub const PLAYER_COLLISION_RADIUS: f32 = 6.0;
 pub const FOOD_BAR_MAX: f32 = 100.0;
-const STATS_MAX: f32 = 100.0;
+pub const STATS_MAX: f32 = 100.0;
 const DEATH_OVERLAY_ALPHA: f32 = 0.8;
-const STATUS_PIPS: usize = 4;
-const STATUS_CHUNK: f32 = 25.0;
-const STATUS_ICON_SIZE: f32 = 24.0;
-const STATUS_PANEL_PADDING: f32 = 6.0;
-const STATUS_ROW_GAP: f32 = 6.0;
-const STATUS_PIP_GAP: f32 = 4.0;
-const STATUS_PANEL_ALPHA: f32 = 1.0;
-const STATUS_ROW_ALPHA: f32 = 1.0;
+const RESPAWN_STAT_FACTOR: f32 = 0.5;
 
 #[derive(Component)]
 pub struct Player;
 
-#[derive(Component)]
-struct StatusPip {
-    kind: StatusKind,
-    index: usize,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum StatusKind {
-    Food,
-    Health,
-    Stamina,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum PipState {
-    Empty,
-    Half,
-    Full,
-}
-
-#[derive(Resource, Clone)]
-struct StatusIconHandles {
-    food_empty: Handle<Image>,
-    food_half: Handle<Image>,
-    food_full: Handle<Image>,
-    health_empty: Handle<Image>,
-    health_half: Handle<Image>,
-    health_full: Handle<Image>,
-    stamina_empty: Handle<Image>,
-    stamina_half: Handle<Image>,
-    stamina_full: Handle<Image>,
-}
-
 #[derive(Resource)]
 pub struct DeathRespawnState {
     pub is_dead: bool,
+    pub respawn_point: Option<Vec2>,
 }
 
 impl DeathRespawnState {
-    fn new() -> Self {
-        Self { is_dead: false }
+    pub fn new() -> Self {
+        Self {
+            is_dead: false,
+            respawn_point: None,
+        }
     }
 }
 
-#[derive(Component)]
-struct DeathOverlay;
+impl Default for DeathRespawnState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-impl StatusIconHandles {
-    fn new(asset_server: &AssetServer) -> Self {
-        Self {
-            food_empty: asset_server.load("food_empty.png"),
-            food_half: asset_server.load("food_half.png"),
-            food_full: asset_server.load("food_full.png"),
-            health_empty: asset_server.load("health_empty.png"),
-            health_half: asset_server.load("health_half.png"),
-            health_full: asset_server.load("health_full.png"),
-            stamina_empty: asset_server.load("stamina_empty.png"),
-            stamina_half: asset_server.load("stamina_half.png"),
-            stamina_full: asset_server.load("stamina_full.png"),
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    Starvation,
+    Exhaustion,
+    Poison,
+    Enemy,
+    Wildlife,
+    Ambush,
+}
+
+impl DeathCause {
+    pub fn name(self) -> &'static str {
+        match self {
+            DeathCause::Starvation => "starvation",
+            DeathCause::Exhaustion => "exhaustion",
+            DeathCause::Poison => "poison",
+            DeathCause::Enemy => "enemy",
+            DeathCause::Wildlife => "wildlife",
+            DeathCause::Ambush => "ambush",
         }
     }
 
-    fn handle_for(&self, kind: StatusKind, state: PipState) -> Handle<Image> {
-        match (kind, state) {
-            // Swap empty/full visuals so "full" reads as brighter.
-            (StatusKind::Food, PipState::Empty) => self.food_full.clone(),
-            (StatusKind::Food, PipState::Half) => self.food_half.clone(),
-            (StatusKind::Food, PipState::Full) => self.food_empty.clone(),
-            (StatusKind::Health, PipState::Empty) => self.health_full.clone(),
-            (StatusKind::Health, PipState::Half) => self.health_half.clone(),
-            (StatusKind::Health, PipState::Full) => self.health_empty.clone(),
-            (StatusKind::Stamina, PipState::Empty) => self.stamina_full.clone(),
-            (StatusKind::Stamina, PipState::Half) => self.stamina_half.clone(),
-            (StatusKind::Stamina, PipState::Full) => self.stamina_empty.clone(),
+    fn describe_key(self) -> &'static str {
+        match self {
+            DeathCause::Starvation => "death.cause.starvation",
+            DeathCause::Exhaustion => "death.cause.exhaustion",
+            DeathCause::Poison => "death.cause.poison",
+            DeathCause::Enemy => "death.cause.enemy",
+            DeathCause::Wildlife => "death.cause.wildlife",
+            DeathCause::Ambush => "death.cause.ambush",
         }
     }
 }
 
+// Per-life numbers shown on the death screen; reset on every new game.
+#[derive(Resource, Default)]
+pub struct RunSummary {
+    pub started_at: f32,
+    pub distance: f32,
+    // Tiles in map cells first explored this life.
+    pub tiles_explored: u32,
+    pub food_eaten: u32,
+    pub last_damage: Option<DeathCause>,
+}
+
+#[derive(Component)]
+struct DeathOverlay;
+
+#[derive(Component)]
+struct DeathSummaryText;
+
+#[derive(Component)]
+struct DeathPromptText;
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Facing {
@@ -119,10 +136,139 @@ pub struct Stats {
     pub food_bar: f32,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaminaUse {
+    Movement,
+    Attack,
+    Tool,
+}
+
+impl StaminaUse {
+    pub fn name(self) -> &'static str {
+        match self {
+            StaminaUse::Movement => "moving",
+            StaminaUse::Attack => "attacking",
+            StaminaUse::Tool => "using a tool",
+        }
+    }
+}
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct StaminaSpent {
+    pub entity: Entity,
+    pub amount: f32,
+    pub source: StaminaUse,
+}
+
+// Sent when stamina runs out, and when an action is refused for the lack of
+// it.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct Exhausted {
+    pub entity: Entity,
+    pub source: StaminaUse,
+    pub refused: bool,
+}
+
+#[derive(SystemParam)]
+pub struct StaminaMessages<'w> {
+    spent: MessageWriter<'w, StaminaSpent>,
+    exhausted: MessageWriter<'w, Exhausted>,
+}
+
+impl Stats {
+    // For one-off actions: spends the full cost or nothing. Returns whether
+    // the action may go ahead.
+    pub fn try_spend_stamina(
+        &mut self,
+        entity: Entity,
+        amount: f32,
+        source: StaminaUse,
+        messages: &mut StaminaMessages,
+    ) -> bool {
+        if self.stamina < amount {
+            messages.exhausted.write(Exhausted {
+                entity,
+                source,
+                refused: true,
+            });
+            return false;
+        }
+        self.drain_stamina(entity, amount, source, messages);
+        true
+    }
+
+    // For continuous costs that can't be refused: spends what is left.
+    pub fn drain_stamina(
+        &mut self,
+        entity: Entity,
+        amount: f32,
+        source: StaminaUse,
+        messages: &mut StaminaMessages,
+    ) {
+        let spent = amount.min(self.stamina);
+        if spent <= 0.0 {
+            return;
+        }
+        self.stamina -= spent;
+        messages.spent.write(StaminaSpent {
+            entity,
+            amount: spent,
+            source,
+        });
+        if self.stamina <= 0.0 {
+            self.stamina = 0.0;
+            messages.exhausted.write(Exhausted {
+                entity,
+                source,
+                refused: false,
+            });
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct MovementTracker {
     seconds: f32,
-    is_moving: bool,
+    pub is_moving: bool,
+}
+
+// Crouched movement: slower, with a narrower light and quieter steps.
+#[derive(Component, Default)]
+pub struct Sneak {
+    pub active: bool,
+}
+
+#[derive(Component, Default)]
+pub struct Velocity(pub Vec2);
+
+// Tuning for the acceleration-based movement model, in pixels per second.
+#[derive(Resource, Debug, Clone)]
+pub struct MovementConfig {
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub friction: f32,
+    pub rubble_factor: f32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            max_speed: 140.0,
+            acceleration: 900.0,
+            friction: 1100.0,
+            rubble_factor: 0.8,
+        }
+    }
+}
+
+impl MovementConfig {
+    // Loose ground lowers both top speed and how quickly it is reached.
+    pub fn surface_factor(&self, kind: TileKind) -> f32 {
+        match kind {
+            TileKind::Ground | TileKind::Water => 1.0,
+            TileKind::Rubble => self.rubble_factor,
+        }
+    }
 }
 
 #[derive(Component, Debug, Clone, Copy)]
@@ -130,16 +276,47 @@ pub struct PlayerState {
     pub facing: Facing,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IdlePose {
+    Still,
+    Blink,
+    Sway,
+}
+
+// Standing still, the character waits a few seconds and then blinks or
+// sways for a moment before settling again.
+#[derive(Component, Debug)]
+struct IdleAnimation {
+    pose: IdlePose,
+    remaining: f32,
+}
+
+impl Default for IdleAnimation {
+    fn default() -> Self {
+        Self {
+            pose: IdlePose::Still,
+            remaining: IDLE_WAIT_SECONDS.0,
+        }
+    }
+}
+
 fn spawn_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    profile: Res<PlayerProfile>,
+    grid: Res<WorldGrid>,
+    mut event_log: ResMut<EventLog>,
+    mut summary: ResMut<RunSummary>,
     mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
-    let texture: Handle<Image> = asset_server.load("player.png");
+    event_log.push(&time, format!("playing as {}", profile.kind.name()));
+    summary.started_at = time.elapsed_secs();
+    let texture: Handle<Image> = asset_server.load(profile.sprite_sheet);
     let layout = TextureAtlasLayout::from_grid(
         UVec2::new(PLAYER_SIZE as u32, PLAYER_SIZE as u32),
         ATLAS_COLUMNS,
-        1,
+        ATLAS_ROWS,
         None,
         None,
     );
@@ -147,8 +324,7 @@ fn spawn_player(
 
     let facing = Facing::Down;
 
-    let center_x = (WIDTH as f32 / 2.0).floor() * WORLD_TILE_SIZE;
-    let center_y = (HEIGHT as f32 / 2.0).floor() * WORLD_TILE_SIZE;
+    let center = grid.center();
 
     commands.spawn((
         Sprite::from_atlas_image(
@@ -158,64 +334,157 @@ fn spawn_player(
                 index: facing_index(facing),
             },
         ),
-        Transform::from_translation(Vec3::new(center_x, center_y, 0.0)),
+        Transform::from_translation(center.extend(0.0)),
         Player,
         PlayerState { facing },
+        IdleAnimation::default(),
         Stats {
-            health: STATS_MAX,
-            stamina: STATS_MAX,
-            food_bar: FOOD_BAR_MAX,
+            health: profile.health,
+            stamina: profile.stamina,
+            food_bar: profile.food_bar,
         },
         MovementTracker { seconds: 0.0, is_moving: false},
+        Sneak::default(),
+        Velocity::default(),
+        ToolBelt::default(),
+        Inventory::default(),
+        AttackState::default(),
+        StatusEffects::default(),
+        Faction::Player,
     ));
 }
 
 fn energy_system(
     time: Res<Time>,
     death_state: Res<DeathRespawnState>,
-    mut query: Query<(&MovementTracker, &mut Stats)> 
+    difficulty: Res<Difficulty>,
+    mut summary: ResMut<RunSummary>,
+    mut stamina_messages: StaminaMessages,
+    mut query: Query<(Entity, &MovementTracker, &StatusEffects, &Inventory, &mut Stats)> 
 ){
     if death_state.is_dead {
         return;
     }
 
-    let Ok((tracker, mut stats)) = query.single_mut() else {
+    let Ok((entity, tracker, effects, inventory, mut stats)) = query.single_mut() else {
         return;
     };
 
-    let stamina_drain_per_sec = 8.0;
-    let stamina_regen_per_sec = 12.0;
+    let drain_multiplier = difficulty.drain_multiplier();
+    let burden_multiplier = 1.0 + (OVERBURDENED_STAMINA_FACTOR - 1.0) * inventory.burden();
+    let food_drain_multiplier = drain_multiplier * effects.food_drain_multiplier();
+    let stamina_drain_per_sec = 8.0 * drain_multiplier * burden_multiplier;
+    let stamina_regen_per_sec = 12.0 * effects.stamina_regen_multiplier();
     let health_drain_per_sec = 3.0;
-    let food_bar_drain_per_sec = 2.0;
-    let food_bar_empty_drain_per_sec = 4.0;
+    let food_bar_drain_per_sec = 2.0 * food_drain_multiplier;
+    let food_bar_empty_drain_per_sec = 4.0 * food_drain_multiplier;
     let food_bar_empty_health_drain_per_sec = 10.0;
     let dt = time.delta_secs();
 
     stats.food_bar = (stats.food_bar - food_bar_drain_per_sec * dt).max(0.0);
 
+    // Remember the biggest source of damage so a death can be explained.
+    let mut damage: Vec<(DeathCause, f32)> = Vec::new();
     if stats.food_bar <= 0.0{
-        stats.health = (stats.health - food_bar_empty_health_drain_per_sec * dt).max(0.0)
+        stats.health = (stats.health - food_bar_empty_health_drain_per_sec * dt).max(0.0);
+        damage.push((DeathCause::Starvation, food_bar_empty_health_drain_per_sec));
     }
 
     if tracker.is_moving {
-        stats.stamina = (stats.stamina - stamina_drain_per_sec * dt).max(0.0);
+        stats.drain_stamina(
+            entity,
+            stamina_drain_per_sec * dt,
+            StaminaUse::Movement,
+            &mut stamina_messages,
+        );
         if stats.stamina <= 0.0{
             stats.health = (stats.health - health_drain_per_sec * dt).max(0.0);
+            damage.push((DeathCause::Exhaustion, health_drain_per_sec));
         }
     }
     let allow_regen = stats.stamina < 100.0 && stats.food_bar > 0.0;
-    if !tracker.is_moving{
-        if allow_regen {
-            stats.stamina = (stats.stamina + stamina_regen_per_sec * dt).min(100.0);
-            stats.food_bar = (stats.food_bar - food_bar_empty_drain_per_sec * dt).max(0.0);
+    if !tracker.is_moving && allow_regen {
+        stats.stamina = (stats.stamina + stamina_regen_per_sec * dt).min(100.0);
+        stats.food_bar = (stats.food_bar - food_bar_empty_drain_per_sec * dt).max(0.0);
+    }
+
+    let effect_health = effects.health_per_sec() * dt;
+    stats.health = (stats.health + effect_health).clamp(0.0, STATS_MAX);
+    if effects.health_per_sec() < 0.0 {
+        damage.push((DeathCause::Poison, -effects.health_per_sec()));
+    }
+    if let Some((cause, _)) = damage.into_iter().max_by(|a, b| a.1.total_cmp(&b.1)) {
+        summary.last_damage = Some(cause);
+    }
+}
+
+fn log_exhaustion(
+    time: Res<Time>,
+    mut exhausted: MessageReader<Exhausted>,
+    mut event_log: ResMut<EventLog>,
+) {
+    for message in exhausted.read() {
+        let line = if message.refused {
+            format!("too exhausted for {}", message.source.name())
+        } else {
+            format!("exhausted from {}", message.source.name())
+        };
+        event_log.push(&time, line);
+    }
+}
+
+// Walls and water both stop movement.
+pub fn solid_overlaps(grid: &WorldGrid, position: Vec2, radius: f32) -> bool {
+    let min_x = ((position.x - radius) / WORLD_TILE_SIZE).floor() as i32;
+    let max_x = ((position.x + radius) / WORLD_TILE_SIZE).floor() as i32;
+    let min_y = ((position.y - radius) / WORLD_TILE_SIZE).floor() as i32;
+    let max_y = ((position.y + radius) / WORLD_TILE_SIZE).floor() as i32;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if !grid.in_bounds(x, y) || !grid.is_solid(x, y) {
+                continue;
+            }
+            // Closest point of the tile to the circle center.
+            let tile_min = Vec2::new(x as f32, y as f32) * WORLD_TILE_SIZE;
+            let closest = position.clamp(tile_min, tile_min + Vec2::splat(WORLD_TILE_SIZE));
+            if closest.distance_squared(position) < radius * radius {
+                return true;
+            }
         }
     }
+    false
+}
+
+fn is_blocked(
+    position: Vec2,
+    grid: &WorldGrid,
+    food_tracker: &FoodTracker,
+    obstacle_query: &Query<(&Transform, &Obstacle), Without<Player>>,
+) -> bool {
+    let food_blocked = food_tracker
+        .query_radius(position, FOOD_COLLISION_RADIUS)
+        .next()
+        .is_some();
+    if food_blocked || solid_overlaps(grid, position, PLAYER_COLLISION_RADIUS) {
+        return true;
+    }
+    obstacle_query.iter().any(|(transform, obstacle)| {
+        let reach = obstacle.radius + PLAYER_COLLISION_RADIUS;
+        transform.translation.truncate().distance_squared(position) < reach * reach
+    })
 }
 
 fn move_player(
     input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    inventory_screen: Res<InventoryScreen>,
     time: Res<Time>,
+    profile: Res<PlayerProfile>,
+    movement: Res<MovementConfig>,
+    progress: Res<PersistentProgress>,
+    mut summary: ResMut<RunSummary>,
     food_tracker: Res<FoodTracker>,
+    grid: Res<WorldGrid>,
     death_state: Res<DeathRespawnState>,
     mut query: Query<
         (
@@ -223,60 +492,117 @@ fn move_player(
             &mut PlayerState,
             &mut Sprite,
             &mut MovementTracker,
+            &mut Velocity,
             &Stats,
+            &AttackState,
+            &StatusEffects,
+            &Sneak,
+            &Inventory,
         ),
         With<Player>,
     >,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Player>>,
 ) {
     if death_state.is_dead {
         return;
     }
 
-    let Ok((mut transform, mut state, mut sprite, mut tracker, stats)) = query.single_mut() else {
+    let Ok((
+        mut transform,
+        mut state,
+        mut sprite,
+        mut tracker,
+        mut velocity,
+        stats,
+        attack,
+        effects,
+        sneak,
+        inventory,
+    )) = query.single_mut()
+    else {
         return;
     };
 
+    // The arrows move round the inventory while it's open, so the player
+    // stands still.
     let mut direction = Vec2::ZERO;
-    if input.pressed(KeyCode::ArrowLeft) {
+    let held = |action| !inventory_screen.open && input.pressed(settings.key(action));
+    if held(Action::MoveLeft) {
         direction.x -= 1.0;
     }
-    if input.pressed(KeyCode::ArrowRight) {
+    if held(Action::MoveRight) {
         direction.x += 1.0;
     }
-    if input.pressed(KeyCode::ArrowUp) {
+    if held(Action::MoveUp) {
         direction.y += 1.0;
     }
-    if input.pressed(KeyCode::ArrowDown) {
+    if held(Action::MoveDown) {
         direction.y -= 1.0;
     }
 
     let dt = time.delta_secs();
-    let mut did_move = false;
+    let tile_x = (transform.translation.x / WORLD_TILE_SIZE).floor() as i32;
+    let tile_y = (transform.translation.y / WORLD_TILE_SIZE).floor() as i32;
+    let surface = grid.tile_kind(tile_x, tile_y).unwrap_or(TileKind::Ground);
+    let surface_factor = movement.surface_factor(surface);
+
+    let mut max_speed = movement.max_speed
+        * profile.speed_factor
+        * effects.speed_multiplier()
+        * surface_factor;
+    if stats.stamina <= 0.0 {
+        max_speed *= LOW_STAMINA_SPEED_FACTOR;
+    }
+    if sneak.active {
+        max_speed *= SNEAK_SPEED_FACTOR;
+    }
+    max_speed *= 1.0 - (1.0 - OVERBURDENED_SPEED_FACTOR) * inventory.burden();
+    if progress.has_perk(Perk::Pathfinder) {
+        max_speed *= PATHFINDER_SPEED_FACTOR;
+    }
+    // Accelerate towards the wanted velocity while a key is held and let
+    // friction bring the player to a stop otherwise.
     if direction != Vec2::ZERO {
-        let speed = if stats.stamina <= 0.0 {
-            MOVE_SPEED * LOW_STAMINA_SPEED_FACTOR
+        let target = direction.normalize() * max_speed;
+        let acceleration = movement.acceleration * surface_factor;
+        velocity.0 = velocity.0.move_towards(target, acceleration * dt);
+    } else {
+        velocity.0 = velocity.0.move_towards(Vec2::ZERO, movement.friction * dt);
+    }
+
+    let mut did_move = false;
+    if velocity.0 != Vec2::ZERO {
+        let delta = velocity.0 * dt;
+        // Resolve each axis separately so the player slides along whatever
+        // blocks one direction of travel.
+        let start = transform.translation.truncate();
+        let mut position = start;
+        if is_blocked(start, &grid, &food_tracker, &obstacle_query) {
+            // Something spawned on top of the player; let them walk out of it.
+            position += delta;
         } else {
-            MOVE_SPEED
-        };
-        let delta = direction.normalize() * speed * dt;
-        let proposed_x = transform.translation.x + delta.x;
-        let proposed_y = transform.translation.y + delta.y;
-        let collision_radius_sq = FOOD_COLLISION_RADIUS * FOOD_COLLISION_RADIUS;
-        let blocked = food_tracker.iter_locations().any(|location| {
-            let food_x = location.x as f32 * WORLD_TILE_SIZE;
-            let food_y = location.y as f32 * WORLD_TILE_SIZE;
-            let dx = proposed_x - food_x;
-            let dy = proposed_y - food_y;
-            (dx * dx + dy * dy) <= collision_radius_sq
-        });
-        if !blocked {
-            transform.translation.x = proposed_x;
-            transform.translation.y = proposed_y;
+            let step_x = position + Vec2::new(delta.x, 0.0);
+            if is_blocked(step_x, &grid, &food_tracker, &obstacle_query) {
+                velocity.0.x = 0.0;
+            } else {
+                position = step_x;
+            }
+            let step_y = position + Vec2::new(0.0, delta.y);
+            if is_blocked(step_y, &grid, &food_tracker, &obstacle_query) {
+                velocity.0.y = 0.0;
+            } else {
+                position = step_y;
+            }
+        }
+        if position != start {
+            summary.distance += position.distance(start);
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
             did_move = true;
-        } else {
-            tracker.is_moving = false;
         }
+    }
 
+    if direction != Vec2::ZERO {
         if direction.x != 0.0 && direction.y != 0.0 {
             state.facing = if direction.x > 0.0 && direction.y > 0.0 {
                 Facing::UpRight
@@ -307,20 +633,78 @@ fn move_player(
     }
 
     if let Some(atlas) = sprite.texture_atlas.as_mut() {
-        atlas.index = facing_index(state.facing);
+        // The second atlas row holds the attack pose for each facing.
+        let row_offset = if attack.is_swinging() { ATLAS_COLUMNS as usize } else { 0 };
+        atlas.index = facing_index(state.facing) + row_offset;
     }
 
     let min_x = WORLD_TILE_SIZE;
-    let max_x = (WIDTH as f32 - 2.0) * WORLD_TILE_SIZE;
+    let max_x = (grid.width as f32 - 2.0) * WORLD_TILE_SIZE;
     let min_y = WORLD_TILE_SIZE;
-    let max_y = (HEIGHT as f32 - 2.0) * WORLD_TILE_SIZE;
+    let max_y = (grid.height as f32 - 2.0) * WORLD_TILE_SIZE;
 
     transform.translation.x = transform.translation.x.clamp(min_x, max_x);
     transform.translation.y = transform.translation.y.clamp(min_y, max_y);
 }
 
+fn animate_idle(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut query: Query<
+        (&MovementTracker, &AttackState, &PlayerState, &mut IdleAnimation, &mut Sprite),
+        With<Player>,
+    >,
+) {
+    let Ok((tracker, attack, state, mut idle, mut sprite)) = query.single_mut() else {
+        return;
+    };
+    let mut rng = rand::rng();
+    if death_state.is_dead || tracker.is_moving || attack.is_swinging() {
+        idle.pose = IdlePose::Still;
+        idle.remaining = rng.random_range(IDLE_WAIT_SECONDS.0..IDLE_WAIT_SECONDS.1);
+        return;
+    }
+
+    idle.remaining -= time.delta_secs();
+    if idle.remaining <= 0.0 {
+        (idle.pose, idle.remaining) = match idle.pose {
+            IdlePose::Still if rng.random_bool(BLINK_CHANCE) => (IdlePose::Blink, BLINK_SECONDS),
+            IdlePose::Still => (IdlePose::Sway, SWAY_SECONDS),
+            IdlePose::Blink | IdlePose::Sway => (
+                IdlePose::Still,
+                rng.random_range(IDLE_WAIT_SECONDS.0..IDLE_WAIT_SECONDS.1),
+            ),
+        };
+    }
+
+    // Rows three and four of the atlas hold the blink and sway frames.
+    let row = match idle.pose {
+        IdlePose::Still => 0,
+        IdlePose::Blink => BLINK_ROW,
+        IdlePose::Sway => SWAY_ROW,
+    };
+    if let Some(atlas) = sprite.texture_atlas.as_mut() {
+        atlas.index = facing_index(state.facing) + row * ATLAS_COLUMNS as usize;
+    }
+}
+
+fn toggle_sneak(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    death_state: Res<DeathRespawnState>,
+    mut query: Query<&mut Sneak, With<Player>>,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::Sneak)) {
+        return;
+    }
+    if let Ok(mut sneak) = query.single_mut() {
+        sneak.active = !sneak.active;
+    }
+}
+
 fn setup_death_respawn(mut commands: Commands) {
-    commands.insert_resource(DeathRespawnState::new());
+    commands.insert_resource(DeathRespawnState::default());
+    commands.insert_resource(RunSummary::default());
 }
 
 fn setup_death_overlay(mut commands: Commands) {
@@ -343,28 +727,113 @@ fn setup_death_overlay(mut commands: Commands) {
             DeathOverlay,
         ))
         .with_children(|parent| {
-            parent.spawn((
-                Text::new("You Died\nPress Enter (or R) for New Game"),
-                TextFont::from_font_size(48.0),
-                TextColor(Color::srgb(0.95, 0.1, 0.1)),
-                TextLayout::new_with_justify(Justify::Center),
-            ));
+            parent
+                .spawn(Node {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: px(16.0),
+                    ..default()
+                })
+                .with_children(|column| {
+                    column.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(48.0),
+                        TextColor(Color::srgb(0.95, 0.1, 0.1)),
+                        TextLayout::new_with_justify(Justify::Center),
+                        LocalizedText("death.title"),
+                    ));
+                    column.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(22.0),
+                        TextColor(Color::srgb(0.9, 0.85, 0.8)),
+                        TextLayout::new_with_justify(Justify::Center),
+                        DeathSummaryText,
+                    ));
+                    column.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(28.0),
+                        TextColor(Color::srgb(0.95, 0.1, 0.1)),
+                        TextLayout::new_with_justify(Justify::Center),
+                        DeathPromptText,
+                    ));
+                });
         });
 }
 
+fn update_death_prompt(
+    rules: Res<GameRules>,
+    locale: Res<Locale>,
+    mut prompt_query: Query<&mut Text, With<DeathPromptText>>,
+) {
+    let Ok(mut text) = prompt_query.single_mut() else {
+        return;
+    };
+    text.0 = locale
+        .get(match rules.death_mode {
+            DeathMode::Respawn => "death.respawn",
+            DeathMode::Permadeath => "death.new_world",
+        })
+        .to_string();
+}
+
+// Drops the inventory contents in a small square around the given tile.
+fn drop_carried_items(commands: &mut Commands, inventory: &mut Inventory, tile: Location2D) {
+    let items = inventory
+        .take_all()
+        .into_iter()
+        .flat_map(|(kind, count)| std::iter::repeat_n(kind, count as usize));
+    for (index, kind) in items.enumerate() {
+        let offset = index as i32 % 9;
+        let location = Location2D {
+            x: tile.x + offset % 3 - 1,
+            y: tile.y + offset / 3 - 1,
+        };
+        spawn_world_item(commands, kind, location);
+    }
+}
+
 fn handle_death_and_respawn(
     mut commands: Commands,
     input: Res<ButtonInput<KeyCode>>,
+    (time, locale): (Res<Time>, Res<Locale>),
+    profile: Res<PlayerProfile>,
+    rules: Res<GameRules>,
+    grid: Res<WorldGrid>,
+    (mut regenerate, mut retry): (MessageWriter<RegenerateWorld>, ResMut<SeedRetry>),
+    mut progress: ResMut<PersistentProgress>,
+    mut event_log: ResMut<EventLog>,
     mut death_state: ResMut<DeathRespawnState>,
+    mut summary: ResMut<RunSummary>,
     mut food_tracker: ResMut<FoodTracker>,
     food_entities: Query<Entity, With<Food>>,
     mut overlay_query: Query<&mut Visibility, With<DeathOverlay>>,
+    mut summary_text_query: Query<&mut Text, With<DeathSummaryText>>,
     mut query: Query<
-        (&mut Transform, &mut Stats, &mut MovementTracker, &mut PlayerState),
+        (
+            &mut Transform,
+            &mut Stats,
+            &mut MovementTracker,
+            &mut PlayerState,
+            &mut Inventory,
+            &mut StatusEffects,
+            &mut Sneak,
+            &mut Velocity,
+        ),
         With<Player>,
     >,
 ) {
-    let Ok((mut transform, mut stats, mut tracker, mut player_state)) = query.single_mut() else {
+    let Ok((
+        mut transform,
+        mut stats,
+        mut tracker,
+        mut player_state,
+        mut inventory,
+        mut effects,
+        mut sneak,
+        mut velocity,
+    )) = query.single_mut()
+    else {
         return;
     };
     let Ok(mut overlay_visibility) = overlay_query.single_mut() else {
@@ -373,6 +842,25 @@ fn handle_death_and_respawn(
 
     if !death_state.is_dead && stats.health <= 0.0 {
         death_state.is_dead = true;
+        let cause = locale.get(
+            summary
+                .last_damage
+                .map_or("death.cause.unknown", DeathCause::describe_key),
+        );
+        event_log.push(&time, format!("player died: {cause}"));
+        if let Ok(mut text) = summary_text_query.single_mut() {
+            let survived = (time.elapsed_secs() - summary.started_at).max(0.0) as u32;
+            text.0 = locale.format(
+                "death.summary",
+                &[
+                    ("cause", cause),
+                    ("time", &format!("{}:{:02}", survived / 60, survived % 60)),
+                    ("explored", &summary.tiles_explored.to_string()),
+                    ("walked", &format!("{:.0}", summary.distance / WORLD_TILE_SIZE)),
+                    ("eaten", &summary.food_eaten.to_string()),
+                ],
+            );
+        }
         tracker.is_moving = false;
         tracker.seconds = 0.0;
         *overlay_visibility = Visibility::Visible;
@@ -390,20 +878,58 @@ fn handle_death_and_respawn(
     }
 
     tracker.is_moving = false;
+    // Replaying a seed from the leaderboard is always a fresh world.
+    let retry_seed = retry.seed.take();
     let new_game_pressed = input.just_pressed(KeyCode::Enter) || input.just_pressed(KeyCode::KeyR);
-    if !new_game_pressed {
+    if !new_game_pressed && retry_seed.is_none() {
         return;
     }
 
-    let center_x = (WIDTH as f32 / 2.0).floor() * WORLD_TILE_SIZE;
-    let center_y = (HEIGHT as f32 / 2.0).floor() * WORLD_TILE_SIZE;
+    let death_mode = if retry_seed.is_some() {
+        DeathMode::Permadeath
+    } else {
+        rules.death_mode
+    };
+    let stat_factor = match death_mode {
+        DeathMode::Respawn => {
+            let death_tile = Location2D {
+                x: (transform.translation.x / WORLD_TILE_SIZE).floor() as i32,
+                y: (transform.translation.y / WORLD_TILE_SIZE).floor() as i32,
+            };
+            drop_carried_items(&mut commands, &mut inventory, death_tile);
+            event_log.push(&time, "respawned");
+            RESPAWN_STAT_FACTOR
+        }
+        DeathMode::Permadeath => {
+            // Shrines that set the old respawn point are gone with the world.
+            regenerate.write(RegenerateWorld { seed: retry_seed });
+            death_state.respawn_point = None;
+            inventory.clear();
+            event_log.push(&time, "new game started in a new world");
+            1.0
+        }
+    };
+    if !rules.keep_progress {
+        *progress = PersistentProgress::default();
+    } else if death_mode == DeathMode::Permadeath {
+        // Experience and perks carry over, but the old map is gone.
+        progress.explored.clear();
+    }
+    let respawn = death_state.respawn_point.unwrap_or(grid.center());
 
-    transform.translation.x = center_x;
-    transform.translation.y = center_y;
-    stats.health = STATS_MAX;
-    stats.stamina = STATS_MAX;
-    stats.food_bar = FOOD_BAR_MAX;
+    transform.translation.x = respawn.x;
+    transform.translation.y = respawn.y;
+    stats.health = profile.health * stat_factor;
+    stats.stamina = profile.stamina * stat_factor;
+    stats.food_bar = profile.food_bar * stat_factor;
     player_state.facing = Facing::Down;
+    effects.clear();
+    sneak.active = false;
+    velocity.0 = Vec2::ZERO;
+    *summary = RunSummary {
+        started_at: time.elapsed_secs(),
+        ..default()
+    };
     death_state.is_dead = false;
     *overlay_visibility = Visibility::Hidden;
 
@@ -426,128 +952,52 @@ fn facing_index(facing: Facing) -> usize {
     }
 }
 
-
-fn setup_status_ui(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-) {
-    let icon_handles = StatusIconHandles::new(&asset_server);
-    commands.insert_resource(icon_handles.clone());
-
-    commands
-        .spawn((
-            Node {
-                position_type: PositionType::Absolute,
-                left: px(16.0),
-                top: px(16.0),
-                padding: UiRect::all(px(STATUS_PANEL_PADDING)),
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                row_gap: px(STATUS_ROW_GAP),
-                ..default()
-            },
-            BackgroundColor(Color::srgba(0.86, 0.86, 0.86, STATUS_PANEL_ALPHA)),
-            BorderColor::all(Color::srgb(0.25, 0.25, 0.25)),
-        ))
-        .with_children(|panel| {
-            spawn_status_row(panel, &icon_handles, StatusKind::Food);
-            spawn_status_row(panel, &icon_handles, StatusKind::Health);
-            spawn_status_row(panel, &icon_handles, StatusKind::Stamina);
-        });
+// A preset profile skips the character picker.
+#[derive(Clone, Debug, Default)]
+pub struct PlayerPlugin {
+    pub profile: Option<PlayerProfile>,
+    pub movement: MovementConfig,
 }
 
-fn update_status_ui(
-    player_query: Query<&Stats, With<Player>>,
-    icon_handles: Res<StatusIconHandles>,
-    mut pip_query: Query<(&StatusPip, &mut ImageNode)>,
-) {
-    let Ok(stats) = player_query.single() else {
-        return;
-    };
-
-    for (pip, mut image) in &mut pip_query {
-        let value = status_value(stats, pip.kind);
-        let state = pip_state(value, pip.index);
-        image.image = icon_handles.handle_for(pip.kind, state);
+impl PlayerPlugin {
+    pub fn with_profile(mut self, profile: PlayerProfile) -> Self {
+        self.profile = Some(profile);
+        self
     }
-}
 
-fn spawn_status_row(
-    parent: &mut ChildSpawnerCommands,
-    icon_handles: &StatusIconHandles,
-    kind: StatusKind,
-) {
-    parent
-        .spawn((
-            Node {
-                display: Display::Flex,
-                flex_direction: FlexDirection::Row,
-                column_gap: px(STATUS_PIP_GAP),
-                padding: UiRect::all(px(4.0)),
-                ..default()
-            },
-            BackgroundColor(Color::srgba(0.93, 0.93, 0.93, STATUS_ROW_ALPHA)),
-        ))
-        .with_children(|row| {
-            for index in 0..STATUS_PIPS {
-                row.spawn((
-                    Node {
-                        width: px(STATUS_ICON_SIZE),
-                        height: px(STATUS_ICON_SIZE),
-                        ..default()
-                    },
-                    ImageNode::new(icon_handles.handle_for(kind, PipState::Full)),
-                    StatusPip { kind, index },
-                ));
-            }
-        });
-}
-
-fn status_value(stats: &Stats, kind: StatusKind) -> f32 {
-    match kind {
-        StatusKind::Food => stats.food_bar,
-        StatusKind::Health => stats.health,
-        StatusKind::Stamina => stats.stamina,
-    }
-}
-
-fn pip_state(value: f32, index: usize) -> PipState {
-    let clamped_value = value.clamp(0.0, 100.0);
-    let start = index as f32 * STATUS_CHUNK;
-    let fill = ((clamped_value - start) / STATUS_CHUNK).clamp(0.0, 1.0);
-
-    if fill > 0.5 {
-        PipState::Full
-    } else if fill > 0.0 {
-        PipState::Half
-    } else {
-        PipState::Empty
+    pub fn with_movement(mut self, movement: MovementConfig) -> Self {
+        self.movement = movement;
+        self
     }
 }
 
-
-pub struct PlayerPlugin;
-
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Startup,
-            (
-                setup_death_respawn,
-                spawn_player,
-                setup_status_ui,
-                setup_death_overlay,
-            ),
-        )
+        if let Some(profile) = self.profile {
+            app.insert_resource(profile);
+        }
+        app.add_message::<StaminaSpent>()
+            .add_message::<Exhausted>()
+            .insert_resource(self.movement.clone())
+            .add_systems(
+                Startup,
+                (setup_death_respawn, setup_death_overlay),
+            )
             .add_systems(
                 Update,
                 (
+                    spawn_player.run_if(not(any_with_component::<Player>)),
+                    update_death_prompt
+                        .run_if(resource_changed::<GameRules>.or(resource_changed::<Locale>)),
                     handle_death_and_respawn,
+                    toggle_sneak,
                     move_player,
-                    update_status_ui,
+                    animate_idle,
                     (energy_system),
+                    log_exhaustion,
                 )
-                    .chain(),
+                    .chain()
+                    .run_if(in_state(AppState::Playing).and(resource_exists::<PlayerProfile>)),
             );
     }
 }
@@ -0,0 +1,198 @@
+// experience, perks and explored map cells; kept across deaths when the
+// game rules say so
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use crate::event_log::EventLog;
+use crate::food_catalog::FoodItem;
+use crate::hud::HudElement;
+use crate::player::{DeathRespawnState, Player, RunSummary};
+use crate::world::{TilesChanged, WorldGrid, WORLD_TILE_SIZE};
+
+pub const EXPLORE_CELL_TILES: i32 = 25;
+const EXPLORE_RADIUS_CELLS: i32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Perk {
+    // Food restores more of the food bar.
+    Forager,
+    // Slightly faster on foot.
+    Pathfinder,
+    // Knows disguised food on sight.
+    Herbalist,
+    // Has a compass without carrying one.
+    Wayfinder,
+}
+
+impl Perk {
+    pub fn name(self) -> &'static str {
+        match self {
+            Perk::Forager => "Forager",
+            Perk::Pathfinder => "Pathfinder",
+            Perk::Herbalist => "Herbalist",
+            Perk::Wayfinder => "Wayfinder",
+        }
+    }
+}
+
+// Perks unlock once total experience reaches the threshold.
+const PERK_UNLOCKS: [(u32, Perk); 4] = [
+    (40, Perk::Forager),
+    (80, Perk::Herbalist),
+    (120, Perk::Pathfinder),
+    (160, Perk::Wayfinder),
+];
+
+pub const FORAGER_FOOD_FACTOR: f32 = 1.25;
+pub const PATHFINDER_SPEED_FACTOR: f32 = 1.1;
+
+#[derive(Message, Clone, Copy, Debug)]
+pub struct XpGained {
+    pub amount: u32,
+    pub reason: &'static str,
+}
+
+// Survives death only when GameRules::keep_progress is set; the death
+// handler resets it otherwise.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct PersistentProgress {
+    pub xp: u32,
+    pub perks: Vec<Perk>,
+    pub explored: HashSet<IVec2>,
+    // Disguised foods the player has tasted.
+    pub identified_foods: HashSet<FoodItem>,
+}
+
+impl PersistentProgress {
+    pub fn has_perk(&self, perk: Perk) -> bool {
+        self.perks.contains(&perk)
+    }
+
+    pub fn identifies(&self, food: FoodItem) -> bool {
+        !food.is_disguised()
+            || self.has_perk(Perk::Herbalist)
+            || self.identified_foods.contains(&food)
+    }
+
+    pub fn food_name(&self, food: FoodItem) -> &'static str {
+        if self.identifies(food) {
+            food.name
+        } else {
+            food.looks_like
+        }
+    }
+
+    pub fn is_explored(&self, tile_x: i32, tile_y: i32) -> bool {
+        let cell = IVec2::new(tile_x, tile_y).div_euclid(IVec2::splat(EXPLORE_CELL_TILES));
+        self.explored.contains(&cell)
+    }
+}
+
+#[derive(Component)]
+struct ProgressHudText;
+
+fn setup_progress_hud(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: px(16.0),
+            top: px(84.0),
+            ..default()
+        },
+        Text::new(""),
+        TextFont::from_font_size(14.0),
+        TextColor(Color::srgb(0.8, 0.8, 0.6)),
+        HudElement::default(),
+        ProgressHudText,
+    ));
+}
+
+fn gain_xp(
+    time: Res<Time>,
+    mut gains: MessageReader<XpGained>,
+    mut progress: ResMut<PersistentProgress>,
+    mut event_log: ResMut<EventLog>,
+) {
+    for gain in gains.read() {
+        let before = progress.xp;
+        progress.xp += gain.amount;
+        event_log.push(&time, format!("+{} xp ({})", gain.amount, gain.reason));
+        for (threshold, perk) in PERK_UNLOCKS {
+            if before < threshold && progress.xp >= threshold && !progress.has_perk(perk) {
+                progress.perks.push(perk);
+                event_log.push(&time, format!("perk unlocked: {}", perk.name()));
+            }
+        }
+    }
+}
+
+// Remembers the cells around the player and asks caches such as the
+// minimap to redraw newly explored ones.
+fn explore_cells(
+    grid: Res<WorldGrid>,
+    death_state: Res<DeathRespawnState>,
+    mut progress: ResMut<PersistentProgress>,
+    mut summary: ResMut<RunSummary>,
+    mut tiles_changed: MessageWriter<TilesChanged>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    if death_state.is_dead {
+        return;
+    }
+    let Ok(transform) = player_query.single() else {
+        return;
+    };
+    let tile = (transform.translation.truncate() / WORLD_TILE_SIZE).floor().as_ivec2();
+    let center = tile.div_euclid(IVec2::splat(EXPLORE_CELL_TILES));
+    for dy in -EXPLORE_RADIUS_CELLS..=EXPLORE_RADIUS_CELLS {
+        for dx in -EXPLORE_RADIUS_CELLS..=EXPLORE_RADIUS_CELLS {
+            let cell = center + IVec2::new(dx, dy);
+            let min = cell * EXPLORE_CELL_TILES;
+            if !grid.in_bounds(min.x, min.y) || progress.explored.contains(&cell) {
+                continue;
+            }
+            progress.explored.insert(cell);
+            // Cells on the far edges hang off the map.
+            let span = (IVec2::new(grid.width as i32, grid.height as i32) - min)
+                .min(IVec2::splat(EXPLORE_CELL_TILES));
+            summary.tiles_explored += (span.x * span.y) as u32;
+            tiles_changed.write(TilesChanged {
+                min,
+                max: min + IVec2::splat(EXPLORE_CELL_TILES - 1),
+            });
+        }
+    }
+}
+
+fn update_progress_hud(
+    progress: Res<PersistentProgress>,
+    mut text_query: Query<&mut Text, With<ProgressHudText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let perks: Vec<&str> = progress.perks.iter().map(|perk| perk.name()).collect();
+    text.0 = if perks.is_empty() {
+        format!("XP {}", progress.xp)
+    } else {
+        format!("XP {}  {}", progress.xp, perks.join(", "))
+    };
+}
+
+pub struct ProgressPlugin;
+
+impl Plugin for ProgressPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<XpGained>()
+            .insert_resource(PersistentProgress::default())
+            .add_systems(Startup, setup_progress_hud)
+            .add_systems(
+                Update,
+                (
+                    gain_xp,
+                    explore_cells,
+                    update_progress_hud.run_if(resource_changed::<PersistentProgress>),
+                ),
+            );
+    }
+}
@@ -0,0 +1,124 @@
+// projectiles: things in flight that hurt the player on contact and stop at
+// walls (water they fly over). Each runs out after a while; hits, on the
+// player or a wall, go out as ProjectileHit messages, and the player's own
+// damage is taken here.
+use bevy::prelude::*;
+
+use crate::player::{DeathCause, DeathRespawnState, Player, RunSummary, Stats, PLAYER_COLLISION_RADIUS};
+use crate::world::{WorldGrid, WORLD_TILE_SIZE};
+
+const PROJECTILE_Z: f32 = 0.8;
+
+#[derive(Component)]
+pub struct Projectile {
+    pub velocity: Vec2,
+    pub damage: f32,
+    pub radius: f32,
+    pub remaining: f32,
+}
+
+// Target is None when it hit a wall.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ProjectileHit {
+    pub position: Vec2,
+    pub target: Option<Entity>,
+    pub damage: f32,
+}
+
+// Velocity is in tiles per second. Projectiles glow, so they show in the
+// dark too.
+pub fn spawn_projectile(
+    commands: &mut Commands,
+    position: Vec2,
+    velocity: Vec2,
+    damage: f32,
+    lifetime: f32,
+    radius: f32,
+    color: Color,
+) -> Entity {
+    commands
+        .spawn((
+            Projectile {
+                velocity,
+                damage,
+                radius,
+                remaining: lifetime,
+            },
+            Sprite::from_color(color, Vec2::splat(radius * 2.0)),
+            Transform::from_translation(position.extend(PROJECTILE_Z)),
+        ))
+        .id()
+}
+
+fn hits_wall(grid: &WorldGrid, position: Vec2) -> bool {
+    let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+    !grid.in_bounds(tile.x, tile.y) || grid.walls[tile.y as usize][tile.x as usize]
+}
+
+fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    death_state: Res<DeathRespawnState>,
+    mut hits: MessageWriter<ProjectileHit>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform), Without<Player>>,
+) {
+    let dt = time.delta_secs();
+    let player = player_query
+        .single()
+        .ok()
+        .filter(|_| !death_state.is_dead)
+        .map(|(entity, transform)| (entity, transform.translation.truncate()));
+    for (entity, mut projectile, mut transform) in &mut projectile_query {
+        projectile.remaining -= dt;
+        if projectile.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let next = transform.translation.truncate() + projectile.velocity * WORLD_TILE_SIZE * dt;
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+        let target = player
+            .filter(|(_, player_pos)| {
+                player_pos.distance(next) <= PLAYER_COLLISION_RADIUS + projectile.radius
+            })
+            .map(|(player, _)| player);
+        if target.is_none() && !hits_wall(&grid, next) {
+            continue;
+        }
+        hits.write(ProjectileHit {
+            position: next,
+            target,
+            damage: projectile.damage,
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+fn hurt_player(
+    death_state: Res<DeathRespawnState>,
+    mut hits: MessageReader<ProjectileHit>,
+    mut summary: ResMut<RunSummary>,
+    mut player_query: Query<(Entity, &mut Stats), With<Player>>,
+) {
+    let Ok((player, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+    for hit in hits.read() {
+        if death_state.is_dead || hit.target != Some(player) {
+            continue;
+        }
+        stats.health = (stats.health - hit.damage).max(0.0);
+        summary.last_damage = Some(DeathCause::Enemy);
+    }
+}
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ProjectileHit>()
+            .add_systems(Update, (move_projectiles, hurt_player).chain());
+    }
+}
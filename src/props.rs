@@ -0,0 +1,260 @@
+// static world props (trees, rocks, grass) drawn as one merged mesh per chunk
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::NoFrustumCulling;
+use bevy::mesh::{Indices, Mesh};
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use rand::Rng;
+use std::collections::HashMap;
+
+use crate::food::Location2D;
+use crate::light::{lit_tint, LightQuality, LightingSystems};
+use crate::player::Player;
+use crate::world::{world_rng, WorldGrid, WorldRegenerated, WorldSeed, WORLD_TILE_SIZE};
+
+const PROP_CHUNK_SIZE: usize = 100;
+const PROP_Z: f32 = 0.5;
+const GRASS_TUFT_COUNT: usize = 6000;
+const GRASS_TUFT_SIZE: Vec2 = Vec2::new(4.0, 3.0);
+const GRASS_SEED_SALT: u64 = 0x6a55;
+
+// Entities with this are drawn into their chunk's prop mesh instead of
+// carrying a sprite; despawning the entity removes the quad.
+#[derive(Component, Clone, Copy)]
+pub struct Prop {
+    pub size: Vec2,
+    pub tint: Color,
+}
+
+struct PropInstance {
+    location: Location2D,
+    size: Vec2,
+    tint: Color,
+    source: Option<Entity>,
+}
+
+struct PropChunk {
+    mesh: Handle<Mesh>,
+    instances: Vec<PropInstance>,
+    lit: bool,
+}
+
+#[derive(Resource, Default)]
+struct PropChunks {
+    cols: usize,
+    bounds: UVec2,
+    chunks: Vec<PropChunk>,
+    owners: HashMap<Entity, usize>,
+}
+
+impl PropChunks {
+    fn chunk_index(&self, location: Location2D) -> Option<usize> {
+        let in_bounds = location.x >= 0
+            && location.y >= 0
+            && location.x < self.bounds.x as i32
+            && location.y < self.bounds.y as i32;
+        if !in_bounds {
+            return None;
+        }
+        let chunk_x = location.x as usize / PROP_CHUNK_SIZE;
+        let chunk_y = location.y as usize / PROP_CHUNK_SIZE;
+        Some(chunk_y * self.cols + chunk_x)
+    }
+}
+
+fn empty_prop_mesh() -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, Vec::<[f32; 4]>::new());
+    mesh.insert_indices(Indices::U32(Vec::new()));
+    mesh
+}
+
+// Only props standing on lit tiles get quads, so dark chunks cost nothing.
+fn build_prop_mesh(grid: &WorldGrid, instances: &[PropInstance]) -> Mesh {
+    let mut positions = Vec::with_capacity(instances.len() * 4);
+    let mut colors = Vec::with_capacity(instances.len() * 4);
+    let mut indices = Vec::with_capacity(instances.len() * 6);
+
+    for instance in instances {
+        let location = instance.location;
+        let Some(color) = lit_tint(grid, location.x, location.y, instance.tint) else {
+            continue;
+        };
+        let center = Vec2::new(
+            location.x as f32 * WORLD_TILE_SIZE,
+            location.y as f32 * WORLD_TILE_SIZE,
+        );
+        let half = instance.size * 0.5;
+        let (x0, y0) = (center.x - half.x, center.y - half.y);
+        let (x1, y1) = (center.x + half.x, center.y + half.y);
+
+        let base = positions.len() as u32;
+        positions.extend_from_slice(&[
+            [x0, y0, 0.0],
+            [x1, y0, 0.0],
+            [x1, y1, 0.0],
+            [x0, y1, 0.0],
+        ]);
+        let color = color.to_linear();
+        colors.extend_from_slice(&[[color.red, color.green, color.blue, color.alpha]; 4]);
+        indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+fn setup_prop_chunks(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+) {
+    let cols = grid.width.div_ceil(PROP_CHUNK_SIZE);
+    let rows = grid.height.div_ceil(PROP_CHUNK_SIZE);
+    let material = materials.add(ColorMaterial::from(Color::WHITE));
+
+    let mut props = PropChunks {
+        cols,
+        bounds: UVec2::new(grid.width as u32, grid.height as u32),
+        ..default()
+    };
+    for _ in 0..cols * rows {
+        let mesh = meshes.add(empty_prop_mesh());
+        // The mesh shrinks and grows with the light, so a cached bounding
+        // box would cull props that came into view later.
+        commands.spawn((
+            Mesh2d(mesh.clone()),
+            MeshMaterial2d(material.clone()),
+            Transform::from_translation(Vec3::new(0.0, 0.0, PROP_Z)),
+            NoFrustumCulling,
+        ));
+        props.chunks.push(PropChunk {
+            mesh,
+            instances: Vec::new(),
+            lit: false,
+        });
+    }
+
+    scatter_grass(&mut props, &grid, *seed);
+    commands.insert_resource(props);
+}
+
+// Grass is purely decorative, so it never becomes an entity.
+fn scatter_grass(props: &mut PropChunks, grid: &WorldGrid, seed: WorldSeed) {
+    let mut rng = world_rng(seed, GRASS_SEED_SALT);
+    for _ in 0..GRASS_TUFT_COUNT {
+        let x = rng.random_range(0..grid.width as i32);
+        let y = rng.random_range(0..grid.height as i32);
+        if grid.is_solid(x, y) {
+            continue;
+        }
+        let shade = rng.random_range(0.8..1.1);
+        let location = Location2D { x, y };
+        let Some(index) = props.chunk_index(location) else {
+            continue;
+        };
+        props.chunks[index].instances.push(PropInstance {
+            location,
+            size: GRASS_TUFT_SIZE,
+            tint: Color::srgb(0.3 * shade, 0.45 * shade, 0.2 * shade),
+            source: None,
+        });
+    }
+}
+
+// Entity-backed props are despawned and respawned by their own modules, so
+// only the grass needs replacing here.
+fn regrow_grass(
+    mut regenerated: MessageReader<WorldRegenerated>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    mut props: ResMut<PropChunks>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for chunk in &mut props.chunks {
+        chunk.instances.retain(|instance| instance.source.is_some());
+    }
+    scatter_grass(&mut props, &grid, *seed);
+}
+
+fn track_props(
+    mut props: ResMut<PropChunks>,
+    added_query: Query<(Entity, &Prop, &Location2D), Added<Prop>>,
+    mut removed: RemovedComponents<Prop>,
+) {
+    for entity in removed.read() {
+        let Some(index) = props.owners.remove(&entity) else {
+            continue;
+        };
+        props.chunks[index]
+            .instances
+            .retain(|instance| instance.source != Some(entity));
+    }
+
+    for (entity, prop, location) in &added_query {
+        let Some(index) = props.chunk_index(*location) else {
+            continue;
+        };
+        props.chunks[index].instances.push(PropInstance {
+            location: *location,
+            size: prop.size,
+            tint: prop.tint,
+            source: Some(entity),
+        });
+        props.owners.insert(entity, index);
+    }
+}
+
+// Rebuilds the meshes of chunks the light can reach this frame and empties
+// chunks it just left.
+fn light_props(
+    grid: Res<WorldGrid>,
+    quality: Res<LightQuality>,
+    mut props: ResMut<PropChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate() / WORLD_TILE_SIZE;
+    let reach = quality.range.ceil() as i32 + 2;
+    let chunk_of = |value: i32, limit: usize| {
+        (value.clamp(0, limit as i32 - 1) as usize) / PROP_CHUNK_SIZE
+    };
+    let min_x = chunk_of(player_pos.x as i32 - reach, grid.width);
+    let max_x = chunk_of(player_pos.x as i32 + reach, grid.width);
+    let min_y = chunk_of(player_pos.y as i32 - reach, grid.height);
+    let max_y = chunk_of(player_pos.y as i32 + reach, grid.height);
+
+    let cols = props.cols;
+    for (index, chunk) in props.chunks.iter_mut().enumerate() {
+        let (chunk_x, chunk_y) = (index % cols, index / cols);
+        let in_reach = (min_x..=max_x).contains(&chunk_x) && (min_y..=max_y).contains(&chunk_y);
+        if in_reach {
+            let _ = meshes.insert(chunk.mesh.id(), build_prop_mesh(&grid, &chunk.instances));
+            chunk.lit = true;
+        } else if chunk.lit {
+            let _ = meshes.insert(chunk.mesh.id(), empty_prop_mesh());
+            chunk.lit = false;
+        }
+    }
+}
+
+pub struct PropPlugin;
+
+impl Plugin for PropPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_prop_chunks)
+            .add_systems(Update, (regrow_grass, track_props))
+            .add_systems(PostUpdate, light_props.after(LightingSystems));
+    }
+}
@@ -0,0 +1,67 @@
+// per-game rules picked when a new game starts
+use bevy::prelude::*;
+use std::env;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DeathMode {
+    // Revive at the spawn point with halved stats, dropping carried items.
+    #[default]
+    Respawn,
+    // Death ends the run and the next game starts in a new world.
+    Permadeath,
+}
+
+impl DeathMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            DeathMode::Respawn => "Respawn",
+            DeathMode::Permadeath => "Permadeath",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            DeathMode::Respawn => DeathMode::Permadeath,
+            DeathMode::Permadeath => DeathMode::Respawn,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [DeathMode::Respawn, DeathMode::Permadeath]
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct GameRules {
+    pub death_mode: DeathMode,
+    // Experience, perks and map memory survive death; stats always reset.
+    pub keep_progress: bool,
+}
+
+fn game_rules() -> GameRules {
+    // DEATH_MODE and KEEP_PROGRESS preset the rules when the character
+    // picker is skipped.
+    let mode_key = "DEATH_MODE";
+    let death_mode = env::var(mode_key)
+        .ok()
+        .and_then(|value| DeathMode::from_name(&value))
+        .unwrap_or_default();
+    let keep_key = "KEEP_PROGRESS";
+    let keep_progress = env::var(keep_key)
+        .map(|value| matches!(value.as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    GameRules {
+        death_mode,
+        keep_progress,
+    }
+}
+
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(game_rules());
+    }
+}
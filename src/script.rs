@@ -0,0 +1,312 @@
+// scripted events: a trigger fires once and queues its actions with delays
+use bevy::prelude::*;
+
+use crate::enemy::{find_dark_spawn, spawn_enemy};
+use crate::food::SpawnFoodCache;
+use crate::hud::HudElement;
+use crate::light::LightConfig;
+use crate::locale::Locale;
+use crate::player::{DeathRespawnState, Player, PlayerState, Stats};
+use crate::settings::{key_label, Action, GameSettings};
+use crate::world::WorldGrid;
+
+const NARRATION_SECONDS: f32 = 4.0;
+const NARRATION_FADE_SECONDS: f32 = 1.0;
+const FIRST_NIGHT_CACHE_SIZE: usize = 3;
+const FIRST_NIGHT_CACHE_RADIUS_TILES: i32 = 40;
+// Well under a normal enemy, so the first one can be fought off.
+const FIRST_NIGHT_ENEMY_STRENGTH: f32 = 0.4;
+// How long to wait before looking again when there's nowhere dark to spawn.
+const SPAWN_RETRY_SECONDS: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScriptTrigger {
+    GameStart,
+    Elapsed(f32),
+    FoodBelow(f32),
+}
+
+#[derive(Clone, Debug)]
+pub enum ScriptAction {
+    Narrate(String),
+    SpawnFoodCache { count: usize, radius_tiles: i32 },
+    // Out of the light near the player, scaled like spawn_enemy's strength.
+    SpawnEnemy { strength: f32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct ScriptStep {
+    pub delay: f32,
+    pub action: ScriptAction,
+}
+
+#[derive(Clone, Debug)]
+pub struct ScriptedEvent {
+    pub trigger: ScriptTrigger,
+    pub steps: Vec<ScriptStep>,
+    fired: bool,
+}
+
+impl ScriptedEvent {
+    pub fn new(trigger: ScriptTrigger) -> Self {
+        Self {
+            trigger,
+            steps: Vec::new(),
+            fired: false,
+        }
+    }
+
+    pub fn then(mut self, delay: f32, action: ScriptAction) -> Self {
+        self.steps.push(ScriptStep { delay, action });
+        self
+    }
+}
+
+struct PendingAction {
+    remaining: f32,
+    action: ScriptAction,
+}
+
+#[derive(Resource, Default)]
+pub struct ScriptRunner {
+    events: Vec<ScriptedEvent>,
+    pending: Vec<PendingAction>,
+    elapsed: f32,
+}
+
+impl ScriptRunner {
+    pub fn add(&mut self, event: ScriptedEvent) {
+        self.events.push(event);
+    }
+
+    // Shows a line on the narration text straight away, for announcements
+    // from outside a script.
+    pub fn narrate(&mut self, line: impl Into<String>) {
+        self.pending.push(PendingAction {
+            remaining: 0.0,
+            action: ScriptAction::Narrate(line.into()),
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.pending.clear();
+        self.elapsed = 0.0;
+    }
+}
+
+#[derive(Component)]
+struct NarrationText {
+    remaining: f32,
+}
+
+fn first_night_events(locale: &Locale, settings: &GameSettings) -> Vec<ScriptedEvent> {
+    let key = |action| key_label(settings.key(action));
+    let narrate = |line: &str| ScriptAction::Narrate(locale.get(line).to_string());
+    let move_hint = locale.format(
+        "script.move",
+        &[
+            ("up", &key(Action::MoveUp)),
+            ("left", &key(Action::MoveLeft)),
+            ("down", &key(Action::MoveDown)),
+            ("right", &key(Action::MoveRight)),
+        ],
+    );
+    let food_hint = locale.format("script.apples", &[("key", &key(Action::Interact))]);
+    vec![
+        ScriptedEvent::new(ScriptTrigger::GameStart)
+            .then(1.0, narrate("script.night_falls"))
+            .then(NARRATION_SECONDS, ScriptAction::Narrate(move_hint))
+            .then(
+                0.0,
+                ScriptAction::SpawnFoodCache {
+                    count: FIRST_NIGHT_CACHE_SIZE,
+                    radius_tiles: FIRST_NIGHT_CACHE_RADIUS_TILES,
+                },
+            ),
+        ScriptedEvent::new(ScriptTrigger::Elapsed(12.0))
+            .then(0.0, ScriptAction::Narrate(food_hint)),
+        ScriptedEvent::new(ScriptTrigger::FoodBelow(60.0)).then(0.0, narrate("script.hungry")),
+        ScriptedEvent::new(ScriptTrigger::Elapsed(45.0))
+            .then(
+                0.0,
+                ScriptAction::SpawnEnemy {
+                    strength: FIRST_NIGHT_ENEMY_STRENGTH,
+                },
+            )
+            .then(0.0, narrate("script.shuffling")),
+    ]
+}
+
+fn setup_scripts(mut commands: Commands) {
+    commands.insert_resource(ScriptRunner::default());
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: px(0.0),
+            bottom: px(64.0),
+            width: percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Text::new(""),
+        TextFont::from_font_size(22.0),
+        TextColor(Color::srgba(0.9, 0.85, 0.7, 0.0)),
+        TextLayout::new_with_justify(Justify::Center),
+        HudElement::default(),
+        NarrationText { remaining: 0.0 },
+    ));
+}
+
+// Every new run starts the first night over, unless it's been turned off
+// in the settings.
+fn queue_first_night(
+    locale: Res<Locale>,
+    settings: Res<GameSettings>,
+    mut runner: ResMut<ScriptRunner>,
+    player_query: Query<(), Added<Player>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+    runner.clear();
+    if settings.skip_first_night {
+        return;
+    }
+    for event in first_night_events(&locale, &settings) {
+        runner.add(event);
+    }
+}
+
+fn check_script_triggers(
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    mut runner: ResMut<ScriptRunner>,
+    player_query: Query<&Stats, With<Player>>,
+) {
+    // The first night ends with the player's first death.
+    if death_state.is_dead {
+        runner.clear();
+        return;
+    }
+    let Ok(stats) = player_query.single() else {
+        return;
+    };
+
+    runner.elapsed += time.delta_secs();
+    let elapsed = runner.elapsed;
+    let mut fired_steps = Vec::new();
+    for event in runner.events.iter_mut().filter(|event| !event.fired) {
+        let triggered = match event.trigger {
+            ScriptTrigger::GameStart => true,
+            ScriptTrigger::Elapsed(seconds) => elapsed >= seconds,
+            ScriptTrigger::FoodBelow(value) => stats.food_bar < value,
+        };
+        if triggered {
+            event.fired = true;
+            fired_steps.extend(event.steps.iter().cloned());
+        }
+    }
+
+    // Step delays are relative to the previous step of the same event.
+    let mut offset = 0.0;
+    for step in fired_steps {
+        offset += step.delay;
+        runner.pending.push(PendingAction {
+            remaining: offset,
+            action: step.action,
+        });
+    }
+}
+
+fn run_script_actions(
+    mut commands: Commands,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    (grid, light_config): (Res<WorldGrid>, Res<LightConfig>),
+    mut runner: ResMut<ScriptRunner>,
+    mut food_cache_writer: MessageWriter<SpawnFoodCache>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+    mut narration_query: Query<(&mut Text, &mut NarrationText)>,
+) {
+    if death_state.is_dead {
+        return;
+    }
+
+    let dt = time.delta_secs();
+    let mut ready = Vec::new();
+    runner.pending.retain_mut(|pending| {
+        pending.remaining -= dt;
+        if pending.remaining <= 0.0 {
+            ready.push(pending.action.clone());
+            false
+        } else {
+            true
+        }
+    });
+
+    for action in ready {
+        match action {
+            ScriptAction::Narrate(line) => {
+                if let Ok((mut text, mut narration)) = narration_query.single_mut() {
+                    text.0 = line;
+                    narration.remaining = NARRATION_SECONDS;
+                }
+            }
+            ScriptAction::SpawnFoodCache {
+                count,
+                radius_tiles,
+            } => {
+                food_cache_writer.write(SpawnFoodCache {
+                    count,
+                    radius_tiles,
+                });
+            }
+            ScriptAction::SpawnEnemy { strength } => {
+                let Ok((transform, state)) = player_query.single() else {
+                    continue;
+                };
+                let player_pos = transform.translation.truncate();
+                match find_dark_spawn(&grid, &light_config, player_pos, state.facing) {
+                    Some(position) => {
+                        spawn_enemy(&mut commands, position, strength);
+                    }
+                    // Tried again shortly rather than dropped.
+                    None => runner.pending.push(PendingAction {
+                        remaining: SPAWN_RETRY_SECONDS,
+                        action: ScriptAction::SpawnEnemy { strength },
+                    }),
+                }
+            }
+        }
+    }
+}
+
+fn fade_narration(
+    time: Res<Time>,
+    mut narration_query: Query<(&mut TextColor, &mut NarrationText)>,
+) {
+    let Ok((mut color, mut narration)) = narration_query.single_mut() else {
+        return;
+    };
+    narration.remaining = (narration.remaining - time.delta_secs()).max(0.0);
+    let alpha = (narration.remaining / NARRATION_FADE_SECONDS).clamp(0.0, 1.0);
+    color.0.set_alpha(alpha);
+}
+
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_scripts).add_systems(
+            Update,
+            (
+                queue_first_night,
+                check_script_triggers,
+                run_script_actions,
+                fade_narration,
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,890 @@
+// player settings: volumes, how the dark is drawn, UI and text size, the
+// status colours, language, skipping the first night, key bindings and which
+// tutorial hints are done.
+// They're edited on the settings screen, reached from the title and pause
+// menus, and written to settings.ron whenever it's closed. Gameplay reads
+// keys through GameSettings; everything else is pushed out to the resources
+// that already drive it.
+use bevy::audio::{GlobalVolume, Volume};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, fs, path::Path, path::PathBuf};
+
+use crate::light::LightConfig;
+use crate::locale::{Locale, DEFAULT_LANGUAGE};
+use crate::menu::{MenuFocus, MenuNav};
+use crate::tutorial::TutorialHint;
+
+pub const DEFAULT_SETTINGS_FILE: &str = "settings.ron";
+const VOLUME_STEP: f32 = 0.1;
+const BRIGHTNESS_FLOOR_STEP: f32 = 0.05;
+const MAX_BRIGHTNESS_FLOOR: f32 = 0.5;
+const UI_SCALE_STEP: f32 = 0.25;
+// Up to 3x for high-DPI screens that report no scale factor of their own.
+const UI_SCALE_RANGE: (f32, f32) = (0.5, 3.0);
+const TEXT_SIZE_STEP: f32 = 0.25;
+const TEXT_SIZE_RANGE: (f32, f32) = (0.75, 2.0);
+const SETTINGS_TEXT_COLOR: Color = Color::srgb(0.9, 0.85, 0.7);
+
+// Things the player can rebind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Sneak,
+    Attack,
+    Interact,
+    Throw,
+    Inventory,
+    Crafting,
+    Map,
+    SwitchTool,
+    UseTool,
+    FeedPet,
+    UseItem,
+    EatItem,
+    DropItem,
+    SplitStack,
+    EquipItem,
+}
+
+impl Action {
+    pub const ALL: [Action; 19] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Sneak,
+        Action::Attack,
+        Action::Interact,
+        Action::Throw,
+        Action::Inventory,
+        Action::Crafting,
+        Action::Map,
+        Action::SwitchTool,
+        Action::UseTool,
+        Action::FeedPet,
+        Action::UseItem,
+        Action::EatItem,
+        Action::DropItem,
+        Action::SplitStack,
+        Action::EquipItem,
+    ];
+
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Action::MoveUp => "action.move_up",
+            Action::MoveDown => "action.move_down",
+            Action::MoveLeft => "action.move_left",
+            Action::MoveRight => "action.move_right",
+            Action::Sneak => "action.sneak",
+            Action::Attack => "action.attack",
+            Action::Interact => "action.interact",
+            Action::Throw => "action.throw",
+            Action::Inventory => "action.inventory",
+            Action::Crafting => "action.crafting",
+            Action::Map => "action.map",
+            Action::SwitchTool => "action.switch_tool",
+            Action::UseTool => "action.use_tool",
+            Action::FeedPet => "action.feed_pet",
+            Action::UseItem => "action.use_item",
+            Action::EatItem => "action.eat_item",
+            Action::DropItem => "action.drop_item",
+            Action::SplitStack => "action.split_stack",
+            Action::EquipItem => "action.equip_item",
+        }
+    }
+
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            Action::MoveUp => KeyCode::ArrowUp,
+            Action::MoveDown => KeyCode::ArrowDown,
+            Action::MoveLeft => KeyCode::ArrowLeft,
+            Action::MoveRight => KeyCode::ArrowRight,
+            Action::Sneak => KeyCode::KeyC,
+            Action::Attack => KeyCode::Space,
+            Action::Interact => KeyCode::KeyE,
+            Action::Throw => KeyCode::KeyT,
+            Action::Inventory => KeyCode::KeyI,
+            Action::Crafting => KeyCode::KeyK,
+            Action::Map => KeyCode::KeyM,
+            Action::SwitchTool => KeyCode::KeyQ,
+            Action::UseTool => KeyCode::KeyF,
+            Action::FeedPet => KeyCode::KeyH,
+            Action::UseItem => KeyCode::KeyG,
+            Action::EatItem => KeyCode::KeyU,
+            Action::DropItem => KeyCode::KeyX,
+            Action::SplitStack => KeyCode::KeyV,
+            Action::EquipItem => KeyCode::KeyR,
+        }
+    }
+}
+
+// Keys an action can be bound to. Escape, Enter and the F keys stay with
+// the menus and debug tools.
+const BINDABLE_KEYS: [KeyCode; 58] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::Quote,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::Backquote,
+];
+
+// Saved by the variant name, e.g. "KeyE" or "ArrowUp".
+fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.into_iter().find(|key| key_name(*key) == name)
+}
+
+// Shorter names for the screen: "E" rather than "KeyE".
+pub fn key_label(key: KeyCode) -> String {
+    let name = key_name(key);
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DitherMode {
+    // A fixed pattern that breaks up the bands of light.
+    #[default]
+    Ordered,
+    // Plain bands.
+    Off,
+}
+
+impl DitherMode {
+    fn label_key(self) -> &'static str {
+        match self {
+            DitherMode::Ordered => "settings.dither.ordered",
+            DitherMode::Off => "settings.dither.off",
+        }
+    }
+
+    fn toggled(self) -> Self {
+        match self {
+            DitherMode::Ordered => DitherMode::Off,
+            DitherMode::Off => DitherMode::Ordered,
+        }
+    }
+}
+
+// Colours for the health, food and stamina pips and the flash when a meal
+// changes them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusPalette {
+    // Red health, green food, blue stamina.
+    #[default]
+    Classic,
+    // Vermilion, yellow and blue, which stay apart with red-green colour
+    // blindness.
+    ColorSafe,
+    // No colour at all; the pip shapes tell the rows apart.
+    Monochrome,
+}
+
+impl StatusPalette {
+    const ALL: [StatusPalette; 3] = [
+        StatusPalette::Classic,
+        StatusPalette::ColorSafe,
+        StatusPalette::Monochrome,
+    ];
+
+    fn label_key(self) -> &'static str {
+        match self {
+            StatusPalette::Classic => "settings.palette.classic",
+            StatusPalette::ColorSafe => "settings.palette.color_safe",
+            StatusPalette::Monochrome => "settings.palette.monochrome",
+        }
+    }
+
+    fn stepped(self, direction: f32) -> Self {
+        let count = Self::ALL.len();
+        let index = Self::ALL.iter().position(|palette| *palette == self).unwrap_or(0);
+        let next = if direction > 0.0 {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        };
+        Self::ALL[next]
+    }
+}
+
+// Volumes run from 0 to 1. Music volume is kept for when there's music;
+// nothing plays any yet.
+#[derive(Resource, Clone, Debug)]
+pub struct GameSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    // Lit ground never draws darker than this share of full brightness.
+    pub brightness_floor: f32,
+    pub dither: DitherMode,
+    // Lets the performance governor trade lighting quality for frame rate.
+    pub auto_perf_scaling: bool,
+    // Scales all UI, text included.
+    pub ui_scale: f32,
+    // Scales UI text on top of ui_scale, leaving the rest of the UI as is.
+    pub text_size: f32,
+    pub palette: StatusPalette,
+    // A file name in the locales directory, e.g. "en".
+    pub language: String,
+    // New runs start without the first night's narration and food.
+    pub skip_first_night: bool,
+    // Tutorial hints the player has been through, which don't show again.
+    pub tutorial_done: Vec<TutorialHint>,
+    keys: HashMap<Action, KeyCode>,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            brightness_floor: 0.0,
+            dither: DitherMode::Ordered,
+            auto_perf_scaling: true,
+            ui_scale: 1.0,
+            text_size: 1.0,
+            palette: StatusPalette::Classic,
+            language: DEFAULT_LANGUAGE.to_string(),
+            skip_first_night: false,
+            tutorial_done: Vec::new(),
+            keys: HashMap::new(),
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.keys.get(&action).copied().unwrap_or(action.default_key())
+    }
+
+    // Whatever else had the key gets this action's old one, so nothing is
+    // left unbound.
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        let old = self.key(action);
+        if let Some(other) = Action::ALL
+            .into_iter()
+            .find(|other| *other != action && self.key(*other) == key)
+        {
+            self.keys.insert(other, old);
+        }
+        self.keys.insert(action, key);
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        let text = fs::read_to_string(path).map_err(SettingsError::Io)?;
+        let record: SettingsRecord =
+            ron::from_str(&text).map_err(|err| SettingsError::Parse(err.to_string()))?;
+        let mut settings = Self {
+            master_volume: record.master_volume.clamp(0.0, 1.0),
+            music_volume: record.music_volume.clamp(0.0, 1.0),
+            sfx_volume: record.sfx_volume.clamp(0.0, 1.0),
+            brightness_floor: record.brightness_floor.clamp(0.0, MAX_BRIGHTNESS_FLOOR),
+            dither: record.dither,
+            auto_perf_scaling: record.auto_perf_scaling,
+            ui_scale: record.ui_scale.clamp(UI_SCALE_RANGE.0, UI_SCALE_RANGE.1),
+            text_size: record.text_size.clamp(TEXT_SIZE_RANGE.0, TEXT_SIZE_RANGE.1),
+            palette: record.palette,
+            language: record.language,
+            skip_first_night: record.skip_first_night,
+            tutorial_done: record.tutorial_done,
+            keys: HashMap::new(),
+        };
+        for (action, name) in record.keys {
+            match key_from_name(&name) {
+                Some(key) => settings.bind(action, key),
+                None => warn!("ignoring unknown key {name} for {action:?}"),
+            }
+        }
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        let record = SettingsRecord {
+            master_volume: self.master_volume,
+            music_volume: self.music_volume,
+            sfx_volume: self.sfx_volume,
+            brightness_floor: self.brightness_floor,
+            dither: self.dither,
+            auto_perf_scaling: self.auto_perf_scaling,
+            ui_scale: self.ui_scale,
+            text_size: self.text_size,
+            palette: self.palette,
+            language: self.language.clone(),
+            skip_first_night: self.skip_first_night,
+            tutorial_done: self.tutorial_done.clone(),
+            keys: Action::ALL
+                .into_iter()
+                .map(|action| (action, key_name(self.key(action))))
+                .collect(),
+        };
+        let text = ron::ser::to_string_pretty(&record, ron::ser::PrettyConfig::default())
+            .map_err(|err| SettingsError::Parse(err.to_string()))?;
+        fs::write(path, text).map_err(SettingsError::Io)
+    }
+}
+
+// How settings.ron is laid out; anything missing takes its default.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+struct SettingsRecord {
+    master_volume: f32,
+    music_volume: f32,
+    sfx_volume: f32,
+    brightness_floor: f32,
+    dither: DitherMode,
+    auto_perf_scaling: bool,
+    ui_scale: f32,
+    text_size: f32,
+    palette: StatusPalette,
+    language: String,
+    skip_first_night: bool,
+    tutorial_done: Vec<TutorialHint>,
+    keys: HashMap<Action, String>,
+}
+
+impl Default for SettingsRecord {
+    fn default() -> Self {
+        let settings = GameSettings::default();
+        Self {
+            master_volume: settings.master_volume,
+            music_volume: settings.music_volume,
+            sfx_volume: settings.sfx_volume,
+            brightness_floor: settings.brightness_floor,
+            dither: settings.dither,
+            auto_perf_scaling: settings.auto_perf_scaling,
+            ui_scale: settings.ui_scale,
+            text_size: settings.text_size,
+            palette: settings.palette,
+            language: settings.language,
+            skip_first_night: settings.skip_first_night,
+            tutorial_done: settings.tutorial_done,
+            keys: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(std::io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(err) => write!(f, "could not access settings: {err}"),
+            SettingsError::Parse(err) => write!(f, "could not read settings: {err}"),
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct SettingsConfig {
+    pub settings_path: PathBuf,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SettingsRow {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    BrightnessFloor,
+    Dither,
+    AutoPerfScaling,
+    UiScale,
+    TextSize,
+    Palette,
+    Language,
+    SkipFirstNight,
+    Key(Action),
+    Back,
+}
+
+fn settings_rows() -> Vec<SettingsRow> {
+    let mut rows = vec![
+        SettingsRow::MasterVolume,
+        SettingsRow::MusicVolume,
+        SettingsRow::SfxVolume,
+        SettingsRow::BrightnessFloor,
+        SettingsRow::Dither,
+        SettingsRow::AutoPerfScaling,
+        SettingsRow::UiScale,
+        SettingsRow::TextSize,
+        SettingsRow::Palette,
+        SettingsRow::Language,
+        SettingsRow::SkipFirstNight,
+    ];
+    rows.extend(Action::ALL.into_iter().map(SettingsRow::Key));
+    rows.push(SettingsRow::Back);
+    rows
+}
+
+// Open while the settings screen is up; the menus stay out of the way
+// until it closes.
+#[derive(Resource, Default)]
+pub struct SettingsScreen {
+    pub open: bool,
+    selected: usize,
+    // The action waiting for a key press.
+    rebinding: Option<Action>,
+}
+
+impl SettingsScreen {
+    pub fn show(&mut self) {
+        self.open = true;
+        self.selected = 0;
+        self.rebinding = None;
+    }
+}
+
+#[derive(Component)]
+struct SettingsRoot;
+
+// The size a piece of UI text was spawned with, before text_size.
+#[derive(Component)]
+struct BaseFontSize(f32);
+
+#[derive(Component)]
+struct SettingsTitle;
+
+#[derive(Component)]
+struct SettingsHint;
+
+#[derive(Component)]
+struct SettingsRowButton(usize);
+
+#[derive(Component)]
+struct SettingsRowText(usize);
+
+fn setup_settings_screen(mut commands: Commands) {
+    let rows = settings_rows().len();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(0.0),
+                width: percent(100.0),
+                height: percent(100.0),
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.95)),
+            GlobalZIndex(210),
+            SettingsRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    margin: UiRect::bottom(px(12.0)),
+                    ..default()
+                },
+                Text::new(""),
+                TextFont::from_font_size(22.0),
+                TextColor(SETTINGS_TEXT_COLOR),
+                SettingsTitle,
+            ));
+            for index in 0..rows {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            padding: UiRect::axes(px(12.0), px(1.0)),
+                            ..default()
+                        },
+                        MenuFocus::default(),
+                        SettingsRowButton(index),
+                    ))
+                    .with_child((
+                        Text::new(""),
+                        TextFont::from_font_size(22.0),
+                        TextColor(SETTINGS_TEXT_COLOR),
+                        SettingsRowText(index),
+                    ));
+            }
+            parent.spawn((
+                Node {
+                    margin: UiRect::top(px(12.0)),
+                    ..default()
+                },
+                Text::new(""),
+                TextFont::from_font_size(22.0),
+                TextColor(SETTINGS_TEXT_COLOR),
+                SettingsHint,
+            ));
+        });
+}
+
+fn step_volume(volume: &mut f32, direction: f32) {
+    *volume = ((*volume + VOLUME_STEP * direction) / VOLUME_STEP).round() * VOLUME_STEP;
+    *volume = volume.clamp(0.0, 1.0);
+}
+
+// Steps through the languages found on disk, wrapping at either end.
+fn step_language(language: &mut String, locale: &Locale, direction: f32) {
+    let languages = locale.languages();
+    if languages.is_empty() {
+        return;
+    }
+    let count = languages.len();
+    let current = languages.iter().position(|(code, _)| code == language.as_str());
+    let next = match current {
+        Some(index) if direction > 0.0 => (index + 1) % count,
+        Some(index) => (index + count - 1) % count,
+        None => 0,
+    };
+    *language = languages[next].0.clone();
+}
+
+fn close_settings_screen(
+    screen: &mut SettingsScreen,
+    settings: &GameSettings,
+    config: &SettingsConfig,
+) {
+    screen.open = false;
+    screen.rebinding = None;
+    if let Err(err) = settings.save(&config.settings_path) {
+        warn!("could not save settings to {}: {err}", config.settings_path.display());
+    }
+}
+
+// Up and down pick a row, left and right change it, Enter starts a rebind
+// and Escape backs out of one or closes the screen; a d-pad, the left stick
+// and the mouse work too. Keys can only be rebound from the keyboard.
+fn settings_input(
+    mut input: ResMut<ButtonInput<KeyCode>>,
+    mut nav: ResMut<MenuNav>,
+    config: Res<SettingsConfig>,
+    locale: Res<Locale>,
+    mut screen: ResMut<SettingsScreen>,
+    mut settings: ResMut<GameSettings>,
+    row_query: Query<(&Interaction, &SettingsRowButton), Changed<Interaction>>,
+) {
+    if !screen.open {
+        return;
+    }
+    if let Some(action) = screen.rebinding {
+        if nav.back {
+            screen.rebinding = None;
+        } else if let Some(key) = BINDABLE_KEYS.into_iter().find(|key| input.just_pressed(*key)) {
+            settings.bind(action, key);
+            screen.rebinding = None;
+        }
+        // The new key shouldn't also act in the game this frame.
+        input.reset_all();
+        *nav = MenuNav::default();
+        return;
+    }
+
+    let rows = settings_rows();
+    let mut clicked = false;
+    for (interaction, row) in &row_query {
+        if *interaction != Interaction::None {
+            screen.selected = row.0;
+            clicked |= *interaction == Interaction::Pressed;
+        }
+    }
+    if nav.up {
+        screen.selected = (screen.selected + rows.len() - 1) % rows.len();
+    }
+    if nav.down {
+        screen.selected = (screen.selected + 1) % rows.len();
+    }
+    let row = rows[screen.selected.min(rows.len() - 1)];
+    let confirm = nav.confirm || clicked;
+    if nav.back || (row == SettingsRow::Back && confirm) {
+        close_settings_screen(&mut screen, &settings, &config);
+        input.reset_all();
+        *nav = MenuNav::default();
+        return;
+    }
+    if let SettingsRow::Key(action) = row
+        && confirm
+    {
+        screen.rebinding = Some(action);
+        return;
+    }
+
+    let direction = if nav.left {
+        -1.0
+    } else if nav.right {
+        1.0
+    } else {
+        return;
+    };
+    match row {
+        SettingsRow::MasterVolume => step_volume(&mut settings.master_volume, direction),
+        SettingsRow::MusicVolume => step_volume(&mut settings.music_volume, direction),
+        SettingsRow::SfxVolume => step_volume(&mut settings.sfx_volume, direction),
+        SettingsRow::BrightnessFloor => {
+            settings.brightness_floor = (settings.brightness_floor
+                + BRIGHTNESS_FLOOR_STEP * direction)
+                .clamp(0.0, MAX_BRIGHTNESS_FLOOR);
+        }
+        SettingsRow::Dither => settings.dither = settings.dither.toggled(),
+        SettingsRow::AutoPerfScaling => settings.auto_perf_scaling = !settings.auto_perf_scaling,
+        SettingsRow::UiScale => {
+            settings.ui_scale = (settings.ui_scale + UI_SCALE_STEP * direction)
+                .clamp(UI_SCALE_RANGE.0, UI_SCALE_RANGE.1);
+        }
+        SettingsRow::TextSize => {
+            settings.text_size = (settings.text_size + TEXT_SIZE_STEP * direction)
+                .clamp(TEXT_SIZE_RANGE.0, TEXT_SIZE_RANGE.1);
+        }
+        SettingsRow::Palette => settings.palette = settings.palette.stepped(direction),
+        SettingsRow::Language => step_language(&mut settings.language, &locale, direction),
+        SettingsRow::SkipFirstNight => settings.skip_first_night = !settings.skip_first_night,
+        SettingsRow::Key(_) | SettingsRow::Back => {}
+    }
+}
+
+fn update_settings_screen(
+    screen: Res<SettingsScreen>,
+    settings: Res<GameSettings>,
+    locale: Res<Locale>,
+    mut root_query: Query<&mut Node, With<SettingsRoot>>,
+    mut focus_query: Query<(&SettingsRowButton, &mut MenuFocus)>,
+    mut row_text_query: Query<(&SettingsRowText, &mut Text)>,
+    mut title_query: Query<&mut Text, (With<SettingsTitle>, Without<SettingsRowText>)>,
+    mut hint_query: Query<
+        &mut Text,
+        (With<SettingsHint>, Without<SettingsTitle>, Without<SettingsRowText>),
+    >,
+) {
+    if let Ok(mut node) = root_query.single_mut() {
+        node.display = if screen.open { Display::Flex } else { Display::None };
+    }
+    if !screen.open {
+        return;
+    }
+    for (row, mut focus) in &mut focus_query {
+        let focused = row.0 == screen.selected;
+        if focus.focused != focused {
+            focus.focused = focused;
+        }
+    }
+    if let Ok(mut text) = title_query.single_mut() {
+        text.0 = locale.get("settings.title").to_string();
+    }
+    if let Ok(mut text) = hint_query.single_mut() {
+        text.0 = locale.get("settings.hint").to_string();
+    }
+    let percent = |value: f32| format!("{:.0}%", value * 100.0);
+    let row_line = |key: &str, value: &str| format!("{}: {value}", locale.get(key));
+    let language_name = locale
+        .languages()
+        .iter()
+        .find(|(code, _)| *code == settings.language)
+        .map_or(settings.language.as_str(), |(_, name)| name.as_str());
+    let rows = settings_rows();
+    for (row_text, mut text) in &mut row_text_query {
+        let Some(row) = rows.get(row_text.0) else {
+            continue;
+        };
+        let line = match *row {
+            SettingsRow::MasterVolume => {
+                row_line("settings.master_volume", &percent(settings.master_volume))
+            }
+            SettingsRow::MusicVolume => {
+                row_line("settings.music_volume", &percent(settings.music_volume))
+            }
+            SettingsRow::SfxVolume => {
+                row_line("settings.sfx_volume", &percent(settings.sfx_volume))
+            }
+            SettingsRow::BrightnessFloor => {
+                row_line("settings.brightness_floor", &percent(settings.brightness_floor))
+            }
+            SettingsRow::Dither => {
+                row_line("settings.dither", locale.get(settings.dither.label_key()))
+            }
+            SettingsRow::AutoPerfScaling => {
+                let value = if settings.auto_perf_scaling {
+                    "settings.on"
+                } else {
+                    "settings.off"
+                };
+                row_line("settings.auto_perf_scaling", locale.get(value))
+            }
+            SettingsRow::UiScale => {
+                row_line("settings.ui_scale", &format!("x{:.2}", settings.ui_scale))
+            }
+            SettingsRow::TextSize => {
+                row_line("settings.text_size", &format!("x{:.2}", settings.text_size))
+            }
+            SettingsRow::Palette => {
+                row_line("settings.palette", locale.get(settings.palette.label_key()))
+            }
+            SettingsRow::Language => row_line("settings.language", language_name),
+            SettingsRow::SkipFirstNight => {
+                let value = if settings.skip_first_night {
+                    "settings.on"
+                } else {
+                    "settings.off"
+                };
+                row_line("settings.skip_first_night", locale.get(value))
+            }
+            SettingsRow::Key(action) if screen.rebinding == Some(action) => {
+                row_line(action.label_key(), locale.get("settings.press_key"))
+            }
+            SettingsRow::Key(action) => {
+                row_line(action.label_key(), &key_label(settings.key(action)))
+            }
+            SettingsRow::Back => locale.get("settings.back").to_string(),
+        };
+        if text.0 != line {
+            text.0 = line;
+        }
+    }
+}
+
+// No file yet just means nothing has been changed from the defaults.
+fn load_settings(mut commands: Commands, config: Res<SettingsConfig>) {
+    match GameSettings::load(&config.settings_path) {
+        Ok(settings) => commands.insert_resource(settings),
+        Err(SettingsError::Io(_)) => {}
+        Err(err) => warn!("ignoring {}: {err}", config.settings_path.display()),
+    }
+}
+
+// Pushes the settings out to whatever they control.
+fn apply_settings(
+    settings: Res<GameSettings>,
+    mut ui_scale: ResMut<UiScale>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut light_config: ResMut<LightConfig>,
+) {
+    ui_scale.0 = settings.ui_scale;
+    global_volume.volume = Volume::Linear(settings.master_volume);
+    light_config.brightness_floor = settings.brightness_floor;
+    light_config.dithered = settings.dither == DitherMode::Ordered;
+}
+
+// Text spawned at any point picks up the current size, so new UI doesn't
+// have to know about the setting.
+fn size_new_text(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    mut text_query: Query<(Entity, &mut TextFont), (Added<TextFont>, With<Text>)>,
+) {
+    for (entity, mut font) in &mut text_query {
+        commands.entity(entity).insert(BaseFontSize(font.font_size));
+        font.font_size *= settings.text_size;
+    }
+}
+
+fn resize_text(
+    settings: Res<GameSettings>,
+    mut text_query: Query<(&BaseFontSize, &mut TextFont)>,
+) {
+    for (base, mut font) in &mut text_query {
+        let size = base.0 * settings.text_size;
+        if font.font_size != size {
+            font.font_size = size;
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SettingsPlugin {
+    pub settings_path: PathBuf,
+}
+
+impl Default for SettingsPlugin {
+    fn default() -> Self {
+        Self {
+            settings_path: PathBuf::from(DEFAULT_SETTINGS_FILE),
+        }
+    }
+}
+
+impl SettingsPlugin {
+    pub fn with_settings_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.settings_path = path.into();
+        self
+    }
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameSettings::default())
+            .insert_resource(SettingsConfig {
+                settings_path: self.settings_path.clone(),
+            })
+            .init_resource::<SettingsScreen>()
+            .add_systems(Startup, (load_settings, setup_settings_screen))
+            .add_systems(
+                Update,
+                (
+                    (settings_input, update_settings_screen).chain(),
+                    apply_settings.run_if(resource_changed::<GameSettings>),
+                    (size_new_text, resize_text.run_if(resource_changed::<GameSettings>)).chain(),
+                ),
+            );
+    }
+}
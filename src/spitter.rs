@@ -0,0 +1,145 @@
+// spitters: enemies that hang back out of reach and spit at the player
+// instead of closing in. They turn up in the dark like other enemies, and
+// their spit glows, so it can be seen coming and dodged.
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::enemy::{find_dark_spawn, spawn_enemy, Enemy, KeepDistance};
+use crate::light::{LightConfig, Lightable};
+use crate::player::{DeathRespawnState, Player, PlayerState};
+use crate::projectile::spawn_projectile;
+use crate::world::{WorldGrid, WORLD_TILE_SIZE};
+
+const DEFAULT_MAX_SPITTERS: usize = 2;
+const DEFAULT_SPAWN_SECONDS: f32 = 30.0;
+const SPITTER_STRENGTH: f32 = 0.8;
+const SPITTER_TINT: Color = Color::srgb(0.35, 0.55, 0.2);
+const KEEP_DISTANCE: f32 = 25.0;
+const FIRE_RANGE: f32 = 35.0;
+const FIRE_COOLDOWN_SECONDS: f32 = 2.0;
+const SPIT_SPEED: f32 = 45.0;
+const SPIT_DAMAGE: f32 = 8.0;
+const SPIT_LIFETIME: f32 = 1.5;
+const SPIT_RADIUS: f32 = 2.0;
+const SPIT_COLOR: Color = Color::srgb(0.6, 0.95, 0.3);
+
+#[derive(Component)]
+pub struct Spitter {
+    cooldown: f32,
+}
+
+#[derive(Resource)]
+pub struct SpitterConfig {
+    pub max_spitters: usize,
+    pub timer: Timer,
+}
+
+fn spawn_spitters(
+    mut commands: Commands,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    grid: Res<WorldGrid>,
+    light_config: Res<LightConfig>,
+    mut config: ResMut<SpitterConfig>,
+    player_query: Query<(&Transform, &PlayerState), With<Player>>,
+    spitter_query: Query<(), With<Spitter>>,
+) {
+    if death_state.is_dead || !config.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    if spitter_query.iter().count() >= config.max_spitters {
+        return;
+    }
+    let Ok((player_transform, state)) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let Some(position) = find_dark_spawn(&grid, &light_config, player_pos, state.facing) else {
+        return;
+    };
+    let spitter = spawn_enemy(&mut commands, position, SPITTER_STRENGTH);
+    commands.entity(spitter).insert((
+        Spitter {
+            cooldown: FIRE_COOLDOWN_SECONDS,
+        },
+        KeepDistance(KEEP_DISTANCE),
+        Lightable { tint: SPITTER_TINT },
+    ));
+}
+
+// Only at a player they're chasing, straight at where the player is now.
+fn spit(
+    mut commands: Commands,
+    time: Res<Time>,
+    death_state: Res<DeathRespawnState>,
+    player_query: Query<&Transform, With<Player>>,
+    mut spitter_query: Query<(&mut Spitter, &Enemy, &Transform), Without<Player>>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let dt = time.delta_secs();
+    for (mut spitter, enemy, transform) in &mut spitter_query {
+        spitter.cooldown = (spitter.cooldown - dt).max(0.0);
+        let position = transform.translation.truncate();
+        if death_state.is_dead
+            || !enemy.chasing
+            || spitter.cooldown > 0.0
+            || position.distance(player_pos) > FIRE_RANGE * WORLD_TILE_SIZE
+        {
+            continue;
+        }
+        spitter.cooldown = FIRE_COOLDOWN_SECONDS;
+        let direction = (player_pos - position).normalize_or(enemy.facing);
+        spawn_projectile(
+            &mut commands,
+            position,
+            direction * SPIT_SPEED,
+            SPIT_DAMAGE,
+            SPIT_LIFETIME,
+            SPIT_RADIUS,
+            SPIT_COLOR,
+        );
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpitterPlugin {
+    pub max_spitters: usize,
+    pub spawn_seconds: f32,
+}
+
+impl Default for SpitterPlugin {
+    fn default() -> Self {
+        Self {
+            max_spitters: DEFAULT_MAX_SPITTERS,
+            spawn_seconds: DEFAULT_SPAWN_SECONDS,
+        }
+    }
+}
+
+impl SpitterPlugin {
+    pub fn with_max_spitters(mut self, max_spitters: usize) -> Self {
+        self.max_spitters = max_spitters;
+        self
+    }
+
+    pub fn with_spawn_seconds(mut self, seconds: f32) -> Self {
+        self.spawn_seconds = seconds;
+        self
+    }
+}
+
+impl Plugin for SpitterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SpitterConfig {
+            max_spitters: self.max_spitters,
+            timer: Timer::new(
+                Duration::from_secs_f32(self.spawn_seconds.max(0.1)),
+                TimerMode::Repeating,
+            ),
+        })
+        .add_systems(Update, (spawn_spitters, spit).chain());
+    }
+}
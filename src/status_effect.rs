@@ -0,0 +1,400 @@
+// timed status effects on the player, ticked alongside the energy system.
+// Active ones show as icons along the bottom of the status panel, each in a
+// ring of dots that go out as the effect wears off.
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::f32::consts::TAU;
+
+use crate::day_night::DayNightCycle;
+use crate::hud::StatusPanel;
+use crate::player::{DeathRespawnState, Exhausted, Player};
+
+const POISON_DAMAGE_PER_STACK: f32 = 2.0;
+const POISON_MAX_STACKS: u32 = 3;
+const REGENERATION_PER_SEC: f32 = 3.0;
+const SLOW_SPEED_FACTOR: f32 = 0.6;
+const WELL_FED_DRAIN_FACTOR: f32 = 0.5;
+const WELL_FED_MAX_SECONDS: f32 = 120.0;
+const COLD_FOOD_DRAIN_FACTOR: f32 = 1.3;
+const EXHAUSTED_SECONDS: f32 = 10.0;
+const EXHAUSTED_STAMINA_REGEN_FACTOR: f32 = 0.5;
+const EFFECT_ICON_SIZE: f32 = 22.0;
+const RING_DOTS: usize = 12;
+const RING_DOT_SIZE: f32 = 4.0;
+// From the icon's centre to the dots' centres.
+const RING_RADIUS: f32 = EFFECT_ICON_SIZE * 0.5 + 4.0;
+const EFFECT_SLOT_SIZE: f32 = RING_RADIUS * 2.0 + RING_DOT_SIZE;
+const RING_DOT_OFF: Color = Color::srgba(0.2, 0.2, 0.2, 0.35);
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusEffectKind {
+    Poison,
+    Regeneration,
+    Slow,
+    WellFed,
+    // Through the night, until dawn.
+    Cold,
+    // For a while after stamina runs out.
+    Exhausted,
+}
+
+// How a new application combines with an effect that is already active.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum StackRule {
+    Refresh,
+    Extend { max_seconds: f32 },
+    Intensify { max_stacks: u32 },
+}
+
+impl StatusEffectKind {
+    pub const ALL: [StatusEffectKind; 6] = [
+        StatusEffectKind::Poison,
+        StatusEffectKind::Regeneration,
+        StatusEffectKind::Slow,
+        StatusEffectKind::WellFed,
+        StatusEffectKind::Cold,
+        StatusEffectKind::Exhausted,
+    ];
+
+    fn stack_rule(self) -> StackRule {
+        match self {
+            StatusEffectKind::Poison => StackRule::Intensify {
+                max_stacks: POISON_MAX_STACKS,
+            },
+            StatusEffectKind::Regeneration
+            | StatusEffectKind::Slow
+            | StatusEffectKind::Cold
+            | StatusEffectKind::Exhausted => StackRule::Refresh,
+            StatusEffectKind::WellFed => StackRule::Extend {
+                max_seconds: WELL_FED_MAX_SECONDS,
+            },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusEffectKind::Poison => "Poison",
+            StatusEffectKind::Regeneration => "Regeneration",
+            StatusEffectKind::Slow => "Slow",
+            StatusEffectKind::WellFed => "Well fed",
+            StatusEffectKind::Cold => "Cold",
+            StatusEffectKind::Exhausted => "Exhausted",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            StatusEffectKind::Poison => "P",
+            StatusEffectKind::Regeneration => "R",
+            StatusEffectKind::Slow => "S",
+            StatusEffectKind::WellFed => "W",
+            StatusEffectKind::Cold => "C",
+            StatusEffectKind::Exhausted => "E",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            StatusEffectKind::Poison => Color::srgb(0.45, 0.75, 0.2),
+            StatusEffectKind::Regeneration => Color::srgb(0.85, 0.3, 0.4),
+            StatusEffectKind::Slow => Color::srgb(0.35, 0.5, 0.85),
+            StatusEffectKind::WellFed => Color::srgb(0.9, 0.7, 0.25),
+            StatusEffectKind::Cold => Color::srgb(0.7, 0.88, 0.95),
+            StatusEffectKind::Exhausted => Color::srgb(0.6, 0.52, 0.45),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    pub remaining: f32,
+    // The longest it has had left, for how full its ring is.
+    pub duration: f32,
+    pub stacks: u32,
+}
+
+impl StatusEffect {
+    pub fn fraction_left(&self) -> f32 {
+        (self.remaining / self.duration.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Component, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, kind: StatusEffectKind, seconds: f32) {
+        let Some(effect) = self.effects.iter_mut().find(|effect| effect.kind == kind) else {
+            self.effects.push(StatusEffect {
+                kind,
+                remaining: seconds,
+                duration: seconds,
+                stacks: 1,
+            });
+            return;
+        };
+        match kind.stack_rule() {
+            StackRule::Refresh => effect.remaining = effect.remaining.max(seconds),
+            StackRule::Extend { max_seconds } => {
+                effect.remaining = (effect.remaining + seconds).min(max_seconds);
+            }
+            StackRule::Intensify { max_stacks } => {
+                effect.stacks = (effect.stacks + 1).min(max_stacks);
+                effect.remaining = effect.remaining.max(seconds);
+            }
+        }
+        effect.duration = effect.duration.max(effect.remaining);
+    }
+
+    pub fn get(&self, kind: StatusEffectKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|effect| effect.kind == kind)
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    fn tick(&mut self, dt: f32) {
+        for effect in &mut self.effects {
+            effect.remaining -= dt;
+        }
+        self.effects.retain(|effect| effect.remaining > 0.0);
+    }
+
+    // Net health change per second from poison and regeneration.
+    pub fn health_per_sec(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|effect| match effect.kind {
+                StatusEffectKind::Poison => -POISON_DAMAGE_PER_STACK * effect.stacks as f32,
+                StatusEffectKind::Regeneration => REGENERATION_PER_SEC,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.get(StatusEffectKind::Slow).is_some() {
+            SLOW_SPEED_FACTOR
+        } else {
+            1.0
+        }
+    }
+
+    pub fn food_drain_multiplier(&self) -> f32 {
+        let mut multiplier = 1.0;
+        if self.get(StatusEffectKind::WellFed).is_some() {
+            multiplier *= WELL_FED_DRAIN_FACTOR;
+        }
+        if self.get(StatusEffectKind::Cold).is_some() {
+            multiplier *= COLD_FOOD_DRAIN_FACTOR;
+        }
+        multiplier
+    }
+
+    pub fn stamina_regen_multiplier(&self) -> f32 {
+        if self.get(StatusEffectKind::Exhausted).is_some() {
+            EXHAUSTED_STAMINA_REGEN_FACTOR
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Component)]
+struct StatusEffectRow;
+
+#[derive(Component)]
+struct StatusEffectIcon {
+    kind: StatusEffectKind,
+}
+
+// One of the dots round an icon, counted clockwise from the top.
+#[derive(Component)]
+struct StatusEffectRingDot {
+    kind: StatusEffectKind,
+    index: usize,
+}
+
+#[derive(Component)]
+struct StatusEffectStacks {
+    kind: StatusEffectKind,
+}
+
+fn tick_status_effects(time: Res<Time>, mut query: Query<&mut StatusEffects>) {
+    let dt = time.delta_secs();
+    for mut effects in &mut query {
+        effects.tick(dt);
+    }
+}
+
+// Reapplied every frame of the night, so it runs out at dawn.
+fn chill_at_night(
+    cycle: Res<DayNightCycle>,
+    death_state: Res<DeathRespawnState>,
+    mut player_query: Query<&mut StatusEffects, With<Player>>,
+) {
+    if death_state.is_dead || !cycle.is_night() {
+        return;
+    }
+    for mut effects in &mut player_query {
+        effects.apply(StatusEffectKind::Cold, cycle.seconds_until_dawn());
+    }
+}
+
+// Only running dry counts, not an action turned down for lack of stamina.
+fn tire_on_exhaustion(
+    mut exhausted: MessageReader<Exhausted>,
+    mut player_query: Query<&mut StatusEffects, With<Player>>,
+) {
+    for message in exhausted.read().filter(|message| !message.refused) {
+        if let Ok(mut effects) = player_query.get_mut(message.entity) {
+            effects.apply(StatusEffectKind::Exhausted, EXHAUSTED_SECONDS);
+        }
+    }
+}
+
+fn spawn_effect_icon(row: &mut ChildSpawnerCommands, kind: StatusEffectKind) {
+    let centre = EFFECT_SLOT_SIZE * 0.5;
+    let icon_offset = centre - EFFECT_ICON_SIZE * 0.5;
+    row.spawn((
+        Node {
+            width: px(EFFECT_SLOT_SIZE),
+            height: px(EFFECT_SLOT_SIZE),
+            display: Display::None,
+            ..default()
+        },
+        StatusEffectIcon { kind },
+    ))
+    .with_children(|slot| {
+        slot.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(icon_offset),
+                top: px(icon_offset),
+                width: px(EFFECT_ICON_SIZE),
+                height: px(EFFECT_ICON_SIZE),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border_radius: BorderRadius::MAX,
+                ..default()
+            },
+            BackgroundColor(kind.color()),
+        ))
+        .with_child((
+            Text::new(kind.label()),
+            TextFont::from_font_size(14.0),
+            TextColor(Color::srgb(0.1, 0.1, 0.1)),
+        ));
+        for index in 0..RING_DOTS {
+            let angle = index as f32 / RING_DOTS as f32 * TAU;
+            let dot = Vec2::new(angle.sin(), -angle.cos()) * RING_RADIUS
+                + Vec2::splat(centre - RING_DOT_SIZE * 0.5);
+            slot.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: px(dot.x),
+                    top: px(dot.y),
+                    width: px(RING_DOT_SIZE),
+                    height: px(RING_DOT_SIZE),
+                    border_radius: BorderRadius::MAX,
+                    ..default()
+                },
+                BackgroundColor(RING_DOT_OFF),
+                StatusEffectRingDot { kind, index },
+            ));
+        }
+        slot.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: px(0.0),
+                bottom: px(0.0),
+                ..default()
+            },
+            Text::new(""),
+            TextFont::from_font_size(10.0),
+            TextColor(Color::srgb(0.1, 0.1, 0.1)),
+            StatusEffectStacks { kind },
+        ));
+    });
+}
+
+// Under the health, food and stamina rows, hidden while nothing is active.
+fn attach_status_effect_row(
+    mut commands: Commands,
+    panel_query: Query<Entity, Added<StatusPanel>>,
+) {
+    for panel in &panel_query {
+        commands.entity(panel).with_children(|panel| {
+            panel
+                .spawn((
+                    Node {
+                        display: Display::None,
+                        flex_direction: FlexDirection::Row,
+                        column_gap: px(2.0),
+                        ..default()
+                    },
+                    StatusEffectRow,
+                ))
+                .with_children(|row| {
+                    for kind in StatusEffectKind::ALL {
+                        spawn_effect_icon(row, kind);
+                    }
+                });
+        });
+    }
+}
+
+fn update_status_effect_hud(
+    player_query: Query<&StatusEffects, With<Player>>,
+    mut row_query: Query<&mut Node, (With<StatusEffectRow>, Without<StatusEffectIcon>)>,
+    mut icon_query: Query<(&StatusEffectIcon, &mut Node), Without<StatusEffectRow>>,
+    mut dot_query: Query<(&StatusEffectRingDot, &mut BackgroundColor)>,
+    mut stacks_query: Query<(&StatusEffectStacks, &mut Text)>,
+) {
+    let Ok(effects) = player_query.single() else {
+        return;
+    };
+    let shown = |active: bool| if active { Display::Flex } else { Display::None };
+    for mut node in &mut row_query {
+        node.display = shown(!effects.effects.is_empty());
+    }
+    for (icon, mut node) in &mut icon_query {
+        node.display = shown(effects.get(icon.kind).is_some());
+    }
+    for (dot, mut background) in &mut dot_query {
+        let Some(effect) = effects.get(dot.kind) else {
+            continue;
+        };
+        let lit = (effect.fraction_left() * RING_DOTS as f32).ceil() as usize;
+        let color = if dot.index < lit { dot.kind.color() } else { RING_DOT_OFF };
+        background.set_if_neq(BackgroundColor(color));
+    }
+    for (stacks, mut text) in &mut stacks_query {
+        let label = match effects.get(stacks.kind) {
+            Some(effect) if effect.stacks > 1 => format!("x{}", effect.stacks),
+            _ => String::new(),
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+pub struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (chill_at_night, tire_on_exhaustion, tick_status_effects).chain(),
+                (attach_status_effect_row, update_status_effect_hud).chain(),
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,169 @@
+// taming wildlife with thrown food. Each time an animal eats bait the player
+// threw it gets a little tamer, shown by a bar over its head, and after
+// enough meals it joins the player's side and follows them around. Saves
+// keep tamed animals, which come back beside the player.
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::event_log::EventLog;
+use crate::faction::Faction;
+use crate::wildlife::{spawn_animal, Animal, AnimalKind};
+use crate::world::WORLD_TILE_SIZE;
+
+const BAR_WIDTH: f32 = 8.0;
+const BAR_HEIGHT: f32 = 1.0;
+const BAR_GAP: f32 = 2.0;
+const BAR_TRACK_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.8);
+const BAR_FILL_COLOR: Color = Color::srgb(0.95, 0.6, 0.75);
+// Restored animals are spread around the player this far out.
+const RESTORE_DISTANCE: f32 = 8.0;
+
+// On food the player threw.
+#[derive(Component)]
+pub struct Bait;
+
+// On an animal that has eaten bait but isn't tame yet.
+#[derive(Component)]
+pub struct Taming {
+    pub meals: u32,
+}
+
+#[derive(Component)]
+pub struct Tamed;
+
+#[derive(Message, Debug, Clone, Copy)]
+pub struct BaitEaten {
+    pub animal: Entity,
+}
+
+#[derive(Component)]
+struct TamingBar;
+
+#[derive(Component)]
+struct TamingFill;
+
+// The bigger the animal, the more it takes to win over.
+pub fn meals_to_tame(kind: AnimalKind) -> u32 {
+    match kind {
+        AnimalKind::Rabbit => 3,
+        AnimalKind::Deer => 5,
+        AnimalKind::Bird => 4,
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SavedTame {
+    pub kind: String,
+}
+
+impl SavedTame {
+    pub fn new(kind: AnimalKind) -> Self {
+        Self {
+            kind: kind.name().to_string(),
+        }
+    }
+}
+
+pub fn to_saved<'a>(tamed: impl IntoIterator<Item = &'a Animal>) -> Vec<SavedTame> {
+    tamed.into_iter().map(|animal| SavedTame::new(animal.kind)).collect()
+}
+
+// Unknown kinds are skipped so an old or hand-edited save still loads.
+pub fn restore_tamed(commands: &mut Commands, saved: &[SavedTame], player_pos: Vec2) {
+    let count = saved.len().max(1) as f32;
+    for (index, tame) in saved.iter().enumerate() {
+        let Some(kind) = AnimalKind::from_name(&tame.kind) else {
+            warn!("dropping unknown saved animal {}", tame.kind);
+            continue;
+        };
+        let angle = index as f32 / count * std::f32::consts::TAU;
+        let position = player_pos + Vec2::from_angle(angle) * RESTORE_DISTANCE * WORLD_TILE_SIZE;
+        let animal = spawn_animal(commands, kind, position);
+        commands.entity(animal).insert((Tamed, Faction::Player));
+    }
+}
+
+fn count_meals(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut event_log: ResMut<EventLog>,
+    mut eaten: MessageReader<BaitEaten>,
+    mut animal_query: Query<(&Animal, Option<&mut Taming>), Without<Tamed>>,
+) {
+    for meal in eaten.read() {
+        let Ok((animal, taming)) = animal_query.get_mut(meal.animal) else {
+            continue;
+        };
+        let needed = meals_to_tame(animal.kind);
+        let meals = taming.as_ref().map_or(0, |taming| taming.meals) + 1;
+        let name = animal.kind.name();
+        if meals >= needed {
+            commands
+                .entity(meal.animal)
+                .remove::<Taming>()
+                .insert((Tamed, Faction::Player));
+            event_log.push(&time, format!("the {name} is tame and follows you now"));
+            continue;
+        }
+        event_log.push(&time, format!("the {name} took the bait ({meals}/{needed})"));
+        match taming {
+            Some(mut taming) => taming.meals = meals,
+            None => {
+                commands.entity(meal.animal).insert(Taming { meals }).with_child((
+                    TamingBar,
+                    Sprite::from_color(BAR_TRACK_COLOR, Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                    Transform::from_xyz(0.0, animal.kind.size() / 2.0 + BAR_GAP, 0.1),
+                    children![(
+                        TamingFill,
+                        Sprite::from_color(BAR_FILL_COLOR, Vec2::ZERO),
+                        Transform::from_xyz(0.0, 0.0, 0.01),
+                    )],
+                ));
+            }
+        }
+    }
+}
+
+fn update_taming_bars(
+    animal_query: Query<(&Animal, &Taming, &Children), Changed<Taming>>,
+    bar_query: Query<&Children, With<TamingBar>>,
+    mut fill_query: Query<(&mut Sprite, &mut Transform), With<TamingFill>>,
+) {
+    for (animal, taming, children) in &animal_query {
+        let ratio = (taming.meals as f32 / meals_to_tame(animal.kind) as f32).clamp(0.0, 1.0);
+        for fill in children
+            .iter()
+            .filter_map(|child| bar_query.get(child).ok())
+            .flat_map(|fills| fills.iter())
+        {
+            if let Ok((mut sprite, mut transform)) = fill_query.get_mut(fill) {
+                sprite.custom_size = Some(Vec2::new(BAR_WIDTH * ratio, BAR_HEIGHT));
+                transform.translation.x = -BAR_WIDTH * (1.0 - ratio) / 2.0;
+            }
+        }
+    }
+}
+
+// The bar goes once the animal is tame.
+fn remove_taming_bars(
+    mut commands: Commands,
+    tamed_query: Query<&Children, Added<Tamed>>,
+    bar_query: Query<(), With<TamingBar>>,
+) {
+    for children in &tamed_query {
+        for child in children.iter().filter(|child| bar_query.contains(*child)) {
+            commands.entity(child).despawn();
+        }
+    }
+}
+
+pub struct TamingPlugin;
+
+impl Plugin for TamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<BaitEaten>().add_systems(
+            Update,
+            (count_meals, update_taming_bars, remove_taming_bars).chain(),
+        );
+    }
+}
@@ -0,0 +1,156 @@
+// throwing carried items along the facing direction: food in the selected
+// hotbar slot lands as bait, otherwise a stone is thrown
+use bevy::prelude::*;
+
+use crate::food::{place_food, FoodTracker, Location2D};
+use crate::food_catalog::FoodCatalog;
+use crate::hotbar::Hotbar;
+use crate::items::{spawn_world_item, Inventory, ItemKind};
+use crate::light::facing_dir;
+use crate::menu::AppState;
+use crate::noise::NoiseEvent;
+use crate::player::{DeathRespawnState, Player, PlayerState};
+use crate::settings::{Action, GameSettings};
+use crate::taming::Bait;
+use crate::world::{WorldGrid, WORLD_TILE_SIZE};
+
+const THROW_DISTANCE: f32 = 70.0;
+const THROW_SECONDS: f32 = 0.6;
+const THROW_ARC_HEIGHT: f32 = 14.0;
+const THROWN_SIZE: f32 = 6.0;
+const ROCK_LANDING_LOUDNESS: f32 = 1.0;
+const FOOD_LANDING_LOUDNESS: f32 = 0.4;
+
+#[derive(Component)]
+struct Thrown {
+    kind: ItemKind,
+    start: Vec2,
+    end: Vec2,
+    elapsed: f32,
+}
+
+fn tile_at(pos: Vec2) -> (i32, i32) {
+    (
+        (pos.x / WORLD_TILE_SIZE).floor() as i32,
+        (pos.y / WORLD_TILE_SIZE).floor() as i32,
+    )
+}
+
+// Walks the throw line and stops just short of the first wall or water tile.
+fn landing_point(grid: &WorldGrid, start: Vec2, dir: Vec2) -> Vec2 {
+    let steps = THROW_DISTANCE.ceil() as i32;
+    let mut landing = start;
+    for step in 1..=steps {
+        let candidate = start + dir * step as f32 * WORLD_TILE_SIZE;
+        let (x, y) = tile_at(candidate);
+        if grid.is_solid(x, y) {
+            break;
+        }
+        landing = candidate;
+    }
+    landing
+}
+
+fn throw_item(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    death_state: Res<DeathRespawnState>,
+    grid: Res<WorldGrid>,
+    hotbar: Res<Hotbar>,
+    mut player_query: Query<(&Transform, &PlayerState, &mut Inventory), With<Player>>,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::Throw)) {
+        return;
+    }
+    let Ok((transform, state, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    let kind = match inventory.slot(hotbar.selected) {
+        Some((kind @ ItemKind::Food(_), _)) => {
+            inventory.remove_from_slot(hotbar.selected, 1);
+            kind
+        }
+        _ if inventory.remove(ItemKind::Stone, 1) => ItemKind::Stone,
+        _ => return,
+    };
+
+    let start = transform.translation.truncate();
+    let dir = facing_dir(state.facing).as_vec2().normalize();
+    let end = landing_point(&grid, start, dir);
+    commands.spawn((
+        Thrown {
+            kind,
+            start,
+            end,
+            elapsed: 0.0,
+        },
+        Sprite::from_color(kind.tint(), Vec2::splat(THROWN_SIZE)),
+        Transform::from_translation(start.extend(2.0)),
+    ));
+}
+
+// Thrown food becomes ground food that animals can smell; if the tile
+// already has some, it lands as a plain item instead.
+fn fly_thrown_items(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    catalog: Res<FoodCatalog>,
+    mut food_tracker: ResMut<FoodTracker>,
+    mut noises: MessageWriter<NoiseEvent>,
+    mut thrown_query: Query<(Entity, &mut Thrown, &mut Transform)>,
+) {
+    for (entity, mut thrown, mut transform) in &mut thrown_query {
+        thrown.elapsed += time.delta_secs();
+        let t = (thrown.elapsed / THROW_SECONDS).clamp(0.0, 1.0);
+        let ground = thrown.start.lerp(thrown.end, t);
+        // Fake the height of the lob by lifting the sprite on screen.
+        let lift = (t * std::f32::consts::PI).sin() * THROW_ARC_HEIGHT;
+        transform.translation.x = ground.x;
+        transform.translation.y = ground.y + lift;
+        transform.scale = Vec3::splat(1.0 + lift / THROW_ARC_HEIGHT * 0.5);
+
+        if t < 1.0 {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        let (x, y) = tile_at(thrown.end);
+        let location = Location2D { x, y };
+        let loudness = match thrown.kind {
+            ItemKind::Food(food) => {
+                let placed = catalog.get(food).and_then(|def| {
+                    place_food(&mut commands, &asset_server, &mut food_tracker, location, def)
+                });
+                match placed {
+                    Some(food_entity) => {
+                        commands.entity(food_entity).insert(Bait);
+                    }
+                    None => spawn_world_item(&mut commands, thrown.kind, location),
+                }
+                FOOD_LANDING_LOUDNESS
+            }
+            _ => {
+                spawn_world_item(&mut commands, thrown.kind, location);
+                ROCK_LANDING_LOUDNESS
+            }
+        };
+        noises.write(NoiseEvent {
+            position: thrown.end,
+            loudness,
+        });
+    }
+}
+
+pub struct ThrowPlugin;
+
+impl Plugin for ThrowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (throw_item, fly_thrown_items)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
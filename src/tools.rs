@@ -0,0 +1,234 @@
+// axe and pickaxe: facing-directional use against obstacles and walls
+use bevy::prelude::*;
+
+use crate::food::Location2D;
+use crate::hud::HudElement;
+use crate::items::{spawn_world_item, Inventory, ItemKind};
+use crate::light::facing_dir;
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::noise::NoiseEvent;
+use crate::obstacle::{Obstacle, ObstacleKind};
+use crate::player::{DeathRespawnState, Player, PlayerState, StaminaMessages, StaminaUse, Stats};
+use crate::progress::PersistentProgress;
+use crate::settings::{key_label, Action, GameSettings};
+use crate::world::{dig_walls, TilesChanged, WorldChunks, WorldGrid, WORLD_TILE_SIZE};
+
+const TOOL_REACH: f32 = 18.0;
+const TOOL_HIT_SLACK: f32 = 4.0;
+const TOOL_STAMINA_COST: f32 = 6.0;
+const DIG_RADIUS: i32 = 3;
+const WOOD_PER_TREE: u32 = 3;
+const FIBER_PER_TREE: u32 = 1;
+const STONE_PER_ROCK: u32 = 2;
+const STONE_PER_DIG: u32 = 1;
+const CHOP_LOUDNESS: f32 = 0.8;
+const DIG_LOUDNESS: f32 = 1.2;
+const TOOLS: [ItemKind; 2] = [ItemKind::Axe, ItemKind::Pickaxe];
+const HUD_MATERIALS: [ItemKind; 3] = [ItemKind::Wood, ItemKind::Stone, ItemKind::Fiber];
+
+// Only ever holds a tool that's in the inventory; it's emptied when the
+// tool is dropped or traded away.
+#[derive(Component, Default)]
+pub struct ToolBelt {
+    pub equipped: Option<ItemKind>,
+}
+
+#[derive(Component)]
+struct ToolHudText;
+
+fn setup_tool_hud(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: px(16.0),
+            top: px(16.0),
+            ..default()
+        },
+        Text::new(""),
+        TextFont::from_font_size(18.0),
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        HudElement::default(),
+        ToolHudText,
+    ));
+}
+
+// Steps through the tools that are carried.
+fn switch_tool(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    mut query: Query<(&mut ToolBelt, &Inventory), With<Player>>,
+) {
+    if !input.just_pressed(settings.key(Action::SwitchTool)) {
+        return;
+    }
+    let Ok((mut belt, inventory)) = query.single_mut() else {
+        return;
+    };
+    let carried: Vec<ItemKind> = TOOLS
+        .into_iter()
+        .filter(|tool| inventory.count(*tool) > 0)
+        .collect();
+    let current = belt
+        .equipped
+        .and_then(|tool| carried.iter().position(|carried| *carried == tool));
+    belt.equipped = match current {
+        Some(index) => Some(carried[(index + 1) % carried.len()]),
+        None => carried.first().copied(),
+    };
+}
+
+fn drop_missing_tool(mut query: Query<(&mut ToolBelt, &Inventory), With<Player>>) {
+    for (mut belt, inventory) in &mut query {
+        if let Some(tool) = belt.equipped
+            && inventory.count(tool) == 0
+        {
+            belt.equipped = None;
+        }
+    }
+}
+
+// Whatever doesn't fit in the inventory falls where the tool struck.
+fn harvest(
+    commands: &mut Commands,
+    inventory: &mut Inventory,
+    kind: ItemKind,
+    amount: u32,
+    tile: Location2D,
+) {
+    let left_over = inventory.add_within_limit(kind, amount);
+    for _ in 0..left_over {
+        spawn_world_item(commands, kind, tile);
+    }
+}
+
+fn use_tool(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    death_state: Res<DeathRespawnState>,
+    mut grid: ResMut<WorldGrid>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    chunks: Res<WorldChunks>,
+    mut tiles_changed: MessageWriter<TilesChanged>,
+    mut noises: MessageWriter<NoiseEvent>,
+    mut stamina_messages: StaminaMessages,
+    mut player_query: Query<
+        (Entity, &Transform, &PlayerState, &ToolBelt, &mut Inventory, &mut Stats),
+        With<Player>,
+    >,
+    obstacle_query: Query<(Entity, &Obstacle, &Transform), Without<Player>>,
+) {
+    if death_state.is_dead || !input.just_pressed(settings.key(Action::UseTool)) {
+        return;
+    }
+    let Ok((player, transform, state, belt, mut inventory, mut stats)) = player_query.single_mut() else {
+        return;
+    };
+
+    let dir = facing_dir(state.facing).as_vec2().normalize();
+    let target = transform.translation.truncate() + dir * TOOL_REACH * WORLD_TILE_SIZE;
+    let wanted = match belt.equipped {
+        Some(ItemKind::Axe) => ObstacleKind::Tree,
+        Some(ItemKind::Pickaxe) => ObstacleKind::Rock,
+        _ => return,
+    };
+    // Every swing costs stamina, whether or not it connects.
+    if !stats.try_spend_stamina(player, TOOL_STAMINA_COST, StaminaUse::Tool, &mut stamina_messages) {
+        return;
+    }
+
+    let hit = obstacle_query
+        .iter()
+        .filter(|(_, obstacle, _)| obstacle.kind == wanted)
+        .map(|(entity, obstacle, obstacle_transform)| {
+            let dist = obstacle_transform.translation.truncate().distance(target);
+            (entity, obstacle, dist)
+        })
+        .filter(|(_, obstacle, dist)| *dist <= obstacle.radius + TOOL_HIT_SLACK)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+
+    let target_tile = Location2D {
+        x: (target.x / WORLD_TILE_SIZE).floor() as i32,
+        y: (target.y / WORLD_TILE_SIZE).floor() as i32,
+    };
+
+    if let Some((entity, obstacle, _)) = hit {
+        noises.write(NoiseEvent {
+            position: target,
+            loudness: CHOP_LOUDNESS,
+        });
+        commands.entity(entity).despawn();
+        match obstacle.kind {
+            ObstacleKind::Tree => {
+                harvest(&mut commands, &mut inventory, ItemKind::Wood, WOOD_PER_TREE, target_tile);
+                harvest(&mut commands, &mut inventory, ItemKind::Fiber, FIBER_PER_TREE, target_tile);
+            }
+            ObstacleKind::Rock => {
+                harvest(&mut commands, &mut inventory, ItemKind::Stone, STONE_PER_ROCK, target_tile)
+            }
+        }
+    } else if belt.equipped == Some(ItemKind::Pickaxe) {
+        let (tile_x, tile_y) = (target_tile.x, target_tile.y);
+        let removed = dig_walls(&mut grid, &mut meshes, &chunks, tile_x, tile_y, DIG_RADIUS);
+        if removed > 0 {
+            noises.write(NoiseEvent {
+                position: target,
+                loudness: DIG_LOUDNESS,
+            });
+            tiles_changed.write(TilesChanged {
+                min: IVec2::new(tile_x - DIG_RADIUS, tile_y - DIG_RADIUS),
+                max: IVec2::new(tile_x + DIG_RADIUS, tile_y + DIG_RADIUS),
+            });
+            harvest(&mut commands, &mut inventory, ItemKind::Stone, STONE_PER_DIG, target_tile);
+        }
+    }
+}
+
+fn update_tool_hud(
+    settings: Res<GameSettings>,
+    locale: Res<Locale>,
+    progress: Res<PersistentProgress>,
+    player_query: Query<(&ToolBelt, &Inventory), With<Player>>,
+    mut text_query: Query<&mut Text, With<ToolHudText>>,
+) {
+    let Ok((belt, inventory)) = player_query.single() else {
+        return;
+    };
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let tool = match belt.equipped {
+        Some(tool) => tool.display_name(&progress, &locale),
+        None => locale.get("tools.none").to_string(),
+    };
+    let materials: Vec<String> = HUD_MATERIALS
+        .into_iter()
+        .map(|kind| format!("{} {}", kind.display_name(&progress, &locale), inventory.count(kind)))
+        .collect();
+    let line = locale.format(
+        "tools.hud",
+        &[
+            ("tool", &tool),
+            ("key", &key_label(settings.key(Action::SwitchTool))),
+            ("materials", &materials.join("  ")),
+        ],
+    );
+    if text.0 != line {
+        text.0 = line;
+    }
+}
+
+pub struct ToolPlugin;
+
+impl Plugin for ToolPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_tool_hud)
+            .add_systems(
+                Update,
+                (drop_missing_tool, switch_tool, use_tool, update_tool_hud)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,298 @@
+// tooltips: a small panel beside the cursor for hovered UI, under a widget
+// picked with the keyboard, or pinned above the item the player is standing
+// next to. UI tooltips wait a moment before showing, long text wraps, and
+// the panel is kept on screen.
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::food::{
+    Food, FoodQuality, FoodStats, Freshness, Location2D, FOOD_PICKUP_RADIUS_TILES,
+};
+use crate::food_catalog::{payload_lines, FoodCatalog};
+use crate::hud::HudVisibility;
+use crate::items::{WorldItem, ITEM_PICKUP_RADIUS};
+use crate::locale::Locale;
+use crate::player::{DeathRespawnState, Player};
+use crate::progress::PersistentProgress;
+use crate::world::WORLD_TILE_SIZE;
+use crate::MainCamera;
+
+const CURSOR_OFFSET: Vec2 = Vec2::new(16.0, 12.0);
+const WORLD_OFFSET: Vec2 = Vec2::new(10.0, -28.0);
+const ANCHOR_GAP: f32 = 4.0;
+const DEFAULT_DELAY_SECONDS: f32 = 0.35;
+const DEFAULT_MAX_WIDTH: f32 = 240.0;
+
+// Shown beside the cursor while the node (which needs an Interaction, e.g.
+// a Button) is hovered. Empty text shows nothing.
+#[derive(Component, Default, Clone)]
+pub struct Tooltip(pub String);
+
+// Shows the node's tooltip just under it (or above, near the bottom of the
+// screen) instead of by the cursor, and while focused as well as on hover.
+// Screens driven by keys set focused on whatever is selected.
+#[derive(Component, Default, Clone, Copy)]
+pub struct TooltipAnchor {
+    pub focused: bool,
+}
+
+// A tooltip pinned to a point in the world. Hovered UI wins when both are
+// set.
+#[derive(Resource, Default)]
+pub struct WorldTooltip {
+    pub text: Option<String>,
+    pub position: Vec2,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct TooltipConfig {
+    pub delay_seconds: f32,
+    pub max_width: f32,
+}
+
+// The node being waited on and for how long.
+#[derive(Default)]
+struct TooltipHover {
+    target: Option<Entity>,
+    elapsed: f32,
+}
+
+#[derive(Component)]
+struct TooltipPanel;
+
+#[derive(Component)]
+struct TooltipText;
+
+fn setup_tooltip(mut commands: Commands, config: Res<TooltipConfig>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::axes(px(8.0), px(5.0)),
+                border: UiRect::all(px(1.0)),
+                max_width: px(config.max_width),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.06, 0.06, 0.07, 0.92)),
+            BorderColor::all(Color::srgb(0.35, 0.35, 0.38)),
+            GlobalZIndex(200),
+            Visibility::Hidden,
+            TooltipPanel,
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont::from_font_size(14.0),
+            TextColor(Color::srgb(0.92, 0.92, 0.88)),
+            // Words too long for a line are broken rather than spilling out.
+            TextLayout::new_with_linebreak(LineBreak::WordOrCharacter),
+            TooltipText,
+        ));
+}
+
+// Describes whichever food or dropped item within pickup reach is closest;
+// the interact prompt says how to pick it up. It's part of the HUD, so it
+// goes when the HUD is hidden.
+fn nearby_item_tooltip(
+    death_state: Res<DeathRespawnState>,
+    hud: Res<HudVisibility>,
+    catalog: Res<FoodCatalog>,
+    progress: Res<PersistentProgress>,
+    locale: Res<Locale>,
+    mut tooltip: ResMut<WorldTooltip>,
+    player_query: Query<&Transform, With<Player>>,
+    food_query: Query<
+        (&FoodStats, &FoodQuality, &Freshness, &Location2D, &Visibility),
+        With<Food>,
+    >,
+    item_query: Query<(&WorldItem, &Transform, &Visibility), Without<Player>>,
+) {
+    tooltip.text = None;
+    if death_state.is_dead || !hud.shown {
+        return;
+    }
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let mut closest: Option<(f32, Vec2, String)> = None;
+    let mut consider = |distance: f32, position: Vec2, text: &dyn Fn() -> String| {
+        if closest.as_ref().is_none_or(|(best, _, _)| distance < *best) {
+            closest = Some((distance, position, text()));
+        }
+    };
+
+    let food_reach = FOOD_PICKUP_RADIUS_TILES as f32 * WORLD_TILE_SIZE;
+    for (food, quality, freshness, location, visibility) in &food_query {
+        if *visibility != Visibility::Visible {
+            continue;
+        }
+        let position = Vec2::new(location.x as f32, location.y as f32) * WORLD_TILE_SIZE;
+        let distance = position.distance(player_pos);
+        if distance > food_reach {
+            continue;
+        }
+        consider(distance, position, &|| {
+            let mut lines = vec![progress.food_name(food.food).to_string()];
+            if progress.identifies(food.food) || freshness.is_rotten() {
+                lines.extend(payload_lines(
+                    &locale,
+                    food.food_bar_regen,
+                    food.health_regen,
+                    food.stamina_regen,
+                    food.effect,
+                ));
+            }
+            if *quality != FoodQuality::Normal {
+                let quality_key = format!("quality.{}", quality.name());
+                let quality = locale.get(&quality_key);
+                lines.push(locale.format("tooltip.quality", &[("quality", quality)]));
+            }
+            lines.push(locale.get(freshness.describe_key()).to_string());
+            lines.join("\n")
+        });
+    }
+    for (item, transform, visibility) in &item_query {
+        if *visibility != Visibility::Visible {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let distance = position.distance(player_pos);
+        if distance > ITEM_PICKUP_RADIUS * WORLD_TILE_SIZE {
+            continue;
+        }
+        consider(distance, position, &|| item.kind.describe(&catalog, &progress, &locale));
+    }
+
+    if let Some((_, position, text)) = closest {
+        tooltip.text = Some(text);
+        tooltip.position = position;
+    }
+}
+
+// Hovered UI comes first, then a focused anchor, then the world tooltip.
+// Positions are worked out in UI pixels, which UiScale stretches.
+fn show_tooltip(
+    time: Res<Time<Real>>,
+    config: Res<TooltipConfig>,
+    ui_scale: Res<UiScale>,
+    world_tooltip: Res<WorldTooltip>,
+    mut hover: Local<TooltipHover>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    target_query: Query<(
+        Entity,
+        &Tooltip,
+        &InheritedVisibility,
+        Option<&Interaction>,
+        Option<&TooltipAnchor>,
+        &ComputedNode,
+        &UiGlobalTransform,
+    )>,
+    mut panel_query: Query<(&mut Node, &mut Visibility, &ComputedNode), With<TooltipPanel>>,
+    mut text_query: Query<&mut Text, With<TooltipText>>,
+) {
+    let Ok((mut node, mut visibility, panel)) = panel_query.single_mut() else {
+        return;
+    };
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    let scale = ui_scale.0.max(f32::EPSILON);
+    let screen = Vec2::new(window.width(), window.height()) / scale;
+    let cursor = window.cursor_position().map(|cursor| cursor / scale);
+
+    let candidates: Vec<_> = target_query
+        .iter()
+        .filter(|(_, tooltip, shown, ..)| shown.get() && !tooltip.0.is_empty())
+        .collect();
+    let hovered = candidates.iter().find(|(_, _, _, interaction, ..)| {
+        interaction.is_some_and(|interaction| *interaction != Interaction::None)
+    });
+    let focused = candidates
+        .iter()
+        .find(|(_, _, _, _, anchor, ..)| anchor.is_some_and(|anchor| anchor.focused));
+    let target = hovered.or(focused).copied();
+
+    if hover.target != target.map(|(entity, ..)| entity) {
+        hover.target = target.map(|(entity, ..)| entity);
+        hover.elapsed = 0.0;
+    } else {
+        hover.elapsed += time.delta_secs();
+    }
+    let waiting = hover.elapsed < config.delay_seconds;
+
+    let panel_size = panel.size() * panel.inverse_scale_factor();
+    let placed = match target {
+        Some(_) if waiting => None,
+        Some((_, tooltip, _, _, Some(_), widget, transform)) => {
+            let size = widget.size() * widget.inverse_scale_factor();
+            let center = transform.translation * widget.inverse_scale_factor();
+            let below = center.y + size.y * 0.5 + ANCHOR_GAP;
+            let top = if below + panel_size.y > screen.y {
+                center.y - size.y * 0.5 - ANCHOR_GAP - panel_size.y
+            } else {
+                below
+            };
+            Some((tooltip.0.clone(), Vec2::new(center.x - size.x * 0.5, top)))
+        }
+        Some((_, tooltip, ..)) => cursor.map(|cursor| (tooltip.0.clone(), cursor + CURSOR_OFFSET)),
+        None => world_tooltip.text.clone().and_then(|text| {
+            let (camera, camera_transform) = camera_query.single().ok()?;
+            let point = world_tooltip.position.extend(0.0);
+            let viewport = camera.world_to_viewport(camera_transform, point).ok()?;
+            Some((text, viewport / scale + WORLD_OFFSET))
+        }),
+    };
+    let Some((text, position)) = placed else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    let position = position.min(screen - panel_size).max(Vec2::ZERO);
+    visibility.set_if_neq(Visibility::Visible);
+    node.left = px(position.x);
+    node.top = px(position.y);
+    if let Ok(mut panel_text) = text_query.single_mut()
+        && panel_text.0 != text
+    {
+        panel_text.0 = text;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TooltipPlugin {
+    pub delay_seconds: f32,
+    pub max_width: f32,
+}
+
+impl Default for TooltipPlugin {
+    fn default() -> Self {
+        Self {
+            delay_seconds: DEFAULT_DELAY_SECONDS,
+            max_width: DEFAULT_MAX_WIDTH,
+        }
+    }
+}
+
+impl TooltipPlugin {
+    pub fn with_delay(mut self, seconds: f32) -> Self {
+        self.delay_seconds = seconds.max(0.0);
+        self
+    }
+
+    pub fn with_max_width(mut self, width: f32) -> Self {
+        self.max_width = width.max(40.0);
+        self
+    }
+}
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WorldTooltip::default())
+            .insert_resource(TooltipConfig {
+                delay_seconds: self.delay_seconds,
+                max_width: self.max_width,
+            })
+            .add_systems(Startup, setup_tooltip)
+            .add_systems(Update, (nearby_item_tooltip, show_tooltip).chain());
+    }
+}
@@ -0,0 +1,330 @@
+// a trader who sits at the camp and swaps gathered wood, stone and fiber for
+// food and tools. Offers come from trades.ron in the same shape as recipes:
+// the inputs are what the trader asks for, the output what they hand over.
+// E next to the trader opens the trade screen.
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+use crate::crafting::{Recipe, RecipeBook};
+use crate::event_log::EventLog;
+use crate::faction::Faction;
+use crate::food::Location2D;
+use crate::food_catalog::FoodCatalog;
+use crate::interact::Interactable;
+use crate::items::{spawn_world_item, Inventory};
+use crate::landmark::Camp;
+use crate::locale::{Locale, LocalizedText};
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, Player};
+use crate::progress::PersistentProgress;
+use crate::settings::{Action, GameSettings};
+use crate::world::WORLD_TILE_SIZE;
+
+pub const DEFAULT_TRADES_FILE: &str = "assets/trades.ron";
+const TRADER_SIZE: f32 = 8.0;
+const TRADER_OFFSET: Vec3 = Vec3::new(8.0, 0.0, 0.1);
+const TRADER_TINT: Color = Color::srgb(0.85, 0.7, 0.45);
+const TRADE_RADIUS: f32 = 16.0;
+const AFFORDABLE_COLOR: Color = Color::srgb(0.3, 0.34, 0.3);
+const UNMET_COLOR: Color = Color::srgb(0.18, 0.18, 0.19);
+const AFFORDABLE_TEXT: Color = Color::srgb(0.92, 0.92, 0.9);
+const UNMET_TEXT: Color = Color::srgb(0.45, 0.45, 0.45);
+
+#[derive(Component)]
+pub struct Trader;
+
+// Trades read exactly like recipes, so they share the recipe loader.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct TradeOffers(pub RecipeBook);
+
+#[derive(Resource, Clone, Debug)]
+pub struct TradeConfig {
+    pub trades_path: PathBuf,
+}
+
+#[derive(Resource, Default)]
+pub struct TradeScreen {
+    pub open: bool,
+}
+
+#[derive(Component)]
+struct TradeOverlay;
+
+#[derive(Component)]
+struct TradeButton(usize);
+
+#[derive(Component)]
+struct TradeLabel(usize);
+
+fn offer_label(offer: &Recipe, progress: &PersistentProgress, locale: &Locale) -> String {
+    let asks: Vec<String> = offer
+        .inputs
+        .iter()
+        .map(|(kind, amount)| format!("{} {amount}", kind.display_name(progress, locale)))
+        .collect();
+    locale.format(
+        "trade.offer",
+        &[
+            ("item", &offer.output.display_name(progress, locale)),
+            ("amount", &offer.amount.to_string()),
+            ("price", &asks.join(", ")),
+        ],
+    )
+}
+
+fn load_trade_offers(mut commands: Commands, config: Res<TradeConfig>, catalog: Res<FoodCatalog>) {
+    let book = match RecipeBook::load(&config.trades_path, &catalog) {
+        Ok(book) => book,
+        Err(err) => {
+            warn!("no trades from {}: {err}", config.trades_path.display());
+            RecipeBook::default()
+        }
+    };
+    commands.insert_resource(TradeOffers(book));
+}
+
+// The trader stands beside the camp's fire as its child, so it goes when
+// the camp does.
+fn spawn_traders(mut commands: Commands, camp_query: Query<Entity, Added<Camp>>) {
+    for camp in &camp_query {
+        commands.entity(camp).with_child((
+            Trader,
+            Interactable::new("interact.trade", TRADE_RADIUS * WORLD_TILE_SIZE),
+            Faction::Npc,
+            Sprite::from_color(TRADER_TINT, Vec2::splat(TRADER_SIZE)),
+            Transform::from_translation(TRADER_OFFSET),
+        ));
+    }
+}
+
+fn setup_trade_screen(mut commands: Commands, offers: Res<TradeOffers>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                top: px(0.0),
+                width: percent(100.0),
+                height: percent(100.0),
+                display: Display::Flex,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            GlobalZIndex(150),
+            Visibility::Hidden,
+            TradeOverlay,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(px(16.0)),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        row_gap: px(6.0),
+                        min_width: px(280.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.12, 0.12, 0.13)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(24.0),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        LocalizedText("trade.title"),
+                    ));
+                    if offers.0.iter().next().is_none() {
+                        panel.spawn((
+                            Text::new(""),
+                            TextFont::from_font_size(16.0),
+                            TextColor(UNMET_TEXT),
+                            LocalizedText("trade.empty"),
+                        ));
+                    }
+                    for index in 0..offers.0.iter().count() {
+                        panel
+                            .spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(px(10.0), px(6.0)),
+                                    ..default()
+                                },
+                                BackgroundColor(UNMET_COLOR),
+                                TradeButton(index),
+                            ))
+                            .with_child((
+                                Text::new(""),
+                                TextFont::from_font_size(16.0),
+                                TextColor(UNMET_TEXT),
+                                TradeLabel(index),
+                            ));
+                    }
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(14.0),
+                        TextColor(Color::srgb(0.55, 0.55, 0.55)),
+                        LocalizedText("trade.hint"),
+                    ));
+                });
+        });
+}
+
+// Opens next to a trader and closes on E, Esc or walking off.
+fn toggle_trade_screen(
+    input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
+    death_state: Res<DeathRespawnState>,
+    mut screen: ResMut<TradeScreen>,
+    player_query: Query<&Transform, With<Player>>,
+    trader_query: Query<&GlobalTransform, With<Trader>>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let near_trader = trader_query.iter().any(|transform| {
+        transform.translation().truncate().distance(player_pos) <= TRADE_RADIUS * WORLD_TILE_SIZE
+    });
+    if death_state.is_dead || !near_trader {
+        screen.open = false;
+        return;
+    }
+    if input.just_pressed(settings.key(Action::Interact)) {
+        screen.open = !screen.open;
+    } else if screen.open && input.just_pressed(KeyCode::Escape) {
+        screen.open = false;
+    }
+}
+
+// Like crafting, whatever doesn't fit is dropped at the player's feet.
+fn trade(
+    mut commands: Commands,
+    time: Res<Time>,
+    screen: Res<TradeScreen>,
+    offers: Res<TradeOffers>,
+    progress: Res<PersistentProgress>,
+    locale: Res<Locale>,
+    mut event_log: ResMut<EventLog>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    button_query: Query<(&Interaction, &TradeButton), Changed<Interaction>>,
+) {
+    if !screen.open {
+        return;
+    }
+    let Ok((transform, mut inventory)) = player_query.single_mut() else {
+        return;
+    };
+    for (interaction, button) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(offer) = offers.0.get(button.0) else {
+            continue;
+        };
+        let name = offer.output.display_name(&progress, &locale).to_lowercase();
+        if !offer.can_craft(&inventory) {
+            event_log.push(&time, format!("the trader wants more for {name}"));
+            continue;
+        }
+        for (kind, amount) in &offer.inputs {
+            inventory.remove(*kind, *amount);
+        }
+        let left_over = inventory.add_within_limit(offer.output, offer.amount);
+        let tile = (transform.translation.truncate() / WORLD_TILE_SIZE).floor().as_ivec2();
+        for _ in 0..left_over {
+            spawn_world_item(&mut commands, offer.output, Location2D { x: tile.x, y: tile.y });
+        }
+        event_log.push(&time, format!("traded for {name} x{}", offer.amount));
+    }
+}
+
+// Offers the player can't afford are greyed out.
+fn update_trade_screen(
+    screen: Res<TradeScreen>,
+    offers: Res<TradeOffers>,
+    progress: Res<PersistentProgress>,
+    locale: Res<Locale>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut overlay_query: Query<&mut Visibility, With<TradeOverlay>>,
+    mut button_query: Query<(&TradeButton, &mut BackgroundColor)>,
+    mut label_query: Query<(&TradeLabel, &mut Text, &mut TextColor)>,
+) {
+    if let Ok(mut visibility) = overlay_query.single_mut() {
+        visibility.set_if_neq(if screen.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !screen.open {
+        return;
+    }
+    let Ok(inventory) = player_query.single() else {
+        return;
+    };
+    let affordable =
+        |index: usize| offers.0.get(index).is_some_and(|offer| offer.can_craft(inventory));
+    for (button, mut color) in &mut button_query {
+        let fill = if affordable(button.0) {
+            AFFORDABLE_COLOR
+        } else {
+            UNMET_COLOR
+        };
+        color.set_if_neq(BackgroundColor(fill));
+    }
+    for (label, mut text, mut color) in &mut label_query {
+        let line = offers
+            .0
+            .get(label.0)
+            .map(|offer| offer_label(offer, &progress, &locale))
+            .unwrap_or_default();
+        if text.0 != line {
+            text.0 = line;
+        }
+        let fill = if affordable(label.0) {
+            AFFORDABLE_TEXT
+        } else {
+            UNMET_TEXT
+        };
+        color.set_if_neq(TextColor(fill));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TraderPlugin {
+    pub trades_path: PathBuf,
+}
+
+impl Default for TraderPlugin {
+    fn default() -> Self {
+        Self {
+            trades_path: PathBuf::from(DEFAULT_TRADES_FILE),
+        }
+    }
+}
+
+impl TraderPlugin {
+    pub fn with_trades_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.trades_path = path.into();
+        self
+    }
+}
+
+impl Plugin for TraderPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TradeConfig {
+            trades_path: self.trades_path.clone(),
+        })
+        .insert_resource(TradeScreen::default())
+        .add_systems(Startup, (load_trade_offers, setup_trade_screen).chain())
+        .add_systems(
+            Update,
+            (spawn_traders, toggle_trade_screen, trade, update_trade_screen)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
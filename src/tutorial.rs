@@ -0,0 +1,242 @@
+// tutorial hints for a first game, one at a time and in order: how to move,
+// what the lantern does, picking food up and eating it. Each waits until
+// it's relevant, e.g. eating until the player is hungry with food on them,
+// and goes once the player has done the thing; doing it before the hint
+// comes up counts too. Finished hints are kept in the settings file, so they
+// don't come back in later games.
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::food::{EatFood, Food};
+use crate::hud::HudElement;
+use crate::interact::Interactable;
+use crate::items::{Inventory, ItemKind};
+use crate::locale::Locale;
+use crate::menu::AppState;
+use crate::player::{DeathRespawnState, MovementTracker, Player, Stats};
+use crate::settings::{key_label, Action, GameSettings, SettingsConfig};
+
+// The lantern hint has nothing to do, so it's up for this long.
+const LIGHT_HINT_SECONDS: f32 = 8.0;
+// The eating hint waits for the food bar to drop this low.
+const HUNGRY_FOOD_BAR: f32 = 50.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TutorialHint {
+    Move,
+    Light,
+    PickUp,
+    Eat,
+}
+
+impl TutorialHint {
+    const ALL: [TutorialHint; 4] = [
+        TutorialHint::Move,
+        TutorialHint::Light,
+        TutorialHint::PickUp,
+        TutorialHint::Eat,
+    ];
+
+    fn text_key(self) -> &'static str {
+        match self {
+            TutorialHint::Move => "tutorial.move",
+            TutorialHint::Light => "tutorial.light",
+            TutorialHint::PickUp => "tutorial.pick_up",
+            TutorialHint::Eat => "tutorial.eat",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct TutorialState {
+    showing: Option<TutorialHint>,
+    shown_for: f32,
+    // Food carried last frame: eating from the inventory or hotbar takes one
+    // away and picking some up adds one.
+    carried_food: Option<u32>,
+}
+
+#[derive(Component)]
+struct TutorialPanel;
+
+#[derive(Component)]
+struct TutorialText;
+
+fn carried_food(inventory: &Inventory) -> u32 {
+    inventory
+        .slots()
+        .iter()
+        .filter(|(kind, _)| matches!(kind, ItemKind::Food(_)))
+        .map(|(_, count)| count)
+        .sum()
+}
+
+// Above the narration line, which sits just over the hotbar.
+fn setup_tutorial(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: px(0.0),
+                bottom: px(104.0),
+                width: percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            HudElement::default(),
+            TutorialPanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::axes(px(10.0), px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.06, 0.06, 0.07, 0.8)),
+                ))
+                .with_child((
+                    Text::new(""),
+                    TextFont::from_font_size(16.0),
+                    TextColor(Color::srgb(0.95, 0.9, 0.7)),
+                    TextLayout::new_with_justify(Justify::Center),
+                    TutorialText,
+                ));
+        });
+}
+
+// Runs after Update, so a meal and the food it took out of the inventory
+// turn up in the same frame.
+fn track_tutorial_progress(
+    time: Res<Time>,
+    config: Res<SettingsConfig>,
+    mut settings: ResMut<GameSettings>,
+    mut tutorial: ResMut<TutorialState>,
+    mut meals: MessageReader<EatFood>,
+    player_query: Query<(&MovementTracker, &Inventory), With<Player>>,
+) {
+    let ate = meals.read().count() > 0;
+    let Ok((movement, inventory)) = player_query.single() else {
+        tutorial.carried_food = None;
+        return;
+    };
+    let carried = carried_food(inventory);
+    let previous = tutorial.carried_food.replace(carried);
+
+    let mut done = Vec::new();
+    if movement.is_moving {
+        done.push(TutorialHint::Move);
+    }
+    if let Some(previous) = previous {
+        if carried > previous {
+            done.push(TutorialHint::PickUp);
+        }
+        if ate && carried < previous {
+            done.push(TutorialHint::Eat);
+        }
+    }
+    if tutorial.showing.is_some() {
+        tutorial.shown_for += time.delta_secs();
+    }
+    if tutorial.showing == Some(TutorialHint::Light) && tutorial.shown_for >= LIGHT_HINT_SECONDS {
+        done.push(TutorialHint::Light);
+    }
+
+    done.retain(|hint| !settings.tutorial_done.contains(hint));
+    if done.is_empty() {
+        return;
+    }
+    if tutorial.showing.is_some_and(|hint| done.contains(&hint)) {
+        tutorial.showing = None;
+    }
+    settings.tutorial_done.extend(done);
+    if let Err(err) = settings.save(&config.settings_path) {
+        warn!("could not save settings to {}: {err}", config.settings_path.display());
+    }
+}
+
+// Only the first unfinished hint is ever up, once whatever it's about is at
+// hand.
+fn show_tutorial_hint(
+    state: Res<State<AppState>>,
+    death_state: Res<DeathRespawnState>,
+    (locale, settings): (Res<Locale>, Res<GameSettings>),
+    mut tutorial: ResMut<TutorialState>,
+    player_query: Query<(&Transform, &Stats, &Inventory), With<Player>>,
+    food_query: Query<(&Interactable, &GlobalTransform, &InheritedVisibility), With<Food>>,
+    mut panel_query: Query<&mut Visibility, With<TutorialPanel>>,
+    mut text_query: Query<&mut Text, With<TutorialText>>,
+) {
+    let Ok(mut visibility) = panel_query.single_mut() else {
+        return;
+    };
+    let next = TutorialHint::ALL
+        .into_iter()
+        .find(|hint| !settings.tutorial_done.contains(hint));
+    let player = player_query.single().ok();
+    let at_hand = |hint: TutorialHint| {
+        let Some((transform, stats, inventory)) = player else {
+            return false;
+        };
+        match hint {
+            TutorialHint::Move | TutorialHint::Light => true,
+            TutorialHint::PickUp => {
+                let player_pos = transform.translation.truncate();
+                food_query.iter().any(|(interactable, food, shown)| {
+                    shown.get()
+                        && food.translation().truncate().distance(player_pos) <= interactable.reach
+                })
+            }
+            TutorialHint::Eat => stats.food_bar < HUNGRY_FOOD_BAR && carried_food(inventory) > 0,
+        }
+    };
+    let playing = *state.get() == AppState::Playing && !death_state.is_dead;
+    if tutorial.showing != next && playing && next.is_some_and(at_hand) {
+        tutorial.showing = next;
+        tutorial.shown_for = 0.0;
+    }
+    let Some(hint) = tutorial.showing.filter(|_| playing) else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Visible);
+
+    let key = |action| key_label(settings.key(action));
+    let line = match hint {
+        TutorialHint::Move => locale.format(
+            hint.text_key(),
+            &[
+                ("up", &key(Action::MoveUp)),
+                ("left", &key(Action::MoveLeft)),
+                ("down", &key(Action::MoveDown)),
+                ("right", &key(Action::MoveRight)),
+            ],
+        ),
+        TutorialHint::Light => locale.get(hint.text_key()).to_string(),
+        TutorialHint::PickUp => locale.format(hint.text_key(), &[("key", &key(Action::Interact))]),
+        TutorialHint::Eat => locale.format(
+            hint.text_key(),
+            &[("use", &key(Action::UseItem)), ("key", &key(Action::Inventory))],
+        ),
+    };
+    if let Ok(mut text) = text_query.single_mut()
+        && text.0 != line
+    {
+        text.0 = line;
+    }
+}
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TutorialState>()
+            .add_systems(Startup, setup_tutorial)
+            .add_systems(Update, show_tutorial_hint)
+            .add_systems(
+                PostUpdate,
+                track_tutorial_progress.run_if(in_state(AppState::Playing)),
+            );
+    }
+}
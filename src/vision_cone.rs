@@ -0,0 +1,104 @@
+// translucent overlays of enemy vision cones, so the player can see where
+// not to step. Each cone is a child of its enemy, showing only when the
+// enemy itself is lit; it shrinks while the player sneaks and turns red once
+// the enemy gives chase.
+use bevy::prelude::*;
+use bevy::sprite_render::AlphaMode2d;
+
+use crate::enemy::{Enemy, SNEAK_VISION_FACTOR, VISION_ANGLE_DEGREES, VISION_RANGE};
+use crate::player::{Player, Sneak};
+use crate::world::WORLD_TILE_SIZE;
+
+// Under the enemy's sprite, over the ground and footprints.
+const CONE_Z: f32 = -0.45;
+const WATCHING_COLOR: Color = Color::srgba(0.95, 0.85, 0.4, 0.12);
+const CHASING_COLOR: Color = Color::srgba(0.95, 0.25, 0.2, 0.18);
+
+#[derive(Component)]
+struct VisionCone;
+
+#[derive(Resource)]
+struct VisionConeAssets {
+    mesh: Handle<Mesh>,
+    watching: Handle<ColorMaterial>,
+    chasing: Handle<ColorMaterial>,
+}
+
+fn setup_vision_cones(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let half_angle = (VISION_ANGLE_DEGREES * 0.5).to_radians();
+    let mesh = meshes.add(CircularSector::new(VISION_RANGE * WORLD_TILE_SIZE, half_angle));
+    let mut material = |color: Color| {
+        materials.add(ColorMaterial {
+            color,
+            alpha_mode: AlphaMode2d::Blend,
+            ..default()
+        })
+    };
+    let watching = material(WATCHING_COLOR);
+    let chasing = material(CHASING_COLOR);
+    commands.insert_resource(VisionConeAssets {
+        mesh,
+        watching,
+        chasing,
+    });
+}
+
+fn attach_vision_cones(
+    mut commands: Commands,
+    assets: Res<VisionConeAssets>,
+    enemy_query: Query<Entity, Added<Enemy>>,
+) {
+    for enemy in &enemy_query {
+        commands.entity(enemy).with_child((
+            VisionCone,
+            Mesh2d(assets.mesh.clone()),
+            MeshMaterial2d(assets.watching.clone()),
+            Transform::from_xyz(0.0, 0.0, CONE_Z),
+        ));
+    }
+}
+
+// The sector mesh points up, so it's turned a quarter less than the facing.
+fn update_vision_cones(
+    assets: Res<VisionConeAssets>,
+    player_query: Query<&Sneak, With<Player>>,
+    enemy_query: Query<(&Enemy, &Children)>,
+    mut cone_query: Query<(&mut Transform, &mut MeshMaterial2d<ColorMaterial>), With<VisionCone>>,
+) {
+    let scale = if player_query.single().is_ok_and(|sneak| sneak.active) {
+        SNEAK_VISION_FACTOR
+    } else {
+        1.0
+    };
+    for (enemy, children) in &enemy_query {
+        for child in children.iter() {
+            let Ok((mut transform, mut material)) = cone_query.get_mut(child) else {
+                continue;
+            };
+            transform.rotation =
+                Quat::from_rotation_z(enemy.facing.to_angle() - std::f32::consts::FRAC_PI_2);
+            transform.scale = Vec3::new(scale, scale, 1.0);
+            let wanted = if enemy.chasing {
+                &assets.chasing
+            } else {
+                &assets.watching
+            };
+            if material.0 != *wanted {
+                material.0 = wanted.clone();
+            }
+        }
+    }
+}
+
+pub struct VisionConePlugin;
+
+impl Plugin for VisionConePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_vision_cones)
+            .add_systems(Update, (attach_vision_cones, update_vision_cones).chain());
+    }
+}
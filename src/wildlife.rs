@@ -0,0 +1,554 @@
+// passive animals (rabbits, deer and birds) that wander in the dark and bolt
+// when the light finds them or something stronger and hostile comes near;
+// killing one leaves its loot table's drops and a carcass behind. Food left on the ground
+// draws hungry ones in, so it doubles as bait, and whatever they eat is gone
+// for the player too. Hit one and it runs, unless it's big
+// enough to fight back; feed one enough bait and it follows the player. What
+// each kind does when is up to its behavior tree.
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path};
+
+use crate::behavior::{load_behavior_trees, Behavior, BehaviorError, BehaviorNode};
+use crate::carcass::LeavesCarcass;
+use crate::combat::{Health, Loot};
+use crate::faction::{strength, Faction, FactionRelations, Provoked};
+use crate::event_log::EventLog;
+use crate::food::{FoodStats, FoodTracker, Location2D};
+use crate::light::{is_lit, Lightable};
+use crate::menu::AppState;
+use crate::obstacle::Obstacle;
+use crate::player::{solid_overlaps, DeathCause, DeathRespawnState, Player, RunSummary, Stats};
+use crate::taming::{Bait, BaitEaten, Tamed, Taming};
+use crate::world::{world_rng, WorldGrid, WorldRegenerated, WorldSeed, WORLD_TILE_SIZE};
+
+const RABBIT_COUNT: usize = 40;
+const DEER_COUNT: usize = 12;
+const BIRD_COUNT: usize = 30;
+const EDGE_MARGIN: i32 = 16;
+const SPAWN_CLEARING_RADIUS: i32 = 64;
+const WILDLIFE_SEED_SALT: u64 = 0x4a11;
+const WANDER_TURN_SECONDS: (f32, f32) = (1.0, 4.0);
+const REST_CHANCE: f64 = 0.3;
+// How far an animal can smell food, and how close it gets before eating it.
+const FOOD_SCENT_RADIUS: f32 = 60.0;
+const FOOD_EAT_RADIUS: f32 = 3.0;
+// After eating, an animal leaves food alone for this long.
+const FULL_SECONDS: f32 = 45.0;
+// Tamed animals keep this close, and hurry to catch up from twice as far.
+const FOLLOW_DISTANCE: f32 = 12.0;
+// Hostile creatures stronger than the animal send it running from this far.
+const THREAT_RADIUS: f32 = 30.0;
+const BITE_DAMAGE: f32 = 8.0;
+const BITE_COOLDOWN_SECONDS: f32 = 1.2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnimalKind {
+    Rabbit,
+    Deer,
+    Bird,
+}
+
+impl AnimalKind {
+    pub const ALL: [AnimalKind; 3] = [AnimalKind::Rabbit, AnimalKind::Deer, AnimalKind::Bird];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AnimalKind::Rabbit => "rabbit",
+            AnimalKind::Deer => "deer",
+            AnimalKind::Bird => "bird",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn size(self) -> f32 {
+        match self {
+            AnimalKind::Rabbit => 6.0,
+            AnimalKind::Deer => 12.0,
+            AnimalKind::Bird => 4.0,
+        }
+    }
+
+    fn health(self) -> f32 {
+        match self {
+            AnimalKind::Rabbit => 25.0,
+            AnimalKind::Deer => 75.0,
+            AnimalKind::Bird => 10.0,
+        }
+    }
+
+    fn wander_speed(self) -> f32 {
+        match self {
+            AnimalKind::Rabbit => 12.0,
+            AnimalKind::Deer => 8.0,
+            AnimalKind::Bird => 16.0,
+        }
+    }
+
+    fn flee_speed(self) -> f32 {
+        match self {
+            AnimalKind::Rabbit => 70.0,
+            AnimalKind::Deer => 55.0,
+            AnimalKind::Bird => 90.0,
+        }
+    }
+
+    fn loot_table(self) -> &'static str {
+        self.name()
+    }
+
+    // Meat is butchered from the carcass rather than dropped.
+    fn meat(self) -> u32 {
+        match self {
+            AnimalKind::Rabbit => 1,
+            AnimalKind::Deer => 3,
+            AnimalKind::Bird => 1,
+        }
+    }
+
+    fn tint(self) -> Color {
+        match self {
+            AnimalKind::Rabbit => Color::srgb(0.75, 0.68, 0.55),
+            AnimalKind::Deer => Color::srgb(0.55, 0.38, 0.22),
+            AnimalKind::Bird => Color::srgb(0.5, 0.55, 0.62),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimalCondition {
+    // The player hit it lately.
+    Provoked,
+    // Something hostile and stronger than it is close by.
+    Threatened,
+    Lit,
+    // It hasn't eaten lately.
+    Hungry,
+    Tamed,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimalAction {
+    // Charge the player and bite; fails while the player is dead.
+    FightBack,
+    // Run from the nearest threat, or else from the player.
+    Flee,
+    // Go for the nearest food it can smell; fails if there's none.
+    Eat,
+    // Amble about, stopping now and then.
+    Wander,
+    // Keep close to the player; fails while the player is dead.
+    Follow,
+}
+
+pub type AnimalTree = BehaviorNode<AnimalCondition, AnimalAction>;
+
+// Rabbits, deer and birds all bolt and forage alike; only deer are big
+// enough to fight back. Tamed ones stick with the player unless something
+// hostile comes near.
+fn default_tree(kind: AnimalKind) -> AnimalTree {
+    use BehaviorNode::{Action, Condition, Selector, Sequence};
+    let mut branches = vec![Sequence(vec![
+        Condition(AnimalCondition::Tamed),
+        Selector(vec![
+            Sequence(vec![
+                Condition(AnimalCondition::Threatened),
+                Action(AnimalAction::Flee),
+            ]),
+            Action(AnimalAction::Follow),
+        ]),
+    ])];
+    if kind == AnimalKind::Deer {
+        branches.push(Sequence(vec![
+            Condition(AnimalCondition::Provoked),
+            Action(AnimalAction::FightBack),
+        ]));
+    }
+    branches.extend([
+        Sequence(vec![
+            Selector(vec![
+                Condition(AnimalCondition::Threatened),
+                Condition(AnimalCondition::Provoked),
+                Condition(AnimalCondition::Lit),
+            ]),
+            Action(AnimalAction::Flee),
+        ]),
+        Sequence(vec![
+            Condition(AnimalCondition::Hungry),
+            Action(AnimalAction::Eat),
+        ]),
+        Action(AnimalAction::Wander),
+    ]);
+    Selector(branches)
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct AnimalBehaviors {
+    trees: HashMap<AnimalKind, AnimalTree>,
+}
+
+impl Default for AnimalBehaviors {
+    fn default() -> Self {
+        let trees = AnimalKind::ALL
+            .into_iter()
+            .map(|kind| (kind, default_tree(kind)))
+            .collect();
+        Self { trees }
+    }
+}
+
+impl AnimalBehaviors {
+    // Trees are keyed by animal name; kinds the file leaves out keep their
+    // built-in tree.
+    pub fn load(path: &Path) -> Result<Self, BehaviorError> {
+        let mut behaviors = Self::default();
+        for (name, tree) in load_behavior_trees(path)? {
+            let kind = AnimalKind::from_name(&name).ok_or(BehaviorError::UnknownArchetype(name))?;
+            behaviors.trees.insert(kind, tree);
+        }
+        Ok(behaviors)
+    }
+
+    pub fn tree(&self, kind: AnimalKind) -> &AnimalTree {
+        &self.trees[&kind]
+    }
+}
+
+// What an animal knows this frame, and the action its tree settled on.
+struct AnimalSenses {
+    provoked: bool,
+    threatened: bool,
+    lit: bool,
+    hungry: bool,
+    tamed: bool,
+    player_alive: bool,
+    smells_food: bool,
+    plan: Option<AnimalAction>,
+}
+
+impl Behavior<AnimalCondition, AnimalAction> for AnimalSenses {
+    fn check(&mut self, condition: &AnimalCondition) -> bool {
+        match condition {
+            AnimalCondition::Provoked => self.provoked,
+            AnimalCondition::Threatened => self.threatened,
+            AnimalCondition::Lit => self.lit,
+            AnimalCondition::Hungry => self.hungry,
+            AnimalCondition::Tamed => self.tamed,
+        }
+    }
+
+    fn act(&mut self, action: &AnimalAction) -> bool {
+        let possible = match action {
+            AnimalAction::FightBack | AnimalAction::Follow => self.player_alive,
+            AnimalAction::Eat => self.smells_food,
+            AnimalAction::Flee | AnimalAction::Wander => true,
+        };
+        if possible {
+            self.plan = Some(*action);
+        }
+        possible
+    }
+}
+
+#[derive(Component)]
+pub struct Animal {
+    pub kind: AnimalKind,
+    pub fleeing: bool,
+    heading: Vec2,
+    turn_timer: f32,
+    bite_cooldown: f32,
+    full_for: f32,
+}
+
+fn spawn_wildlife(
+    mut commands: Commands,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+) {
+    place_wildlife(&mut commands, &grid, *seed);
+}
+
+fn respawn_wildlife(
+    mut commands: Commands,
+    mut regenerated: MessageReader<WorldRegenerated>,
+    grid: Res<WorldGrid>,
+    seed: Res<WorldSeed>,
+    animal_query: Query<Entity, With<Animal>>,
+) {
+    if regenerated.read().count() == 0 {
+        return;
+    }
+    for entity in &animal_query {
+        commands.entity(entity).despawn();
+    }
+    place_wildlife(&mut commands, &grid, *seed);
+}
+
+// Animals aren't saved in world files, so imported worlds get them too.
+fn place_wildlife(commands: &mut Commands, grid: &WorldGrid, seed: WorldSeed) {
+    if grid.width as i32 <= EDGE_MARGIN * 2 || grid.height as i32 <= EDGE_MARGIN * 2 {
+        return;
+    }
+    let mut rng = world_rng(seed, WILDLIFE_SEED_SALT);
+    let center = IVec2::new(grid.width as i32 / 2, grid.height as i32 / 2);
+    // Birds come last so existing seeds keep their rabbits and deer.
+    let kinds = std::iter::repeat_n(AnimalKind::Rabbit, RABBIT_COUNT)
+        .chain(std::iter::repeat_n(AnimalKind::Deer, DEER_COUNT))
+        .chain(std::iter::repeat_n(AnimalKind::Bird, BIRD_COUNT));
+    for kind in kinds {
+        let tile = IVec2::new(
+            rng.random_range(EDGE_MARGIN..grid.width as i32 - EDGE_MARGIN),
+            rng.random_range(EDGE_MARGIN..grid.height as i32 - EDGE_MARGIN),
+        );
+        if tile.distance_squared(center) < SPAWN_CLEARING_RADIUS * SPAWN_CLEARING_RADIUS {
+            continue;
+        }
+        let position = tile.as_vec2() * WORLD_TILE_SIZE;
+        if solid_overlaps(grid, position, kind.size() / 2.0) {
+            continue;
+        }
+        spawn_animal(commands, kind, position);
+    }
+}
+
+pub fn spawn_animal(commands: &mut Commands, kind: AnimalKind, position: Vec2) -> Entity {
+    let tile = (position / WORLD_TILE_SIZE).floor().as_ivec2();
+    commands
+        .spawn((
+            Animal {
+                kind,
+                fleeing: false,
+                heading: Vec2::ZERO,
+                turn_timer: 0.0,
+                bite_cooldown: 0.0,
+                full_for: 0.0,
+            },
+            Health::new(kind.health()),
+            Faction::Wildlife,
+            Loot {
+                table: kind.loot_table(),
+            },
+            LeavesCarcass { meat: kind.meat() },
+            Lightable { tint: kind.tint() },
+            Location2D {
+                x: tile.x,
+                y: tile.y,
+            },
+            Sprite::from_color(Color::WHITE, Vec2::splat(kind.size())),
+            Visibility::Hidden,
+            Transform::from_translation(position.extend(0.7)),
+        ))
+        .id()
+}
+
+// Each animal's tree picks what it does this frame; moving and steering
+// around things is the same whatever it picked.
+fn move_animals(
+    mut commands: Commands,
+    time: Res<Time>,
+    grid: Res<WorldGrid>,
+    death_state: Res<DeathRespawnState>,
+    relations: Res<FactionRelations>,
+    behaviors: Res<AnimalBehaviors>,
+    mut summary: ResMut<RunSummary>,
+    mut food_tracker: ResMut<FoodTracker>,
+    mut event_log: ResMut<EventLog>,
+    mut bait_eaten: MessageWriter<BaitEaten>,
+    food_query: Query<&FoodStats>,
+    bait_query: Query<(), With<Bait>>,
+    mut player_query: Query<(&Transform, &mut Stats), With<Player>>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Animal>>,
+    threat_query: Query<(&Transform, &Faction, &Health), (Without<Animal>, Without<Player>)>,
+    mut animal_query: Query<
+        (
+            Entity,
+            &mut Animal,
+            &Faction,
+            &Health,
+            Option<&Provoked>,
+            Has<Taming>,
+            Has<Tamed>,
+            &mut Location2D,
+            &mut Transform,
+        ),
+        Without<Player>,
+    >,
+) {
+    let dt = time.delta_secs();
+    let mut player = player_query.single_mut().ok();
+    let player_pos = player
+        .as_ref()
+        .map(|(transform, _)| transform.translation.truncate());
+    let mut rng = rand::rng();
+
+    for (entity, mut animal, faction, health, provoked, taming, tamed, mut location, mut transform) in
+        &mut animal_query
+    {
+        let position = transform.translation.truncate();
+        animal.bite_cooldown = (animal.bite_cooldown - dt).max(0.0);
+        animal.full_for = (animal.full_for - dt).max(0.0);
+        let threat = threat_query
+            .iter()
+            .filter(|(_, other, other_health)| {
+                relations.hostile(*faction, **other) && strength(other_health) > strength(health)
+            })
+            .map(|(other_transform, _, _)| other_transform.translation.truncate())
+            .filter(|other_pos| other_pos.distance(position) <= THREAT_RADIUS * WORLD_TILE_SIZE)
+            .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+        let bait = food_tracker
+            .query_radius(position, FOOD_SCENT_RADIUS * WORLD_TILE_SIZE)
+            .map(|(food_location, entity)| {
+                let food_pos =
+                    Vec2::new(food_location.x as f32, food_location.y as f32) * WORLD_TILE_SIZE;
+                (entity, food_location, food_pos, food_pos.distance(position))
+            })
+            .min_by(|a, b| a.3.total_cmp(&b.3));
+        let mut senses = AnimalSenses {
+            provoked: provoked.is_some(),
+            threatened: threat.is_some(),
+            // Once it has taken bait, the lantern doesn't scare it off.
+            lit: !taming && is_lit(&grid, location.x, location.y),
+            hungry: animal.full_for <= 0.0,
+            tamed,
+            player_alive: player.is_some() && !death_state.is_dead,
+            smells_food: bait.is_some(),
+            plan: None,
+        };
+        behaviors.tree(animal.kind).run(&mut senses);
+        animal.fleeing = senses.plan == Some(AnimalAction::Flee);
+        let speed = match senses.plan {
+            Some(AnimalAction::FightBack) => {
+                let Some((player_transform, stats)) = player.as_mut() else {
+                    continue;
+                };
+                let offset = player_transform.translation.truncate() - position;
+                animal.heading = offset.normalize_or_zero();
+                if offset.length() <= animal.kind.size() && animal.bite_cooldown <= 0.0 {
+                    animal.bite_cooldown = BITE_COOLDOWN_SECONDS;
+                    stats.health = (stats.health - BITE_DAMAGE).max(0.0);
+                    summary.last_damage = Some(DeathCause::Wildlife);
+                }
+                if offset.length() <= animal.kind.size() * 0.5 {
+                    continue;
+                }
+                animal.kind.flee_speed()
+            }
+            Some(AnimalAction::Flee) => {
+                if let Some(from) = threat.or(player_pos) {
+                    animal.heading = (position - from).normalize_or(animal.heading);
+                }
+                animal.kind.flee_speed()
+            }
+            Some(AnimalAction::Eat) => {
+                let Some((food, food_location, food_pos, distance)) = bait else {
+                    continue;
+                };
+                if distance <= FOOD_EAT_RADIUS * WORLD_TILE_SIZE {
+                    // Only worth a mention if the player could see it go.
+                    if is_lit(&grid, food_location.x, food_location.y)
+                        && let Ok(stats) = food_query.get(food)
+                    {
+                        event_log.push(
+                            &time,
+                            format!(
+                                "a {} ate the {}",
+                                animal.kind.name(),
+                                stats.food.looks_like.to_lowercase()
+                            ),
+                        );
+                    }
+                    if bait_query.contains(food) {
+                        bait_eaten.write(BaitEaten { animal: entity });
+                    }
+                    animal.full_for = FULL_SECONDS;
+                    food_tracker.remove(&food_location);
+                    commands.entity(food).despawn();
+                    animal.heading = Vec2::ZERO;
+                    animal.turn_timer = 0.0;
+                    continue;
+                }
+                animal.heading = (food_pos - position).normalize_or_zero();
+                animal.kind.wander_speed()
+            }
+            Some(AnimalAction::Wander) => {
+                animal.turn_timer -= dt;
+                if animal.turn_timer <= 0.0 {
+                    let (min, max) = WANDER_TURN_SECONDS;
+                    animal.turn_timer = rng.random_range(min..max);
+                    animal.heading = if rng.random_bool(REST_CHANCE) {
+                        Vec2::ZERO
+                    } else {
+                        Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU))
+                    };
+                }
+                animal.kind.wander_speed()
+            }
+            Some(AnimalAction::Follow) => {
+                let Some(player_pos) = player_pos else {
+                    continue;
+                };
+                let offset = player_pos - position;
+                if offset.length() <= FOLLOW_DISTANCE * WORLD_TILE_SIZE {
+                    continue;
+                }
+                animal.heading = offset.normalize_or_zero();
+                if offset.length() > FOLLOW_DISTANCE * 2.0 * WORLD_TILE_SIZE {
+                    animal.kind.flee_speed()
+                } else {
+                    animal.kind.wander_speed()
+                }
+            }
+            // A tree that settles on nothing leaves the animal where it is.
+            None => continue,
+        };
+        if animal.heading == Vec2::ZERO {
+            continue;
+        }
+
+        let radius = animal.kind.size() / 2.0;
+        let blocked = |candidate: Vec2| {
+            solid_overlaps(&grid, candidate, radius)
+                || obstacle_query.iter().any(|(obstacle_transform, obstacle)| {
+                    let reach = obstacle.radius + radius;
+                    obstacle_transform.translation.truncate().distance_squared(candidate)
+                        < reach * reach
+                })
+        };
+        let step = animal.heading * speed * WORLD_TILE_SIZE * dt;
+        let next = [step, Vec2::new(step.x, 0.0), Vec2::new(0.0, step.y)]
+            .into_iter()
+            .map(|step| position + step)
+            .find(|candidate| !blocked(*candidate));
+        let Some(next) = next else {
+            // Cornered: turn around and try again next frame.
+            animal.heading = -animal.heading;
+            continue;
+        };
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+        let tile = (next / WORLD_TILE_SIZE).floor().as_ivec2();
+        location.set_if_neq(Location2D {
+            x: tile.x,
+            y: tile.y,
+        });
+    }
+}
+
+pub struct WildlifePlugin;
+
+impl Plugin for WildlifePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimalBehaviors>()
+            .add_systems(Startup, spawn_wildlife)
+            .add_systems(
+                Update,
+                (respawn_wildlife, move_animals)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
@@ -4,23 +4,160 @@ use bevy::mesh::{Indices, Mesh, VertexAttributeValues};
 use bevy::prelude::*;
 use bevy::render::render_resource::PrimitiveTopology;
 use bevy::prelude::MeshMaterial2d;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::{collections::VecDeque, env};
 
-pub const HEIGHT: usize = 1000;
-pub const WIDTH: usize = 1000;
+use crate::menu::AppState;
+use crate::world_file::{load_world, world_file_from_env, ImportedWorld, WorldChoice};
+
+pub const DEFAULT_WORLD_WIDTH: usize = 1000;
+pub const DEFAULT_WORLD_HEIGHT: usize = 1000;
+// Landmarks keep 40 tiles from the edge, so smaller maps can't place them.
+const MIN_WORLD_SIZE: usize = 128;
 
 pub const WORLD_TILE_SIZE: f32 = 1.0;
 pub const PLAYER_SIZE: f32 = 24.0;
-const CHUNK_SIZE: usize = 25;
+pub const CHUNK_SIZE: usize = 25;
 const WALL_THICKNESS: usize = 6;
+const TILES_PER_POND: usize = 40_000;
+const POND_LOBES: usize = 3;
+const POND_MIN_RADIUS: i32 = 10;
+const POND_MAX_RADIUS: i32 = 26;
+const POND_SPAWN_CLEARING: i32 = 80;
+const POND_SEED_SALT: u64 = 0x9011d;
 const USE_WALL_TEXTURE: bool = false;
+// Land this close to a pond counts as shore.
+const SHORE_TILES: i32 = 16;
 
 pub type Field = Vec<Vec<bool>>;
 
+// Surface of a tile; walls live in their own layer. Water can't be walked
+// on but can be fished from its edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileKind {
+    Ground,
+    Rubble,
+    Water,
+}
+
+// The broad kind of land around a tile, for things that grow or spawn
+// differently across the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Biome {
+    Plain,
+    Rubble,
+    Shore,
+}
+
+impl Biome {
+    pub fn name(self) -> &'static str {
+        match self {
+            Biome::Plain => "plain",
+            Biome::Rubble => "rubble",
+            Biome::Shore => "shore",
+        }
+    }
+}
+
+impl TileKind {
+    // Multiplied into the lit floor colour.
+    pub fn light_tint(self) -> [f32; 3] {
+        match self {
+            TileKind::Ground | TileKind::Rubble => [1.0, 1.0, 1.0],
+            TileKind::Water => [0.35, 0.55, 0.95],
+        }
+    }
+}
+
 #[derive(Resource, Debug, Clone)]
 pub struct WorldGrid {
+    pub width: usize,
+    pub height: usize,
     pub field: Field,
     pub brightness: Vec<Vec<f32>>,
     pub walls: Vec<Vec<bool>>,
+    pub tiles: Vec<Vec<TileKind>>,
+}
+
+impl WorldGrid {
+    fn new(width: usize, height: usize, walls: Vec<Vec<bool>>) -> Self {
+        Self {
+            width,
+            height,
+            field: vec![vec![false; width]; height],
+            brightness: vec![vec![0.0; width]; height],
+            walls,
+            tiles: vec![vec![TileKind::Ground; width]; height],
+        }
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32
+    }
+
+    // World position of the middle of the map, where new games start.
+    pub fn center(&self) -> Vec2 {
+        Vec2::new(
+            (self.width as f32 / 2.0).floor(),
+            (self.height as f32 / 2.0).floor(),
+        ) * WORLD_TILE_SIZE
+    }
+
+    // The whole map in world units, from the corner of tile (0, 0).
+    pub fn world_rect(&self) -> Rect {
+        Rect::new(
+            0.0,
+            0.0,
+            self.width as f32 * WORLD_TILE_SIZE,
+            self.height as f32 * WORLD_TILE_SIZE,
+        )
+    }
+
+    pub fn tile_kind(&self, x: i32, y: i32) -> Option<TileKind> {
+        if !self.in_bounds(x, y) {
+            return None;
+        }
+        Some(self.tiles[y as usize][x as usize])
+    }
+
+    pub fn is_water(&self, x: i32, y: i32) -> bool {
+        self.tile_kind(x, y) == Some(TileKind::Water)
+    }
+
+    // Shore wins over rubble, so rubble beside a pond is shore.
+    pub fn biome(&self, tile: IVec2) -> Biome {
+        if self.near_water(tile, SHORE_TILES) {
+            Biome::Shore
+        } else if self.tile_kind(tile.x, tile.y) == Some(TileKind::Rubble) {
+            Biome::Rubble
+        } else {
+            Biome::Plain
+        }
+    }
+
+    // A cheap check: the tile itself and one probe `reach` tiles away in
+    // each direction.
+    pub fn near_water(&self, tile: IVec2, reach: i32) -> bool {
+        [IVec2::ZERO, IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .into_iter()
+            .map(|dir| tile + dir * reach)
+            .any(|probe| self.is_water(probe.x, probe.y))
+    }
+
+    // Walls and water block movement and placement; off the map counts too.
+    pub fn is_solid(&self, x: i32, y: i32) -> bool {
+        !self.in_bounds(x, y) || self.walls[y as usize][x as usize] || self.is_water(x, y)
+    }
+
+    fn set_water(&mut self, water: &[Vec<bool>]) {
+        for (row, water_row) in self.tiles.iter_mut().zip(water) {
+            for (tile, &is_water) in row.iter_mut().zip(water_row) {
+                if is_water {
+                    *tile = TileKind::Water;
+                }
+            }
+        }
+    }
 }
 
 #[derive(Resource, Debug, Clone)]
@@ -28,26 +165,115 @@ pub struct WorldChunks {
     pub cols: usize,
     pub rows: usize,
     pub meshes: Vec<Handle<Mesh>>,
+    pub wall_meshes: Vec<Option<Handle<Mesh>>>,
 }
 
-fn vector_field() -> Field {
-    let field = vec![vec![false; WIDTH]; HEIGHT];
-    return field;
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct WorldSeed(pub u64);
+
+// Sent after tiles in the inclusive rectangle were edited, so caches built
+// from the grid can refresh just that area.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct TilesChanged {
+    pub min: IVec2,
+    pub max: IVec2,
+}
+
+// Request a fresh world: walls and tiles are reset and a new seed is rolled,
+// unless one is given to replay a particular world.
+#[derive(Message, Clone, Copy, Debug, Default)]
+pub struct RegenerateWorld {
+    pub seed: Option<u64>,
+}
+
+// Sent once the grid and seed have been replaced, so anything placed from
+// the old seed can be respawned.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct WorldRegenerated;
+
+fn world_seed(configured: Option<u64>) -> WorldSeed {
+    let seed_key = "WORLD_SEED_KEY";
+    let seed_value: Option<u64> = configured.or_else(|| {
+        env::var(seed_key)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+    });
+    WorldSeed(seed_value.unwrap_or_else(rand::random))
 }
 
-fn brightness_field() -> Vec<Vec<f32>> {
-    vec![vec![0.0; WIDTH]; HEIGHT]
+// Each generation pass salts the shared world seed so passes don't mirror
+// each other's rolls.
+pub fn world_rng(seed: WorldSeed, salt: u64) -> StdRng {
+    StdRng::seed_from_u64(seed.0 ^ salt)
 }
 
-fn walls_field() -> Vec<Vec<bool>> {
-    let mut walls = vec![vec![false; WIDTH]; HEIGHT];
-    for y in 0..HEIGHT {
-        for x in 0..WIDTH {
-            let is_wall = x < WALL_THICKNESS
+// A few ponds, each a cluster of overlapping discs, kept clear of the walls
+// and of the spawn point in the middle.
+fn dig_ponds(grid: &mut WorldGrid, seed: WorldSeed) {
+    let mut rng = world_rng(seed, POND_SEED_SALT);
+    let pond_count = grid.width * grid.height / TILES_PER_POND;
+    let center = IVec2::new(grid.width as i32 / 2, grid.height as i32 / 2);
+    let margin = WALL_THICKNESS as i32 + POND_MAX_RADIUS * 2;
+    if grid.width as i32 <= margin * 2 || grid.height as i32 <= margin * 2 {
+        return;
+    }
+    for _ in 0..pond_count {
+        let pond = IVec2::new(
+            rng.random_range(margin..grid.width as i32 - margin),
+            rng.random_range(margin..grid.height as i32 - margin),
+        );
+        if pond.distance_squared(center) < POND_SPAWN_CLEARING * POND_SPAWN_CLEARING {
+            continue;
+        }
+        for _ in 0..POND_LOBES {
+            let radius = rng.random_range(POND_MIN_RADIUS..=POND_MAX_RADIUS);
+            let lobe = pond
+                + IVec2::new(
+                    rng.random_range(-radius..=radius),
+                    rng.random_range(-radius..=radius),
+                );
+            for y in (lobe.y - radius)..=(lobe.y + radius) {
+                for x in (lobe.x - radius)..=(lobe.x + radius) {
+                    let inside = IVec2::new(x, y).distance_squared(lobe) <= radius * radius;
+                    if inside && !grid.is_solid(x, y) {
+                        grid.tiles[y as usize][x as usize] = TileKind::Water;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Every tile that can be walked to from start without crossing walls or
+// water. Obstacles are ignored since they can be cleared.
+pub fn reachable_tiles(grid: &WorldGrid, start: IVec2) -> Vec<Vec<bool>> {
+    let mut reached = vec![vec![false; grid.width]; grid.height];
+    if grid.is_solid(start.x, start.y) {
+        return reached;
+    }
+    reached[start.y as usize][start.x as usize] = true;
+    let mut frontier = VecDeque::from([start]);
+    while let Some(tile) = frontier.pop_front() {
+        for step in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = tile + step;
+            if grid.is_solid(next.x, next.y) || reached[next.y as usize][next.x as usize] {
+                continue;
+            }
+            reached[next.y as usize][next.x as usize] = true;
+            frontier.push_back(next);
+        }
+    }
+    reached
+}
+
+fn walls_field(width: usize, height: usize) -> Vec<Vec<bool>> {
+    let mut walls = vec![vec![false; width]; height];
+    for (y, row) in walls.iter_mut().enumerate() {
+        for (x, tile) in row.iter_mut().enumerate() {
+            *tile = x < WALL_THICKNESS
                 || y < WALL_THICKNESS
-                || x >= WIDTH - WALL_THICKNESS
-                || y >= HEIGHT - WALL_THICKNESS;
-            walls[y][x] = is_wall;
+                || x >= width - WALL_THICKNESS
+                || y >= height - WALL_THICKNESS;
         }
     }
     walls
@@ -90,6 +316,161 @@ pub fn set_chunk_tile_color(
     colors[base + 3] = color;
 }
 
+fn chunk_bounds(grid: &WorldGrid, chunk_x: usize, chunk_y: usize) -> (usize, usize, usize, usize) {
+    let start_x = chunk_x * CHUNK_SIZE;
+    let start_y = chunk_y * CHUNK_SIZE;
+    let end_x = (start_x + CHUNK_SIZE).min(grid.width);
+    let end_y = (start_y + CHUNK_SIZE).min(grid.height);
+    (start_x, start_y, end_x, end_y)
+}
+
+fn build_wall_mesh(grid: &WorldGrid, chunk_x: usize, chunk_y: usize) -> Option<Mesh> {
+    let (start_x, start_y, end_x, end_y) = chunk_bounds(grid, chunk_x, chunk_y);
+    let chunk_w = end_x - start_x;
+    let chunk_h = end_y - start_y;
+
+    let mut wall_positions = Vec::with_capacity(chunk_w * chunk_h * 4);
+    let mut wall_uvs = Vec::with_capacity(chunk_w * chunk_h * 4);
+    let mut wall_colors = Vec::with_capacity(chunk_w * chunk_h * 4);
+    let mut wall_indices = Vec::with_capacity(chunk_w * chunk_h * 6);
+
+    for local_y in 0..chunk_h {
+        for local_x in 0..chunk_w {
+            let world_x = start_x + local_x;
+            let world_y = start_y + local_y;
+            if !is_wall_tile(grid, world_x, world_y) {
+                continue;
+            }
+            let x0 = local_x as f32 * WORLD_TILE_SIZE;
+            let y0 = local_y as f32 * WORLD_TILE_SIZE;
+            let x1 = x0 + WORLD_TILE_SIZE;
+            let y1 = y0 + WORLD_TILE_SIZE;
+
+            let dist_left = world_x;
+            let dist_right = grid.width - 1 - world_x;
+            let dist_bottom = world_y;
+            let dist_top = grid.height - 1 - world_y;
+            let mut edge = 0;
+            let mut dist = dist_left;
+            if dist_right < dist {
+                dist = dist_right;
+                edge = 1;
+            }
+            if dist_bottom < dist {
+                dist = dist_bottom;
+                edge = 2;
+            }
+            if dist_top < dist {
+                dist = dist_top;
+                edge = 3;
+            }
+            let thickness = WALL_THICKNESS as f32;
+            let t0 = dist as f32 / thickness;
+            let t1 = (dist as f32 + 1.0) / thickness;
+            let (u0, u1, v0, v1) = if edge <= 1 {
+                (t0, t1, 0.0, 1.0)
+            } else {
+                (0.0, 1.0, t0, t1)
+            };
+            let wall_base = wall_positions.len() as u32;
+            wall_positions.extend_from_slice(&[
+                [x0, y0, 0.0],
+                [x1, y0, 0.0],
+                [x1, y1, 0.0],
+                [x0, y1, 0.0],
+            ]);
+            wall_uvs.extend_from_slice(&[
+                [u0, v0],
+                [u1, v0],
+                [u1, v1],
+                [u0, v1],
+            ]);
+            let wall_color = Color::WHITE.to_linear();
+            let wall_color = [
+                wall_color.red,
+                wall_color.green,
+                wall_color.blue,
+                wall_color.alpha,
+            ];
+            wall_colors.extend_from_slice(&[wall_color; 4]);
+            wall_indices.extend_from_slice(&[
+                wall_base,
+                wall_base + 2,
+                wall_base + 1,
+                wall_base,
+                wall_base + 3,
+                wall_base + 2,
+            ]);
+        }
+    }
+
+    if wall_positions.is_empty() {
+        return None;
+    }
+    let mut wall_mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    wall_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, wall_positions);
+    wall_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, wall_uvs);
+    wall_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, wall_colors);
+    wall_mesh.insert_indices(Indices::U32(wall_indices));
+    Some(wall_mesh)
+}
+
+fn empty_mesh() -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, Vec::<[f32; 2]>::new());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, Vec::<[f32; 4]>::new());
+    mesh.insert_indices(Indices::U32(Vec::new()));
+    mesh
+}
+
+// Removes diggable wall tiles in a square around (x, y) and rebuilds the
+// touched chunk wall meshes. The outermost ring of the map is bedrock.
+pub fn dig_walls(
+    grid: &mut WorldGrid,
+    meshes: &mut Assets<Mesh>,
+    chunks: &WorldChunks,
+    x: i32,
+    y: i32,
+    radius: i32,
+) -> usize {
+    let mut removed = 0;
+    let mut touched_chunks = Vec::new();
+    for ty in (y - radius)..=(y + radius) {
+        for tx in (x - radius)..=(x + radius) {
+            let is_bedrock = tx <= 0
+                || ty <= 0
+                || tx >= grid.width as i32 - 1
+                || ty >= grid.height as i32 - 1;
+            if is_bedrock {
+                continue;
+            }
+            let ux = tx as usize;
+            let uy = ty as usize;
+            if !grid.walls[uy][ux] {
+                continue;
+            }
+            grid.walls[uy][ux] = false;
+            grid.tiles[uy][ux] = TileKind::Rubble;
+            removed += 1;
+            let chunk = (ux / CHUNK_SIZE, uy / CHUNK_SIZE);
+            if !touched_chunks.contains(&chunk) {
+                touched_chunks.push(chunk);
+            }
+        }
+    }
+
+    for (chunk_x, chunk_y) in touched_chunks {
+        let index = chunk_y * chunks.cols + chunk_x;
+        let Some(Some(handle)) = chunks.wall_meshes.get(index) else {
+            continue;
+        };
+        let mesh = build_wall_mesh(grid, chunk_x, chunk_y).unwrap_or_else(empty_mesh);
+        let _ = meshes.insert(handle.id(), mesh);
+    }
+    removed
+}
+
 fn spawn_chunks(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -98,12 +479,18 @@ fn spawn_chunks(
     grid: Res<WorldGrid>,
     mut chunks: ResMut<WorldChunks>,
 ) {
-    let cols = (WIDTH + CHUNK_SIZE - 1) / CHUNK_SIZE;
-    let rows = (HEIGHT + CHUNK_SIZE - 1) / CHUNK_SIZE;
+    // Built when the first game starts; later ones regenerate them in place.
+    if !chunks.meshes.is_empty() {
+        return;
+    }
+    let cols = grid.width.div_ceil(CHUNK_SIZE);
+    let rows = grid.height.div_ceil(CHUNK_SIZE);
     chunks.cols = cols;
     chunks.rows = rows;
     chunks.meshes.clear();
     chunks.meshes.reserve(cols * rows);
+    chunks.wall_meshes.clear();
+    chunks.wall_meshes.reserve(cols * rows);
 
     let floor_material = materials.add(ColorMaterial::from(Color::WHITE));
     let wall_material = if USE_WALL_TEXTURE {
@@ -119,10 +506,7 @@ fn spawn_chunks(
 
     for chunk_y in 0..rows {
         for chunk_x in 0..cols {
-            let start_x = chunk_x * CHUNK_SIZE;
-            let start_y = chunk_y * CHUNK_SIZE;
-            let end_x = (start_x + CHUNK_SIZE).min(WIDTH);
-            let end_y = (start_y + CHUNK_SIZE).min(HEIGHT);
+            let (start_x, start_y, end_x, end_y) = chunk_bounds(&grid, chunk_x, chunk_y);
             let chunk_w = end_x - start_x;
             let chunk_h = end_y - start_y;
 
@@ -130,15 +514,9 @@ fn spawn_chunks(
             let mut uvs = Vec::with_capacity(chunk_w * chunk_h * 4);
             let mut colors = Vec::with_capacity(chunk_w * chunk_h * 4);
             let mut indices = Vec::with_capacity(chunk_w * chunk_h * 6);
-            let mut wall_positions = Vec::with_capacity(chunk_w * chunk_h * 4);
-            let mut wall_uvs = Vec::with_capacity(chunk_w * chunk_h * 4);
-            let mut wall_colors = Vec::with_capacity(chunk_w * chunk_h * 4);
-            let mut wall_indices = Vec::with_capacity(chunk_w * chunk_h * 6);
 
             for local_y in 0..chunk_h {
                 for local_x in 0..chunk_w {
-                    let world_x = start_x + local_x;
-                    let world_y = start_y + local_y;
                     let x0 = local_x as f32 * WORLD_TILE_SIZE;
                     let y0 = local_y as f32 * WORLD_TILE_SIZE;
                     let x1 = x0 + WORLD_TILE_SIZE;
@@ -153,7 +531,6 @@ fn spawn_chunks(
                     ]);
                     uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
 
-                    let is_wall = is_wall_tile(&grid, world_x, world_y);
                     let color = Color::BLACK.to_linear();
                     let color = [color.red, color.green, color.blue, color.alpha];
                     colors.extend_from_slice(&[color; 4]);
@@ -166,64 +543,6 @@ fn spawn_chunks(
                         base + 3,
                         base + 2,
                     ]);
-
-                    if is_wall {
-                        let dist_left = world_x;
-                        let dist_right = WIDTH - 1 - world_x;
-                        let dist_bottom = world_y;
-                        let dist_top = HEIGHT - 1 - world_y;
-                        let mut edge = 0;
-                        let mut dist = dist_left;
-                        if dist_right < dist {
-                            dist = dist_right;
-                            edge = 1;
-                        }
-                        if dist_bottom < dist {
-                            dist = dist_bottom;
-                            edge = 2;
-                        }
-                        if dist_top < dist {
-                            dist = dist_top;
-                            edge = 3;
-                        }
-                        let thickness = WALL_THICKNESS as f32;
-                        let t0 = dist as f32 / thickness;
-                        let t1 = (dist as f32 + 1.0) / thickness;
-                        let (u0, u1, v0, v1) = if edge <= 1 {
-                            (t0, t1, 0.0, 1.0)
-                        } else {
-                            (0.0, 1.0, t0, t1)
-                        };
-                        let wall_base = wall_positions.len() as u32;
-                        wall_positions.extend_from_slice(&[
-                            [x0, y0, 0.0],
-                            [x1, y0, 0.0],
-                            [x1, y1, 0.0],
-                            [x0, y1, 0.0],
-                        ]);
-                        wall_uvs.extend_from_slice(&[
-                            [u0, v0],
-                            [u1, v0],
-                            [u1, v1],
-                            [u0, v1],
-                        ]);
-                        let wall_color = Color::WHITE.to_linear();
-                        let wall_color = [
-                            wall_color.red,
-                            wall_color.green,
-                            wall_color.blue,
-                            wall_color.alpha,
-                        ];
-                        wall_colors.extend_from_slice(&[wall_color; 4]);
-                        wall_indices.extend_from_slice(&[
-                            wall_base,
-                            wall_base + 2,
-                            wall_base + 1,
-                            wall_base,
-                            wall_base + 3,
-                            wall_base + 2,
-                        ]);
-                    }
                 }
             }
 
@@ -247,43 +566,147 @@ fn spawn_chunks(
                 Transform::from_translation(chunk_origin),
             ));
 
-            if !wall_positions.is_empty() {
-                let mut wall_mesh =
-                    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
-                wall_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, wall_positions);
-                wall_mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, wall_uvs);
-                wall_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, wall_colors);
-                wall_mesh.insert_indices(Indices::U32(wall_indices));
-                let wall_handle = meshes.add(wall_mesh);
-                commands.spawn((
-                    Mesh2d(wall_handle),
-                    MeshMaterial2d(wall_material.clone()),
-                    Transform::from_translation(Vec3::new(
-                        chunk_origin.x,
-                        chunk_origin.y,
-                        -0.5,
-                    )),
-                ));
-            }
+            let Some(wall_mesh) = build_wall_mesh(&grid, chunk_x, chunk_y) else {
+                chunks.wall_meshes.push(None);
+                continue;
+            };
+            let wall_handle = meshes.add(wall_mesh);
+            chunks.wall_meshes.push(Some(wall_handle.clone()));
+            commands.spawn((
+                Mesh2d(wall_handle),
+                MeshMaterial2d(wall_material.clone()),
+                Transform::from_translation(Vec3::new(
+                    chunk_origin.x,
+                    chunk_origin.y,
+                    -0.5,
+                )),
+            ));
         }
     }
 }
 
-pub struct WorldPlugin;
+// Anything that swaps ImportedWorld before a RegenerateWorld should run
+// before this set, so the rebuild sees the new world.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WorldRegeneration;
+
+// Imported worlds keep their layout; only generated worlds get a new seed.
+fn regenerate_world(
+    mut requests: MessageReader<RegenerateWorld>,
+    mut grid: ResMut<WorldGrid>,
+    mut seed: ResMut<WorldSeed>,
+    imported: Option<Res<ImportedWorld>>,
+    chunks: Res<WorldChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut tiles_changed: MessageWriter<TilesChanged>,
+    mut regenerated: MessageWriter<WorldRegenerated>,
+) {
+    let Some(request) = requests.read().last().copied() else {
+        return;
+    };
+
+    let (width, height) = (grid.width, grid.height);
+    match imported {
+        Some(imported) => {
+            *grid = WorldGrid::new(width, height, imported.0.wall_field());
+            grid.set_water(&imported.0.water_field());
+        }
+        None => {
+            *seed = WorldSeed(request.seed.unwrap_or_else(rand::random));
+            *grid = WorldGrid::new(width, height, walls_field(width, height));
+            dig_ponds(&mut grid, *seed);
+        }
+    }
+
+    // Digging only ever removes walls, so the chunks that started with wall
+    // meshes are the only ones that need rebuilding.
+    for (index, handle) in chunks.wall_meshes.iter().enumerate() {
+        let Some(handle) = handle else {
+            continue;
+        };
+        let (chunk_x, chunk_y) = (index % chunks.cols, index / chunks.cols);
+        let mesh = build_wall_mesh(&grid, chunk_x, chunk_y).unwrap_or_else(empty_mesh);
+        let _ = meshes.insert(handle.id(), mesh);
+    }
+
+    tiles_changed.write(TilesChanged {
+        min: IVec2::ZERO,
+        max: IVec2::new(width as i32 - 1, height as i32 - 1),
+    });
+    regenerated.write(WorldRegenerated);
+}
+
+// The seed falls back to WORLD_SEED_KEY and then a random one.
+#[derive(Clone, Debug)]
+pub struct WorldPlugin {
+    pub width: usize,
+    pub height: usize,
+    pub seed: Option<u64>,
+}
+
+impl Default for WorldPlugin {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WORLD_WIDTH,
+            height: DEFAULT_WORLD_HEIGHT,
+            seed: None,
+        }
+    }
+}
+
+impl WorldPlugin {
+    pub fn with_size(mut self, width: usize, height: usize) -> Self {
+        self.width = width.max(MIN_WORLD_SIZE);
+        self.height = height.max(MIN_WORLD_SIZE);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(ClearColor(Color::BLACK))
-            .insert_resource(WorldGrid {
-                field: vector_field(),
-                brightness: brightness_field(),
-                walls: walls_field(),
-            })
+        let seed = world_seed(self.seed);
+        let chosen = world_file_from_env();
+        let imported = chosen
+            .as_deref()
+            .and_then(|path| load_world(path, self.width, self.height));
+        let grid = match &imported {
+            Some(file) => {
+                let mut grid = WorldGrid::new(self.width, self.height, file.wall_field());
+                grid.set_water(&file.water_field());
+                grid
+            }
+            None => {
+                let walls = walls_field(self.width, self.height);
+                let mut grid = WorldGrid::new(self.width, self.height, walls);
+                dig_ponds(&mut grid, seed);
+                grid
+            }
+        };
+        if let Some(file) = imported {
+            app.insert_resource(ImportedWorld(file))
+                .insert_resource(WorldChoice {
+                    files: Vec::new(),
+                    selected: chosen,
+                });
+        }
+        app.add_message::<TilesChanged>()
+            .add_message::<RegenerateWorld>()
+            .add_message::<WorldRegenerated>()
+            .insert_resource(ClearColor(Color::BLACK))
+            .insert_resource(seed)
+            .insert_resource(grid)
             .insert_resource(WorldChunks {
                 cols: 0,
                 rows: 0,
                 meshes: Vec::new(),
+                wall_meshes: Vec::new(),
             })
-            .add_systems(Startup, spawn_chunks);
+            .add_systems(OnEnter(AppState::Playing), spawn_chunks)
+            .add_systems(Update, regenerate_world.in_set(WorldRegeneration));
     }
 }
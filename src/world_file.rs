@@ -0,0 +1,433 @@
+// shareable world files: walls, water, obstacles and landmarks, no player state
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::event_log::EventLog;
+use crate::food::Location2D;
+use crate::landmark::{Landmark, LandmarkKind};
+use crate::menu::AppState;
+use crate::obstacle::{Obstacle, ObstacleKind};
+use crate::world::{TileKind, WorldGrid};
+
+pub const WORLD_FILE_VERSION: u32 = 1;
+const EXPORT_DIR: &str = "worlds";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ObstacleRecord {
+    pub kind: ObstacleKind,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct LandmarkRecord {
+    pub kind: LandmarkKind,
+    pub x: i32,
+    pub y: i32,
+}
+
+// Wall and water rows are run-length encoded, alternating clear/set and
+// always starting with a clear run (which may be zero long). Files written
+// before there was water simply have none.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WorldFile {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    pub walls: Vec<Vec<u32>>,
+    #[serde(default)]
+    pub water: Vec<Vec<u32>>,
+    pub obstacles: Vec<ObstacleRecord>,
+    pub landmarks: Vec<LandmarkRecord>,
+}
+
+#[derive(Debug)]
+pub enum WorldFileError {
+    Io(std::io::Error),
+    Parse(String),
+    Version(u32),
+    Size {
+        width: usize,
+        height: usize,
+        expected_width: usize,
+        expected_height: usize,
+    },
+    WallRow(usize),
+    WaterRow(usize),
+    OutOfBounds { x: i32, y: i32 },
+}
+
+impl fmt::Display for WorldFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldFileError::Io(err) => write!(f, "could not read world file: {err}"),
+            WorldFileError::Parse(err) => write!(f, "could not parse world file: {err}"),
+            WorldFileError::Version(version) => write!(
+                f,
+                "world file version {version} is not supported (expected {WORLD_FILE_VERSION})"
+            ),
+            WorldFileError::Size {
+                width,
+                height,
+                expected_width,
+                expected_height,
+            } => write!(
+                f,
+                "world file is {width}x{height}, this world is {expected_width}x{expected_height}"
+            ),
+            WorldFileError::WallRow(row) => write!(f, "wall row {row} does not cover the map width"),
+            WorldFileError::WaterRow(row) => {
+                write!(f, "water row {row} does not cover the map width")
+            }
+            WorldFileError::OutOfBounds { x, y } => write!(f, "entry at ({x}, {y}) is outside the map"),
+        }
+    }
+}
+
+// Present when a world file was imported for this game.
+#[derive(Resource, Clone)]
+pub struct ImportedWorld(pub WorldFile);
+
+// The world the title menu starts new games in: a generated one, or one of
+// the files in the worlds directory.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct WorldChoice {
+    pub files: Vec<PathBuf>,
+    // None for a generated world.
+    pub selected: Option<PathBuf>,
+}
+
+impl WorldChoice {
+    // Cycles through the generated world and then each file.
+    pub fn step(&mut self, direction: i32) {
+        let count = self.files.len() as i32 + 1;
+        let current = self
+            .selected
+            .as_ref()
+            .and_then(|path| self.files.iter().position(|file| file == path))
+            .map_or(0, |index| index as i32 + 1);
+        let next = (current + direction).rem_euclid(count);
+        self.selected = (next > 0).then(|| self.files[next as usize - 1].clone());
+    }
+
+    // The chosen file's name, or None for a generated world.
+    pub fn name(&self) -> Option<String> {
+        let path = self.selected.as_ref()?;
+        let stem = path.file_stem().unwrap_or(path.as_os_str());
+        Some(stem.to_string_lossy().into_owned())
+    }
+
+    pub fn load(&self, width: usize, height: usize) -> Option<WorldFile> {
+        load_world(self.selected.as_deref()?, width, height)
+    }
+}
+
+impl WorldFile {
+    pub fn load(path: &Path, width: usize, height: usize) -> Result<Self, WorldFileError> {
+        let text = fs::read_to_string(path).map_err(WorldFileError::Io)?;
+        let file: WorldFile =
+            ron::from_str(&text).map_err(|err| WorldFileError::Parse(err.to_string()))?;
+        file.validate(width, height)?;
+        Ok(file)
+    }
+
+    pub fn validate(&self, width: usize, height: usize) -> Result<(), WorldFileError> {
+        if self.version != WORLD_FILE_VERSION {
+            return Err(WorldFileError::Version(self.version));
+        }
+        if self.width != width || self.height != height {
+            return Err(WorldFileError::Size {
+                width: self.width,
+                height: self.height,
+                expected_width: width,
+                expected_height: height,
+            });
+        }
+        check_rows(&self.walls, width, height).map_err(WorldFileError::WallRow)?;
+        if !self.water.is_empty() {
+            check_rows(&self.water, width, height).map_err(WorldFileError::WaterRow)?;
+        }
+        let positions = self
+            .obstacles
+            .iter()
+            .map(|record| (record.x, record.y))
+            .chain(self.landmarks.iter().map(|record| (record.x, record.y)));
+        for (x, y) in positions {
+            let in_bounds = x >= 0 && y >= 0 && x < width as i32 && y < height as i32;
+            if !in_bounds {
+                return Err(WorldFileError::OutOfBounds { x, y });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn wall_field(&self) -> Vec<Vec<bool>> {
+        decode_rows(&self.walls, self.width)
+    }
+
+    pub fn water_field(&self) -> Vec<Vec<bool>> {
+        decode_rows(&self.water, self.width)
+    }
+}
+
+// The index of the first row that doesn't cover the map width.
+fn check_rows(rows: &[Vec<u32>], width: usize, height: usize) -> Result<(), usize> {
+    if rows.len() != height {
+        return Err(rows.len());
+    }
+    for (row, runs) in rows.iter().enumerate() {
+        let covered: u64 = runs.iter().map(|run| *run as u64).sum();
+        if covered != width as u64 {
+            return Err(row);
+        }
+    }
+    Ok(())
+}
+
+fn decode_rows(rows: &[Vec<u32>], width: usize) -> Vec<Vec<bool>> {
+    rows.iter()
+        .map(|runs| {
+            let mut row = Vec::with_capacity(width);
+            for (index, run) in runs.iter().enumerate() {
+                let is_set = index % 2 == 1;
+                row.extend(std::iter::repeat_n(is_set, *run as usize));
+            }
+            row
+        })
+        .collect()
+}
+
+fn encode_row(row: &[bool]) -> Vec<u32> {
+    let mut runs = Vec::new();
+    let mut current = false;
+    let mut length = 0;
+    for &tile in row {
+        if tile == current {
+            length += 1;
+        } else {
+            runs.push(length);
+            current = tile;
+            length = 1;
+        }
+    }
+    runs.push(length);
+    runs
+}
+
+// The world file named by WORLD_FILE in the environment, if any.
+pub fn world_file_from_env() -> Option<PathBuf> {
+    env::var("WORLD_FILE").ok().map(PathBuf::from)
+}
+
+// A bad file (or one made for a different world size) falls back to a
+// generated world rather than refusing to start.
+pub fn load_world(path: &Path, width: usize, height: usize) -> Option<WorldFile> {
+    match WorldFile::load(path, width, height) {
+        Ok(file) => Some(file),
+        Err(err) => {
+            warn!("ignoring world file {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+// Lists the exported worlds each time the title comes up, so one exported
+// during a run can be picked for the next. The chosen file stays on the
+// list even when it lives elsewhere.
+fn list_world_files(mut choice: ResMut<WorldChoice>) {
+    let mut files: Vec<PathBuf> = fs::read_dir(EXPORT_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(selected) = &choice.selected
+        && !files.contains(selected)
+    {
+        files.push(selected.clone());
+    }
+    files.sort();
+    choice.files = files;
+}
+
+fn export_world(
+    input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut event_log: ResMut<EventLog>,
+    grid: Res<WorldGrid>,
+    obstacle_query: Query<(&Obstacle, &Location2D)>,
+    landmark_query: Query<(&Landmark, &Location2D)>,
+) {
+    if !input.just_pressed(KeyCode::F5) {
+        return;
+    }
+
+    let file = WorldFile {
+        version: WORLD_FILE_VERSION,
+        width: grid.width,
+        height: grid.height,
+        walls: grid.walls.iter().map(|row| encode_row(row)).collect(),
+        water: grid
+            .tiles
+            .iter()
+            .map(|row| {
+                let water: Vec<bool> = row.iter().map(|tile| *tile == TileKind::Water).collect();
+                encode_row(&water)
+            })
+            .collect(),
+        obstacles: obstacle_query
+            .iter()
+            .map(|(obstacle, location)| ObstacleRecord {
+                kind: obstacle.kind,
+                x: location.x,
+                y: location.y,
+            })
+            .collect(),
+        landmarks: landmark_query
+            .iter()
+            .map(|(landmark, location)| LandmarkRecord {
+                kind: landmark.kind,
+                x: location.x,
+                y: location.y,
+            })
+            .collect(),
+    };
+
+    let text = match ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default()) {
+        Ok(text) => text,
+        Err(err) => {
+            warn!("could not serialize world: {err}");
+            return;
+        }
+    };
+    let stamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let path = Path::new(EXPORT_DIR).join(format!("world-{stamp}.ron"));
+    let result = fs::create_dir_all(EXPORT_DIR).and_then(|_| fs::write(&path, text));
+    match result {
+        Ok(()) => event_log.push(&time, format!("exported world to {}", path.display())),
+        Err(err) => warn!("could not write {}: {err}", path.display()),
+    }
+}
+
+pub struct WorldFilePlugin;
+
+impl Plugin for WorldFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldChoice>()
+            .add_systems(OnEnter(AppState::MainMenu), list_world_files)
+            .add_systems(Update, export_world.run_if(in_state(AppState::Playing)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 6;
+    const HEIGHT: usize = 3;
+
+    fn sample() -> WorldFile {
+        let walls = [
+            vec![true; WIDTH],
+            vec![true, false, false, true, true, false],
+            vec![false; WIDTH],
+        ];
+        WorldFile {
+            version: WORLD_FILE_VERSION,
+            width: WIDTH,
+            height: HEIGHT,
+            walls: walls.iter().map(|row| encode_row(row)).collect(),
+            water: Vec::new(),
+            obstacles: vec![ObstacleRecord {
+                kind: ObstacleKind::Rock,
+                x: 1,
+                y: 1,
+            }],
+            landmarks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn rows_round_trip() {
+        let rows = [
+            vec![false; WIDTH],
+            vec![true; WIDTH],
+            vec![true, false, false, true, true, false],
+            vec![false, true, false, true, false, true],
+        ];
+        for row in rows {
+            let runs = encode_row(&row);
+            assert_eq!(runs[0] == 0, row.first() == Some(&true), "{row:?} -> {runs:?}");
+            assert_eq!(decode_rows(&[runs], row.len()), vec![row]);
+        }
+    }
+
+    #[test]
+    fn file_round_trips_through_ron() {
+        let file = sample();
+        let text = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default()).unwrap();
+        let read: WorldFile = ron::from_str(&text).unwrap();
+        read.validate(WIDTH, HEIGHT).unwrap();
+        assert_eq!(read.wall_field(), file.wall_field());
+        assert_eq!(read.wall_field()[1], [true, false, false, true, true, false]);
+    }
+
+    #[test]
+    fn files_without_water_have_none() {
+        let text = format!(
+            "(version: {WORLD_FILE_VERSION}, width: 6, height: 3, \
+             walls: [[6], [0, 6], [6]], obstacles: [], landmarks: [])"
+        );
+        let read: WorldFile = ron::from_str(&text).unwrap();
+        assert!(read.water.is_empty());
+        read.validate(WIDTH, HEIGHT).unwrap();
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        assert!(ron::from_str::<WorldFile>("(version: 1, width: 6").is_err());
+
+        let mut file = sample();
+        file.version = WORLD_FILE_VERSION + 1;
+        assert!(matches!(file.validate(WIDTH, HEIGHT), Err(WorldFileError::Version(_))));
+
+        let file = sample();
+        assert!(matches!(
+            file.validate(WIDTH + 1, HEIGHT),
+            Err(WorldFileError::Size { .. })
+        ));
+
+        let mut file = sample();
+        file.walls[2] = vec![2, 3];
+        assert!(matches!(file.validate(WIDTH, HEIGHT), Err(WorldFileError::WallRow(2))));
+
+        let mut file = sample();
+        file.walls.pop();
+        assert!(matches!(file.validate(WIDTH, HEIGHT), Err(WorldFileError::WallRow(2))));
+
+        let mut file = sample();
+        file.water = vec![vec![WIDTH as u32]; HEIGHT - 1];
+        assert!(matches!(file.validate(WIDTH, HEIGHT), Err(WorldFileError::WaterRow(2))));
+
+        let mut file = sample();
+        file.landmarks.push(LandmarkRecord {
+            kind: LandmarkKind::Shrine,
+            x: WIDTH as i32,
+            y: 0,
+        });
+        assert!(matches!(
+            file.validate(WIDTH, HEIGHT),
+            Err(WorldFileError::OutOfBounds { x, y: 0 }) if x == WIDTH as i32
+        ));
+    }
+}